@@ -0,0 +1,460 @@
+// Pure drop computation with no Leptos or web-sys dependency, so it can be
+// driven from tests, other UI frameworks or a backend.
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// Anything usable as a node key: tag ids, file paths interned to ints, collection ids...
+pub trait NodeId: Copy + Eq + Hash + 'static {}
+
+impl<T: Copy + Eq + Hash + 'static> NodeId for T {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Node<Id = u32> {
+    pub id: Id,
+    pub parent_id: Option<Id>,
+    pub position: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DropConfig {
+    // Relative y below which a hover means "insert before"
+    pub before_threshold: f64,
+    // Relative y above which a hover means "insert after"
+    pub after_threshold: f64,
+    // Flat lists (collections, roots) disable nesting; the row is then split in half
+    pub allow_child: bool,
+}
+
+impl Default for DropConfig {
+    fn default() -> Self {
+        DropConfig { before_threshold: 0.25, after_threshold: 0.75, allow_child: true }
+    }
+}
+
+impl DropConfig {
+    pub fn flat() -> Self {
+        DropConfig { before_threshold: 0.5, after_threshold: 0.5, allow_child: false }
+    }
+
+    pub fn zone(&self, pos: f64) -> DropZone {
+        if self.allow_child {
+            if pos < self.before_threshold {
+                DropZone::Before
+            } else if pos > self.after_threshold {
+                DropZone::After
+            } else {
+                DropZone::Child
+            }
+        } else if pos < self.before_threshold {
+            DropZone::Before
+        } else {
+            DropZone::After
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropZone {
+    Before,
+    Child,
+    After,
+}
+
+impl DropZone {
+    pub fn class(&self) -> &'static str {
+        match self {
+            DropZone::Before => "drop-before",
+            DropZone::Child => "drop-child",
+            DropZone::After => "drop-after",
+        }
+    }
+}
+
+pub fn unify_hover_target<Id: NodeId>(tags: &[Node<Id>], current: Node<Id>, relative_y: f64) -> (Id, f64) {
+    unify_hover_target_with(tags, current, relative_y, &DropConfig::default())
+}
+
+pub fn unify_hover_target_with<Id: NodeId>(tags: &[Node<Id>], current: Node<Id>, relative_y: f64, config: &DropConfig) -> (Id, f64) {
+    let mut pos = relative_y.clamp(0.0, 1.0);
+    let mut target = current.id;
+    match config.zone(pos) {
+        DropZone::After => {
+            let mut siblings: Vec<Node<Id>> = tags.iter().copied().filter(|t| t.parent_id == current.parent_id).collect();
+            siblings.sort_by_key(|t| t.position);
+            if let Some(next) = siblings.into_iter().find(|t| t.position > current.position) {
+                target = next.id;
+                pos = 0.0;
+            }
+        }
+        DropZone::Before => pos = 0.0,
+        DropZone::Child => {}
+    }
+    (target, pos)
+}
+
+pub fn is_descendant<Id: NodeId>(tags: &[Node<Id>], ancestor: Id, descendant: Id) -> bool {
+    let mut check = Some(descendant);
+    while let Some(curr) = check {
+        if curr == ancestor {
+            return true;
+        }
+        check = tags.iter().find(|t| t.id == curr).and_then(|t| t.parent_id);
+    }
+    false
+}
+
+pub fn compute_drop_action<Id: NodeId>(dragged_id: Id, target_id: Id, pos: f64, tags: &[Node<Id>]) -> Option<(Option<Id>, i32, &'static str)> {
+    compute_drop_action_with(dragged_id, target_id, pos, tags, &DropConfig::default())
+}
+
+pub fn compute_drop_action_with<Id: NodeId>(dragged_id: Id, target_id: Id, pos: f64, tags: &[Node<Id>], config: &DropConfig) -> Option<(Option<Id>, i32, &'static str)> {
+    if dragged_id == target_id || is_descendant(tags, dragged_id, target_id) {
+        return None;
+    }
+    let target_tag = tags.iter().find(|t| t.id == target_id).copied();
+    let dragged_parent = tags.iter().find(|t| t.id == dragged_id).and_then(|t| t.parent_id);
+    if let Some(tag) = target_tag {
+        match config.zone(pos) {
+            DropZone::Before => {
+                if tag.parent_id == dragged_parent {
+                    let action = "before-same-parent";
+                    return Some((tag.parent_id, tag.position, action));
+                } else {
+                    let action = "before";
+                    return Some((tag.parent_id, tag.position, action));
+                }
+            }
+            DropZone::After => {
+                let action = "after";
+                return Some((tag.parent_id, tag.position + 1, action));
+            }
+            DropZone::Child => {
+                let action = "child";
+                return Some((Some(tag.id), 0, action));
+            }
+        }
+    }
+    Some((None, 0, "root"))
+}
+
+// Orders the multi-selection by tree position and drops nodes whose ancestor is
+// also selected, since those travel with their parent anyway.
+pub fn ordered_drag_set<Id: NodeId>(tags: &[Node<Id>], selected: &[Id]) -> Vec<Id> {
+    fn path_key<Id: NodeId>(tags: &[Node<Id>], id: Id) -> Vec<i32> {
+        let mut key = Vec::new();
+        let mut check = tags.iter().find(|t| t.id == id).copied();
+        while let Some(node) = check {
+            key.push(node.position);
+            check = node.parent_id.and_then(|pid| tags.iter().find(|t| t.id == pid).copied());
+        }
+        key.reverse();
+        key
+    }
+    let mut seen = HashSet::new();
+    let mut ids: Vec<Id> = selected
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .filter(|id| tags.iter().any(|t| t.id == *id))
+        .filter(|id| !selected.iter().any(|other| other != id && is_descendant(tags, *other, *id)))
+        .collect();
+    ids.sort_by_key(|id| path_key(tags, *id));
+    ids
+}
+
+// Mirrors the backend `move_tag` position bookkeeping so follow-up moves of a
+// multi-drag can be computed against the tree as it will look after each step.
+fn simulate_move<Id: NodeId>(tags: &mut [Node<Id>], id: Id, new_parent_id: Option<Id>, target_position: i32) {
+    let Some(current) = tags.iter().find(|t| t.id == id).copied() else { return; };
+    let old_parent_id = current.parent_id;
+    if old_parent_id == new_parent_id {
+        for t in tags.iter_mut().filter(|t| t.parent_id == new_parent_id && t.id != id) {
+            if current.position < target_position && t.position > current.position && t.position <= target_position {
+                t.position -= 1;
+            } else if current.position > target_position && t.position >= target_position && t.position < current.position {
+                t.position += 1;
+            }
+        }
+    }
+    if let Some(t) = tags.iter_mut().find(|t| t.id == id) {
+        t.parent_id = new_parent_id;
+        t.position = target_position;
+    }
+    // Both parents are renumbered from 0; the moved node goes first among ties
+    let parents = if old_parent_id == new_parent_id { vec![new_parent_id] } else { vec![old_parent_id, new_parent_id] };
+    for parent in parents {
+        let mut siblings: Vec<(i32, bool, Id)> = tags
+            .iter()
+            .filter(|t| t.parent_id == parent)
+            .map(|t| (t.position, t.id != id, t.id))
+            .collect();
+        siblings.sort_by_key(|(position, others, _)| (*position, *others));
+        for (index, (_, _, sid)) in siblings.into_iter().enumerate() {
+            if let Some(t) = tags.iter_mut().find(|t| t.id == sid) {
+                t.position = index as i32;
+            }
+        }
+    }
+}
+
+// Computes the ordered list of `(id, new_parent_id, target_position)` moves for a
+// multi-node drag: the first node lands where a single drag would, the rest follow
+// it as consecutive siblings. Returns an empty list when the drop is invalid.
+pub fn compute_multi_drop_actions<Id: NodeId>(dragged_ids: &[Id], target_id: Id, pos: f64, tags: &[Node<Id>]) -> Vec<(Id, Option<Id>, i32)> {
+    compute_multi_drop_actions_with(dragged_ids, target_id, pos, tags, &DropConfig::default())
+}
+
+pub fn compute_multi_drop_actions_with<Id: NodeId>(dragged_ids: &[Id], target_id: Id, pos: f64, tags: &[Node<Id>], config: &DropConfig) -> Vec<(Id, Option<Id>, i32)> {
+    let ordered = ordered_drag_set(tags, dragged_ids);
+    if ordered.iter().any(|id| *id == target_id || is_descendant(tags, *id, target_id)) {
+        return Vec::new();
+    }
+    let Some(first) = ordered.first().copied() else { return Vec::new(); };
+    let Some((parent, first_pos, _action)) = compute_drop_action_with(first, target_id, pos, tags, config) else { return Vec::new(); };
+    let mut sim: Vec<Node<Id>> = tags.to_vec();
+    let mut moves = vec![(first, parent, first_pos)];
+    simulate_move(&mut sim, first, parent, first_pos);
+    let mut prev = first;
+    for id in ordered.into_iter().skip(1) {
+        let prev_pos = sim.iter().find(|t| t.id == prev).map(|t| t.position).unwrap_or(0);
+        let node = sim.iter().find(|t| t.id == id).copied();
+        let target = match node {
+            Some(n) if n.parent_id == parent && n.position < prev_pos => prev_pos,
+            _ => prev_pos + 1,
+        };
+        moves.push((id, parent, target));
+        simulate_move(&mut sim, id, parent, target);
+        prev = id;
+    }
+    moves
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AutoScrollConfig {
+    // Distance in px from the container edge where scrolling kicks in
+    pub threshold: f64,
+    // Maximum scroll step in px per tick, reached at the very edge
+    pub speed: f64,
+    pub interval_ms: i32,
+}
+
+impl Default for AutoScrollConfig {
+    fn default() -> Self {
+        AutoScrollConfig { threshold: 40.0, speed: 12.0, interval_ms: 16 }
+    }
+}
+
+// Scroll step for a pointer at `pointer_y` inside a container spanning `top..bottom`;
+// grows linearly as the pointer approaches (or passes) an edge.
+pub fn auto_scroll_delta(pointer_y: f64, top: f64, bottom: f64, config: &AutoScrollConfig) -> f64 {
+    if config.threshold <= 0.0 || bottom <= top {
+        return 0.0;
+    }
+    if pointer_y < top + config.threshold {
+        let depth = ((top + config.threshold - pointer_y) / config.threshold).min(1.0);
+        -config.speed * depth
+    } else if pointer_y > bottom - config.threshold {
+        let depth = ((pointer_y - (bottom - config.threshold)) / config.threshold).min(1.0);
+        config.speed * depth
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn node(id: u32, parent_id: Option<u32>, position: i32) -> Node {
+        Node { id, parent_id, position }
+    }
+
+    // 1 ─┬─ 2
+    //    └─ 3 ── 4
+    // 5
+    fn sample() -> Vec<Node> {
+        vec![node(1, None, 0), node(2, Some(1), 0), node(3, Some(1), 1), node(4, Some(3), 0), node(5, None, 1)]
+    }
+
+    #[test]
+    fn zones_follow_default_thresholds() {
+        let config = DropConfig::default();
+        assert_eq!(config.zone(0.1), DropZone::Before);
+        assert_eq!(config.zone(0.5), DropZone::Child);
+        assert_eq!(config.zone(0.9), DropZone::After);
+    }
+
+    #[test]
+    fn flat_config_never_yields_child() {
+        let config = DropConfig::flat();
+        assert_eq!(config.zone(0.49), DropZone::Before);
+        assert_eq!(config.zone(0.5), DropZone::After);
+        assert_eq!(compute_drop_action_with(2, 5, 0.5, &sample(), &config), Some((None, 2, "after")));
+    }
+
+    #[test]
+    fn descendant_walks_parent_chain() {
+        let tags = sample();
+        assert!(is_descendant(&tags, 1, 4));
+        assert!(!is_descendant(&tags, 2, 4));
+        assert!(!is_descendant(&tags, 5, 1));
+    }
+
+    #[test]
+    fn cannot_drop_onto_self_or_descendant() {
+        let tags = sample();
+        assert_eq!(compute_drop_action(1, 1, 0.5, &tags), None);
+        assert_eq!(compute_drop_action(1, 4, 0.5, &tags), None);
+    }
+
+    #[test]
+    fn drop_actions_per_zone() {
+        let tags = sample();
+        assert_eq!(compute_drop_action(5, 3, 0.5, &tags), Some((Some(3), 0, "child")));
+        assert_eq!(compute_drop_action(5, 3, 0.1, &tags), Some((Some(1), 1, "before")));
+        assert_eq!(compute_drop_action(2, 3, 0.1, &tags), Some((Some(1), 1, "before-same-parent")));
+        assert_eq!(compute_drop_action(5, 2, 0.9, &tags), Some((Some(1), 1, "after")));
+        assert_eq!(compute_drop_action(5, 99, 0.5, &tags), Some((None, 0, "root")));
+    }
+
+    #[test]
+    fn hover_after_redirects_to_next_sibling() {
+        let tags = sample();
+        assert_eq!(unify_hover_target(&tags, tags[1], 0.9), (3, 0.0));
+        // Last sibling keeps its own "after" zone
+        assert_eq!(unify_hover_target(&tags, tags[2], 0.9), (3, 0.9));
+        assert_eq!(unify_hover_target(&tags, tags[2], 0.1), (3, 0.0));
+    }
+
+    #[test]
+    fn drag_set_drops_selected_descendants_and_sorts_by_tree_order() {
+        let tags = sample();
+        assert_eq!(ordered_drag_set(&tags, &[5, 4, 3, 3, 42]), vec![3, 5]);
+    }
+
+    #[test]
+    fn multi_drop_places_nodes_consecutively() {
+        let tags = sample();
+        let moves = compute_multi_drop_actions(&[2, 5], 4, 0.5, &tags);
+        assert_eq!(moves, vec![(2, Some(4), 0), (5, Some(4), 1)]);
+        assert!(compute_multi_drop_actions(&[1, 5], 4, 0.5, &tags).is_empty());
+    }
+
+    #[test]
+    fn works_with_non_integer_ids() {
+        let tags = vec![
+            Node { id: "a", parent_id: None, position: 0 },
+            Node { id: "b", parent_id: Some("a"), position: 0 },
+        ];
+        assert!(is_descendant(&tags, "a", "b"));
+        assert_eq!(compute_drop_action("a", "b", 0.5, &tags), None);
+        assert_eq!(compute_drop_action("b", "a", 0.9, &tags), Some((None, 1, "after")));
+    }
+
+    // Random forest of up to 12 nodes: each node's parent is an earlier node or
+    // none, with positions numbered per parent in insertion order
+    fn forest() -> impl Strategy<Value = Vec<Node>> {
+        prop::collection::vec(prop::option::of(any::<prop::sample::Index>()), 1..12).prop_map(|parents| {
+            let mut nodes: Vec<Node> = Vec::new();
+            for (i, parent) in parents.into_iter().enumerate() {
+                let parent_id = parent.filter(|_| i > 0).map(|p| p.index(i) as u32);
+                let position = nodes.iter().filter(|n| n.parent_id == parent_id).count() as i32;
+                nodes.push(node(i as u32, parent_id, position));
+            }
+            nodes
+        })
+    }
+
+    fn children(tags: &[Node], parent: Option<u32>) -> Vec<u32> {
+        let mut kids: Vec<Node> = tags.iter().copied().filter(|t| t.parent_id == parent).collect();
+        kids.sort_by_key(|t| t.position);
+        kids.into_iter().map(|t| t.id).collect()
+    }
+
+    // No cycles, and every parent's children numbered 0..n
+    fn check_tree(tags: &[Node]) -> Result<(), TestCaseError> {
+        for tag in tags {
+            let mut cursor = tag.parent_id;
+            let mut steps = 0;
+            while let Some(parent) = cursor {
+                steps += 1;
+                prop_assert!(steps <= tags.len(), "cycle through {}", tag.id);
+                cursor = tags.iter().find(|t| t.id == parent).and_then(|t| t.parent_id);
+            }
+        }
+        for parent in std::iter::once(None).chain(tags.iter().map(|t| Some(t.id))) {
+            let mut positions: Vec<i32> = tags.iter().filter(|t| t.parent_id == parent).map(|t| t.position).collect();
+            positions.sort();
+            prop_assert_eq!(positions, (0..tags.iter().filter(|t| t.parent_id == parent).count() as i32).collect::<Vec<_>>());
+        }
+        Ok(())
+    }
+
+    // Nodes that didn't move keep their relative order under every parent
+    fn check_stable(before: &[Node], after: &[Node], moved: &[u32]) -> Result<(), TestCaseError> {
+        for parent in std::iter::once(None).chain(before.iter().map(|t| Some(t.id))) {
+            let still = |ids: Vec<u32>| ids.into_iter().filter(|id| !moved.contains(id)).collect::<Vec<_>>();
+            prop_assert_eq!(still(children(before, parent)), still(children(after, parent)));
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn single_drop_keeps_the_tree_valid(
+            tags in forest(),
+            dragged in any::<prop::sample::Index>(),
+            target in any::<prop::sample::Index>(),
+            pos in 0.0f64..=1.0,
+        ) {
+            let dragged = dragged.index(tags.len()) as u32;
+            let target = target.index(tags.len()) as u32;
+            let action = compute_drop_action(dragged, target, pos, &tags);
+            if dragged == target || is_descendant(&tags, dragged, target) {
+                prop_assert_eq!(action, None);
+                return Ok(());
+            }
+            let (parent, position, _) = action.unwrap();
+            let mut after = tags.clone();
+            simulate_move(&mut after, dragged, parent, position);
+            check_tree(&after)?;
+            check_stable(&tags, &after, &[dragged])?;
+        }
+
+        #[test]
+        fn multi_drop_keeps_the_tree_valid(
+            tags in forest(),
+            selected in prop::collection::vec(any::<prop::sample::Index>(), 1..5),
+            target in any::<prop::sample::Index>(),
+            pos in 0.0f64..=1.0,
+        ) {
+            let selected: Vec<u32> = selected.iter().map(|i| i.index(tags.len()) as u32).collect();
+            let target = target.index(tags.len()) as u32;
+            let moves = compute_multi_drop_actions(&selected, target, pos, &tags);
+            if moves.is_empty() {
+                return Ok(());
+            }
+            let mut after = tags.clone();
+            for (id, parent, position) in &moves {
+                simulate_move(&mut after, *id, *parent, *position);
+            }
+            check_tree(&after)?;
+            let moved: Vec<u32> = moves.iter().map(|(id, _, _)| *id).collect();
+            check_stable(&tags, &after, &moved)?;
+            // The dragged nodes end up side by side, in drag-set order
+            let siblings = children(&after, moves[0].1);
+            let start = siblings.iter().position(|id| *id == moved[0]).unwrap();
+            prop_assert_eq!(siblings.get(start..start + moved.len()), Some(&moved[..]));
+        }
+    }
+
+    #[test]
+    fn auto_scroll_ramps_near_edges() {
+        let config = AutoScrollConfig::default();
+        assert_eq!(auto_scroll_delta(200.0, 0.0, 400.0, &config), 0.0);
+        assert_eq!(auto_scroll_delta(0.0, 0.0, 400.0, &config), -config.speed);
+        assert_eq!(auto_scroll_delta(380.0, 0.0, 400.0, &config), config.speed / 2.0);
+        assert_eq!(auto_scroll_delta(10.0, 0.0, 0.0, &config), 0.0);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::headless::*;
+use leptos::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+
+// Signal-backed ids additionally need to be shareable across the reactive arena.
+pub trait DndId: NodeId + Send + Sync {}
+
+impl<T: NodeId + Send + Sync> DndId for T {}
+
+pub fn end_drag<Id: DndId>(set_dragging_id: WriteSignal<Option<Id>>, set_drop_target_id: WriteSignal<Option<Id>>, set_drag_just_ended: WriteSignal<bool>) {
+    set_dragging_id.set(None);
+    set_drop_target_id.set(None);
+    set_drag_just_ended.set(true);
+    if let Some(win) = web_sys::window() {
+        let clear = set_drag_just_ended;
+        let cb = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+            clear.set(false);
+        });
+        let _ = win.set_timeout_with_callback_and_timeout_and_arguments_0(cb.as_ref().unchecked_ref(), 100);
+        cb.forget();
+    }
+}
+
+#[derive(Clone)]
+pub struct DndSignals<Id: DndId = u32> {
+    pub dragging_id_read: ReadSignal<Option<Id>>,
+    pub dragging_id_write: WriteSignal<Option<Id>>,
+    pub drop_target_id_read: ReadSignal<Option<Id>>,
+    pub drop_target_id_write: WriteSignal<Option<Id>>,
+    pub drop_position_read: ReadSignal<f64>,
+    pub drop_position_write: WriteSignal<f64>,
+    pub drag_just_ended_read: ReadSignal<bool>,
+    pub drag_just_ended_write: WriteSignal<bool>,
+    pub selected_ids_read: ReadSignal<Vec<Id>>,
+    pub selected_ids_write: WriteSignal<Vec<Id>>,
+    // Key of the external drop zone under the pointer, see `make_zone_on_mouseup`
+    pub active_zone_read: ReadSignal<Option<String>>,
+    pub active_zone_write: WriteSignal<Option<String>>,
+}
+
+pub fn create_dnd_signals<Id: DndId>() -> DndSignals<Id> {
+    let (dragging_id_read, dragging_id_write) = signal(None::<Id>);
+    let (drop_target_id_read, drop_target_id_write) = signal(None::<Id>);
+    let (drop_position_read, drop_position_write) = signal(0.5f64);
+    let (drag_just_ended_read, drag_just_ended_write) = signal(false);
+    let (selected_ids_read, selected_ids_write) = signal(Vec::<Id>::new());
+    let (active_zone_read, active_zone_write) = signal(None::<String>);
+    DndSignals {
+        dragging_id_read,
+        dragging_id_write,
+        drop_target_id_read,
+        drop_target_id_write,
+        drop_position_read,
+        drop_position_write,
+        drag_just_ended_read,
+        drag_just_ended_write,
+        selected_ids_read,
+        selected_ids_write,
+        active_zone_read,
+        active_zone_write,
+    }
+}
+
+// Nodes that a drag started on `tag_id` should carry: the whole multi-selection
+// when the grabbed node is part of it, otherwise just the grabbed node.
+pub fn drag_set<Id: DndId>(dnd: &DndSignals<Id>, tag_id: Id) -> Vec<Id> {
+    let selected = dnd.selected_ids_read.get_untracked();
+    if selected.contains(&tag_id) {
+        selected
+    } else {
+        vec![tag_id]
+    }
+}
+
+pub fn make_on_mousedown<Id: DndId>(dnd: DndSignals<Id>, tag_id: Id) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |ev: web_sys::MouseEvent| {
+        if ev.button() == 0 {
+            if let Some(target) = ev.target() {
+                if target.dyn_ref::<web_sys::HtmlInputElement>().is_some() { return; }
+                if target.dyn_ref::<web_sys::HtmlButtonElement>().is_some() { return; }
+            }
+            if ev.ctrl_key() || ev.meta_key() {
+                dnd.selected_ids_write.update(|ids| {
+                    if let Some(i) = ids.iter().position(|id| *id == tag_id) {
+                        ids.remove(i);
+                    } else {
+                        ids.push(tag_id);
+                    }
+                });
+                ev.stop_propagation();
+                ev.prevent_default();
+                return;
+            }
+            if !dnd.selected_ids_read.get_untracked().contains(&tag_id) {
+                dnd.selected_ids_write.set(Vec::new());
+            }
+            dnd.dragging_id_write.set(Some(tag_id));
+            ev.stop_propagation();
+        }
+    }
+}
+
+pub fn make_on_mousemove<Id: DndId>(dnd: DndSignals<Id>, current: Node<Id>, get_nodes: impl Fn() -> Vec<Node<Id>> + Copy + 'static) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    make_on_mousemove_with(dnd, current, get_nodes, DropConfig::default())
+}
+
+pub fn make_on_mousemove_with<Id: DndId>(dnd: DndSignals<Id>, current: Node<Id>, get_nodes: impl Fn() -> Vec<Node<Id>> + Copy + 'static, config: DropConfig) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_some() {
+            if let Some(target) = ev.current_target() {
+                if let Some(element) = target.dyn_ref::<web_sys::HtmlElement>() {
+                    let rect = element.get_bounding_client_rect();
+                    let y = ev.client_y() as f64;
+                    let top = rect.top();
+                    let height = rect.height();
+                    if height > 0.0 {
+                        let relative_y = ((y - top) / height).clamp(0.0, 1.0);
+                        let nodes = get_nodes();
+                        let (target_id_effective, pos_effective) = unify_hover_target_with(&nodes, current, relative_y, &config);
+                        dnd.drop_target_id_write.set(Some(target_id_effective));
+                        dnd.drop_position_write.set(pos_effective);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Released outside any node: the drop target was cleared on mouseleave.
+pub fn make_on_mouseleave<Id: DndId>(dnd: DndSignals<Id>) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |_ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_some() {
+            dnd.drop_target_id_write.set(None);
+        }
+    }
+}
+
+// Ends the drag without moving anything.
+pub fn cancel_drag<Id: DndId>(dnd: &DndSignals<Id>, on_cancel: impl Fn()) {
+    dnd.active_zone_write.set(None);
+    end_drag(dnd.dragging_id_write, dnd.drop_target_id_write, dnd.drag_just_ended_write);
+    on_cancel();
+}
+
+pub fn bind_escape_cancel<Id: DndId>(dnd: DndSignals<Id>, on_cancel: impl Fn() + Copy + 'static) {
+    let Some(window) = web_sys::window() else { return; };
+    let on_keydown = wasm_bindgen::closure::Closure::<dyn FnMut(_)>::new(move |ev: web_sys::KeyboardEvent| {
+        if ev.key() == "Escape" && dnd.dragging_id_read.get_untracked().is_some() {
+            ev.prevent_default();
+            cancel_drag(&dnd, on_cancel);
+        }
+    });
+    let _ = window.add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref());
+    on_keydown.forget();
+}
+
+// External drop zones (a delete bin, the file list, a root chip...) share the drag
+// session with the tree. The zone's own mouseup fires before the window-level
+// handler, so the drop is consumed there and the tree sees no drag left to apply.
+pub fn make_zone_on_mouseenter<Id: DndId>(dnd: DndSignals<Id>, zone: impl Into<String>) -> impl Fn(web_sys::MouseEvent) + Clone + 'static {
+    let zone = zone.into();
+    move |_ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_some() {
+            dnd.drop_target_id_write.set(None);
+            dnd.active_zone_write.set(Some(zone.clone()));
+        }
+    }
+}
+
+pub fn make_zone_on_mouseleave<Id: DndId>(dnd: DndSignals<Id>) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |_ev: web_sys::MouseEvent| {
+        dnd.active_zone_write.set(None);
+    }
+}
+
+// `on_drop` receives the full drag set (multi-selection aware).
+pub fn make_zone_on_mouseup<Id: DndId>(dnd: DndSignals<Id>, zone: impl Into<String>, on_drop: impl Fn(Vec<Id>) + Clone + 'static) -> impl Fn(web_sys::MouseEvent) + Clone + 'static {
+    let zone = zone.into();
+    move |ev: web_sys::MouseEvent| {
+        let Some(dragged_id) = dnd.dragging_id_read.get_untracked() else { return; };
+        if dnd.active_zone_read.get_untracked().as_deref() != Some(zone.as_str()) {
+            return;
+        }
+        ev.prevent_default();
+        let ids = drag_set(&dnd, dragged_id);
+        dnd.active_zone_write.set(None);
+        end_drag(dnd.dragging_id_write, dnd.drop_target_id_write, dnd.drag_just_ended_write);
+        on_drop(ids);
+    }
+}
+
+pub fn is_zone_active<Id: DndId>(dnd: &DndSignals<Id>, zone: &str) -> bool {
+    dnd.dragging_id_read.get().is_some() && dnd.active_zone_read.get().as_deref() == Some(zone)
+}
+
+pub fn make_label_click_guard<Id: DndId>(dnd: DndSignals<Id>) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_some() || dnd.drag_just_ended_read.get_untracked() || ev.ctrl_key() || ev.meta_key() {
+            ev.stop_propagation();
+            ev.prevent_default();
+        }
+    }
+}
+
+pub fn make_checkbox_change_guard<Id: DndId>(dnd: DndSignals<Id>, on_toggle: impl Fn(Id) + Copy + 'static, tag_id: Id) -> impl Fn(web_sys::Event) + Copy + 'static {
+    move |ev: web_sys::Event| {
+        if dnd.dragging_id_read.get_untracked().is_none() && !dnd.drag_just_ended_read.get_untracked() {
+            on_toggle(tag_id);
+        } else {
+            ev.stop_propagation();
+            ev.prevent_default();
+        }
+    }
+}
+
+pub fn make_checkbox_click_guard<Id: DndId>(dnd: DndSignals<Id>) -> impl Fn(web_sys::MouseEvent) + Copy + 'static {
+    move |ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_some() || dnd.drag_just_ended_read.get_untracked() {
+            ev.stop_propagation();
+            ev.prevent_default();
+        }
+    }
+}
+
+pub fn bind_global_mouseup<Id: DndId>(
+    dnd: DndSignals<Id>,
+    get_nodes: impl Fn() -> Vec<Node<Id>> + Copy + 'static,
+    on_drop: impl Fn(Id, Option<Id>, i32) + Copy + 'static,
+    on_cancel: impl Fn() + Copy + 'static,
+) {
+    let window = web_sys::window().unwrap();
+    let on_mouseup = wasm_bindgen::closure::Closure::<dyn FnMut(_)>::new(move |_ev: web_sys::MouseEvent| {
+        match (dnd.dragging_id_read.get_untracked(), dnd.drop_target_id_read.get_untracked()) {
+            (Some(dragged_id), Some(target_id)) => {
+                let pos = dnd.drop_position_read.get_untracked();
+                let nodes = get_nodes();
+                for (id, new_parent_id, target_position) in compute_multi_drop_actions(&drag_set(&dnd, dragged_id), target_id, pos, &nodes) {
+                    on_drop(id, new_parent_id, target_position);
+                }
+                end_drag(dnd.dragging_id_write, dnd.drop_target_id_write, dnd.drag_just_ended_write);
+            }
+            (Some(_), None) => cancel_drag(&dnd, on_cancel),
+            _ => {}
+        }
+    });
+    let _ = window.add_event_listener_with_callback("mouseup", on_mouseup.as_ref().unchecked_ref());
+    on_mouseup.forget();
+}
+
+pub fn bind_auto_scroll<Id: DndId>(dnd: DndSignals<Id>, container: NodeRef<leptos::html::Div>, config: AutoScrollConfig) {
+    let Some(window) = web_sys::window() else { return; };
+    let velocity = Rc::new(Cell::new(0.0f64));
+
+    let velocity_move = velocity.clone();
+    let on_mousemove = wasm_bindgen::closure::Closure::<dyn FnMut(_)>::new(move |ev: web_sys::MouseEvent| {
+        if dnd.dragging_id_read.get_untracked().is_none() {
+            velocity_move.set(0.0);
+            return;
+        }
+        if let Some(el) = container.get_untracked() {
+            let rect = el.get_bounding_client_rect();
+            velocity_move.set(auto_scroll_delta(ev.client_y() as f64, rect.top(), rect.bottom(), &config));
+        }
+    });
+    let _ = window.add_event_listener_with_callback("mousemove", on_mousemove.as_ref().unchecked_ref());
+    on_mousemove.forget();
+
+    let velocity_tick = velocity.clone();
+    let on_tick = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+        let v = velocity_tick.get();
+        if v == 0.0 {
+            return;
+        }
+        if dnd.dragging_id_read.get_untracked().is_none() {
+            velocity_tick.set(0.0);
+            return;
+        }
+        if let Some(el) = container.get_untracked() {
+            el.set_scroll_top(el.scroll_top() + v.round() as i32);
+        }
+    });
+    let _ = window.set_interval_with_callback_and_timeout_and_arguments_0(on_tick.as_ref().unchecked_ref(), config.interval_ms);
+    on_tick.forget();
+}
+
+// Expands a collapsed node after the drag has hovered it for `delay_ms`, and
+// re-collapses every node it opened once the drag ends outside of them.
+pub fn bind_hover_expand<Id: DndId>(
+    dnd: DndSignals<Id>,
+    get_nodes: impl Fn() -> Vec<Node<Id>> + Copy + 'static,
+    is_collapsed: impl Fn(Id) -> bool + Copy + 'static,
+    on_expand: impl Fn(Id) + Copy + 'static,
+    on_collapse: impl Fn(Id) + Copy + 'static,
+    delay_ms: i32,
+) {
+    let generation = Rc::new(Cell::new(0u32));
+    let last_target = Rc::new(Cell::new(None::<Id>));
+    let auto_expanded = Rc::new(std::cell::RefCell::new(Vec::<Id>::new()));
+
+    let generation_hover = generation.clone();
+    let last_target_hover = last_target.clone();
+    let auto_expanded_hover = auto_expanded.clone();
+    Effect::new(move |_| {
+        let target = dnd.drop_target_id_read.get();
+        let gen = generation_hover.get().wrapping_add(1);
+        generation_hover.set(gen);
+        let Some(target_id) = target else {
+            // Pointer left every node mid-drag: a release now cancels, so nothing was "landed" in
+            if dnd.dragging_id_read.get_untracked().is_some() {
+                last_target_hover.set(None);
+            }
+            return;
+        };
+        last_target_hover.set(Some(target_id));
+        if dnd.dragging_id_read.get_untracked().is_none() || !is_collapsed(target_id) {
+            return;
+        }
+        let Some(window) = web_sys::window() else { return; };
+        let generation_timer = generation_hover.clone();
+        let expanded = auto_expanded_hover.clone();
+        let cb = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+            let still_hovering = generation_timer.get() == gen
+                && dnd.dragging_id_read.get_untracked().is_some()
+                && dnd.drop_target_id_read.get_untracked() == Some(target_id);
+            if still_hovering && is_collapsed(target_id) {
+                on_expand(target_id);
+                expanded.borrow_mut().push(target_id);
+            }
+        });
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(cb.as_ref().unchecked_ref(), delay_ms);
+        cb.forget();
+    });
+
+    Effect::new(move |was_dragging: Option<bool>| {
+        let dragging = dnd.dragging_id_read.get().is_some();
+        if was_dragging == Some(true) && !dragging {
+            let nodes = get_nodes();
+            let landed = last_target.get();
+            for id in auto_expanded.borrow_mut().drain(..) {
+                let dropped_inside = landed.map(|t| is_descendant(&nodes, id, t)).unwrap_or(false);
+                if !dropped_inside {
+                    on_collapse(id);
+                }
+            }
+            last_target.set(None);
+        }
+        dragging
+    });
+}
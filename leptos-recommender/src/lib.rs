@@ -11,58 +11,392 @@ extern "C" {
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
-pub struct RecommendItem { pub name: String, pub score: f32, pub source: String }
+pub struct RecommendItem {
+    pub name: String,
+    pub score: f32,
+    pub source: String,
+    #[serde(default)]
+    pub diff: Diff,
+}
+
+// How a suggestion relates to the tags the file already has
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Diff {
+    // Not on the file yet
+    #[default]
+    New,
+    // Already on the file and the model agrees
+    Has,
+    // Already on the file but the model rates it below UNLIKELY_SCORE
+    Unlikely,
+}
+
+// Raw score under which a current tag is flagged as unlikely
+pub const UNLIKELY_SCORE: f32 = 0.2;
+
+// How much one source counts: suggestions scoring below `threshold` are dropped,
+// the rest have their score multiplied by `weight` before sources are merged
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SourceSettings { pub enabled: bool, pub weight: f32, pub threshold: f32 }
+
+impl Default for SourceSettings {
+    fn default() -> Self { Self { enabled: true, weight: 1.0, threshold: 0.0 } }
+}
+
+// Per-source settings, stored as JSON under the "recommend_sources" setting
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecommendSources {
+    // Text LLM on the file name
+    pub llm: SourceSettings,
+    // Vision LLM on images
+    pub vision: SourceSettings,
+    // Text LLM on the start of text and code files
+    pub content: SourceSettings,
+    // File-name matching, used when the vision model returns nothing or is off
+    pub rule: SourceSettings,
+    // Tagger plugins
+    pub plugins: SourceSettings,
+    // Hide suggestions the file already has instead of dimming them
+    pub hide_existing: bool,
+    // Ask the models to rate the file's current tags and flag the unlikely ones
+    pub flag_unlikely: bool,
+    // Vision requests in flight at once during batch runs
+    pub image_concurrency: usize,
+    pub types: FileTypes,
+    // Work out suggestions for new untagged files while the app is idle
+    pub background_indexing: bool,
+}
+
+// Which kinds of files get recommendations; the others are skipped
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileTypes { pub images: bool, pub text: bool, pub other: bool }
+
+impl Default for FileTypes {
+    fn default() -> Self { Self { images: true, text: true, other: true } }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind { Image, Text, Other }
+
+// The model a file is sent to; Rule is the local file-name matching images
+// get while the vision model is off
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route { Vision, Content, Title, Rule }
+
+impl Default for RecommendSources {
+    fn default() -> Self {
+        Self {
+            llm: SourceSettings { threshold: 0.6, ..Default::default() },
+            vision: SourceSettings { threshold: 0.6, ..Default::default() },
+            content: SourceSettings { threshold: 0.6, ..Default::default() },
+            rule: SourceSettings { weight: 0.5, threshold: 0.3, ..Default::default() },
+            plugins: SourceSettings::default(),
+            hide_existing: false,
+            flag_unlikely: true,
+            image_concurrency: 3,
+            types: FileTypes::default(),
+            background_indexing: false,
+        }
+    }
+}
+
+impl RecommendSources {
+    pub fn for_source(&self, source: &str) -> &SourceSettings {
+        match source {
+            "llm-vision" => &self.vision,
+            "llm-content" => &self.content,
+            "rule" => &self.rule,
+            s if s.starts_with("plugin:") => &self.plugins,
+            _ => &self.llm,
+        }
+    }
+
+    pub fn for_source_mut(&mut self, source: &str) -> &mut SourceSettings {
+        match source {
+            "llm-vision" => &mut self.vision,
+            "llm-content" => &mut self.content,
+            "rule" => &mut self.rule,
+            s if s.starts_with("plugin:") => &mut self.plugins,
+            _ => &mut self.llm,
+        }
+    }
+
+    // Whether batch runs include files of this path's kind at all
+    pub fn includes(&self, path: &str) -> bool {
+        match file_kind(path) {
+            FileKind::Image => self.types.images,
+            FileKind::Text => self.types.text,
+            FileKind::Other => self.types.other,
+        }
+    }
+
+    // Images go to the vision model, text and code to the content model and
+    // everything else by title. None when the file's kind or model is off.
+    pub fn route(&self, path: &str) -> Option<Route> {
+        if !self.includes(path) {
+            return None;
+        }
+        match file_kind(path) {
+            FileKind::Image if self.vision.enabled => Some(Route::Vision),
+            FileKind::Image => self.rule.enabled.then_some(Route::Rule),
+            FileKind::Text if self.content.enabled => Some(Route::Content),
+            FileKind::Text | FileKind::Other => self.llm.enabled.then_some(Route::Title),
+        }
+    }
+}
 
+pub const IMAGE_EXTS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+
+// Sent to the content model with the start of the file
+pub const TEXT_EXTS: [&str; 36] = [
+    "txt", "md", "markdown", "rst", "org", "log", "csv", "tsv", "json", "yaml", "yml", "toml", "ini", "xml",
+    "html", "htm", "css", "rs", "py", "js", "ts", "tsx", "jsx", "go", "java", "kt", "c", "h", "cpp", "hpp",
+    "cs", "rb", "php", "sh", "sql", "tex",
+];
+
+pub fn file_kind(path: &str) -> FileKind {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if IMAGE_EXTS.contains(&ext.as_str()) {
+        FileKind::Image
+    } else if TEXT_EXTS.contains(&ext.as_str()) {
+        FileKind::Text
+    } else {
+        FileKind::Other
+    }
+}
+
+async fn tauri_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue> {
+    let win = web_sys::window().unwrap();
+    let tauri = Reflect::get(&win, &JsValue::from_str("__TAURI__")).unwrap();
+    let core = Reflect::get(&tauri, &JsValue::from_str("core")).unwrap();
+    let invoke_fn = Reflect::get(&core, &JsValue::from_str("invoke")).unwrap().dyn_into::<Function>().unwrap();
+    let promise_val = invoke_fn.call2(&core, &JsValue::from_str(cmd), &args).unwrap();
+    let promise = promise_val.dyn_into::<Promise>().unwrap();
+    wasm_bindgen_futures::JsFuture::from(promise).await
+}
+
+// Suggestions from every enabled source for the file, thresholded and weighted
+// per source. A tag suggested by several sources keeps its best weighted score;
+// the top `top_k` new ones are returned, followed by the file's `existing` tags
+// the sources rated (marked Has or Unlikely).
 pub async fn generate_for_file(
     file_path: String,
     labels: Vec<String>,
+    existing: Vec<String>,
     top_k: usize,
-    threshold: f32,
+    sources: &RecommendSources,
     base_url: Option<String>,
     model: Option<String>,
 ) -> Vec<RecommendItem> {
-    let ext = std::path::Path::new(&file_path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.to_lowercase())
-        .unwrap_or_default();
-    console::log_1(&format!("[RECO] start file='{}' ext='{}' labels={}, top_k={} threshold={}", file_path, ext, labels.len(), top_k, threshold).into());
-    async fn tauri_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue> {
-        let win = web_sys::window().unwrap();
-        let tauri = Reflect::get(&win, &JsValue::from_str("__TAURI__")).unwrap();
-        let core = Reflect::get(&tauri, &JsValue::from_str("core")).unwrap();
-        let invoke_fn = Reflect::get(&core, &JsValue::from_str("invoke")).unwrap().dyn_into::<Function>().unwrap();
-        let promise_val = invoke_fn.call2(&core, &JsValue::from_str(cmd), &args).unwrap();
-        let promise = promise_val.dyn_into::<Promise>().unwrap();
-        wasm_bindgen_futures::JsFuture::from(promise).await
+    if !sources.includes(&file_path) {
+        return Vec::new();
+    }
+    let rated = if sources.flag_unlikely { existing.clone() } else { Vec::new() };
+    let mut list = Vec::new();
+    if let Some(route) = sources.route(&file_path) {
+        // Thresholds are applied per source below
+        let request = LlmRequest { labels: labels.clone(), existing: rated, top_k, threshold: 0.0, base_url, model, ..Default::default() };
+        list.extend(llm_items(file_path.clone(), route, request).await);
     }
-    if ["jpg", "jpeg", "png", "webp"].contains(&ext.as_str()) {
-        #[derive(serde::Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct VisionArgs { image_path: String, labels: Vec<String>, top_k: usize, threshold: f32, base_url: Option<String>, model: Option<String> }
-        let args = VisionArgs { image_path: file_path.clone(), labels, top_k, threshold, base_url, model };
-        let val = match tauri_invoke("generate_image_tags_llm", serde_wasm_bindgen::to_value(&args).unwrap()).await {
-            Ok(v) => v,
-            Err(e) => { console::error_1(&format!("[RECO] vision invoke error: {:?}", e).into()); return vec![] }
+    finish(file_path, labels, list, &existing, top_k, sources).await
+}
+
+// Like generate_for_file for many images at once: the vision requests run in
+// parallel in the backend (up to `image_concurrency`), reporting each image as
+// "image-tag-status". Images skipped by a cancel are left out of the result.
+pub async fn generate_for_images(
+    files: Vec<(String, Vec<String>)>,
+    labels: Vec<String>,
+    top_k: usize,
+    sources: &RecommendSources,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Vec<(String, Vec<RecommendItem>)> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ImageRequest { image_path: String, existing: Vec<String> }
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct BatchArgs { requests: Vec<ImageRequest>, labels: Vec<String>, top_k: usize, threshold: f32, base_url: Option<String>, model: Option<String>, concurrency: usize }
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ImageResult { image_path: String, items: Vec<RecommendItem>, error: Option<String> }
+
+    let fetched: Vec<(String, Vec<RecommendItem>)> = if sources.vision.enabled {
+        let requests = files
+            .iter()
+            .map(|(path, existing)| ImageRequest {
+                image_path: path.clone(),
+                existing: if sources.flag_unlikely { existing.clone() } else { Vec::new() },
+            })
+            .collect();
+        let args = BatchArgs { requests, labels: labels.clone(), top_k, threshold: 0.0, base_url, model, concurrency: sources.image_concurrency };
+        let results = match tauri_invoke("generate_image_tags_llm_batch", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+            Ok(v) => serde_wasm_bindgen::from_value::<Vec<ImageResult>>(v).unwrap_or_default(),
+            Err(e) => { console::error_1(&format!("[RECO] vision batch invoke error: {:?}", e).into()); vec![] }
         };
-        match serde_wasm_bindgen::from_value::<Vec<RecommendItem>>(val) {
-            Ok(list) => { console::log_1(&format!("[RECO] vision items=[{}]", list.iter().map(|ri| format!("{}:{:.3}:{}", ri.name, ri.score, ri.source)).collect::<Vec<_>>().join(", ")).into()); list }
-            Err(e) => { console::error_1(&format!("[RECO] vision parse error: {}", e).into()); vec![] }
-        }
+        results
+            .into_iter()
+            .map(|r| {
+                if let Some(e) = r.error { console::error_1(&format!("[RECO] vision error for '{}': {}", r.image_path, e).into()); }
+                (r.image_path, r.items)
+            })
+            .collect()
     } else {
-        #[derive(serde::Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct LlmArgs { title: String, labels: Vec<String>, top_k: usize, threshold: f32, base_url: Option<String>, model: Option<String> }
-        let title = std::path::Path::new(&file_path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
-        if title.is_empty() { return vec![]; }
-        let args = LlmArgs { title, labels, top_k, threshold, base_url, model };
-        let val = match tauri_invoke("generate_tags_llm", serde_wasm_bindgen::to_value(&args).unwrap()).await {
-            Ok(v) => v,
-            Err(e) => { console::error_1(&format!("[RECO] llm invoke error: {:?}", e).into()); return vec![] }
-        };
-        match serde_wasm_bindgen::from_value::<Vec<RecommendItem>>(val) {
-            Ok(list) => { console::log_1(&format!("[RECO] llm items=[{}]", list.iter().map(|ri| format!("{}:{:.3}:{}", ri.name, ri.score, ri.source)).collect::<Vec<_>>().join(", ")).into()); list }
-            Err(e) => { console::error_1(&format!("[RECO] llm parse error: {}", e).into()); vec![] }
+        let mut fetched = Vec::new();
+        for (path, _) in &files {
+            let list = if sources.rule.enabled { rule_items(path.clone(), labels.clone(), top_k).await } else { Vec::new() };
+            fetched.push((path.clone(), list));
+        }
+        fetched
+    };
+    let mut out = Vec::new();
+    for (path, list) in fetched {
+        let existing = files.iter().find(|(p, _)| *p == path).map(|(_, e)| e.clone()).unwrap_or_default();
+        let items = finish(path.clone(), labels.clone(), list, &existing, top_k, sources).await;
+        out.push((path, items));
+    }
+    out
+}
+
+// Adds plugin suggestions to the model ones, then thresholds, weights and merges them
+async fn finish(
+    file_path: String,
+    labels: Vec<String>,
+    mut list: Vec<RecommendItem>,
+    existing: &[String],
+    top_k: usize,
+    sources: &RecommendSources,
+) -> Vec<RecommendItem> {
+    if sources.plugins.enabled {
+        list.extend(plugin_items(file_path, labels).await);
+    }
+    let mut fresh = Vec::new();
+    let mut current = Vec::new();
+    let mut unlikely = Vec::new();
+    for mut item in list {
+        let settings = sources.for_source(&item.source);
+        if !settings.enabled {
+            continue;
+        }
+        let has = existing.contains(&item.name);
+        if item.score < settings.threshold {
+            if has && sources.flag_unlikely && item.score < UNLIKELY_SCORE {
+                item.diff = Diff::Unlikely;
+                unlikely.push(item);
+            }
+            continue;
+        }
+        item.score *= settings.weight;
+        if has {
+            item.diff = Diff::Has;
+            current.push(item);
+        } else {
+            fresh.push(item);
+        }
+    }
+    let mut out = merge(fresh, top_k);
+    let current = merge(current, usize::MAX);
+    // A tag stays unlikely only if no source backed it
+    let unlikely: Vec<RecommendItem> = merge(unlikely, usize::MAX)
+        .into_iter()
+        .filter(|u| !current.iter().any(|c| c.name == u.name))
+        .collect();
+    out.extend(current);
+    out.extend(unlikely);
+    out
+}
+
+fn merge(items: Vec<RecommendItem>, top_k: usize) -> Vec<RecommendItem> {
+    let mut best: Vec<RecommendItem> = Vec::new();
+    for item in items {
+        match best.iter_mut().find(|b| b.name == item.name) {
+            Some(b) if b.score < item.score => *b = item,
+            Some(_) => {}
+            None => best.push(item),
+        }
+    }
+    best.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    best.truncate(top_k);
+    best
+}
+
+async fn plugin_items(file_path: String, labels: Vec<String>) -> Vec<RecommendItem> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PluginArgs { file_path: String, labels: Vec<String> }
+    let args = PluginArgs { file_path, labels };
+    match tauri_invoke("run_tagger_plugins", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+        Ok(v) => serde_wasm_bindgen::from_value::<Vec<RecommendItem>>(v).unwrap_or_default(),
+        Err(e) => { console::error_1(&format!("[RECO] plugin invoke error: {:?}", e).into()); vec![] }
+    }
+}
+
+// Arguments of the vision, content and title commands; each takes one of the path fields
+#[derive(serde::Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct LlmRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    labels: Vec<String>,
+    existing: Vec<String>,
+    top_k: usize,
+    threshold: f32,
+    base_url: Option<String>,
+    model: Option<String>,
+}
+
+// Computed in the backend from the file name alone, without any model
+async fn rule_items(file_path: String, labels: Vec<String>, top_k: usize) -> Vec<RecommendItem> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RuleArgs { file_path: String, labels: Vec<String>, top_k: usize }
+    let args = RuleArgs { file_path, labels, top_k };
+    match tauri_invoke("rule_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+        Ok(v) => serde_wasm_bindgen::from_value::<Vec<RecommendItem>>(v).unwrap_or_default(),
+        Err(e) => { console::error_1(&format!("[RECO] rule invoke error: {:?}", e).into()); vec![] }
+    }
+}
+
+async fn llm_items(file_path: String, route: Route, mut request: LlmRequest) -> Vec<RecommendItem> {
+    console::log_1(&format!("[RECO] start file='{}' route={:?} labels={}, top_k={} threshold={}", file_path, route, request.labels.len(), request.top_k, request.threshold).into());
+    let command = match route {
+        Route::Vision => {
+            request.image_path = Some(file_path);
+            "generate_image_tags_llm"
+        }
+        Route::Content => {
+            request.file_path = Some(file_path);
+            "generate_content_tags_llm"
+        }
+        Route::Title => {
+            let title = std::path::Path::new(&file_path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+            if title.is_empty() { return vec![]; }
+            request.title = Some(title);
+            // Keeps the file itself out of the few-shot examples
+            request.file_path = Some(file_path);
+            "generate_tags_llm"
         }
+        Route::Rule => return rule_items(file_path, request.labels, request.top_k).await,
+    };
+    let val = match tauri_invoke(command, serde_wasm_bindgen::to_value(&request).unwrap()).await {
+        Ok(v) => v,
+        Err(e) => { console::error_1(&format!("[RECO] {} invoke error: {:?}", command, e).into()); return vec![] }
+    };
+    match serde_wasm_bindgen::from_value::<Vec<RecommendItem>>(val) {
+        Ok(list) => { console::log_1(&format!("[RECO] {} items=[{}]", command, list.iter().map(|ri| format!("{}:{:.3}:{}", ri.name, ri.score, ri.source)).collect::<Vec<_>>().join(", ")).into()); list }
+        Err(e) => { console::error_1(&format!("[RECO] {} parse error: {}", command, e).into()); vec![] }
     }
 }
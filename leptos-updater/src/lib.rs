@@ -19,6 +19,12 @@ pub struct UpdaterArgs {
     pub set_update_current: WriteSignal<String>,
     pub update_latest: ReadSignal<String>,
     pub set_update_latest: WriteSignal<String>,
+    pub update_notes: ReadSignal<String>,
+    pub set_update_notes: WriteSignal<String>,
+    pub update_channel: ReadSignal<String>,
+    pub set_update_channel: WriteSignal<String>,
+    pub update_suppressed: ReadSignal<bool>,
+    pub set_update_suppressed: WriteSignal<bool>,
     pub update_has: ReadSignal<bool>,
     pub set_update_has: WriteSignal<bool>,
     pub update_error: ReadSignal<Option<String>>, 
@@ -34,9 +40,60 @@ pub struct UpdaterArgs {
 }
 
 #[derive(serde::Deserialize, Clone)]
-struct UpdateInfo { current: String, latest: Option<String>, has_update: bool }
+struct UpdateInfo { current: String, latest: Option<String>, has_update: bool, #[serde(default)] notes: Option<String>, #[serde(default)] suppressed: bool }
+
+// Renders release notes markdown to HTML. Raw HTML in the manifest is shown as
+// text rather than injected into the page.
+pub fn render_release_notes(markdown: &str) -> String {
+    use pulldown_cmark::{html, Event, Options, Parser};
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS)
+        .map(|event| match event {
+            Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+            other => other,
+        });
+    let mut out = String::new();
+    html::push_html(&mut out, parser);
+    out
+}
+
+#[derive(serde::Serialize)]
+struct SetChannelArgs { channel: String }
+
+#[derive(serde::Serialize)]
+struct SkipVersionArgs { version: String }
+
+#[derive(serde::Serialize)]
+struct SnoozeArgs { version: String, hours: u32 }
+
+// Persists the channel, then re-checks against that channel's manifest.
+async fn switch_channel(args: UpdaterArgs, channel: String) {
+    args.set_update_channel.set(channel.clone());
+    let _ = invoke("set_update_channel", serde_wasm_bindgen::to_value(&SetChannelArgs { channel }).unwrap()).await;
+    let val = invoke("updater_check", JsValue::NULL).await;
+    match serde_wasm_bindgen::from_value::<UpdateInfo>(val) {
+        Ok(info) => {
+            args.set_update_error.set(None);
+            args.set_update_retry_in.set(None);
+            args.set_update_current.set(info.current);
+            args.set_update_latest.set(info.latest.unwrap_or_default());
+            args.set_update_has.set(info.has_update);
+            args.set_update_notes.set(info.notes.unwrap_or_default());
+            args.set_update_suppressed.set(info.suppressed);
+        }
+        Err(_) => {
+            args.set_update_error.set(Some(format!("检查更新失败，将在{}分钟后重试", 10)));
+            args.set_update_retry_in.set(Some(600));
+        }
+    }
+}
 
 pub fn init_update_system(args: UpdaterArgs) {
+    let set_channel = args.set_update_channel;
+    spawn_local(async move {
+        if let Some(channel) = invoke("get_update_channel", JsValue::NULL).await.as_string() {
+            set_channel.set(channel);
+        }
+    });
     let a0 = args.clone();
     let a1 = args.clone();
     let a2 = args.clone();
@@ -65,6 +122,8 @@ pub fn init_update_system(args: UpdaterArgs) {
                     args.set_update_current.set(info.current);
                     args.set_update_latest.set(info.latest.unwrap_or_default());
                     args.set_update_has.set(info.has_update);
+                    args.set_update_notes.set(info.notes.unwrap_or_default());
+                    args.set_update_suppressed.set(info.suppressed);
                 },
                 Err(_) => {
                     done.set(true);
@@ -104,6 +163,8 @@ pub fn init_update_system(args: UpdaterArgs) {
                             args2.set_update_current.set(info.current);
                             args2.set_update_latest.set(info.latest.unwrap_or_default());
                             args2.set_update_has.set(info.has_update);
+                            args2.set_update_notes.set(info.notes.unwrap_or_default());
+                            args2.set_update_suppressed.set(info.suppressed);
                         },
                         Err(_) => {
                             done.set(true);
@@ -161,7 +222,7 @@ pub fn init_update_system(args: UpdaterArgs) {
 pub fn UpdateHeaderButton(args: UpdaterArgs) -> impl IntoView {
     view! {
         <button on:click=move |_| args.set_show_update_modal.set(true) class="header-btn" title="Check Updates">
-            {move || if args.update_has.get() {
+            {move || if args.update_has.get() && !args.update_suppressed.get() {
                 view! { <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;"><path d="M12 2L2 22h20L12 2zm1 15h-2v-2h2v2zm0-4h-2V9h2v4z"/></svg> }
             } else {
                 view! { <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;"><path d="M12 2a10 10 0 1 0 10 10A10.011 10.011 0 0 0 12 2zm1 14h-2v-4h2zm0-6h-2V8h2z"/></svg> }
@@ -183,7 +244,33 @@ pub fn UpdateModal(args: UpdaterArgs) -> impl IntoView {
                     })}
                     <p>{move || format!("Current: {}", args.update_current.get())}</p>
                     <p>{move || format!("Latest: {}", args.update_latest.get())}</p>
+                    <p>
+                        "Channel: "
+                        <select
+                            prop:value=move || args.update_channel.get()
+                            on:change={
+                                let args = args.clone();
+                                move |ev| {
+                                    let channel = event_target_value(&ev);
+                                    spawn_local(switch_channel(args.clone(), channel));
+                                }
+                            }
+                        >
+                            <option value="stable">"Stable"</option>
+                            <option value="beta">"Beta (pre-release)"</option>
+                        </select>
+                    </p>
                     <Show when=move || args.update_has.get() fallback=move || view! { <p>"You are up to date."</p> }>
+                        {move || args.update_suppressed.get().then(|| view! {
+                            <p style="color:#888;">"You chose to skip or postpone this version."</p>
+                        })}
+                        {move || {
+                            let notes = args.update_notes.get();
+                            (!notes.trim().is_empty()).then(|| view! {
+                                <h4>"What's changed"</h4>
+                                <div class="release-notes" inner_html=render_release_notes(&notes)></div>
+                            })
+                        }}
                         <div style="display:flex; gap:8px;">
                             <button on:click=move |_| {
                                 args.set_update_downloading.set(true);
@@ -196,6 +283,26 @@ pub fn UpdateModal(args: UpdaterArgs) -> impl IntoView {
                             }>
                                 "Install"
                             </button>
+                            <button on:click=move |_| {
+                                let version = args.update_latest.get_untracked();
+                                args.set_update_suppressed.set(true);
+                                args.set_show_update_modal.set(false);
+                                spawn_local(async move {
+                                    let _ = invoke("snooze_update", serde_wasm_bindgen::to_value(&SnoozeArgs { version, hours: 24 }).unwrap()).await;
+                                });
+                            }>
+                                "Remind me tomorrow"
+                            </button>
+                            <button on:click=move |_| {
+                                let version = args.update_latest.get_untracked();
+                                args.set_update_suppressed.set(true);
+                                args.set_show_update_modal.set(false);
+                                spawn_local(async move {
+                                    let _ = invoke("skip_update_version", serde_wasm_bindgen::to_value(&SkipVersionArgs { version }).unwrap()).await;
+                                });
+                            }>
+                                "Skip this version"
+                            </button>
                         </div>
                     </Show>
                     <div style="margin-top:8px;">
@@ -1,3 +1,5 @@
+pub mod matching;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct RecommendItem {
     pub name: String,
@@ -5,13 +7,90 @@ pub struct RecommendItem {
     pub source: String,
 }
 
+// Routes requests through an HTTP(S) or SOCKS5 proxy; credentials ride in the URL.
+fn http_client(proxy: Option<&str>) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = proxy.filter(|u| !u.trim().is_empty()) {
+        builder = builder.proxy(reqwest::Proxy::all(url).map_err(|e| format!("invalid proxy: {}", e))?);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+// The endpoint a request goes to when the caller doesn't name one
+pub fn resolve_base_url(base_url: Option<String>) -> String {
+    base_url.unwrap_or_else(|| {
+        std::env::var("LLM_BASE_URL")
+            .unwrap_or_else(|_| "https://api.siliconflow.cn/v1".to_string())
+    })
+}
+
+// Bytes of a text file sent to the content model
+const CONTENT_EXCERPT_BYTES: usize = 6000;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_tags_llm(
     title: String,
     labels: Vec<String>,
+    // Tags the file already has; the model rates each of them as well
+    existing: Vec<String>,
+    // Tagged files and per-tag examples from the user's library
+    examples: PromptExamples,
     top_k: usize,
     threshold: f32,
     base_url: Option<String>,
     model: Option<String>,
+    proxy: Option<String>,
+) -> Result<Vec<RecommendItem>, String> {
+    text_tags(title, None, labels, existing, examples, top_k, threshold, base_url, model, proxy).await
+}
+
+// Like generate_tags_llm, with the start of the file's text added to the prompt.
+// Files that don't read as text are tagged by title alone.
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_content_tags_llm(
+    file_path: String,
+    labels: Vec<String>,
+    existing: Vec<String>,
+    examples: PromptExamples,
+    top_k: usize,
+    threshold: f32,
+    base_url: Option<String>,
+    model: Option<String>,
+    proxy: Option<String>,
+) -> Result<Vec<RecommendItem>, String> {
+    let path = std::path::Path::new(&file_path);
+    let title = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let excerpt = read_excerpt(path);
+    text_tags(title, excerpt, labels, existing, examples, top_k, threshold, base_url, model, proxy).await
+}
+
+fn read_excerpt(path: &std::path::Path) -> Option<String> {
+    use std::io::Read;
+    let mut buf = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(CONTENT_EXCERPT_BYTES as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    if buf.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&buf).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn text_tags(
+    title: String,
+    excerpt: Option<String>,
+    labels: Vec<String>,
+    existing: Vec<String>,
+    examples: PromptExamples,
+    top_k: usize,
+    threshold: f32,
+    base_url: Option<String>,
+    model: Option<String>,
+    proxy: Option<String>,
 ) -> Result<Vec<RecommendItem>, String> {
     use async_openai::config::OpenAIConfig;
     use async_openai::types::{
@@ -22,10 +101,7 @@ pub async fn generate_tags_llm(
 
     let api_key = std::env::var("SILICONFLOW_API_KEY")
         .map_err(|_| "SILICONFLOW_API_KEY not set".to_string())?;
-    let base = base_url.unwrap_or_else(|| {
-        std::env::var("LLM_BASE_URL")
-            .unwrap_or_else(|_| "https://api.siliconflow.cn/v1".to_string())
-    });
+    let base = resolve_base_url(base_url);
     let model_name = model.unwrap_or_else(|| {
         // std::env::var("LLM_MODEL").unwrap_or_else(|_| "deepseek-ai/DeepSeek-V3.2-Exp".to_string())
         std::env::var("LLM_MODEL").unwrap_or_else(|_| "Qwen/Qwen3-VL-32B-Instruct".to_string())
@@ -34,34 +110,10 @@ pub async fn generate_tags_llm(
     let cfg = OpenAIConfig::new()
         .with_api_base(&base)
         .with_api_key(api_key);
-    let client = Client::with_config(cfg);
+    let client = Client::with_config(cfg).with_http_client(http_client(proxy.as_deref())?);
 
-    let lname = title.to_lowercase();
-    let tokens: Vec<&str> = lname
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|s| !s.is_empty())
-        .collect();
-    let mut scored: Vec<(String, i32)> = Vec::new();
-    for l in &labels {
-        let ln = l.to_lowercase();
-        let mut s = 0;
-        if !ln.is_empty() {
-            if lname.contains(&ln) {
-                s += 10;
-            }
-            if tokens.iter().any(|w| *w == ln) {
-                s += 8;
-            }
-            if lname.starts_with(&ln) || lname.ends_with(&ln) {
-                s += 4;
-            }
-        }
-        if s > 0 {
-            scored.push((l.clone(), s));
-        } else {
-            scored.push((l.clone(), 0));
-        }
-    }
+    let source = if excerpt.is_some() { "llm-content" } else { "llm" };
+    let mut scored: Vec<(String, i32)> = labels.iter().map(|l| (l.clone(), matching::score(&title, l))).collect();
     scored.sort_by(|a, b| b.1.cmp(&a.1));
     let max_send = core::cmp::min(scored.len(), 20);
     let preview = scored
@@ -71,7 +123,12 @@ pub async fn generate_tags_llm(
         .collect::<Vec<_>>()
         .join(", ");
     eprintln!("[LLM-FLOW] text prelabel weights [{}]", preview);
-    let labels_to_send: Vec<String> = scored.into_iter().take(max_send).map(|(l, _)| l).collect();
+    let mut labels_to_send: Vec<String> = scored.into_iter().take(max_send).map(|(l, _)| l).collect();
+    for e in &existing {
+        if labels.contains(e) && !labels_to_send.contains(e) {
+            labels_to_send.push(e.clone());
+        }
+    }
 
     let sys = ChatCompletionRequestMessage::System(
         ChatCompletionRequestSystemMessageArgs::default()
@@ -79,11 +136,18 @@ pub async fn generate_tags_llm(
             .build()
             .map_err(|e| e.to_string())?,
     );
+    let content_part = excerpt
+        .as_deref()
+        .map(|text| format!("content（文件开头）:\n{}\n", text))
+        .unwrap_or_default();
     let user_content = format!(
-        "title: {}\nlabels: {}\n要求：只从 labels 中选择，最多 {} 个。",
+        "{}title: {}\n{}labels: {}\n要求：只从 labels 中选择，最多 {} 个。{}",
+        few_shot(&examples, &labels_to_send),
         title,
+        content_part,
         serde_json::to_string(&labels_to_send).unwrap_or_default(),
-        top_k
+        top_k,
+        rate_existing(&existing)
     );
     let user = ChatCompletionRequestMessage::User(
         ChatCompletionRequestUserMessageArgs::default()
@@ -161,7 +225,7 @@ pub async fn generate_tags_llm(
                     out.push(RecommendItem {
                         name,
                         score: confidence,
-                        source: "llm".to_string(),
+                        source: source.to_string(),
                     });
                 }
                 eprintln!(
@@ -181,12 +245,7 @@ pub async fn generate_tags_llm(
         .collect::<Vec<_>>()
         .join(", ");
     eprintln!("[LLM-FLOW] text allowed items [{}]", before);
-    out.sort_by(|a, b| b.score.total_cmp(&a.score));
-    let final_out: Vec<RecommendItem> = out
-        .into_iter()
-        .filter(|x| x.score >= threshold)
-        .take(top_k)
-        .collect();
+    let final_out = select(out, top_k, threshold, &existing);
     let final_str = final_out
         .iter()
         .map(|ri| format!("{}:{:.3}", ri.name, ri.score))
@@ -199,13 +258,19 @@ pub async fn generate_tags_llm(
     Ok(final_out)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_image_tags_llm(
     image_path: String,
     labels: Vec<String>,
+    // Tags the file already has; the model rates each of them as well
+    existing: Vec<String>,
     top_k: usize,
     threshold: f32,
     base_url: Option<String>,
     model: Option<String>,
+    proxy: Option<String>,
+    // Re-encode the image so EXIF/GPS and other metadata stay on this machine
+    strip_metadata: bool,
 ) -> Result<Vec<RecommendItem>, String> {
     use async_openai::config::OpenAIConfig;
     use async_openai::types::{
@@ -218,10 +283,7 @@ pub async fn generate_image_tags_llm(
 
     let api_key = std::env::var("SILICONFLOW_API_KEY")
         .map_err(|_| "SILICONFLOW_API_KEY not set".to_string())?;
-    let base = base_url.unwrap_or_else(|| {
-        std::env::var("LLM_BASE_URL")
-            .unwrap_or_else(|_| "https://api.siliconflow.cn/v1".to_string())
-    });
+    let base = resolve_base_url(base_url);
     let model_name = model.unwrap_or_else(|| {
         // std::env::var("LLM_MODEL").unwrap_or_else(|_| "deepseek-ai/deepseek-vl2".to_string())
         std::env::var("LLM_MODEL").unwrap_or_else(|_| "Qwen/Qwen3-VL-32B-Instruct".to_string())
@@ -242,6 +304,11 @@ pub async fn generate_image_tags_llm(
             _ => "image/jpeg",
         }
     };
+    let (bytes, mime) = if strip_metadata {
+        without_metadata(&bytes, mime).map_err(|e| format!("could not strip image metadata: {}", e))?
+    } else {
+        (bytes, mime)
+    };
     let data_url = {
         use base64::engine::general_purpose::STANDARD;
         use base64::Engine;
@@ -251,7 +318,7 @@ pub async fn generate_image_tags_llm(
     let cfg = OpenAIConfig::new()
         .with_api_base(&base)
         .with_api_key(api_key);
-    let client = Client::with_config(cfg);
+    let client = Client::with_config(cfg).with_http_client(http_client(proxy.as_deref())?);
 
     let sys = ChatCompletionRequestMessage::System(
         ChatCompletionRequestSystemMessageArgs::default()
@@ -262,9 +329,10 @@ pub async fn generate_image_tags_llm(
     let text_part = ChatCompletionRequestMessageContentPart::Text(
         ChatCompletionRequestMessageContentPartTextArgs::default()
             .text(format!(
-                "labels: {}\n最多选择 {} 个，只从 labels 中选择。",
+                "labels: {}\n最多选择 {} 个，只从 labels 中选择。{}",
                 serde_json::to_string(&labels).unwrap_or_default(),
-                top_k
+                top_k,
+                rate_existing(&existing)
             ))
             .build()
             .unwrap(),
@@ -360,47 +428,523 @@ pub async fn generate_image_tags_llm(
         }
     }
     if out.is_empty() {
-        let stem = std::path::Path::new(&image_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-        let tokens: Vec<&str> = stem
-            .split(|c: char| !c.is_alphanumeric())
-            .filter(|s| !s.is_empty())
-            .collect();
-        let mut scored: Vec<(String, i32)> = Vec::new();
-        for l in &labels {
-            let ln = l.to_lowercase();
-            let mut s = 0;
-            if !ln.is_empty() {
-                if stem.contains(&ln) {
-                    s += 10;
-                }
-                if tokens.iter().any(|w| *w == ln) {
-                    s += 8;
-                }
-                if stem.starts_with(&ln) || stem.ends_with(&ln) {
-                    s += 4;
-                }
+        out = rule_tags(&image_path, &labels, top_k);
+    }
+    Ok(select(out, top_k, threshold, &existing))
+}
+
+// Labels matching the file name, worked out locally. The fallback when the
+// vision model returns nothing, and all an image gets with the model turned off.
+pub fn rule_tags(path: &str, labels: &[String], top_k: usize) -> Vec<RecommendItem> {
+    let stem = std::path::Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let mut scored: Vec<(String, i32)> = labels
+        .iter()
+        .map(|l| (l.clone(), matching::score(stem, l)))
+        .filter(|(_, s)| *s > 0)
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored
+        .into_iter()
+        .take(top_k)
+        .map(|(name, s)| RecommendItem {
+            name,
+            // 22 is a full match on every rule
+            score: s as f32 / 22.0,
+            source: "rule".to_string(),
+        })
+        .collect()
+}
+
+// Few-shot material for the text prompts
+#[derive(Clone, Debug, Default)]
+pub struct PromptExamples {
+    // (file name, tags) pairs of recently tagged files
+    pub files: Vec<(String, Vec<String>)>,
+    // Example files the user attached to tags
+    pub tags: Vec<TagExamples>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TagExamples {
+    pub name: String,
+    // File names that are what the tag means, and ones that aren't
+    pub positive: Vec<String>,
+    pub negative: Vec<String>,
+}
+
+// Decodes and re-encodes an image, which leaves EXIF (GPS, camera, time), XMP
+// and text chunks behind. The EXIF rotation is applied to the pixels first so
+// the model still sees the picture upright. PNG and images with transparency
+// stay PNG; everything else becomes JPEG.
+fn without_metadata(bytes: &[u8], mime: &str) -> Result<(Vec<u8>, &'static str), String> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::{ImageDecoder, ImageFormat};
+
+    let mut decoder = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_decoder()
+        .map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut img = image::DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    img.apply_orientation(orientation);
+    let mut out = std::io::Cursor::new(Vec::new());
+    if mime == "image/png" || img.color().has_alpha() {
+        img.write_to(&mut out, ImageFormat::Png).map_err(|e| e.to_string())?;
+        Ok((out.into_inner(), "image/png"))
+    } else {
+        let rgb = image::DynamicImage::ImageRgb8(img.to_rgb8());
+        rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut out, 90)).map_err(|e| e.to_string())?;
+        Ok((out.into_inner(), "image/jpeg"))
+    }
+}
+
+// The user's own tagging, so picks follow their taxonomy rather than generic
+// categories. Tag examples are only shown for labels in the prompt.
+fn few_shot(examples: &PromptExamples, labels: &[String]) -> String {
+    let mut out = String::new();
+    if !examples.files.is_empty() {
+        let lines = examples
+            .files
+            .iter()
+            .map(|(name, tags)| format!("{} → {}", name, serde_json::to_string(tags).unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        out.push_str(&format!("用户已标注的文件示例（文件名 → 标签），请保持一致的打标风格：\n{}\n\n", lines));
+    }
+    let lines = examples
+        .tags
+        .iter()
+        .filter(|t| labels.contains(&t.name))
+        .map(|t| {
+            let mut line = format!("{}：", t.name);
+            if !t.positive.is_empty() {
+                line.push_str(&format!("属于 {}", serde_json::to_string(&t.positive).unwrap_or_default()));
             }
-            if s > 0 {
-                scored.push((l.clone(), s));
+            if !t.negative.is_empty() {
+                if !t.positive.is_empty() {
+                    line.push('；');
+                }
+                line.push_str(&format!("不属于 {}", serde_json::to_string(&t.negative).unwrap_or_default()));
             }
-        }
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
-        for (name, _) in scored.into_iter().take(top_k) {
-            out.push(RecommendItem {
-                name,
-                score: 0.0,
-                source: "rule".to_string(),
-            });
-        }
+            line
+        })
+        .collect::<Vec<_>>();
+    if !lines.is_empty() {
+        out.push_str(&format!("用户为部分标签给出的示例文件，请据此判断：\n{}\n\n", lines.join("\n")));
+    }
+    out
+}
+
+// Extra prompt line asking for a confidence on every current tag, even a low one
+fn rate_existing(existing: &[String]) -> String {
+    if existing.is_empty() {
+        return String::new();
     }
+    format!(
+        "\n文件当前已有标签：{}。请对其中每一个都给出置信度，即使不相关也要给出（低）置信度。",
+        serde_json::to_string(existing).unwrap_or_default()
+    )
+}
+
+// The best `top_k` items at or above `threshold`, plus the items for tags the
+// file already has whatever their score, so the UI can flag doubtful ones
+fn select(mut out: Vec<RecommendItem>, top_k: usize, threshold: f32, existing: &[String]) -> Vec<RecommendItem> {
     out.sort_by(|a, b| b.score.total_cmp(&a.score));
-    Ok(out
+    let (current, fresh): (Vec<RecommendItem>, Vec<RecommendItem>) =
+        out.into_iter().partition(|x| existing.contains(&x.name));
+    fresh
         .into_iter()
         .filter(|x| x.score >= threshold)
         .take(top_k)
-        .collect())
+        .chain(current)
+        .collect()
+}
+
+// A tag as sent to the taxonomy review and query translation, with how many files carry it
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomyTag {
+    pub id: u32,
+    pub name: String,
+    pub parent_id: Option<u32>,
+    pub count: u32,
+}
+
+// One proposed edit to the tag tree. Applied by the UI through the regular tag
+// commands (merge_tags, update_tag, create_tag + move_tag).
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TaxonomyChange {
+    // Fold `source_id` into `target_id`
+    #[serde(rename_all = "camelCase")]
+    Merge { source_id: u32, target_id: u32, reason: String },
+    #[serde(rename_all = "camelCase")]
+    Rename { id: u32, name: String, reason: String },
+    // Put `ids` under a parent tag named `parent`, created at the top level if missing
+    #[serde(rename_all = "camelCase")]
+    Group { parent: String, ids: Vec<u32>, reason: String },
+}
+
+// Asks the model for merges, renames and new parent groupings for the tag list.
+// Proposals naming unknown ids or doing nothing are dropped.
+pub async fn suggest_taxonomy(
+    tags: Vec<TaxonomyTag>,
+    base_url: Option<String>,
+    model: Option<String>,
+    proxy: Option<String>,
+) -> Result<Vec<TaxonomyChange>, String> {
+    use async_openai::config::OpenAIConfig;
+    use async_openai::types::{
+        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
+        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
+    };
+    use async_openai::Client;
+
+    if tags.len() < 2 {
+        return Ok(Vec::new());
+    }
+    let api_key = std::env::var("SILICONFLOW_API_KEY")
+        .map_err(|_| "SILICONFLOW_API_KEY not set".to_string())?;
+    let base = resolve_base_url(base_url);
+    let model_name = model.unwrap_or_else(|| {
+        std::env::var("LLM_MODEL").unwrap_or_else(|_| "Qwen/Qwen3-VL-32B-Instruct".to_string())
+    });
+    let cfg = OpenAIConfig::new()
+        .with_api_base(&base)
+        .with_api_key(api_key);
+    let client = Client::with_config(cfg).with_http_client(http_client(proxy.as_deref())?);
+
+    let sys = ChatCompletionRequestMessage::System(
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content("你是一个标签体系整理助手。输入是用户的标签列表（id、名称、父标签 id、使用次数）。找出重复或近义需要合并的标签、命名不一致需要重命名的标签，以及可以归到一个新父标签下的一组标签。只提出有把握的修改，每条附简短理由。严格输出 JSON：{\"changes\":[{\"kind\":\"merge\",\"sourceId\":number,\"targetId\":number,\"reason\":string}|{\"kind\":\"rename\",\"id\":number,\"name\":string,\"reason\":string}|{\"kind\":\"group\",\"parent\":string,\"ids\":[number],\"reason\":string}]}。合并时保留使用次数多的标签作为 target。不要包含除 JSON 外的任何文本。")
+            .build()
+            .map_err(|e| e.to_string())?,
+    );
+    let user = ChatCompletionRequestMessage::User(
+        ChatCompletionRequestUserMessageArgs::default()
+            .content(format!("tags: {}", serde_json::to_string(&tags).unwrap_or_default()))
+            .build()
+            .map_err(|e| e.to_string())?,
+    );
+    let req = CreateChatCompletionRequestArgs::default()
+        .model(model_name.clone())
+        .temperature(0.0)
+        .messages(vec![sys, user])
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // Whole-library prompts take longer than a single file
+    let timeout_secs: u64 = std::env::var("LLM_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(45)
+        * 2;
+    eprintln!(
+        "[LLM-FLOW] taxonomy request model='{}' base='{}' tags={} timeout={}s",
+        model_name,
+        base,
+        tags.len(),
+        timeout_secs,
+    );
+    let resp = match tokio::time::timeout(
+        std::time::Duration::from_secs(timeout_secs),
+        client.chat().create(req),
+    )
+    .await
+    {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => return Err(e.to_string()),
+        Err(_) => return Err("LLM request timeout".to_string()),
+    };
+    let raw = resp
+        .choices
+        .first()
+        .and_then(|c| c.message.content.clone())
+        .unwrap_or_default();
+    let v = serde_json::from_str::<serde_json::Value>(&raw).unwrap_or_else(|_| {
+        let mut s = raw.replace("```json", "").replace("```", "");
+        if let (Some(start), Some(end)) = (s.find('{'), s.rfind('}')) {
+            s = s[start..=end].to_string();
+        }
+        serde_json::from_str(&s).unwrap_or_else(|_| serde_json::json!({"changes": []}))
+    });
+    let changes: Vec<TaxonomyChange> = v
+        .get("changes")
+        .and_then(|x| x.as_array())
+        .map(|list| list.iter().filter_map(|c| serde_json::from_value(c.clone()).ok()).collect())
+        .unwrap_or_default();
+    eprintln!("[LLM-FLOW] taxonomy proposals={}", changes.len());
+    Ok(valid_changes(changes, &tags))
+}
+
+fn valid_changes(changes: Vec<TaxonomyChange>, tags: &[TaxonomyTag]) -> Vec<TaxonomyChange> {
+    let find = |id: u32| tags.iter().find(|t| t.id == id);
+    changes
+        .into_iter()
+        .filter_map(|change| match change {
+            TaxonomyChange::Merge { source_id, target_id, .. } => {
+                (source_id != target_id && find(source_id).is_some() && find(target_id).is_some()).then_some(change)
+            }
+            TaxonomyChange::Rename { id, ref name, .. } => {
+                let name = name.trim();
+                (!name.is_empty() && find(id).is_some_and(|t| t.name != name)).then_some(change)
+            }
+            TaxonomyChange::Group { parent, ids, reason } => {
+                let parent = parent.trim().to_string();
+                let mut ids: Vec<u32> = ids.into_iter().filter(|id| find(*id).is_some()).collect();
+                ids.sort_unstable();
+                ids.dedup();
+                (!parent.is_empty() && !ids.is_empty()).then_some(TaxonomyChange::Group { parent, ids, reason })
+            }
+        })
+        .collect()
+}
+
+// Structured filter translated from a natural-language query. Dates are Unix
+// seconds (UTC day boundaries), `modified_before` exclusive.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NlFilter {
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+    // Lowercase extensions without the dot
+    pub extensions: Vec<String>,
+    pub name_contains: Option<String>,
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+}
+
+// Translates `query` into a tag filter plus extension, name and date limits.
+// Only ids from `tags` are kept; nothing is run here.
+pub async fn translate_query(
+    query: String,
+    tags: Vec<TaxonomyTag>,
+    base_url: Option<String>,
+    model: Option<String>,
+    proxy: Option<String>,
+) -> Result<NlFilter, String> {
+    use async_openai::config::OpenAIConfig;
+    use async_openai::types::{
+        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
+        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
+    };
+    use async_openai::Client;
+
+    let api_key = std::env::var("SILICONFLOW_API_KEY")
+        .map_err(|_| "SILICONFLOW_API_KEY not set".to_string())?;
+    let base = resolve_base_url(base_url);
+    let model_name = model.unwrap_or_else(|| {
+        std::env::var("LLM_MODEL").unwrap_or_else(|_| "Qwen/Qwen3-VL-32B-Instruct".to_string())
+    });
+    let cfg = OpenAIConfig::new()
+        .with_api_base(&base)
+        .with_api_key(api_key);
+    let client = Client::with_config(cfg).with_http_client(http_client(proxy.as_deref())?);
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let sys = ChatCompletionRequestMessage::System(
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content("你是一个文件搜索助手。把用户的自然语言查询翻译成结构化过滤条件。只能使用给出的标签 id；文件类型用扩展名表示（如 png、pdf）；日期用 YYYY-MM-DD，modifiedBefore 不包含当天。useAnd 为 true 表示必须同时具有所有标签。严格输出 JSON：{\"tagIds\":[number],\"useAnd\":boolean,\"extensions\":[string],\"nameContains\":string|null,\"modifiedAfter\":string|null,\"modifiedBefore\":string|null}。不需要的条件留空。不要包含除 JSON 外的任何文本。")
+            .build()
+            .map_err(|e| e.to_string())?,
+    );
+    let user = ChatCompletionRequestMessage::User(
+        ChatCompletionRequestUserMessageArgs::default()
+            .content(format!(
+                "today: {}\ntags: {}\nquery: {}",
+                format_date(now),
+                serde_json::to_string(&tags).unwrap_or_default(),
+                query
+            ))
+            .build()
+            .map_err(|e| e.to_string())?,
+    );
+    let req = CreateChatCompletionRequestArgs::default()
+        .model(model_name.clone())
+        .temperature(0.0)
+        .messages(vec![sys, user])
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let timeout_secs: u64 = std::env::var("LLM_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(45);
+    eprintln!(
+        "[LLM-FLOW] query request model='{}' base='{}' tags={} timeout={}s",
+        model_name,
+        base,
+        tags.len(),
+        timeout_secs,
+    );
+    let resp = match tokio::time::timeout(
+        std::time::Duration::from_secs(timeout_secs),
+        client.chat().create(req),
+    )
+    .await
+    {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => return Err(e.to_string()),
+        Err(_) => return Err("LLM request timeout".to_string()),
+    };
+    let raw = resp
+        .choices
+        .first()
+        .and_then(|c| c.message.content.clone())
+        .unwrap_or_default();
+    let mut s = raw.replace("```json", "").replace("```", "");
+    if let (Some(start), Some(end)) = (s.find('{'), s.rfind('}')) {
+        s = s[start..=end].to_string();
+    }
+    let v: serde_json::Value = serde_json::from_str(&s).map_err(|_| "Couldn't understand the model's answer".to_string())?;
+    Ok(parse_filter(&v, &tags))
+}
+
+fn parse_filter(v: &serde_json::Value, tags: &[TaxonomyTag]) -> NlFilter {
+    let date = |key: &str| v.get(key).and_then(|x| x.as_str()).and_then(parse_date);
+    let mut tag_ids: Vec<u32> = v
+        .get("tagIds")
+        .and_then(|x| x.as_array())
+        .map(|ids| ids.iter().filter_map(|id| id.as_u64()).map(|id| id as u32).collect())
+        .unwrap_or_default();
+    tag_ids.retain(|id| tags.iter().any(|t| t.id == *id));
+    tag_ids.sort_unstable();
+    tag_ids.dedup();
+    NlFilter {
+        tag_ids,
+        use_and: v.get("useAnd").and_then(|x| x.as_bool()).unwrap_or(true),
+        extensions: v
+            .get("extensions")
+            .and_then(|x| x.as_array())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|e| e.as_str())
+                    .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                    .filter(|e| !e.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        name_contains: v
+            .get("nameContains")
+            .and_then(|x| x.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        modified_after: date("modifiedAfter"),
+        modified_before: date("modifiedBefore"),
+    }
+}
+
+// "YYYY-MM-DD" to Unix seconds at 00:00 UTC
+fn parse_date(text: &str) -> Option<i64> {
+    let mut parts = text.trim().splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    // Days from civil, after Howard Hinnant
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some((era * 146_097 + doe - 719_468) * 86_400)
+}
+
+fn format_date(secs: i64) -> String {
+    let z = secs.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Vectors for `input` from an OpenAI-compatible embeddings endpoint, in input
+// order. Meant for a local backend, so no proxy and the API key is optional.
+pub async fn embed(input: Vec<String>, base_url: String, model: String) -> Result<Vec<Vec<f32>>, String> {
+    use async_openai::config::OpenAIConfig;
+    use async_openai::types::CreateEmbeddingRequestArgs;
+    use async_openai::Client;
+
+    let api_key = std::env::var("EMBEDDING_API_KEY").unwrap_or_else(|_| "local".to_string());
+    let cfg = OpenAIConfig::new()
+        .with_api_base(&base_url)
+        .with_api_key(api_key);
+    let client = Client::with_config(cfg).with_http_client(http_client(None)?);
+    let count = input.len();
+    let req = CreateEmbeddingRequestArgs::default()
+        .model(model)
+        .input(input)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = match tokio::time::timeout(std::time::Duration::from_secs(15), client.embeddings().create(req)).await {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => return Err(e.to_string()),
+        Err(_) => return Err("embedding request timeout".to_string()),
+    };
+    let mut data = resp.data;
+    data.sort_by_key(|e| e.index);
+    eprintln!("[LLM-FLOW] embeddings inputs={} vectors={}", count, data.len());
+    Ok(data.into_iter().map(|e| e.embedding).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 16×8 JPEG, left half red and right half blue, carrying an EXIF block
+    // with orientation 6 (rotate 90° clockwise) and a GPS IFD
+    fn rotated_jpeg_with_gps() -> Vec<u8> {
+        let img = image::RgbImage::from_fn(16, 8, |x, _| if x < 8 { image::Rgb([255, 0, 0]) } else { image::Rgb([0, 0, 255]) });
+        let mut jpeg = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut jpeg, image::ImageFormat::Jpeg).unwrap();
+        let jpeg = jpeg.into_inner();
+
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00".to_vec();
+        // IFD0: Orientation and the GPS IFD pointer (offset 38)
+        tiff.extend_from_slice(&[2, 0]);
+        tiff.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+        tiff.extend_from_slice(&[0x25, 0x88, 4, 0, 1, 0, 0, 0, 38, 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        // GPS IFD: GPSLatitudeRef "N"
+        tiff.extend_from_slice(&[1, 0]);
+        tiff.extend_from_slice(&[0x01, 0x00, 2, 0, 2, 0, 0, 0, b'N', 0, 0, 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(&tiff);
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xff, 0xe1]);
+        out.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(&app1);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    #[test]
+    fn metadata_is_stripped_and_orientation_applied() {
+        let input = rotated_jpeg_with_gps();
+        assert!(input.windows(6).any(|w| w == b"Exif\0\0"));
+
+        let (out, mime) = without_metadata(&input, "image/jpeg").unwrap();
+        assert_eq!(mime, "image/jpeg");
+        assert!(!out.windows(4).any(|w| w == b"Exif"));
+
+        // Rotated clockwise: the red left half is now on top
+        let img = image::load_from_memory(&out).unwrap().to_rgb8();
+        assert_eq!(img.dimensions(), (8, 16));
+        let [r, _, b] = img.get_pixel(4, 3).0;
+        assert!(r > 200 && b < 60, "top should be red, got {:?}", img.get_pixel(4, 3));
+        let [r, _, b] = img.get_pixel(4, 12).0;
+        assert!(b > 200 && r < 60, "bottom should be blue, got {:?}", img.get_pixel(4, 12));
+    }
 }
@@ -0,0 +1,209 @@
+// Rule scoring of tag names against file names that mix Chinese and English.
+// Both sides are folded (full-width to ASCII, lowercase), CJK runs are split into
+// character bigrams since they carry no separators, and Chinese is compared by
+// pinyin as well so "fapiao_2024.pdf" finds the tag 发票.
+
+use pinyin::ToPinyin;
+
+// Pinyin shorter than this matches too much by accident ("an", "li")
+const MIN_PINYIN_MATCH: usize = 4;
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F)
+}
+
+pub fn has_cjk(text: &str) -> bool {
+    text.chars().any(is_cjk)
+}
+
+// Full-width forms to ASCII and lowercase
+pub fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            0xFF01..=0xFF5E => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            0x3000 => ' ',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Script {
+    Cjk,
+    Letter,
+    Digit,
+}
+
+fn script(c: char) -> Script {
+    if is_cjk(c) {
+        Script::Cjk
+    } else if c.is_numeric() {
+        Script::Digit
+    } else {
+        Script::Letter
+    }
+}
+
+// Words of folded `text`: runs split at punctuation and at changes between
+// CJK, letters and digits ("2024年发票scan" -> 2024, 年发票, scan). CJK runs
+// also yield their bigrams so a two-character tag can match inside a phrase.
+pub fn tokens(text: &str) -> Vec<String> {
+    let folded = fold(text);
+    let mut runs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut last: Option<Script> = None;
+    for c in folded.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                runs.push(std::mem::take(&mut current));
+            }
+            last = None;
+            continue;
+        }
+        let s = script(c);
+        if last.is_some_and(|l| l != s) && !current.is_empty() {
+            runs.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+        last = Some(s);
+    }
+    if !current.is_empty() {
+        runs.push(current);
+    }
+    let mut out = Vec::new();
+    for run in runs {
+        let chars: Vec<char> = run.chars().collect();
+        if chars.len() > 2 && is_cjk(chars[0]) {
+            for pair in chars.windows(2) {
+                out.push(pair.iter().collect());
+            }
+        }
+        out.push(run);
+    }
+    out
+}
+
+// Chinese characters as toneless pinyin, everything else folded, without
+// separators: "发票-2024" -> "fapiao2024"
+pub fn pinyin(text: &str) -> String {
+    let folded = fold(text);
+    let mut out = String::new();
+    for (c, p) in folded.chars().zip(folded.as_str().to_pinyin()) {
+        match p {
+            Some(p) => out.push_str(p.plain()),
+            None if c.is_alphanumeric() => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+
+// Rule score of `label` for a file titled `title`; 0 means no match
+pub fn score(title: &str, label: &str) -> i32 {
+    let title_f = fold(title);
+    let label_f = fold(label.trim());
+    if label_f.is_empty() {
+        return 0;
+    }
+    let mut s = 0;
+    if title_f.contains(&label_f) {
+        s += 10;
+    }
+    if tokens(title).contains(&label_f) {
+        s += 8;
+    }
+    if title_f.starts_with(&label_f) || title_f.ends_with(&label_f) {
+        s += 4;
+    }
+    // Same word written in the other script (发票 vs fapiao)
+    if s == 0 && (has_cjk(title) || has_cjk(label)) {
+        let label_py = pinyin(label);
+        if label_py.len() >= MIN_PINYIN_MATCH && pinyin(title).contains(&label_py) {
+            s += 6;
+        }
+    }
+    s
+}
+
+// Edit distance between the folded names as a 0..1 similarity
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = fold(a.trim()).chars().collect();
+    let b: Vec<char> = fold(b.trim()).chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    1.0 - row[b.len()] as f32 / longest as f32
+}
+
+// Similarity at which two tag names are probably the same tag
+const DUPLICATE_SIMILARITY: f32 = 0.8;
+
+// Similarity of two tag names that look like the same tag, e.g. "Screenshots"
+// and "screenshot" or 工作 and 工作中: close by edit distance, or one name
+// inside the other with a single character to spare
+pub fn near_duplicate(a: &str, b: &str) -> Option<f32> {
+    let (fa, fb) = (fold(a.trim()), fold(b.trim()));
+    if fa.is_empty() || fb.is_empty() {
+        return None;
+    }
+    let s = similarity(a, b);
+    let (short, long) = if fa.chars().count() <= fb.chars().count() { (&fa, &fb) } else { (&fb, &fa) };
+    let contained = short.chars().count() >= 2 && long.chars().count() == short.chars().count() + 1 && long.contains(short.as_str());
+    (s >= DUPLICATE_SIMILARITY || contained).then_some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_maps_full_width_to_ascii() {
+        assert_eq!(fold("ＲＥＰＯＲＴ　２０２４！"), "report 2024!");
+        assert_eq!(score("ＲＥＰＯＲＴ_2024", "report"), 22);
+    }
+
+    #[test]
+    fn tokens_split_scripts_and_pair_cjk() {
+        assert_eq!(tokens("2024年发票scan"), vec!["2024", "年发", "发票", "年发票", "scan"]);
+        // Two characters are already a bigram
+        assert_eq!(tokens("发票-Q1"), vec!["发票", "q", "1"]);
+    }
+
+    #[test]
+    fn pinyin_matches_across_scripts() {
+        assert_eq!(pinyin("发票-2024"), "fapiao2024");
+        assert_eq!(score("fapiao_2024", "发票"), 6);
+        assert_eq!(score("2024年发票scan", "发票"), 18);
+    }
+
+    #[test]
+    fn short_pinyin_does_not_match() {
+        // 安 is "an", 李 is "li": both below MIN_PINYIN_MATCH
+        assert_eq!(score("banana", "安"), 0);
+        assert_eq!(score("files", "李"), 0);
+        assert_eq!(score("notes", "  "), 0);
+    }
+
+    #[test]
+    fn near_duplicates_by_distance_or_containment() {
+        assert_eq!(similarity("Report", "report"), 1.0);
+        assert!((similarity("kitten", "sitting") - (1.0 - 3.0 / 7.0)).abs() < 1e-6);
+        assert!(near_duplicate("Screenshots", "screenshot").is_some());
+        assert!(near_duplicate("工作", "工作中").is_some());
+        assert!(near_duplicate("a", "ab").is_none());
+        assert!(near_duplicate("cat", "dog").is_none());
+        assert!(near_duplicate("", "dog").is_none());
+    }
+}
@@ -0,0 +1,167 @@
+// Database benchmarks: directory scans, bulk tagging and tag filters at library
+// sizes from 10k to 1M files. Run with
+//
+//     cargo bench --features bench --bench db
+//
+// TAGME_BENCH_SIZES=10000,100000 picks other sizes. Databases and scan folders
+// are built in the temp directory and removed afterwards.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use app_lib::bench::{create_schema, files_by_tags, link_tags, scan_directory_limited};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rusqlite::{params, Connection};
+
+const DEFAULT_SIZES: &[usize] = &[10_000, 100_000, 1_000_000];
+// Scans read real files, so the folder is never filled past this
+const MAX_SCAN_FILES: usize = 100_000;
+const NOW: i64 = 1_700_000_000;
+
+fn sizes() -> Vec<usize> {
+    std::env::var("TAGME_BENCH_SIZES")
+        .ok()
+        .map(|v| v.split(',').filter_map(|n| n.trim().parse().ok()).collect())
+        .unwrap_or_else(|| DEFAULT_SIZES.to_vec())
+}
+
+// A database with `files` tracked files and three tags: file i carries tag a
+// when i is even, b when it's a multiple of 3 and c when it's a multiple of 5
+struct BenchDb {
+    path: PathBuf,
+    conn: Connection,
+    tags: [u32; 3],
+}
+
+impl BenchDb {
+    fn new(files: usize, tagged: bool) -> Self {
+        let path = std::env::temp_dir().join(format!("tagme-bench-{}-{}.db", std::process::id(), files));
+        let _ = std::fs::remove_file(&path);
+        let mut conn = Connection::open(&path).expect("open bench db");
+        create_schema(&conn).expect("create schema");
+
+        let tx = conn.transaction().unwrap();
+        let mut tags = [0; 3];
+        for (i, name) in ["bench-a", "bench-b", "bench-c"].into_iter().enumerate() {
+            tx.execute(
+                "INSERT INTO tags (name, parent_id, position, created_at) VALUES (?1, NULL, ?2, ?3)",
+                params![name, 100 + i, NOW],
+            )
+            .unwrap();
+            tags[i] = tx.last_insert_rowid() as u32;
+        }
+        {
+            let mut file = tx
+                .prepare(
+                    "INSERT INTO files (path, content_hash, size_bytes, last_modified, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?4, ?4)",
+                )
+                .unwrap();
+            let mut link = tx
+                .prepare("INSERT INTO file_tags (file_id, tag_id, created_at) VALUES (?1, ?2, ?3)")
+                .unwrap();
+            for i in 0..files {
+                file.execute(params![
+                    format!("/bench/dir{:04}/file{:07}.txt", i / 1000, i),
+                    format!("{:064x}", i),
+                    (i % 4096) as i64,
+                    NOW - i as i64
+                ])
+                .unwrap();
+                if !tagged {
+                    continue;
+                }
+                let id = tx.last_insert_rowid();
+                for (tag, every) in tags.iter().zip([2, 3, 5]) {
+                    if i % every == 0 {
+                        link.execute(params![id, tag, NOW]).unwrap();
+                    }
+                }
+            }
+        }
+        tx.commit().unwrap();
+        BenchDb { path, conn, tags }
+    }
+}
+
+impl Drop for BenchDb {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.path.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn tag_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tag_filter");
+    group.sample_size(10);
+    for n in sizes() {
+        let db = BenchDb::new(n, true);
+        let [a, b, c] = db.tags;
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("and", n), &n, |bench, _| {
+            bench.iter(|| files_by_tags(&db.conn, &[a, b], true).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("or", n), &n, |bench, _| {
+            bench.iter(|| files_by_tags(&db.conn, &[a, b, c], false).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bulk_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_insert");
+    group.sample_size(10);
+    for n in sizes() {
+        let mut db = BenchDb::new(n, false);
+        let files: Vec<(u32, String)> = db
+            .conn
+            .prepare("SELECT id, path FROM files")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let tag = db.tags[0];
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("link_tags", n), &n, |bench, _| {
+            bench.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    let tx = db.conn.transaction().unwrap();
+                    let start = Instant::now();
+                    link_tags(&tx, &files, &[tag], NOW).unwrap();
+                    total += start.elapsed();
+                    // Rolled back so every sample starts from an untagged library
+                    drop(tx);
+                }
+                total
+            })
+        });
+    }
+    group.finish();
+}
+
+fn scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+    for n in sizes().into_iter().filter(|n| *n <= MAX_SCAN_FILES) {
+        let dir = std::env::temp_dir().join(format!("tagme-bench-scan-{}-{}", std::process::id(), n));
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..n {
+            std::fs::write(dir.join(format!("file{:07}.txt", i)), b"").unwrap();
+        }
+        let root = dir.to_string_lossy().to_string();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("directory", n), &n, |bench, &n| {
+            bench.iter(|| scan_directory_limited(&root, n).unwrap())
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan, bulk_insert, tag_filter);
+criterion_main!(benches);
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+use crate::db::{self, FileListItem};
+
+// Files inside zip and 7z archives as virtual entries, `archive!inner/path`
+// (`D:\Assets\pack.zip!textures/wood.png`). They are listed next to their
+// archive when expanding is on, and extracted to the cache when they need
+// hashing, previewing or opening. Entries carry the archive's mtime, so a
+// changed archive re-hashes its tagged entries.
+
+const ARCHIVE_EXTS: [&str; 2] = ["zip", "7z"];
+// Entries listed per archive; the rest of a huge archive is left out
+const ARCHIVE_ENTRY_LIMIT: usize = 5000;
+
+// Entries of an archive as of its size and mtime
+struct Listing {
+    size_bytes: u64,
+    last_modified: i64,
+    entries: Vec<FileListItem>,
+}
+
+// Listings per archive path, kept while the archive is unchanged
+static LISTINGS: Mutex<BTreeMap<String, Listing>> = Mutex::new(BTreeMap::new());
+
+// Whether scans list the contents of archives; off unless turned on
+pub fn expand_enabled(app_handle: &tauri::AppHandle) -> bool {
+    db::get_setting(app_handle, "expand_archives").ok().flatten().as_deref() == Some("true")
+}
+
+pub fn set_expand_enabled(app_handle: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(app_handle, "expand_archives", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+pub fn is_archive(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|e| ARCHIVE_EXTS.contains(&e.to_string_lossy().to_ascii_lowercase().as_str()))
+}
+
+// (archive, inner path) of a virtual entry
+pub fn split(path: &str) -> Option<(&str, &str)> {
+    let lower = path.to_ascii_lowercase();
+    ARCHIVE_EXTS
+        .iter()
+        .filter_map(|ext| lower.find(&format!(".{}!", ext)).map(|i| i + ext.len() + 1))
+        .min()
+        .map(|end| (&path[..end], &path[end + 1..]))
+        .filter(|(_, inner)| !inner.is_empty())
+}
+
+pub fn is_virtual(path: &str) -> bool {
+    split(path).is_some()
+}
+
+// The files inside the scanned archive `item`; none when it can't be read
+pub fn entries(item: &FileListItem) -> Vec<FileListItem> {
+    if let Some(l) = LISTINGS.lock().unwrap().get(&item.path) {
+        if l.size_bytes == item.size_bytes && l.last_modified == item.last_modified {
+            return l.entries.clone();
+        }
+    }
+    let names = match list_names(Path::new(&item.path)) {
+        Ok(names) => names,
+        Err(e) => {
+            eprintln!("⚠️ [ARCHIVES] Can't read {}: {}", item.path, e);
+            Vec::new()
+        }
+    };
+    let listed: Vec<FileListItem> = names
+        .into_iter()
+        .take(ARCHIVE_ENTRY_LIMIT)
+        .map(|(name, size_bytes)| FileListItem {
+            path: format!("{}!{}", item.path, name),
+            size_bytes,
+            last_modified: item.last_modified,
+            is_directory: false,
+        })
+        .collect();
+    let listing = Listing { size_bytes: item.size_bytes, last_modified: item.last_modified, entries: listed.clone() };
+    LISTINGS.lock().unwrap().insert(item.path.clone(), listing);
+    listed
+}
+
+// (name, size) of each file in the archive; folders are implied by the names
+fn list_names(archive: &Path) -> Result<Vec<(String, u64)>, String> {
+    let is_7z = archive.extension().is_some_and(|e| e.eq_ignore_ascii_case("7z"));
+    if is_7z {
+        let reader = sevenz_rust::SevenZReader::open(archive, sevenz_rust::Password::empty()).map_err(|e| e.to_string())?;
+        return Ok(reader
+            .archive()
+            .files
+            .iter()
+            .filter(|e| !e.is_directory())
+            .map(|e| (e.name().to_string(), e.size()))
+            .collect());
+    }
+    let file = fs::File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+    let mut names = Vec::new();
+    for i in 0..zip.len() {
+        let entry = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+        if !entry.is_dir() {
+            names.push((entry.name().to_string(), entry.size()));
+        }
+    }
+    Ok(names)
+}
+
+// The extracted copy of the virtual entry `path` in the cache, extracted again
+// only when the archive changed. The copy carries the archive's mtime like the
+// listed entry, so hashing can reuse cached hashes.
+pub fn extract(app_handle: &tauri::AppHandle, path: &str) -> Result<PathBuf, String> {
+    let (archive, inner) = split(path).ok_or_else(|| format!("{} is not inside an archive", path))?;
+    let archive_mtime = fs::metadata(archive)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("{}: {}", archive, e))?;
+    let dir = crate::data_dir::cache_dir(app_handle)?.join("archives");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let id = hex::encode(Sha256::digest(path.as_bytes()));
+    let ext = Path::new(inner).extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let target = dir.join(format!("{}{}", id, ext));
+    let cached = fs::metadata(&target).and_then(|m| m.modified()).is_ok_and(|t| t == truncate_secs(archive_mtime));
+    crate::metrics::cache(app_handle, crate::metrics::ARCHIVE_CACHE, cached);
+    if cached {
+        return Ok(target);
+    }
+    let partial = dir.join(format!("{}.part", id));
+    let mut out = fs::File::create(&partial).map_err(|e| e.to_string())?;
+    let is_7z = Path::new(archive).extension().is_some_and(|e| e.eq_ignore_ascii_case("7z"));
+    if is_7z {
+        let mut reader = sevenz_rust::SevenZReader::open(archive, sevenz_rust::Password::empty()).map_err(|e| e.to_string())?;
+        let mut found = false;
+        reader
+            .for_each_entries(|entry, data| {
+                if entry.name() == inner {
+                    std::io::copy(data, &mut out)?;
+                    found = true;
+                    return Ok(false);
+                }
+                // Entries in a solid block are decoded in order either way
+                std::io::copy(data, &mut std::io::sink())?;
+                Ok(true)
+            })
+            .map_err(|e| e.to_string())?;
+        if !found {
+            let _ = fs::remove_file(&partial);
+            return Err(format!("{} is not in {}", inner, archive));
+        }
+    } else {
+        let file = fs::File::open(archive).map_err(|e| e.to_string())?;
+        let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+        let mut entry = zip.by_name(inner).map_err(|e| format!("{}: {}", inner, e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+    }
+    let _ = out.set_modified(truncate_secs(archive_mtime));
+    drop(out);
+    fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+    Ok(target)
+}
+
+// Listings store whole seconds; the extracted copy's mtime matches them
+fn truncate_secs(time: SystemTime) -> SystemTime {
+    let secs = time.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+}
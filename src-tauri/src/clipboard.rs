@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::Emitter;
+
+use crate::db;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(800);
+
+// Larger clipboard selections are not offered for tagging
+const MAX_PATHS: usize = 200;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_enabled(app_handle: &tauri::AppHandle) -> bool {
+    db::get_setting(app_handle, "clipboard_watch").ok().flatten().as_deref() == Some("true")
+}
+
+pub fn set_enabled(app_handle: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(app_handle, "clipboard_watch", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())?;
+    apply(app_handle, enabled);
+    Ok(())
+}
+
+// Turns the watcher on or off. The polling thread is started on first use and
+// then idles while disabled.
+pub fn apply(app_handle: &tauri::AppHandle, enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled && !STARTED.swap(true, Ordering::SeqCst) {
+        let app = app_handle.clone();
+        std::thread::spawn(move || poll(app));
+    }
+}
+
+// Plain text for the clipboard, e.g. a filter link. Not offered for tagging,
+// since it isn't a path.
+pub fn copy_text(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new().and_then(|mut c| c.set_text(text)).map_err(|e| e.to_string())
+}
+
+// Files copied in Explorer/Finder, or text made only of absolute paths ("Copy as
+// path"). Paths that don't exist are dropped; the rest are canonicalized like
+// roots so they match scanned paths.
+fn read_paths(clipboard: &mut arboard::Clipboard) -> Vec<String> {
+    let raw: Vec<String> = match clipboard.get().file_list() {
+        Ok(files) if !files.is_empty() => files.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        _ => match clipboard.get_text() {
+            Ok(text) => tagme_paths::paths_in_text(&text)
+                .unwrap_or_default()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+    };
+    if raw.len() > MAX_PATHS {
+        return Vec::new();
+    }
+    raw.iter()
+        .filter(|p| Path::new(p).exists())
+        .map(|p| db::canonical_root(p))
+        .collect()
+}
+
+// Emits "clipboard-paths" whenever the clipboard changes to a new set of paths.
+// Whatever is on the clipboard when watching starts is taken as already seen.
+fn poll(app_handle: tauri::AppHandle) {
+    let mut clipboard = None;
+    let mut last: Option<Vec<String>> = None;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if !ENABLED.load(Ordering::SeqCst) {
+            last = None;
+            continue;
+        }
+        if clipboard.is_none() {
+            clipboard = arboard::Clipboard::new().ok();
+        }
+        let Some(cb) = clipboard.as_mut() else {
+            continue;
+        };
+        let paths = read_paths(cb);
+        let seen = last.replace(paths.clone());
+        if let Some(previous) = seen {
+            if paths != previous && !paths.is_empty() {
+                let _ = app_handle.emit("clipboard-paths", &paths);
+            }
+        }
+    }
+}
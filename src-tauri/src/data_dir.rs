@@ -0,0 +1,302 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::db;
+
+// Where the database, its backups and the caches live. By default that's the
+// OS app data dir; users whose system drive is full can move it. The choice is
+// kept in a small pointer file in the config dir (it can't live in the database
+// it points to) and read once per run, so a move takes effect on restart.
+
+pub const DB_FILE: &str = "tagme_app.db";
+const POINTER_FILE: &str = "data_location.json";
+const BACKUPS: &str = "backups";
+const CACHE: &str = "cache";
+// Cache subfolders (archive extractions, remote copies); rebuilt on demand
+const CACHES: &[&str] = &["archives", "remote"];
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Pointer {
+    data_dir: Option<PathBuf>,
+    // The location the library was just moved away from; cleared out on the
+    // next start, once the app runs from the new one
+    previous: Option<PathBuf>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub current: String,
+    pub default: String,
+    pub relocated: bool,
+    // Set after a move until the app restarts
+    pub pending: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveReport {
+    pub target: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+fn default_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    app_handle.path().app_data_dir().expect("failed to get app data dir")
+}
+
+fn pointer_path(app_handle: &tauri::AppHandle) -> PathBuf {
+    app_handle.path().app_config_dir().expect("failed to get app config dir").join(POINTER_FILE)
+}
+
+fn read_pointer(app_handle: &tauri::AppHandle) -> Pointer {
+    fs::read_to_string(pointer_path(app_handle))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+// Written next to the pointer and renamed, so a crash leaves the old or the new one
+fn write_pointer(app_handle: &tauri::AppHandle, pointer: &Pointer) -> Result<(), String> {
+    let path = pointer_path(app_handle);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let partial = path.with_extension("partial");
+    let raw = serde_json::to_string_pretty(pointer).map_err(|e| e.to_string())?;
+    fs::write(&partial, raw).map_err(|e| e.to_string())?;
+    fs::rename(&partial, &path).map_err(|e| e.to_string())
+}
+
+// The data dir for this run
+pub fn data_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    DATA_DIR
+        .get_or_init(|| read_pointer(app_handle).data_dir.unwrap_or_else(|| default_dir(app_handle)))
+        .clone()
+}
+
+// Caches follow a moved library; otherwise they stay in the OS cache dir
+pub fn cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = data_dir(app_handle);
+    if dir != default_dir(app_handle) {
+        Ok(dir.join(CACHE))
+    } else {
+        app_handle.path().app_cache_dir().map_err(|e| e.to_string())
+    }
+}
+
+pub fn location(app_handle: &tauri::AppHandle) -> DataLocation {
+    let current = data_dir(app_handle);
+    let default = default_dir(app_handle);
+    let configured = read_pointer(app_handle).data_dir.unwrap_or_else(|| default.clone());
+    DataLocation {
+        current: current.to_string_lossy().to_string(),
+        default: default.to_string_lossy().to_string(),
+        relocated: current != default,
+        pending: (configured != current).then(|| configured.to_string_lossy().to_string()),
+    }
+}
+
+// Copies the library to `target` (None: back to the default location) and
+// points the next start at it. The database is copied with VACUUM INTO, which
+// includes whatever is still in the WAL, and checked with integrity_check and
+// per-table row counts; backups are compared by hash. Nothing at the current
+// location changes until the app runs from the new one, and finish_move copies
+// the database again then, since the app keeps writing to it until it quits.
+pub fn move_to(app_handle: &tauri::AppHandle, target: Option<PathBuf>) -> Result<MoveReport, String> {
+    let current = data_dir(app_handle);
+    let default = default_dir(app_handle);
+    let target = target.unwrap_or_else(|| default.clone());
+    if location(app_handle).pending.is_some() {
+        return Err("Restart TagMe to finish the previous move first".to_string());
+    }
+    if target == current {
+        return Err("The library is already there".to_string());
+    }
+    if target.starts_with(&current) {
+        return Err("Pick a folder outside the current data folder".to_string());
+    }
+    if target.join(DB_FILE).exists() {
+        return Err(format!("{} already holds a TagMe library", target.display()));
+    }
+    fs::create_dir_all(&target).map_err(|e| format!("{}: {}", target.display(), e))?;
+
+    let db_target = target.join(DB_FILE);
+    let db_source = current.join(DB_FILE);
+    db::copy_database(&db_source, &db_target).map_err(|e| format!("Copying the database failed: {}", e))?;
+    if let Err(e) = db::verify_copy(&db_source, &db_target) {
+        let _ = fs::remove_file(&db_target);
+        return Err(format!("The copied database didn't verify, nothing was moved: {}", e));
+    }
+    let mut report = MoveReport {
+        target: target.to_string_lossy().to_string(),
+        files: 1,
+        bytes: fs::metadata(&db_target).map(|m| m.len()).unwrap_or(0),
+    };
+
+    let backups = current.join(BACKUPS);
+    if backups.is_dir() {
+        let dest = target.join(BACKUPS);
+        fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+        let mut files = Vec::new();
+        db::walk_files(&backups, &mut files);
+        for file in files {
+            let Some(name) = file.file_name() else { continue };
+            let copy = dest.join(name);
+            report.bytes += fs::copy(&file, &copy).map_err(|e| format!("{}: {}", file.display(), e))?;
+            let same = db::hash_file_content(&file).ok().is_some_and(|h| db::hash_file_content(&copy).ok() == Some(h));
+            if !same {
+                return Err(format!("The copy of {} didn't verify, nothing was moved", file.display()));
+            }
+            report.files += 1;
+        }
+    }
+
+    let pointer = Pointer {
+        data_dir: (target != default).then(|| target.clone()),
+        previous: Some(current),
+    };
+    write_pointer(app_handle, &pointer)?;
+    eprintln!("📦 [DATA] Library copied to {} ({} file(s)); used from the next start", target.display(), report.files);
+    Ok(report)
+}
+
+// Called at startup before the database is opened: brings the new location up
+// to date and removes what a finished move left at the old one. Only TagMe's own files are touched, since a custom
+// location may be a folder the user keeps other things in.
+pub fn finish_move(app_handle: &tauri::AppHandle) {
+    let mut pointer = read_pointer(app_handle);
+    let Some(previous) = pointer.previous.take() else { return };
+    let moved_to = pointer.data_dir.clone().unwrap_or_else(|| default_dir(app_handle));
+    if !moved_to.join(DB_FILE).exists() {
+        // E.g. the drive it was moved to isn't plugged in; try again next start
+        eprintln!("⚠️ [DATA] No library at {}; running from {}", moved_to.display(), previous.display());
+        let _ = DATA_DIR.set(previous);
+        return;
+    }
+    if let Err(e) = refresh_copy(&previous, &moved_to) {
+        eprintln!("⚠️ [DATA] Couldn't update the library at {}: {}; running from {}", moved_to.display(), e, previous.display());
+        let _ = DATA_DIR.set(previous);
+        return;
+    }
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(previous.join(format!("{}{}", DB_FILE, suffix)));
+    }
+    let _ = fs::remove_dir_all(previous.join(BACKUPS));
+    let old_cache = if previous == default_dir(app_handle) {
+        app_handle.path().app_cache_dir().ok()
+    } else {
+        Some(previous.join(CACHE))
+    };
+    if let Some(old_cache) = old_cache.filter(|c| Some(c) != cache_dir(app_handle).ok().as_ref()) {
+        for cache in CACHES {
+            let _ = fs::remove_dir_all(old_cache.join(cache));
+        }
+    }
+    let result = if pointer.data_dir.is_some() {
+        write_pointer(app_handle, &pointer)
+    } else {
+        fs::remove_file(pointer_path(app_handle)).map_err(|e| e.to_string())
+    };
+    match result {
+        Ok(()) => eprintln!("📦 [DATA] Removed the old library at {}", previous.display()),
+        Err(e) => eprintln!("⚠️ [DATA] Failed to update {}: {}", POINTER_FILE, e),
+    }
+}
+
+// The watchers, the idle indexer and the clipboard watcher keep writing to the
+// old database between move_to and the restart, so it's copied over the first
+// copy once more while nothing has it open. Backups made meanwhile come along.
+// Skipped when the old database is already gone (a start that stopped midway
+// through the cleanup, or a user who deleted it), leaving the copy as it is.
+fn refresh_copy(previous: &Path, moved_to: &Path) -> Result<(), String> {
+    let source = previous.join(DB_FILE);
+    if !source.exists() {
+        return Ok(());
+    }
+    let partial = moved_to.join(format!("{}.partial", DB_FILE));
+    let _ = fs::remove_file(&partial);
+    db::copy_database(&source, &partial).map_err(|e| e.to_string())?;
+    if let Err(e) = db::verify_copy(&source, &partial) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(moved_to.join(format!("{}{}", DB_FILE, suffix)));
+    }
+    fs::rename(&partial, moved_to.join(DB_FILE)).map_err(|e| e.to_string())?;
+
+    let backups = previous.join(BACKUPS);
+    if backups.is_dir() {
+        let dest = moved_to.join(BACKUPS);
+        fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+        let mut files = Vec::new();
+        db::walk_files(&backups, &mut files);
+        for file in files {
+            let Some(name) = file.file_name() else { continue };
+            if !dest.join(name).exists() {
+                fs::copy(&file, dest.join(name)).map_err(|e| format!("{}: {}", file.display(), e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn temp(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tagme-data-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn refresh_without_the_old_database_keeps_the_copy() {
+        let previous = temp("previous");
+        let moved_to = temp("moved");
+        let conn = Connection::open(moved_to.join(DB_FILE)).unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1), (2);").unwrap();
+        drop(conn);
+        let before = fs::read(moved_to.join(DB_FILE)).unwrap();
+
+        refresh_copy(&previous, &moved_to).unwrap();
+        assert_eq!(fs::read(moved_to.join(DB_FILE)).unwrap(), before);
+        assert!(!previous.join(DB_FILE).exists());
+
+        // The copy helpers don't create a missing source either
+        assert!(db::copy_database(&previous.join(DB_FILE), &previous.join("copy.db")).is_err());
+        assert!(db::verify_copy(&previous.join(DB_FILE), &moved_to.join(DB_FILE)).is_err());
+        assert!(!previous.join(DB_FILE).exists());
+        let _ = fs::remove_dir_all(&previous);
+        let _ = fs::remove_dir_all(&moved_to);
+    }
+
+    #[test]
+    fn refresh_replaces_the_copy_with_the_old_database() {
+        let previous = temp("previous-live");
+        let moved_to = temp("moved-stale");
+        let old = Connection::open(previous.join(DB_FILE)).unwrap();
+        old.execute_batch("PRAGMA journal_mode = WAL; CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);").unwrap();
+        db::copy_database(&previous.join(DB_FILE), &moved_to.join(DB_FILE)).unwrap();
+        old.execute("INSERT INTO t VALUES (2)", []).unwrap();
+        drop(old);
+
+        refresh_copy(&previous, &moved_to).unwrap();
+        let copy = Connection::open(moved_to.join(DB_FILE)).unwrap();
+        let rows: i64 = copy.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 2);
+        let _ = fs::remove_dir_all(&previous);
+        let _ = fs::remove_dir_all(&moved_to);
+    }
+}
@@ -1,11 +1,11 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OpenFlags, Result};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
-use tauri::Manager;
 use std::path::Path;
 use sha2::{Sha256, Digest};
 use std::fs;
 use std::time::SystemTime;
+use unicode_normalization::UnicodeNormalization;
 
 // Lightweight file listing for scan (no hash, not in DB yet)
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,6 +25,13 @@ pub struct FileInfo {
     pub size_bytes: u64,
     pub last_modified: i64,
     pub is_directory: bool,
+    // Most recent file_tags.created_at; only filled by the list queries
+    pub last_tagged: Option<i64>,
+    // Times opened from TagMe, and the last time
+    #[serde(default)]
+    pub open_count: u32,
+    #[serde(default)]
+    pub last_opened_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,6 +41,22 @@ pub struct TagInfo {
     pub parent_id: Option<u32>,
     pub color: Option<String>,
     pub position: i32,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub locked: bool,
+    // For tags without a color of their own: a shade of the nearest colored
+    // ancestor's color, when color inheritance is on (see `apply_shades`)
+    #[serde(default)]
+    pub shade: Option<String>,
+}
+
+// A file row with its tags, as returned by query_files_with_tags
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileWithTags {
+    pub file: FileInfo,
+    pub tags: Vec<TagInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,11 +69,7 @@ pub struct WindowState {
 }
 
 fn get_db_path(app_handle: &AppHandle) -> std::path::PathBuf {
-    app_handle
-        .path()
-        .app_data_dir()
-        .expect("failed to get app data dir")
-        .join("tagme_app.db")
+    crate::data_dir::data_dir(app_handle).join(crate::data_dir::DB_FILE)
 }
 
 pub fn init_db(app_handle: &AppHandle) -> Result<()> {
@@ -60,6 +79,41 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
     }
 
     let conn = Connection::open(&db_path)?;
+    create_schema(&conn)?;
+
+    // Sync roots table with root_directories setting
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let roots = get_root_directories(app_handle).unwrap_or_default();
+    for rp in &roots {
+        let _ = conn.execute(
+            "INSERT OR IGNORE INTO roots (path, created_at, position)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM roots))",
+            params![rp, now],
+        );
+    }
+    // Remove stale roots not in settings
+    let mut stmt = conn.prepare("SELECT path FROM roots")?;
+    let existing: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for ep in existing {
+        if !roots.iter().any(|r| r == &ep) {
+            let _ = conn.execute("DELETE FROM roots WHERE path = ?1", params![ep]);
+        }
+    }
+
+    // Populate files.root_id with the deepest containing root
+    assign_root_ids(&conn)?;
+
+    Ok(())
+}
+
+// Tables, migrations and indexes on an open connection (the benchmarks build
+// their databases with this too)
+pub fn create_schema(conn: &Connection) -> Result<()> {
     conn.execute("PRAGMA foreign_keys = ON", [])?;
     let _ = conn.query_row(
         "PRAGMA journal_mode = WAL",
@@ -82,6 +136,26 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         [],
     )?;
 
+    // Migration: manual chip order and pinned default root
+    let _ = conn.execute(
+        "ALTER TABLE roots ADD COLUMN position INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE roots ADD COLUMN is_default INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    // Migration: roots whose files must never go to a cloud model
+    let _ = conn.execute(
+        "ALTER TABLE roots ADD COLUMN local_only INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    // Migration: tags given to files that newly appear in a root (JSON id list)
+    let _ = conn.execute(
+        "ALTER TABLE roots ADD COLUMN default_tags TEXT NOT NULL DEFAULT '[]'",
+        [],
+    );
+
     // Files table (new installs include root_id)
     conn.execute(
         "CREATE TABLE IF NOT EXISTS files (
@@ -103,6 +177,37 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         "ALTER TABLE files ADD COLUMN is_directory INTEGER NOT NULL DEFAULT 0",
         [],
     );
+    // Migration: tagged files that vanished are kept (hidden) until re-linked or forgotten
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN missing_since INTEGER", []);
+    // Migration: filesystem identity (see `file_identity`)
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN volume_id INTEGER", []);
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN file_index INTEGER", []);
+    // Migration: PDF metadata (see `pdf::metadata`)
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN pdf_title TEXT", []);
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN pdf_author TEXT", []);
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN page_count INTEGER", []);
+    // Migration: launcher statistics (see `record_open`)
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN open_count INTEGER NOT NULL DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE files ADD COLUMN last_opened_at INTEGER", []);
+
+    // Full-text index over file paths for `search_files`. The trigram tokenizer
+    // matches any fragment of a name, not just whole words; the triggers keep it
+    // in step with `files`, and migration 6 fills it for existing libraries.
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS files_fts USING fts5(
+            path, content = 'files', content_rowid = 'id', tokenize = 'trigram'
+         );
+         CREATE TRIGGER IF NOT EXISTS files_fts_insert AFTER INSERT ON files BEGIN
+            INSERT INTO files_fts (rowid, path) VALUES (new.id, new.path);
+         END;
+         CREATE TRIGGER IF NOT EXISTS files_fts_delete AFTER DELETE ON files BEGIN
+            INSERT INTO files_fts (files_fts, rowid, path) VALUES ('delete', old.id, old.path);
+         END;
+         CREATE TRIGGER IF NOT EXISTS files_fts_update AFTER UPDATE OF path ON files BEGIN
+            INSERT INTO files_fts (files_fts, rowid, path) VALUES ('delete', old.id, old.path);
+            INSERT INTO files_fts (rowid, path) VALUES (new.id, new.path);
+         END;",
+    )?;
 
     conn.execute(
         "CREATE TABLE IF NOT EXISTS tags (
@@ -124,6 +229,13 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         [],
     );
 
+    // Migration: optional emoji shown before the tag name
+    let _ = conn.execute("ALTER TABLE tags ADD COLUMN icon TEXT", []);
+    // Migration: archived tags are hidden from the tree and suggestions but keep their files
+    let _ = conn.execute("ALTER TABLE tags ADD COLUMN archived INTEGER NOT NULL DEFAULT 0", []);
+    // Migration: locked tags and their subtrees can't be renamed, moved or deleted
+    let _ = conn.execute("ALTER TABLE tags ADD COLUMN locked INTEGER NOT NULL DEFAULT 0", []);
+
     // Initialize positions for existing tags (group by parent_id)
     conn.execute(
         "UPDATE tags SET position = (
@@ -146,6 +258,80 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         [],
     )?;
 
+    // Command run whenever the tag is applied to a file (see hooks.rs)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tag_hooks (
+            tag_id INTEGER PRIMARY KEY,
+            command TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Files the user marked as what a tag does (positive) or doesn't look like,
+    // shown to the recommenders. Keyed by path so untracked files can be examples.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tag_examples (
+            tag_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL,
+            positive INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (tag_id, file_path),
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Suggestions the background indexer worked out ahead of time, as the
+    // recommender's JSON items; dropped once the file gets a tag
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS stored_recommendations (
+            file_path TEXT PRIMARY KEY,
+            items TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Keyed by path with a copy of the tag name: rows outlive the file and tag they describe
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tag_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL,
+            tag_id INTEGER NOT NULL,
+            tag_name TEXT NOT NULL,
+            action TEXT NOT NULL,
+            at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_tag_history_path ON tag_history(file_path, at)",
+        [],
+    );
+
+    // Files kept in the "Pinned" strip above the file list whatever the filter.
+    // Keyed by path like tag examples, so untracked files can be pinned too.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pinned_files (
+            path TEXT PRIMARY KEY,
+            position INTEGER NOT NULL,
+            pinned_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Paths already seen under roots with default tags, so a file only gets them
+    // the first time a scan finds it (and not again after its tags are removed)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS discovered_files (
+            path TEXT PRIMARY KEY,
+            root_id INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     conn.execute(
         "CREATE TABLE IF NOT EXISTS settings (
             key TEXT PRIMARY KEY,
@@ -154,6 +340,35 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         [],
     )?;
 
+    // Local diagnostics counters (see metrics.rs), one row per metric
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metrics (
+            name TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            total_ms REAL NOT NULL DEFAULT 0,
+            max_ms REAL NOT NULL DEFAULT 0,
+            last_ms REAL NOT NULL DEFAULT 0,
+            hits INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Long batch jobs in flight (see journal.rs); a row left over after a
+    // restart is a run that was killed
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS batch_journal (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            args TEXT NOT NULL,
+            total INTEGER NOT NULL,
+            done INTEGER NOT NULL DEFAULT 0,
+            started_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     conn.execute(
         "CREATE TABLE IF NOT EXISTS window_state (
             id INTEGER PRIMARY KEY CHECK (id = 1),
@@ -219,6 +434,18 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
     // Ensure files.root_id column exists for old installs
     let _ = conn.execute("ALTER TABLE files ADD COLUMN root_id INTEGER", []);
 
+    // Migration: indexes for tag filters, per-root listings, recency sorts and
+    // the tag tree (file_tags' primary key only covers lookups by file). Kept below the
+    // ALTER above, since old installs have no files.root_id before it
+    for index in [
+        "CREATE INDEX IF NOT EXISTS idx_file_tags_tag ON file_tags(tag_id, file_id)",
+        "CREATE INDEX IF NOT EXISTS idx_files_root ON files(root_id)",
+        "CREATE INDEX IF NOT EXISTS idx_files_modified ON files(last_modified)",
+        "CREATE INDEX IF NOT EXISTS idx_tags_parent ON tags(parent_id, position)",
+    ] {
+        conn.execute(index, [])?;
+    }
+
     // Migrate single root_directory to root_directories list if necessary
     let roots_json: Option<String> = conn
         .query_row(
@@ -254,47 +481,127 @@ pub fn init_db(app_handle: &AppHandle) -> Result<()> {
         }
     }
 
-    // Sync roots table with root_directories setting
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
-    let roots = get_root_directories(app_handle).unwrap_or_default();
-    for rp in &roots {
-        let _ = conn.execute(
-            "INSERT OR IGNORE INTO roots (path, created_at) VALUES (?1, ?2)",
-            params![rp, now],
-        );
-    }
-    // Remove stale roots not in settings
-    let mut stmt = conn.prepare("SELECT path FROM roots")?;
-    let existing: Vec<String> = stmt
-        .query_map([], |row| row.get(0))?
+    Ok(())
+}
+
+fn load_roots(conn: &Connection) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, path FROM roots")?;
+    let roots = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
         .collect::<Result<Vec<_>, _>>()?;
-    for ep in existing {
-        if !roots.iter().any(|r| r == &ep) {
-            let _ = conn.execute("DELETE FROM roots WHERE path = ?1", params![ep]);
-        }
+    Ok(roots)
+}
+
+// Deepest root containing `path`, compared by path components rather than by
+// string prefix so `C:\Data` doesn't claim files under `C:\Database`
+fn root_id_for(roots: &[(i64, String)], path: &str) -> Option<i64> {
+    let best = tagme_paths::longest_root(path, roots.iter().map(|(_, p)| p.as_str()))?;
+    roots.iter().find(|(_, p)| p == best).map(|(id, _)| *id)
+}
+
+// Recomputes files.root_id for every file; needed whenever the set of roots changes
+fn assign_root_ids(conn: &Connection) -> Result<()> {
+    let roots = load_roots(conn)?;
+    let mut stmt = conn.prepare("SELECT id, path FROM files")?;
+    let files = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    // Batch in a transaction unless a migration already opened one
+    let tx = if conn.is_autocommit() { Some(conn.unchecked_transaction()?) } else { None };
+    for (id, path) in files {
+        conn.execute(
+            "UPDATE files SET root_id = ?1 WHERE id = ?2 AND root_id IS NOT ?1",
+            params![root_id_for(&roots, &path), id],
+        )?;
     }
+    match tx {
+        Some(tx) => tx.commit(),
+        None => Ok(()),
+    }
+}
 
-    // Populate files.root_id by matching longest root path prefix
-    let mut roots_stmt = conn.prepare("SELECT id, path FROM roots")?;
-    let roots_rows = roots_stmt
-        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
-    let mut roots_list: Vec<(i64, String)> = Vec::new();
-    for r in roots_rows { if let Ok(pair) = r { roots_list.push(pair); } }
-    // For each root, assign files whose path starts with root
-    for (rid, rpath) in &roots_list {
-        let like = format!("{}%", rpath);
-        let _ = conn.execute(
-            "UPDATE files SET root_id = ?1 WHERE path LIKE ?2",
-            params![rid, like],
-        );
+// Resolves symlinks and `..` when the folder exists, then normalizes separators,
+// so one folder always maps to one root string
+pub fn canonical_root(path: &str) -> String {
+    let resolved = fs::canonicalize(path)
+        .ok()
+        .and_then(|p| p.to_str().map(strip_verbatim_prefix))
+        .unwrap_or_else(|| path.to_string());
+    tagme_paths::normalize(&resolved)
+}
+
+// fs::canonicalize returns `\\?\C:\...` and `\\?\UNC\server\share` on Windows
+fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
     }
+}
 
+// Moves stored file paths from under `from` to under `to`. A path that already
+// exists under `to` is left alone for the orphan cleanup to pick up.
+fn rebase_file_paths(conn: &Connection, from: &str, to: &str) -> Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    let mut stmt = conn.prepare("SELECT id, path FROM files")?;
+    let files = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, path) in files {
+        let Some(new_path) = tagme_paths::rebase(&path, from, to) else { continue };
+        if new_path == path {
+            continue;
+        }
+        if conn.execute("UPDATE OR IGNORE files SET path = ?1 WHERE id = ?2", params![new_path, id])? > 0 {
+            move_path_refs(conn, &path, &new_path)?;
+        }
+    }
     Ok(())
 }
 
+// Folds roots that name the same folder (trailing separators, case on Windows,
+// symlinked aliases) into the first one, stored in canonical form
+fn dedupe_roots(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, path FROM roots ORDER BY position, id")?;
+    let roots = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut kept: Vec<(i64, String, String)> = Vec::new();
+    let mut dropped: Vec<(i64, i64)> = Vec::new();
+    for (id, path) in roots {
+        let canonical = canonical_root(&path);
+        rebase_file_paths(conn, &path, &canonical)?;
+        match kept.iter().find(|(_, _, c)| tagme_paths::same_path(c, &canonical)) {
+            Some((keep_id, _, _)) => dropped.push((id, *keep_id)),
+            None => kept.push((id, path, canonical)),
+        }
+    }
+    for (id, keep_id) in dropped {
+        // A pinned duplicate passes its pin on
+        conn.execute(
+            "UPDATE roots SET is_default = 1 WHERE id = ?1 AND EXISTS (SELECT 1 FROM roots WHERE id = ?2 AND is_default = 1)",
+            params![keep_id, id],
+        )?;
+        conn.execute("DELETE FROM roots WHERE id = ?1", params![id])?;
+    }
+    for (id, path, canonical) in &kept {
+        if path != canonical {
+            conn.execute("UPDATE roots SET path = ?1 WHERE id = ?2", params![canonical, id])?;
+        }
+    }
+    // init_db re-syncs the roots table from this setting on every start
+    let paths: Vec<&str> = kept.iter().map(|(_, _, c)| c.as_str()).collect();
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('root_directories', ?1)",
+        params![serde_json::to_string(&paths).unwrap_or("[]".to_string())],
+    )?;
+    assign_root_ids(conn)
+}
+
 // Settings functions
 pub fn set_root_directory(app_handle: &AppHandle, path: String) -> Result<()> {
     // Backward compatibility: store single root in settings and ensure roots table
@@ -336,7 +643,8 @@ pub fn set_root_directories(app_handle: &AppHandle, paths: Vec<String>) -> Resul
     let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
     for p in &paths {
         let _ = conn.execute(
-            "INSERT OR IGNORE INTO roots (path, created_at) VALUES (?1, ?2)",
+            "INSERT OR IGNORE INTO roots (path, created_at, position)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM roots))",
             params![p, now],
         );
     }
@@ -355,198 +663,820 @@ pub fn set_root_directories(app_handle: &AppHandle, paths: Vec<String>) -> Resul
 
 pub fn get_root_directories(app_handle: &AppHandle) -> Result<Vec<String>> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    let mut stmt = conn.prepare("SELECT path FROM roots ORDER BY path")?;
+    let mut stmt = conn.prepare("SELECT path FROM roots ORDER BY position, id")?;
     let paths = stmt
         .query_map([], |row| row.get(0))?
         .collect::<Result<Vec<String>, _>>()?;
     Ok(paths)
 }
 
-pub fn add_root_directory(app_handle: &AppHandle, path: String) -> Result<()> {
+// Persist the chip order chosen by dragging in the toolbar
+pub fn reorder_root_directories(app_handle: &AppHandle, paths: Vec<String>) -> Result<()> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
-    conn.execute(
-        "INSERT OR IGNORE INTO roots (path, created_at) VALUES (?1, ?2)",
-        params![path.clone(), now],
-    )?;
-    // Also sync settings list
-    let mut list = get_root_directories(app_handle)?;
-    if !list.iter().any(|p| p == &path) { list.push(path.clone()); }
-    set_root_directories(app_handle, list)?;
-    // Assign root_id for existing files under this root
-    let rid: i64 = conn.query_row(
-        "SELECT id FROM roots WHERE path = ?1",
-        params![path.clone()],
-        |row| row.get(0),
-    )?;
-    let like = format!("{}%", path);
-    let _ = conn.execute("UPDATE files SET root_id = ?1 WHERE path LIKE ?2", params![rid, like]);
+    for (index, p) in paths.iter().enumerate() {
+        conn.execute(
+            "UPDATE roots SET position = ?1 WHERE path = ?2",
+            params![index as i32, p],
+        )?;
+    }
+    let value = serde_json::to_string(&paths).unwrap_or("[]".to_string());
+    let _ = conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('root_directories', ?1)",
+        params![value],
+    );
     Ok(())
 }
 
-pub fn remove_root_directory(app_handle: &AppHandle, path: String) -> Result<()> {
+// Pin (or unpin with None) the root that is auto-filtered on startup
+pub fn set_default_root(app_handle: &AppHandle, path: Option<String>) -> Result<()> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    conn.execute("DELETE FROM roots WHERE path = ?1", params![path.clone()])?;
-    // Also sync settings list
-    let mut list = get_root_directories(app_handle)?;
-    list.retain(|p| p != &path);
-    set_root_directories(app_handle, list)
+    conn.execute("UPDATE roots SET is_default = 0", [])?;
+    if let Some(p) = path {
+        conn.execute("UPDATE roots SET is_default = 1 WHERE path = ?1", params![p])?;
+    }
+    Ok(())
 }
 
-pub fn delete_files_under_root(app_handle: &AppHandle, root_path: String) -> Result<usize> {
+pub fn get_default_root(app_handle: &AppHandle) -> Result<Option<String>> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    // Prefer root_id-based deletion
-    let rid_opt: Option<i64> = conn
-        .query_row(
-            "SELECT id FROM roots WHERE path = ?1",
-            params![root_path.clone()],
-            |row| row.get(0),
-        )
-        .ok();
-    let affected = if let Some(rid) = rid_opt {
-        conn.execute("DELETE FROM files WHERE root_id = ?1", params![rid])?
-    } else {
-        let pattern = format!("{}%", root_path);
-        conn.execute("DELETE FROM files WHERE path LIKE ?1", params![pattern])?
-    };
-    Ok(affected as usize)
+    let result = conn.query_row(
+        "SELECT path FROM roots WHERE is_default = 1 LIMIT 1",
+        [],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(p) => Ok(Some(p)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
-pub fn purge_all_files(app_handle: &AppHandle) -> Result<usize> {
-    let db_path = get_db_path(app_handle);
-    eprintln!("[DB] purge_all_files using path: {}", db_path.to_string_lossy());
-    let conn = Connection::open(&db_path)?;
-    let mut count_before: i64 = 0;
-    if let Ok(mut stmt) = conn.prepare("SELECT COUNT(*) FROM files") {
-        count_before = stmt.query_row([], |row| row.get(0)).unwrap_or(0);
-    }
-    eprintln!("[DB] files count before delete: {}", count_before);
-    let affected = conn.execute("DELETE FROM files", [])?;
-    let mut count_after: i64 = 0;
-    if let Ok(mut stmt) = conn.prepare("SELECT COUNT(*) FROM files") {
-        count_after = stmt.query_row([], |row| row.get(0)).unwrap_or(0);
-    }
-    eprintln!("[DB] files count after delete: {} (affected={})", count_after, affected);
-    Ok(affected as usize)
+pub fn set_root_local_only(app_handle: &AppHandle, path: &str, local_only: bool) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("UPDATE roots SET local_only = ?1 WHERE path = ?2", params![local_only, path])?;
+    Ok(())
 }
 
-pub fn get_db_path_string(app_handle: &AppHandle) -> String {
-    get_db_path(app_handle).to_string_lossy().to_string()
+pub fn get_local_only_roots(app_handle: &AppHandle) -> Result<Vec<String>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT path FROM roots WHERE local_only = 1")?;
+    let roots = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<String>>>()?;
+    Ok(roots)
 }
 
-pub fn get_files_count(app_handle: &AppHandle) -> Result<u32> {
+// Default tags by root path, for roots that have any
+pub fn get_root_default_tags(app_handle: &AppHandle) -> Result<std::collections::HashMap<String, Vec<u32>>> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    let cnt: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))?;
-    Ok(cnt as u32)
+    Ok(root_default_tags(&conn)?.into_iter().map(|(_, path, tags)| (path, tags)).collect())
 }
 
-// File hashing function
-fn hash_file_content(path: &Path) -> Result<String, std::io::Error> {
-    let file = fs::File::open(path)?;
-    let mut reader = std::io::BufReader::new(file);
-    let mut hasher = Sha256::new();
-    std::io::copy(&mut reader, &mut hasher)?;
-    let hash = hasher.finalize();
-    Ok(format!("{:x}", hash))
+fn root_default_tags(conn: &Connection) -> Result<Vec<(i64, String, Vec<u32>)>> {
+    let mut stmt = conn.prepare("SELECT id, path, default_tags FROM roots")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, path, json)| (id, path, serde_json::from_str::<Vec<u32>>(&json).unwrap_or_default()))
+        .filter(|(_, _, tags)| !tags.is_empty())
+        .collect())
 }
 
-// Lightweight file scanning - just list files, no hashing or DB operations
-pub fn scan_directory_lightweight(root_path: String) -> Result<Vec<FileListItem>, std::io::Error> {
-    eprintln!("🔍 Starting lightweight scan for directory: {}", root_path);
-    
-    let mut scanned_items = Vec::new();
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
+// Sets the tags new files under a root get. The files listed in `existing` are
+// already there, so they count as discovered and keep their tags as they are.
+pub fn set_root_default_tags(app_handle: &AppHandle, path: &str, tag_ids: Vec<u32>, existing: &[String]) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    set_default_tags(&conn, path, &tag_ids, existing)
+}
 
-    // Non-recursive scan: read both files and directories in the directory
-    println!("📂 Reading directory entries...");
-    for entry in fs::read_dir(&root_path)? {
-        if let Ok(entry) = entry {
-            if let Ok(file_type) = entry.file_type() {
-                let path = entry.path();
-                let path_str = path.to_string_lossy().to_string();
-                
-                if file_type.is_file() {
-                    // Regular file
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        let size_bytes = metadata.len();
-                        let last_modified = metadata
-                            .modified()
-                            .ok()
-                            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs() as i64)
-                            .unwrap_or(now);
-
-                        scanned_items.push(FileListItem {
-                            path: path_str,
-                            size_bytes,
-                            last_modified,
-                            is_directory: false,
-                        });
-                    }
-                } else if file_type.is_dir() {
-                    // Directory - include it but don't recurse
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        let last_modified = metadata
-                            .modified()
-                            .ok()
-                            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs() as i64)
-                            .unwrap_or(now);
-
-                        scanned_items.push(FileListItem {
-                            path: path_str,
-                            size_bytes: 0, // Directories have no size
-                            last_modified,
-                            is_directory: true,
-                        });
-                    }
-                }
-            }
+fn set_default_tags(conn: &Connection, path: &str, tag_ids: &[u32], existing: &[String]) -> Result<()> {
+    let json = serde_json::to_string(tag_ids).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let tx = conn.unchecked_transaction()?;
+    let root_id: i64 = tx.query_row("SELECT id FROM roots WHERE path = ?1", params![path], |row| row.get(0))?;
+    tx.execute("UPDATE roots SET default_tags = ?1 WHERE id = ?2", params![json, root_id])?;
+    tx.execute("DELETE FROM discovered_files WHERE root_id = ?1", params![root_id])?;
+    if !tag_ids.is_empty() {
+        let mut insert = tx.prepare("INSERT OR IGNORE INTO discovered_files (path, root_id) VALUES (?1, ?2)")?;
+        for file in existing {
+            insert.execute(params![file, root_id])?;
         }
     }
+    tx.commit()
+}
 
-    eprintln!("✅ Lightweight scan complete! Found {} items ({} files + {} folders)", 
-        scanned_items.len(),
-        scanned_items.iter().filter(|i| !i.is_directory).count(),
-        scanned_items.iter().filter(|i| i.is_directory).count()
-    );
-    Ok(scanned_items)
+// Local files in `items` that a scan finds for the first time under a root with
+// default tags, grouped by the tags they get; they're marked as discovered
+pub fn claim_discovered(app_handle: &AppHandle, items: &[FileListItem]) -> Result<Vec<(Vec<u32>, Vec<String>)>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    // Tagging downloads remote files and extracts archive entries; neither is worth it for a rule
+    let paths = items
+        .iter()
+        .filter(|i| !i.is_directory && !crate::remote::is_remote(&i.path) && !crate::archives::is_virtual(&i.path))
+        .map(|i| i.path.as_str());
+    claim_new_files(&conn, paths)
 }
 
-pub fn scan_directories_lightweight(root_paths: Vec<String>) -> Result<Vec<FileListItem>, std::io::Error> {
-    let mut all = Vec::new();
-    for root in root_paths {
-        let mut items = scan_directory_lightweight(root)?;
-        all.append(&mut items);
+fn claim_new_files<'a>(conn: &Connection, paths: impl IntoIterator<Item = &'a str>) -> Result<Vec<(Vec<u32>, Vec<String>)>> {
+    let rules = root_default_tags(conn)?;
+    if rules.is_empty() {
+        return Ok(Vec::new());
     }
-    Ok(all)
+    let roots = load_roots(conn)?;
+    // Deleted tags are dropped from the rule rather than failing the whole batch
+    let mut stmt = conn.prepare("SELECT id FROM tags")?;
+    let known = stmt
+        .query_map([], |row| row.get::<_, u32>(0))?
+        .collect::<Result<std::collections::HashSet<_>, _>>()?;
+    let mut batches: std::collections::BTreeMap<Vec<u32>, Vec<String>> = std::collections::BTreeMap::new();
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert = tx.prepare("INSERT OR IGNORE INTO discovered_files (path, root_id) VALUES (?1, ?2)")?;
+        for path in paths {
+            // A file belongs to its deepest root, whose rule alone applies
+            let Some(root_id) = root_id_for(&roots, path) else { continue };
+            let Some((_, _, tags)) = rules.iter().find(|(id, _, _)| *id == root_id) else { continue };
+            let tags: Vec<u32> = tags.iter().copied().filter(|t| known.contains(t)).collect();
+            if !tags.is_empty() && insert.execute(params![path, root_id])? > 0 {
+                batches.entry(tags).or_default().push(path.to_string());
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(batches.into_iter().collect())
+}
+
+// Data migrations that can take a while on large libraries. Unlike the ALTERs in
+// `init_db` they run after an update behind a progress screen, tracked by the
+// `schema_version` setting. Append only; never renumber.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+    // Runs after `sql`, in the same transaction, for steps that need Rust-side logic
+    pub apply: Option<fn(&Connection) -> Result<()>>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Remove tag links to deleted files or tags",
+        sql: "DELETE FROM file_tags
+              WHERE file_id NOT IN (SELECT id FROM files)
+                 OR tag_id NOT IN (SELECT id FROM tags)",
+        apply: None,
+    },
+    Migration {
+        version: 2,
+        description: "Seed tag history from existing tags",
+        sql: "INSERT INTO tag_history (file_path, tag_id, tag_name, action, at)
+              SELECT f.path, t.id, t.name, 'added', ft.created_at
+              FROM file_tags ft
+              JOIN files f ON f.id = ft.file_id
+              JOIN tags t ON t.id = ft.tag_id
+              WHERE NOT EXISTS (
+                  SELECT 1 FROM tag_history h WHERE h.file_path = f.path AND h.tag_id = t.id
+              )",
+        apply: None,
+    },
+    Migration {
+        version: 3,
+        description: "Merge tags whose names differ only by case or spacing",
+        sql: "",
+        apply: Some(merge_duplicate_tags),
+    },
+    Migration {
+        version: 4,
+        description: "Merge duplicate root folders and store roots in canonical form",
+        sql: "",
+        apply: Some(dedupe_roots),
+    },
+    Migration {
+        version: 5,
+        description: "Record the filesystem identity of tracked files for instant re-linking",
+        sql: "",
+        apply: Some(backfill_file_identity),
+    },
+    Migration {
+        version: 6,
+        description: "Index file names and paths for search",
+        sql: "INSERT INTO files_fts (files_fts) VALUES ('rebuild')",
+        apply: None,
+    },
+];
+
+// Moves file links and children of `source` onto `target`, then deletes `source`
+fn merge_tag_into(conn: &Connection, source: u32, target: u32) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    // Each moved link shows up in the file's history as the target added (unless
+    // the file had it already) and the source removed
+    conn.execute(
+        "INSERT INTO tag_history (file_path, tag_id, tag_name, action, at)
+         SELECT f.path, t.id, t.name, 'added', ?3
+         FROM file_tags ft
+         JOIN files f ON f.id = ft.file_id
+         JOIN tags t ON t.id = ?1
+         WHERE ft.tag_id = ?2
+           AND NOT EXISTS (SELECT 1 FROM file_tags o WHERE o.file_id = ft.file_id AND o.tag_id = ?1)",
+        params![target, source, now],
+    )?;
+    conn.execute(
+        "INSERT INTO tag_history (file_path, tag_id, tag_name, action, at)
+         SELECT f.path, t.id, t.name, 'removed', ?2
+         FROM file_tags ft
+         JOIN files f ON f.id = ft.file_id
+         JOIN tags t ON t.id = ft.tag_id
+         WHERE ft.tag_id = ?1",
+        params![source, now],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO file_tags (file_id, tag_id, created_at)
+         SELECT file_id, ?1, created_at FROM file_tags WHERE tag_id = ?2",
+        params![target, source],
+    )?;
+    conn.execute("DELETE FROM file_tags WHERE tag_id = ?1", params![source])?;
+    conn.execute(
+        "INSERT OR IGNORE INTO tag_examples (tag_id, file_path, positive, created_at)
+         SELECT ?1, file_path, positive, created_at FROM tag_examples WHERE tag_id = ?2",
+        params![target, source],
+    )?;
+    conn.execute("DELETE FROM tag_examples WHERE tag_id = ?1", params![source])?;
+    // A child named like one of target's would break UNIQUE(name, parent_id) once
+    // moved, so it's folded into that child first; the rest are moved as they are
+    let siblings = child_tags(conn, target)?;
+    for (child, name) in child_tags(conn, source)? {
+        if let Some((twin, _)) = siblings.iter().find(|(_, n)| tag_name_key(n) == tag_name_key(&name)) {
+            merge_tag_into(conn, child, *twin)?;
+        }
+    }
+    conn.execute("UPDATE tags SET parent_id = ?1 WHERE parent_id = ?2", params![target, source])?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![source])?;
+    reorder_tags_in_parent(conn, Some(target))
+}
+
+fn child_tags(conn: &Connection, parent: u32) -> Result<Vec<(u32, String)>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM tags WHERE parent_id = ?1")?;
+    let children = stmt.query_map(params![parent], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(children)
+}
+
+// Folds sibling tags with the same normalized name into the oldest one, moving file
+// links and children over, then normalizes the surviving names. Repeats because
+// merging two parents can make their children collide.
+fn merge_duplicate_tags(conn: &Connection) -> Result<()> {
+    loop {
+        let mut stmt = conn.prepare("SELECT id, name, parent_id FROM tags ORDER BY id")?;
+        let tags = stmt
+            .query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<u32>>(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut keepers: std::collections::HashMap<(Option<u32>, String), u32> = std::collections::HashMap::new();
+        let mut merged = 0;
+        for (id, name, parent_id) in tags {
+            let Some(&keep) = keepers.get(&(parent_id, tag_name_key(&name))) else {
+                keepers.insert((parent_id, tag_name_key(&name)), id);
+                continue;
+            };
+            merge_tag_into(conn, id, keep)?;
+            eprintln!("🏷️  [DB] Merged duplicate tag {} (\"{}\") into {}", id, name, keep);
+            merged += 1;
+        }
+        if merged == 0 {
+            break;
+        }
+    }
+
+    let mut stmt = conn.prepare("SELECT id, name FROM tags")?;
+    let names = stmt
+        .query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, name) in names {
+        let normalized = normalize_tag_name(&name);
+        if normalized != name {
+            conn.execute("UPDATE tags SET name = ?1 WHERE id = ?2", params![normalized, id])?;
+        }
+    }
+    Ok(())
 }
 
-// Prune files from DB that no longer exist on disk
-pub fn prune_missing_files(app_handle: &AppHandle) -> Result<()> {
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+fn schema_version(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'schema_version'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0)
+}
+
+pub fn pending_migrations(app_handle: &AppHandle) -> Result<Vec<PendingMigration>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let current = schema_version(&conn);
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+        .map(|m| PendingMigration { version: m.version, description: m.description.to_string() })
+        .collect())
+}
+
+// Runs one migration and bumps `schema_version` atomically with it.
+pub fn run_migration(app_handle: &AppHandle, version: i64) -> Result<()> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    if schema_version(&conn) >= version {
+        return Ok(());
+    }
+    let Some(migration) = MIGRATIONS.iter().find(|m| m.version == version) else {
+        return Err(rusqlite::Error::InvalidParameterName(format!("unknown migration {}", version)));
+    };
+    let tx = conn.transaction()?;
+    tx.execute_batch(migration.sql)?;
+    if let Some(apply) = migration.apply {
+        apply(&tx)?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', ?1)",
+        params![version.to_string()],
+    )?;
+    tx.commit()?;
+    eprintln!("✅ Migration {} applied: {}", version, migration.description);
+    Ok(())
+}
+
+pub fn get_setting(app_handle: &AppHandle, key: &str) -> Result<Option<String>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let result = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn set_setting(app_handle: &AppHandle, key: &str, value: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+// No watchers and no automatic rescans: the Scan button is the only refresh
+pub fn manual_refresh_enabled(app_handle: &AppHandle) -> bool {
+    get_setting(app_handle, "manual_refresh").ok().flatten().as_deref() == Some("true")
+}
+
+// Last tag filter per root, stored as one JSON map in the `tag_filters` setting.
+// The "" key holds the filter used while all roots are shown.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TagFilter {
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+}
+
+fn load_tag_filters(app_handle: &AppHandle) -> Result<std::collections::HashMap<String, TagFilter>> {
+    Ok(get_setting(app_handle, "tag_filters")?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+pub fn get_tag_filter(app_handle: &AppHandle, root: Option<String>) -> Result<Option<TagFilter>> {
+    Ok(load_tag_filters(app_handle)?.remove(&root.unwrap_or_default()))
+}
+
+pub fn set_tag_filter(app_handle: &AppHandle, root: Option<String>, filter: TagFilter) -> Result<()> {
+    let mut filters = load_tag_filters(app_handle)?;
+    filters.insert(root.unwrap_or_default(), filter);
+    let json = serde_json::to_string(&filters).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    set_setting(app_handle, "tag_filters", &json)
+}
+
+// Adds a root in canonical form and returns the stored path. Re-adding an existing
+// root (under any spelling) is a no-op. A root nested inside another, or containing
+// one, is only accepted with `allow_nested`; files then belong to the deepest root
+// (see tagme_paths::longest_root).
+pub fn add_root_directory(app_handle: &AppHandle, path: String, allow_nested: bool) -> Result<String> {
+    let path = canonical_root(&path);
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let roots = load_roots(&conn)?;
+    if let Some((_, existing)) = roots.iter().find(|(_, r)| tagme_paths::same_path(r, &path)) {
+        return Ok(existing.clone());
+    }
+    let overlap = roots.iter().find_map(|(_, r)| {
+        if tagme_paths::is_under(&path, r) {
+            Some(format!("{} is already inside the root {}", path, r))
+        } else if tagme_paths::is_under(r, &path) {
+            Some(format!("{} contains the existing root {}", path, r))
+        } else {
+            None
+        }
+    });
+    if let (Some(reason), false) = (overlap, allow_nested) {
+        return Err(rusqlite::Error::InvalidParameterName(reason));
+    }
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
+    conn.execute(
+        "INSERT OR IGNORE INTO roots (path, created_at, position)
+         VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM roots))",
+        params![path.clone(), now],
+    )?;
+    // Also sync settings list
+    let mut list = get_root_directories(app_handle)?;
+    if !list.iter().any(|p| p == &path) { list.push(path.clone()); }
+    set_root_directories(app_handle, list)?;
+    assign_root_ids(&conn)?;
+    Ok(path)
+}
+
+pub fn remove_root_directory(app_handle: &AppHandle, path: String) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute(
+        "DELETE FROM discovered_files WHERE root_id IN (SELECT id FROM roots WHERE path = ?1)",
+        params![path.clone()],
+    )?;
+    conn.execute("DELETE FROM roots WHERE path = ?1", params![path.clone()])?;
+    // Also sync settings list
+    let mut list = get_root_directories(app_handle)?;
+    list.retain(|p| p != &path);
+    set_root_directories(app_handle, list)?;
+    // Files of a removed nested root fall back to the enclosing root
+    assign_root_ids(&conn)
+}
+
+// Deletes file rows under `root_path` that no other root owns, so purging an
+// outer root keeps the files of roots nested inside it, and purging a nested root
+// keeps files the enclosing root still covers
+pub fn delete_files_under_root(app_handle: &AppHandle, root_path: String) -> Result<usize> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let roots = load_roots(&conn)?;
+    let others: Vec<&str> = roots
+        .iter()
+        .map(|(_, p)| p.as_str())
+        .filter(|p| !tagme_paths::same_path(p, &root_path))
+        .collect();
+    let mut stmt = conn.prepare("SELECT id, path FROM files")?;
+    let ids = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|(_, p)| {
+            tagme_paths::is_under(p, &root_path) && tagme_paths::longest_root(p, others.iter().copied()).is_none()
+        })
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    let tx = conn.unchecked_transaction()?;
+    for id in &ids {
+        tx.execute("DELETE FROM files WHERE id = ?1", params![id])?;
+    }
+    tx.commit()?;
+    Ok(ids.len())
+}
+
+pub fn purge_all_files(app_handle: &AppHandle) -> Result<usize> {
+    let db_path = get_db_path(app_handle);
+    eprintln!("[DB] purge_all_files using path: {}", db_path.to_string_lossy());
+    let conn = Connection::open(&db_path)?;
+    let mut count_before: i64 = 0;
+    if let Ok(mut stmt) = conn.prepare("SELECT COUNT(*) FROM files") {
+        count_before = stmt.query_row([], |row| row.get(0)).unwrap_or(0);
+    }
+    eprintln!("[DB] files count before delete: {}", count_before);
+    let affected = conn.execute("DELETE FROM files", [])?;
+    let mut count_after: i64 = 0;
+    if let Ok(mut stmt) = conn.prepare("SELECT COUNT(*) FROM files") {
+        count_after = stmt.query_row([], |row| row.get(0)).unwrap_or(0);
+    }
+    eprintln!("[DB] files count after delete: {} (affected={})", count_after, affected);
+    Ok(affected as usize)
+}
+
+// Backups older than the newest MAX_BACKUPS are deleted
+const MAX_BACKUPS: usize = 10;
+
+// Consistent snapshot of the database in <app data>/backups, named after the
+// operation that triggered it. VACUUM INTO also folds in the WAL.
+pub fn backup_database(app_handle: &AppHandle, reason: &str) -> Result<std::path::PathBuf> {
+    let db_path = get_db_path(app_handle);
+    let dir = db_path.with_file_name("backups");
+    fs::create_dir_all(&dir).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let millis = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let reason: String = reason.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    let target = dir.join(format!("tagme_app-{}-{}.db", millis, reason));
+    let conn = Connection::open(&db_path)?;
+    conn.execute("VACUUM INTO ?1", params![target.to_string_lossy()])?;
+
+    // Names start with the timestamp, so sorting by name sorts by age
+    let mut backups: Vec<_> = fs::read_dir(&dir)
+        .map(|it| it.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    backups.retain(|p| p.extension().is_some_and(|e| e == "db"));
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in backups.into_iter().take(excess) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(target)
+}
+
+// Opens an existing database without writing to it; a missing file is an error
+// instead of a new empty database
+fn open_source(source: &Path) -> Result<Connection> {
+    Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+}
+
+// A consistent snapshot of the database at `source` (WAL included) at `target`
+pub fn copy_database(source: &Path, target: &Path) -> Result<()> {
+    let conn = open_source(source)?;
+    conn.execute("VACUUM INTO ?1", params![target.to_string_lossy()])?;
+    Ok(())
+}
+
+// Checks a copy made by copy_database: SQLite's integrity check, then the row
+// count of every table against the database at `source`
+pub fn verify_copy(source: &Path, copy: &Path) -> std::result::Result<(), String> {
+    let live = open_source(source).map_err(|e| e.to_string())?;
+    let copied = Connection::open(copy).map_err(|e| e.to_string())?;
+    let check: String = copied
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if check != "ok" {
+        return Err(check);
+    }
+    let tables: Vec<String> = live
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(|e| e.to_string())?;
+    for table in tables {
+        let sql = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+        let count = |conn: &Connection| conn.query_row(&sql, [], |row| row.get::<_, i64>(0));
+        let expected = count(&live).map_err(|e| e.to_string())?;
+        match count(&copied) {
+            Ok(n) if n == expected => {}
+            Ok(n) => return Err(format!("{}: {} row(s) instead of {}", table, n, expected)),
+            Err(e) => return Err(format!("{}: {}", table, e)),
+        }
+    }
+    Ok(())
+}
+
+pub fn get_db_path_string(app_handle: &AppHandle) -> String {
+    get_db_path(app_handle).to_string_lossy().to_string()
+}
+
+pub fn get_files_count(app_handle: &AppHandle) -> Result<u32> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let cnt: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))?;
+    Ok(cnt as u32)
+}
+
+// Filesystem identity of a file: (device, inode) on Unix, (volume serial, file
+// index) on Windows. It survives renames and moves within a volume, so a moved file
+// can be recognized without hashing it again.
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(i64, i64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.dev() as i64, metadata.ino() as i64))
+}
+
+#[cfg(windows)]
+fn file_identity(path: &Path) -> Option<(i64, i64)> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION};
+    let file = fs::File::open(path).ok()?;
+    // SAFETY: the handle stays open for the call and `info` is a plain C struct
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return None;
+    }
+    let index = ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64;
+    Some((info.dwVolumeSerialNumber as i64, index as i64))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn file_identity(_path: &Path) -> Option<(i64, i64)> {
+    None
+}
+
+fn store_file_identity(conn: &Connection, file_id: u32, path: &Path) -> Result<()> {
+    let (volume_id, file_index) = file_identity(path).unzip();
+    conn.execute(
+        "UPDATE files SET volume_id = ?1, file_index = ?2 WHERE id = ?3",
+        params![volume_id, file_index, file_id],
+    )?;
+    Ok(())
+}
+
+fn backfill_file_identity(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, path FROM files WHERE is_directory = 0 AND volume_id IS NULL")?;
+    let files = stmt
+        .query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, path) in files {
+        store_file_identity(conn, id, Path::new(&path))?;
+    }
+    Ok(())
+}
+
+// File hashing function
+pub fn hash_file_content(path: &Path) -> Result<String, std::io::Error> {
+    let file = fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut reader, &mut hasher)?;
+    let hash = hasher.finalize();
+    Ok(format!("{:x}", hash))
+}
+
+// Entries listed per directory before the rest is held back; each "load more"
+// raises a root's limit by this much
+pub const SCAN_PAGE_SIZE: usize = 2000;
+// Upper bound on items listed by one multi-root scan
+pub const SCAN_MAX_TOTAL: usize = 20000;
+
+// Entries a limited scan left out of a root's listing
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanOverflow {
+    pub root: String,
+    pub shown: usize,
+    pub hidden: usize,
+}
+
+// Lightweight file scanning - just list files, no hashing or DB operations
+pub fn scan_directory_lightweight(root_path: String) -> Result<Vec<FileListItem>, std::io::Error> {
+    scan_directory_limited(&root_path, usize::MAX).map(|(items, _)| items)
+}
+
+// Lists at most `limit` entries (by name) and returns them with the directory's
+// total entry count. Only the listed entries are stat'ed, so a 50k-entry folder
+// costs one read_dir rather than 50k metadata calls.
+pub fn scan_directory_limited(root_path: &str, limit: usize) -> Result<(Vec<FileListItem>, usize), std::io::Error> {
+    eprintln!("🔍 Starting lightweight scan for directory: {}", root_path);
+    
+    let mut scanned_items = Vec::new();
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Non-recursive scan: read both files and directories in the directory
+    println!("📂 Reading directory entries...");
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(root_path)?.filter_map(|e| e.ok()).collect();
+    let total = entries.len();
+    if total > limit {
+        entries.sort_by_key(|e| e.file_name());
+        entries.truncate(limit);
+    }
+    for entry in entries {
+        if let Ok(file_type) = entry.file_type() {
+            let path = entry.path();
+            let path_str = path.to_string_lossy().to_string();
+            
+            if file_type.is_file() {
+                // Regular file
+                if let Ok(metadata) = fs::metadata(&path) {
+                    let size_bytes = metadata.len();
+                    let last_modified = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs() as i64)
+                        .unwrap_or(now);
+
+                    scanned_items.push(FileListItem {
+                        path: path_str,
+                        size_bytes,
+                        last_modified,
+                        is_directory: false,
+                    });
+                }
+            } else if file_type.is_dir() {
+                // Directory - include it but don't recurse
+                if let Ok(metadata) = fs::metadata(&path) {
+                    let last_modified = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs() as i64)
+                        .unwrap_or(now);
+
+                    scanned_items.push(FileListItem {
+                        path: path_str,
+                        size_bytes: 0, // Directories have no size
+                        last_modified,
+                        is_directory: true,
+                    });
+                }
+            }
+        }
+    }
+
+    eprintln!("✅ Lightweight scan complete! Found {} items ({} files + {} folders), {} entries total", 
+        scanned_items.len(),
+        scanned_items.iter().filter(|i| !i.is_directory).count(),
+        scanned_items.iter().filter(|i| i.is_directory).count(),
+        total
+    );
+    Ok((scanned_items, total))
+}
+
+// Scans each root up to `limit_for(root)` entries and SCAN_MAX_TOTAL overall,
+// reporting what was held back per root
+pub fn scan_directories_lightweight(
+    root_paths: Vec<String>,
+    limit_for: impl Fn(&str) -> usize,
+) -> Result<(Vec<FileListItem>, Vec<ScanOverflow>), std::io::Error> {
+    let mut all = Vec::new();
+    let mut overflow = Vec::new();
+    // Nested roots are walked by their enclosing root too; list each path once
+    let mut seen = std::collections::HashSet::new();
+    for root in root_paths {
+        let limit = limit_for(&root).min(SCAN_MAX_TOTAL.saturating_sub(all.len()));
+        let (items, total) = scan_directory_limited(&root, limit)?;
+        all.extend(items.into_iter().filter(|item| seen.insert(item.path.clone())));
+        if total > limit {
+            overflow.push(ScanOverflow { root, shown: limit, hidden: total - limit });
+        }
+    }
+    Ok((all, overflow))
+}
+
+// Prune files from DB that no longer exist on disk. Tagged files are only marked
+// missing so the re-link tool can reattach their tags after a move; a marked file
+// that shows up again at its old path is restored. Files under `unreachable`
+// roots are left alone: their drive or share is gone, not the files.
+pub fn prune_missing_files(app_handle: &AppHandle, unreachable: &[String]) -> Result<()> {
     let conn = Connection::open(get_db_path(app_handle))?;
     
     // Get all files from DB
-    let mut stmt = conn.prepare("SELECT id, path FROM files")?;
+    let mut stmt = conn.prepare(
+        "SELECT id, path, missing_since IS NOT NULL,
+                EXISTS (SELECT 1 FROM file_tags ft WHERE ft.file_id = files.id)
+         FROM files",
+    )?;
     let files_iter = stmt.query_map([], |row| {
-        Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+        Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?, row.get::<_, bool>(3)?))
     })?;
 
     let mut ids_to_delete = Vec::new();
+    let mut ids_to_mark = Vec::new();
+    let mut ids_to_restore = Vec::new();
 
     for file_result in files_iter {
-        if let Ok((id, path)) = file_result {
-            if !Path::new(&path).exists() {
-                eprintln!("🗑️ File not found on disk, marking for deletion: {}", path);
-                ids_to_delete.push(id);
+        if let Ok((id, path, marked, tagged)) = file_result {
+            // Remote roots are listed over the network, not stat'ed
+            if crate::remote::is_remote(&path)
+                || tagme_paths::longest_root(&path, unreachable.iter().map(String::as_str)).is_some()
+            {
+                continue;
+            }
+            // An archive entry goes with its archive
+            let exists = Path::new(crate::archives::split(&path).map_or(path.as_str(), |(archive, _)| archive)).exists();
+            if exists && marked {
+                ids_to_restore.push(id);
+            } else if !exists && !marked {
+                if tagged {
+                    eprintln!("❓ Tagged file not found on disk, marking missing: {}", path);
+                    ids_to_mark.push(id);
+                } else {
+                    eprintln!("🗑️ File not found on disk, marking for deletion: {}", path);
+                    ids_to_delete.push(id);
+                }
             }
         }
     }
 
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    for id in &ids_to_mark {
+        conn.execute("UPDATE files SET missing_since = ?1 WHERE id = ?2", params![now, id])?;
+    }
+    for id in &ids_to_restore {
+        conn.execute("UPDATE files SET missing_since = NULL WHERE id = ?1", params![id])?;
+    }
+
     if !ids_to_delete.is_empty() {
+        crate::safety::before_destructive(app_handle, "prune").map_err(rusqlite::Error::InvalidParameterName)?;
         eprintln!("🗑️ Pruning {} missing files from database...", ids_to_delete.len());
         // Delete in batches or one by one
         for id in ids_to_delete {
@@ -569,10 +1499,20 @@ pub fn hash_and_insert_file(app_handle: &AppHandle, path: String) -> Result<u32>
         .unwrap()
         .as_secs() as i64;
 
-    let path_obj = Path::new(&path);
-    
+    // Remote files and archive entries are hashed from their cached copy, which
+    // keeps the mtime they are listed with
+    let remote = crate::remote::is_remote(&path) || crate::archives::is_virtual(&path);
+    let local = if crate::archives::is_virtual(&path) {
+        crate::archives::extract(app_handle, &path).map_err(rusqlite::Error::InvalidParameterName)?
+    } else if remote {
+        crate::remote::local_copy(app_handle, &path).map_err(rusqlite::Error::InvalidParameterName)?
+    } else {
+        std::path::PathBuf::from(&path)
+    };
+    let path_obj = local.as_path();
+
     // Get file metadata
-    let metadata = fs::metadata(&path_obj)
+    let metadata = fs::metadata(path_obj)
         .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
     let is_dir = metadata.is_dir();
     let size_bytes = if is_dir { 0 } else { metadata.len() };
@@ -592,14 +1532,12 @@ pub fn hash_and_insert_file(app_handle: &AppHandle, path: String) -> Result<u32>
         )
         .ok();
 
-    // Find matching root id by longest prefix
-    let rid_opt: Option<i64> = conn
-        .query_row(
-            "SELECT id FROM roots WHERE ?1 LIKE (path || '%') ORDER BY LENGTH(path) DESC LIMIT 1",
-            params![path.clone()],
-            |row| row.get(0),
-        )
-        .ok();
+    // Find the deepest root containing this file
+    let rid_opt = root_id_for(&load_roots(&conn)?, &path);
+    let unchanged = existing
+        .as_ref()
+        .is_some_and(|(_, _, old_size, old_mtime)| *old_size == size_bytes as i64 && *old_mtime == last_modified);
+    let _ = bump_cache(&conn, crate::metrics::HASH_CACHE, unchanged);
 
     let file_id = if let Some((id, _old_hash, old_size, old_mtime)) = existing {
         eprintln!("📄 File exists in DB (id: {})", id);
@@ -607,6 +1545,7 @@ pub fn hash_and_insert_file(app_handle: &AppHandle, path: String) -> Result<u32>
         // Early cutoff: if size and mtime match, reuse old hash
         if old_size == size_bytes as i64 && old_mtime == last_modified {
             eprintln!("   └─ ✨ Metadata unchanged - reusing cached hash");
+            conn.execute("UPDATE files SET missing_since = NULL WHERE id = ?1", params![id])?;
             id
         } else {
             // Metadata changed, need to re-hash
@@ -625,50 +1564,575 @@ pub fn hash_and_insert_file(app_handle: &AppHandle, path: String) -> Result<u32>
             };
             
             conn.execute(
-                "UPDATE files SET content_hash = ?1, size_bytes = ?2, last_modified = ?3, updated_at = ?4, root_id = ?5, is_directory = ?6 WHERE id = ?7",
+                "UPDATE files SET content_hash = ?1, size_bytes = ?2, last_modified = ?3, updated_at = ?4, root_id = ?5, is_directory = ?6, missing_since = NULL WHERE id = ?7",
                 params![new_hash, size_bytes as i64, last_modified, now, rid_opt, if is_dir { 1 } else { 0 }, id],
             )?;
             eprintln!("   └─ ✅ Updated in DB");
             id
         }
-    } else {
-        // New file - must hash and insert
-        eprintln!("📄 New file, hashing and inserting: {}", path);
-        let content_hash = if is_dir {
-            let mut hasher = Sha256::new();
-            let entries_count: u64 = fs::read_dir(&path_obj).ok().map(|it| it.count() as u64).unwrap_or(0);
-            hasher.update(path.as_bytes());
-            hasher.update(last_modified.to_le_bytes());
-            hasher.update(entries_count.to_le_bytes());
-            format!("dir:{:x}", hasher.finalize())
-        } else {
-            hash_file_content(&path_obj)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
-        };
-        
-        conn.execute(
-            "INSERT INTO files (path, content_hash, size_bytes, last_modified, created_at, updated_at, root_id, is_directory)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![path, content_hash, size_bytes as i64, last_modified, now, now, rid_opt, if is_dir { 1 } else { 0 }],
-        )?;
-        let new_id = conn.last_insert_rowid() as u32;
-        eprintln!("   └─ ✅ Inserted with id: {}", new_id);
-        new_id
-    };
+    } else {
+        // New file - must hash and insert
+        eprintln!("📄 New file, hashing and inserting: {}", path);
+        let content_hash = if is_dir {
+            let mut hasher = Sha256::new();
+            let entries_count: u64 = fs::read_dir(&path_obj).ok().map(|it| it.count() as u64).unwrap_or(0);
+            hasher.update(path.as_bytes());
+            hasher.update(last_modified.to_le_bytes());
+            hasher.update(entries_count.to_le_bytes());
+            format!("dir:{:x}", hasher.finalize())
+        } else {
+            hash_file_content(&path_obj)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
+        };
+        
+        conn.execute(
+            "INSERT INTO files (path, content_hash, size_bytes, last_modified, created_at, updated_at, root_id, is_directory)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![path, content_hash, size_bytes as i64, last_modified, now, now, rid_opt, if is_dir { 1 } else { 0 }],
+        )?;
+        let new_id = conn.last_insert_rowid() as u32;
+        eprintln!("   └─ ✅ Inserted with id: {}", new_id);
+        new_id
+    };
+    // The cache copy's identity says nothing about the remote file
+    if !is_dir && !remote {
+        store_file_identity(&conn, file_id, path_obj)?;
+    }
+    if !is_dir && !unchanged && crate::pdf::is_pdf(&path) {
+        store_pdf_meta(&conn, file_id, path_obj)?;
+    }
+
+    Ok(file_id)
+}
+
+fn store_pdf_meta(conn: &Connection, file_id: u32, path: &Path) -> Result<()> {
+    let meta = crate::pdf::metadata(path).unwrap_or_default();
+    conn.execute(
+        "UPDATE files SET pdf_title = ?1, pdf_author = ?2, page_count = ?3 WHERE id = ?4",
+        params![meta.title, meta.author, meta.page_count, file_id],
+    )?;
+    Ok(())
+}
+
+// The title embedded in the tracked PDF at `path`, if it has one
+pub fn pdf_title(app_handle: &AppHandle, path: &str) -> Result<Option<String>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    match conn.query_row("SELECT pdf_title FROM files WHERE path = ?1", params![path], |row| row.get(0)) {
+        Ok(title) => Ok(title),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+
+// Outcome of re-statting a path reported by the watcher
+pub enum StatRefresh {
+    Untracked,
+    Unchanged,
+    Updated(FileInfo),
+}
+
+// Re-stat a file already in the DB after a watcher event and store its new size/mtime
+pub fn refresh_file_stat(app_handle: &AppHandle, path: &str) -> Result<StatRefresh> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    refresh_stat(&conn, path)
+}
+
+pub fn refresh_stat(conn: &Connection, path: &str) -> Result<StatRefresh> {
+    let existing: Option<(u32, String, i64, i64, i64)> = conn
+        .query_row(
+            "SELECT id, content_hash, size_bytes, last_modified, is_directory FROM files WHERE path = ?1",
+            params![path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .ok();
+    let Some((id, content_hash, old_size, old_mtime, is_dir)) = existing else {
+        return Ok(StatRefresh::Untracked);
+    };
+    // A vanished file is left to the full rescan
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(StatRefresh::Untracked);
+    };
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let size_bytes = if metadata.is_dir() { 0 } else { metadata.len() };
+    let last_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(now);
+    if old_size == size_bytes as i64 && old_mtime == last_modified {
+        return Ok(StatRefresh::Unchanged);
+    }
+
+    conn.execute(
+        "UPDATE files SET size_bytes = ?1, last_modified = ?2, updated_at = ?3 WHERE id = ?4",
+        params![size_bytes as i64, last_modified, now, id],
+    )?;
+    Ok(StatRefresh::Updated(FileInfo {
+        id,
+        path: path.to_string(),
+        content_hash,
+        size_bytes,
+        last_modified,
+        is_directory: is_dir != 0,
+        last_tagged: None,
+        open_count: 0,
+        last_opened_at: None,
+    }))
+}
+
+// Recompute the content hash of a tracked file (queued after `refresh_file_stat`)
+pub fn rehash_file(app_handle: &AppHandle, file_id: u32) -> Result<Option<FileInfo>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let row: Option<(String, i64, i64, i64)> = conn
+        .query_row(
+            "SELECT path, size_bytes, last_modified, is_directory FROM files WHERE id = ?1",
+            params![file_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .ok();
+    let Some((path, size_bytes, last_modified, is_dir)) = row else {
+        return Ok(None);
+    };
+    // Directories keep their pseudo-hash; only file contents are re-hashed
+    if is_dir != 0 {
+        return Ok(None);
+    }
+    let content_hash = hash_file_content(Path::new(&path))
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "UPDATE files SET content_hash = ?1 WHERE id = ?2",
+        params![content_hash, file_id],
+    )?;
+    Ok(Some(FileInfo {
+        id: file_id,
+        path,
+        content_hash,
+        size_bytes: size_bytes as u64,
+        last_modified,
+        is_directory: false,
+        last_tagged: None,
+        open_count: 0,
+        last_opened_at: None,
+    }))
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyIssue {
+    pub file_id: u32,
+    pub path: String,
+    // "modified" (content no longer matches the stored hash), "missing" or "unreadable"
+    pub kind: String,
+    pub stored_hash: String,
+    pub current_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub root: Option<String>,
+    pub checked: usize,
+    pub issues: Vec<VerifyIssue>,
+}
+
+// Re-hashes every tracked file (optionally only those under `root`) and compares
+// against the stored hash. Read-only: nothing is updated until the user accepts.
+// `progress` gets (checked, total) after each file.
+pub fn verify_files(
+    app_handle: &AppHandle,
+    root: Option<String>,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<VerifyReport> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT id, path, content_hash FROM files WHERE is_directory = 0 ORDER BY path")?;
+    let files = stmt
+        .query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let files: Vec<_> = files
+        .into_iter()
+        .filter(|(_, path, _)| root.as_deref().is_none_or(|r| tagme_paths::is_under(path, r)))
+        // Checking remote files or archive entries would mean downloading or
+        // extracting all of them
+        .filter(|(_, path, _)| !crate::remote::is_remote(path) && !crate::archives::is_virtual(path))
+        .collect();
+
+    let total = files.len();
+    let mut issues = Vec::new();
+    for (i, (file_id, path, stored_hash)) in files.into_iter().enumerate() {
+        let p = Path::new(&path);
+        let issue = if !p.exists() {
+            Some(("missing", None))
+        } else {
+            match hash_file_content(p) {
+                Ok(hash) if hash == stored_hash => None,
+                Ok(hash) => Some(("modified", Some(hash))),
+                Err(e) => {
+                    eprintln!("⚠️ [VERIFY] Could not read {}: {}", path, e);
+                    Some(("unreadable", None))
+                }
+            }
+        };
+        if let Some((kind, current_hash)) = issue {
+            issues.push(VerifyIssue { file_id, path, kind: kind.to_string(), stored_hash, current_hash });
+        }
+        progress(i + 1, total);
+    }
+    eprintln!("🔍 [VERIFY] Checked {} file(s), {} issue(s)", total, issues.len());
+    Ok(VerifyReport { root, checked: total, issues })
+}
+
+// Stores the current content hash for files the user confirmed as intentionally
+// modified; returns how many were updated
+pub fn accept_file_hashes(app_handle: &AppHandle, file_ids: Vec<u32>) -> Result<usize> {
+    let mut updated = 0;
+    for id in file_ids {
+        if rehash_file(app_handle, id)?.is_some() {
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+
+// A tagged file marked missing by `prune_missing_files`
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFile {
+    pub id: u32,
+    pub path: String,
+    pub size_bytes: u64,
+    pub missing_since: i64,
+}
+
+pub fn get_missing_files(app_handle: &AppHandle) -> Result<Vec<MissingFile>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT id, path, size_bytes, missing_since FROM files
+         WHERE missing_since IS NOT NULL ORDER BY path",
+    )?;
+    let files = stmt
+        .query_map([], |row| {
+            Ok(MissingFile {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                size_bytes: row.get::<_, i64>(2)? as u64,
+                missing_since: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(files)
+}
+
+// A proposed new location for a missing file, for the user to review
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkCandidate {
+    pub file_id: u32,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+struct MissingRow {
+    id: u32,
+    path: String,
+    content_hash: String,
+    size_bytes: u64,
+    last_modified: i64,
+    identity: Option<(i64, i64)>,
+}
+
+// Every regular file below `dir`; symlinks are not followed
+pub fn walk_files(dir: &Path, out: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(|e| e.ok()) {
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => walk_files(&entry.path(), out),
+            Ok(t) if t.is_file() => out.push(entry.path()),
+            _ => {}
+        }
+    }
+}
+
+// Looks for missing files under `dirs`, first by filesystem identity and then by
+// content. Only files whose size matches a missing file are considered; each
+// missing file and each found path is used at most once, and paths already
+// tracked are skipped.
+pub fn find_relink_candidates(app_handle: &AppHandle, dirs: Vec<String>) -> Result<Vec<RelinkCandidate>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT id, path, content_hash, size_bytes, last_modified, volume_id, file_index FROM files
+         WHERE missing_since IS NOT NULL AND is_directory = 0",
+    )?;
+    let mut missing = stmt
+        .query_map([], |row| {
+            Ok(MissingRow {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                content_hash: row.get(2)?,
+                size_bytes: row.get::<_, i64>(3)? as u64,
+                last_modified: row.get(4)?,
+                identity: row.get::<_, Option<i64>>(5)?.zip(row.get::<_, Option<i64>>(6)?),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sizes: std::collections::HashSet<u64> = missing.iter().map(|m| m.size_bytes).collect();
+
+    let mut found = Vec::new();
+    for dir in &dirs {
+        walk_files(Path::new(dir), &mut found);
+    }
+    found.sort();
+    found.dedup();
+
+    let mut candidates = Vec::new();
+    for path in found {
+        if missing.is_empty() {
+            break;
+        }
+        let Ok(metadata) = fs::metadata(&path) else { continue };
+        if !sizes.contains(&metadata.len()) {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let tracked: bool = conn.query_row("SELECT EXISTS (SELECT 1 FROM files WHERE path = ?1)", params![path_str], |row| row.get(0))?;
+        if tracked {
+            continue;
+        }
+        // Same inode/file ID with unchanged size and mtime is the same file, renamed;
+        // anything else has to match by content
+        let last_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+        let identity = file_identity(&path);
+        let by_identity = missing.iter().position(|m| {
+            identity.is_some()
+                && m.identity == identity
+                && m.size_bytes == metadata.len()
+                && Some(m.last_modified) == last_modified
+        });
+        let found = match by_identity {
+            Some(i) => Some(i),
+            None => {
+                let Ok(hash) = hash_file_content(&path) else { continue };
+                missing.iter().position(|m| m.size_bytes == metadata.len() && m.content_hash == hash)
+            }
+        };
+        if let Some(i) = found {
+            let m = missing.swap_remove(i);
+            candidates.push(RelinkCandidate { file_id: m.id, old_path: m.path, new_path: path_str });
+        }
+    }
+    eprintln!("🔗 [RELINK] Found {} candidate(s) in {} folder(s)", candidates.len(), dirs.len());
+    Ok(candidates)
+}
+
+// Moves each missing file to its confirmed new path, keeping its id and tags.
+// Returns how many were re-linked; a target path that is tracked by now is skipped.
+pub fn apply_relinks(app_handle: &AppHandle, links: Vec<RelinkCandidate>) -> Result<usize> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    let tx = conn.transaction()?;
+    let roots = load_roots(&tx)?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let mut relinked = 0;
+    for link in links {
+        let changed = tx.execute(
+            "UPDATE OR IGNORE files SET path = ?1, root_id = ?2, missing_since = NULL, updated_at = ?3
+             WHERE id = ?4 AND missing_since IS NOT NULL",
+            params![link.new_path, root_id_for(&roots, &link.new_path), now, link.file_id],
+        )?;
+        if changed > 0 {
+            move_path_refs(&tx, &link.old_path, &link.new_path)?;
+            store_file_identity(&tx, link.file_id, Path::new(&link.new_path))?;
+            relinked += 1;
+        }
+    }
+    tx.commit()?;
+    eprintln!("🔗 [RELINK] Re-linked {} file(s)", relinked);
+    Ok(relinked)
+}
+
+// (hash, size, mtime) of every tracked file by path, for reusing hashes of
+// files that haven't changed since
+pub fn stored_hashes(app_handle: &AppHandle) -> Result<std::collections::HashMap<String, (String, u64, i64)>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT path, content_hash, size_bytes, last_modified FROM files WHERE is_directory = 0")?;
+    let hashes = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get::<_, i64>(2)? as u64, row.get(3)?))))?
+        .collect::<Result<_, _>>()?;
+    Ok(hashes)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFile {
+    pub id: u32,
+    pub path: String,
+    pub root: Option<String>,
+    pub tag_ids: Vec<u32>,
+}
+
+// Tracked files with identical content, possibly under different roots
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub content_hash: String,
+    pub size_bytes: u64,
+    pub files: Vec<DuplicateFile>,
+}
+
+// Groups of files sharing a content hash and size, most wasted space first.
+// Missing files, remote files and archive entries are left out.
+pub fn duplicate_files(app_handle: &AppHandle) -> Result<Vec<DuplicateGroup>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT f.id, f.path, f.content_hash, f.size_bytes, r.path FROM files f
+         LEFT JOIN roots r ON r.id = f.root_id
+         WHERE f.is_directory = 0 AND f.content_hash != '' AND f.missing_since IS NULL
+           AND f.content_hash IN (
+               SELECT content_hash FROM files
+               WHERE is_directory = 0 AND content_hash != '' AND missing_since IS NULL
+               GROUP BY content_hash HAVING COUNT(*) > 1
+           )
+         ORDER BY f.content_hash, f.size_bytes, f.path",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)? as u64,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut tags: std::collections::HashMap<u32, Vec<u32>> = std::collections::HashMap::new();
+    let mut stmt = conn.prepare("SELECT file_id, tag_id FROM file_tags ORDER BY tag_id")?;
+    for link in stmt.query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?)))? {
+        let (file_id, tag_id) = link?;
+        tags.entry(file_id).or_default().push(tag_id);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    for (id, path, content_hash, size_bytes, root) in rows {
+        if crate::remote::is_remote(&path) || crate::archives::is_virtual(&path) {
+            continue;
+        }
+        let file = DuplicateFile { id, path, root, tag_ids: tags.remove(&id).unwrap_or_default() };
+        match groups.last_mut() {
+            Some(g) if g.content_hash == content_hash && g.size_bytes == size_bytes => g.files.push(file),
+            _ => groups.push(DuplicateGroup { content_hash, size_bytes, files: vec![file] }),
+        }
+    }
+    groups.retain(|g| g.files.len() > 1);
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size_bytes * (g.files.len() as u64 - 1)));
+    Ok(groups)
+}
 
-    Ok(file_id)
+// Drops a file and its tag links from the database (the file itself is untouched)
+pub fn forget_file(app_handle: &AppHandle, file_id: u32) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let _ = conn.execute("PRAGMA foreign_keys = ON", []);
+    conn.execute("DELETE FROM files WHERE id = ?1", params![file_id])?;
+    Ok(())
+}
+
+// Id of the tracked file at `path`
+pub fn file_id_by_path(app_handle: &AppHandle, path: &str) -> Result<Option<u32>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    match conn.query_row("SELECT id FROM files WHERE path = ?1", params![path], |row| row.get(0)) {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// Renames a file within its folder, on disk and in the database, keeping its tags
+// and history. Returns the new path.
+pub fn rename_file(app_handle: &AppHandle, path: &str, new_name: &str) -> Result<String> {
+    let invalid = |msg: &str| rusqlite::Error::InvalidParameterName(msg.to_string());
+    if new_name.is_empty() || new_name.contains(['/', '\\']) {
+        return Err(invalid("New name must be a plain file name"));
+    }
+    let old = Path::new(path);
+    if !old.is_file() {
+        return Err(invalid("Only existing files can be renamed"));
+    }
+    let parent = old.parent().ok_or_else(|| invalid("File has no parent folder"))?;
+    let new_path = parent.join(new_name);
+    if new_path.exists() {
+        return Err(invalid("A file with that name already exists"));
+    }
+    let new_path_str = new_path.to_string_lossy().to_string();
+
+    fs::rename(old, &new_path).map_err(|e| invalid(&e.to_string()))?;
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    conn.execute(
+        "UPDATE files SET path = ?1, updated_at = ?2 WHERE path = ?3",
+        params![new_path_str, now, path],
+    )?;
+    move_path_refs(&conn, path, &new_path_str)?;
+    Ok(new_path_str)
 }
 
+// Points the path-keyed rows that follow a file (its history, its pin) at the
+// file's new path
+fn move_path_refs(conn: &Connection, old: &str, new: &str) -> Result<()> {
+    conn.execute("UPDATE tag_history SET file_path = ?1 WHERE file_path = ?2", params![new, old])?;
+    conn.execute("UPDATE OR IGNORE pinned_files SET path = ?1 WHERE path = ?2", params![new, old])?;
+    conn.execute("UPDATE OR IGNORE discovered_files SET path = ?1 WHERE path = ?2", params![new, old])?;
+    Ok(())
+}
+
+// Counts a successful open of a tracked file; untracked files aren't recorded
+pub fn record_open(app_handle: &AppHandle, path: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    conn.execute(
+        "UPDATE files SET open_count = open_count + 1, last_opened_at = ?1 WHERE path = ?2",
+        params![now, path],
+    )?;
+    Ok(())
+}
 
 // Get all files
 pub fn get_all_files(app_handle: &AppHandle) -> Result<Vec<FileInfo>> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    let mut stmt = conn.prepare(
-        "SELECT id, path, content_hash, size_bytes, last_modified, is_directory FROM files ORDER BY path",
-    )?;
+    select_files(&conn, "", "", [])
+}
+
+// Present files in path order; `filter` adds "AND ..." conditions and `tail`
+// (LIMIT/OFFSET) is appended to the query
+fn select_files(conn: &Connection, filter: &str, tail: &str, params: impl rusqlite::Params) -> Result<Vec<FileInfo>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, path, content_hash, size_bytes, last_modified, is_directory,
+                (SELECT MAX(created_at) FROM file_tags WHERE file_id = files.id), open_count, last_opened_at
+         FROM files WHERE missing_since IS NULL {} ORDER BY path {}",
+        filter, tail
+    ))?;
 
     let files = stmt
-        .query_map([], |row| {
+        .query_map(params, |row| {
             Ok(FileInfo {
                 id: row.get(0)?,
                 path: row.get(1)?,
@@ -676,6 +2140,9 @@ pub fn get_all_files(app_handle: &AppHandle) -> Result<Vec<FileInfo>> {
                 size_bytes: row.get::<_, i64>(3)? as u64,
                 last_modified: row.get(4)?,
                 is_directory: row.get::<_, i64>(5)? != 0,
+                last_tagged: row.get(6)?,
+                open_count: row.get(7)?,
+                last_opened_at: row.get(8)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -683,14 +2150,246 @@ pub fn get_all_files(app_handle: &AppHandle) -> Result<Vec<FileInfo>> {
     Ok(files)
 }
 
+// One slice of the unfiltered file list, in the same order as get_all_files,
+// so the UI can show the first rows before the whole library is loaded
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesPage {
+    pub rows: Vec<FileWithTags>,
+    // Present files in the whole library
+    pub total: usize,
+}
+
+pub fn files_page(app_handle: &AppHandle, offset: usize, limit: usize) -> Result<FilesPage> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let total: i64 = conn.query_row("SELECT COUNT(*) FROM files WHERE missing_since IS NULL", [], |row| row.get(0))?;
+    let files = select_files(&conn, "", "LIMIT ?1 OFFSET ?2", params![limit as i64, offset as i64])?;
+    let mut tags_map = file_tags_map(
+        app_handle,
+        &conn,
+        "WHERE ft.file_id IN (SELECT id FROM files WHERE missing_since IS NULL ORDER BY path LIMIT ?1 OFFSET ?2)",
+        params![limit as i64, offset as i64],
+    )?;
+    let rows = files
+        .into_iter()
+        .map(|file| {
+            let tags = tags_map.remove(&file.id).unwrap_or_default();
+            FileWithTags { file, tags }
+        })
+        .collect();
+    Ok(FilesPage { rows, total: total as usize })
+}
+
+// Large library mode: the frontend only holds one window of rows and pages
+// through the rest with a path cursor
+pub fn large_library_enabled(app_handle: &AppHandle) -> bool {
+    get_setting(app_handle, "large_library").ok().flatten().as_deref() == Some("true")
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesWindow {
+    pub rows: Vec<FileWithTags>,
+    // Files matching the filter across all windows
+    pub total: usize,
+    // Cursor for the following window; None on the last one
+    pub next_cursor: Option<String>,
+}
+
+// Up to `limit` files after the path `after`, matching the tag filter and owned
+// by one of `roots` (all when empty)
+pub fn files_window(
+    app_handle: &AppHandle,
+    after: Option<String>,
+    limit: usize,
+    filter: TagFilter,
+    roots: Vec<String>,
+) -> Result<FilesWindow> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let marks = |n: usize| vec!["?"; n].join(",");
+    let mut clause = String::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    push_tag_filter(&filter, &mut clause, &mut values);
+    if !roots.is_empty() {
+        clause.push_str(&format!(" AND root_id IN (SELECT id FROM roots WHERE path IN ({}))", marks(roots.len())));
+        values.extend(roots.into_iter().map(|r| Box::new(canonical_root(&r)) as Box<dyn rusqlite::ToSql>));
+    }
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM files WHERE missing_since IS NULL {}", clause),
+        rusqlite::params_from_iter(values.iter()),
+        |row| row.get(0),
+    )?;
+
+    if let Some(after) = after {
+        clause.push_str(" AND path > ?");
+        values.push(Box::new(after));
+    }
+    values.push(Box::new(limit as i64));
+    let files = select_files(&conn, &clause, "LIMIT ?", rusqlite::params_from_iter(values.iter()))?;
+    let ids: Vec<u32> = files.iter().map(|f| f.id).collect();
+    let mut tags_map = file_tags_map(
+        app_handle,
+        &conn,
+        &format!("WHERE ft.file_id IN ({})", marks(ids.len())),
+        rusqlite::params_from_iter(ids.iter()),
+    )?;
+    let next_cursor = (files.len() == limit).then(|| files.last().map(|f| f.path.clone())).flatten();
+    let rows = files
+        .into_iter()
+        .map(|file| {
+            let tags = tags_map.remove(&file.id).unwrap_or_default();
+            FileWithTags { file, tags }
+        })
+        .collect();
+    Ok(FilesWindow { rows, total: total as usize, next_cursor })
+}
+
+// Narrows a `select_files` clause to the files matching a tag filter
+fn push_tag_filter(filter: &TagFilter, clause: &mut String, values: &mut Vec<Box<dyn rusqlite::ToSql>>) {
+    if filter.tag_ids.is_empty() {
+        return;
+    }
+    let marks = vec!["?"; filter.tag_ids.len()].join(",");
+    if filter.use_and {
+        clause.push_str(&format!(
+            " AND id IN (SELECT file_id FROM file_tags WHERE tag_id IN ({}) GROUP BY file_id HAVING COUNT(DISTINCT tag_id) = {})",
+            marks,
+            filter.tag_ids.len()
+        ));
+    } else {
+        clause.push_str(&format!(" AND id IN (SELECT file_id FROM file_tags WHERE tag_id IN ({}))", marks));
+    }
+    values.extend(filter.tag_ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+}
+
+// Tracked files whose path contains every word of `query`, in any case, and
+// that match the tag filter too
+pub fn search_files(app_handle: &AppHandle, query: &str, filter: TagFilter) -> Result<Vec<FileInfo>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    search(&conn, query, &filter)
+}
+
+fn search(conn: &Connection, query: &str, filter: &TagFilter) -> Result<Vec<FileInfo>> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut clause = String::new();
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    // Trigrams need three characters; shorter words fall back to a LIKE scan
+    let (long, short): (Vec<&str>, Vec<&str>) = words.into_iter().partition(|w| w.chars().count() >= 3);
+    if !long.is_empty() {
+        // Each word as a quoted phrase, so FTS syntax in the query is taken literally
+        let phrases: Vec<String> = long.iter().map(|w| format!("\"{}\"", w.replace('"', "\"\""))).collect();
+        clause.push_str(" AND id IN (SELECT rowid FROM files_fts WHERE files_fts MATCH ?)");
+        values.push(Box::new(phrases.join(" ")));
+    }
+    for word in short {
+        let escaped = word.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        clause.push_str(" AND path LIKE ? ESCAPE '\\'");
+        values.push(Box::new(format!("%{}%", escaped)));
+    }
+    push_tag_filter(filter, &mut clause, &mut values);
+    select_files(conn, &clause, "", rusqlite::params_from_iter(values.iter()))
+}
+
+// Paths of every tracked file
+pub fn tracked_paths(app_handle: &AppHandle) -> Result<std::collections::HashSet<String>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT path FROM files")?;
+    let paths = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+    Ok(paths)
+}
+
 // Tag CRUD operations
+// Stored form of a tag name: trimmed and NFC-composed, case preserved
+pub fn normalize_tag_name(name: &str) -> String {
+    name.trim().nfc().collect()
+}
+
+// Comparison key for uniqueness: "Work", "work " and decomposed forms share one key
+fn tag_name_key(name: &str) -> String {
+    normalize_tag_name(name).to_lowercase()
+}
+
+// Finds a sibling (same parent) whose name collides with `name`, ignoring `exclude_id`
+fn find_tag_collision(conn: &Connection, name: &str, parent_id: Option<u32>, exclude_id: Option<u32>) -> Result<Option<(u32, String)>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM tags WHERE parent_id IS ?1")?;
+    let key = tag_name_key(name);
+    let siblings = stmt
+        .query_map(params![parent_id], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(siblings
+        .into_iter()
+        .find(|(id, n)| Some(*id) != exclude_id && tag_name_key(n) == key))
+}
+
+fn tag_exists_error(name: &str) -> rusqlite::Error {
+    rusqlite::Error::InvalidParameterName(format!("A tag named \"{}\" already exists here", name))
+}
+
+fn tag_locked_error(name: &str) -> rusqlite::Error {
+    rusqlite::Error::InvalidParameterName(format!("\"{}\" is locked; unlock it first", name))
+}
+
+// Fails when `id` or one of its ancestors is locked
+fn ensure_unlocked(conn: &Connection, id: u32) -> Result<()> {
+    let mut cursor = Some(id);
+    let mut seen = std::collections::HashSet::new();
+    while let Some(cur) = cursor.filter(|c| seen.insert(*c)) {
+        let row: Option<(String, Option<u32>, i64)> = conn
+            .query_row("SELECT name, parent_id, locked FROM tags WHERE id = ?1", params![cur], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .ok();
+        let Some((name, parent_id, locked)) = row else { break };
+        if locked != 0 {
+            return Err(tag_locked_error(&name));
+        }
+        cursor = parent_id;
+    }
+    Ok(())
+}
+
+// Fails when a tag below `id` is locked; deleting `id` would take it along
+fn ensure_no_locked_descendant(conn: &Connection, id: u32) -> Result<()> {
+    let locked: Option<String> = conn
+        .query_row(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT id FROM tags WHERE parent_id = ?1
+                UNION SELECT t.id FROM tags t JOIN subtree s ON t.parent_id = s.id
+             )
+             SELECT name FROM tags WHERE locked = 1 AND id IN subtree LIMIT 1",
+            params![id],
+            |row| row.get(0),
+        )
+        .ok();
+    match locked {
+        Some(name) => Err(tag_locked_error(&name)),
+        None => Ok(()),
+    }
+}
+
+pub fn set_tag_locked(app_handle: &AppHandle, id: u32, locked: bool) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("UPDATE tags SET locked = ?1 WHERE id = ?2", params![locked as i64, id])?;
+    Ok(())
+}
+
+// Returns the existing tag's id when a sibling with the same normalized name exists
 pub fn create_tag(
     app_handle: &AppHandle,
     name: String,
     parent_id: Option<u32>,
     color: Option<String>,
+    icon: Option<String>,
 ) -> Result<u32> {
     let conn = Connection::open(get_db_path(app_handle))?;
+    let name = normalize_tag_name(&name);
+    if let Some((existing_id, _)) = find_tag_collision(&conn, &name, parent_id, None)? {
+        eprintln!("🏷️  [DB] create_tag: \"{}\" matches existing tag {}", name, existing_id);
+        return Ok(existing_id);
+    }
     let now = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
@@ -714,19 +2413,37 @@ pub fn create_tag(
     let new_position = max_position + 1;
 
     conn.execute(
-        "INSERT INTO tags (name, parent_id, color, position, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![name, parent_id, color, new_position, now],
+        "INSERT INTO tags (name, parent_id, color, position, created_at, icon) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![name, parent_id, color, new_position, now, icon],
     )?;
 
     Ok(conn.last_insert_rowid() as u32)
 }
 
+// Sibling order in the tag tree, stored in the `tag_sort_mode` setting.
+// Drag-and-drop still edits `position`, which only shows in manual mode.
+pub const TAG_SORT_MODES: &[&str] = &["manual", "name", "usage"];
+
+fn tag_sort_order(mode: &str) -> &'static str {
+    match mode {
+        "name" => "t.parent_id, t.name COLLATE NOCASE",
+        "usage" => "t.parent_id, usage DESC, t.name COLLATE NOCASE",
+        _ => "t.parent_id, t.position",
+    }
+}
+
 pub fn get_all_tags(app_handle: &AppHandle) -> Result<Vec<TagInfo>> {
     eprintln!("🏷️  [DB] get_all_tags called");
     let conn = Connection::open(get_db_path(app_handle))?;
-    let mut stmt = conn.prepare("SELECT id, name, parent_id, color, position FROM tags ORDER BY parent_id, position")?;
-
-    let tags = stmt
+    let mode = get_setting(app_handle, "tag_sort_mode")?.unwrap_or_default();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.id, t.name, t.parent_id, t.color, t.position, t.icon, t.archived, t.locked,
+                (SELECT COUNT(*) FROM file_tags ft WHERE ft.tag_id = t.id) AS usage
+         FROM tags t ORDER BY {}",
+        tag_sort_order(&mode)
+    ))?;
+
+    let mut tags = stmt
         .query_map([], |row| {
             Ok(TagInfo {
                 id: row.get(0)?,
@@ -734,9 +2451,14 @@ pub fn get_all_tags(app_handle: &AppHandle) -> Result<Vec<TagInfo>> {
                 parent_id: row.get(2)?,
                 color: row.get(3)?,
                 position: row.get(4)?,
+                icon: row.get(5)?,
+                archived: row.get::<_, i64>(6)? != 0,
+                locked: row.get::<_, i64>(7)? != 0,
+                shade: None,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
+    apply_shades(app_handle, &conn, &mut tags)?;
 
     eprintln!("🏷️  [DB] Found {} tags", tags.len());
     for tag in &tags {
@@ -746,6 +2468,84 @@ pub fn get_all_tags(app_handle: &AppHandle) -> Result<Vec<TagInfo>> {
     Ok(tags)
 }
 
+// Whether child tags without a color show a shade of their parent's; off
+// unless turned on
+pub fn inherit_colors_enabled(app_handle: &AppHandle) -> bool {
+    get_setting(app_handle, "inherit_tag_colors").ok().flatten().as_deref() == Some("true")
+}
+
+// Fills `shade` for uncolored tags below a colored one
+fn apply_shades(app_handle: &AppHandle, conn: &Connection, tags: &mut [TagInfo]) -> Result<()> {
+    if tags.iter().all(|t| t.color.is_some()) || !inherit_colors_enabled(app_handle) {
+        return Ok(());
+    }
+    let mut stmt = conn.prepare("SELECT id, parent_id, color FROM tags")?;
+    let all: std::collections::HashMap<u32, (Option<u32>, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+        .collect::<Result<_, _>>()?;
+    for tag in tags.iter_mut().filter(|t| t.color.is_none()) {
+        // Depth below the colored ancestor; bounded in case of a parent cycle
+        let mut parent = tag.parent_id;
+        let mut depth = 1;
+        while let Some((grandparent, color)) = parent.and_then(|id| all.get(&id)).filter(|_| depth <= all.len()) {
+            if let Some(color) = color {
+                tag.shade = shade_color(color, depth);
+                break;
+            }
+            parent = *grandparent;
+            depth += 1;
+        }
+    }
+    Ok(())
+}
+
+// `color` (#rrggbb) moved a step toward white per level below the colored tag,
+// or toward black for colors that are already light; None for other formats
+fn shade_color(color: &str, depth: usize) -> Option<String> {
+    let hex = color.strip_prefix('#').filter(|h| h.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(f32::from);
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    let amount = (0.2 * depth as f32).min(0.6);
+    let target = if 0.299 * r + 0.587 * g + 0.114 * b > 170.0 { 0.0 } else { 255.0 };
+    let mix = |c: f32| (c + (target - c) * amount).round() as u8;
+    Some(format!("#{:02x}{:02x}{:02x}", mix(r), mix(g), mix(b)))
+}
+
+// Number of files carrying each tag; unused tags are absent
+pub fn tag_usage_counts(app_handle: &AppHandle) -> Result<std::collections::HashMap<u32, u32>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT tag_id, COUNT(*) FROM file_tags GROUP BY tag_id")?;
+    let counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    Ok(counts)
+}
+
+// Tags most often found on files that also have `tag_id`, with how many such files
+pub fn co_occurring_tags(app_handle: &AppHandle, tag_id: u32, limit: usize) -> Result<Vec<(u32, u32)>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT b.tag_id, COUNT(*) AS shared
+         FROM file_tags a
+         JOIN file_tags b ON b.file_id = a.file_id AND b.tag_id != a.tag_id
+         WHERE a.tag_id = ?1
+         GROUP BY b.tag_id
+         ORDER BY shared DESC, b.tag_id
+         LIMIT ?2",
+    )?;
+    let tags = stmt
+        .query_map(params![tag_id, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(tags)
+}
+
+// Archived tags keep their files; only the UI hides them
+pub fn set_tag_archived(app_handle: &AppHandle, id: u32, archived: bool) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("UPDATE tags SET archived = ?1 WHERE id = ?2", params![archived as i64, id])?;
+    Ok(())
+}
+
 pub fn update_tag(
     app_handle: &AppHandle,
     id: u32,
@@ -753,6 +2553,19 @@ pub fn update_tag(
     color: Option<String>,
 ) -> Result<()> {
     let conn = Connection::open(get_db_path(app_handle))?;
+    let name = normalize_tag_name(&name);
+    let (parent_id, old_name): (Option<u32>, String) = conn.query_row(
+        "SELECT parent_id, name FROM tags WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    // Recoloring a locked tag is fine; renaming it isn't
+    if name != old_name {
+        ensure_unlocked(&conn, id)?;
+    }
+    if let Some((_, existing)) = find_tag_collision(&conn, &name, parent_id, Some(id))? {
+        return Err(tag_exists_error(&existing));
+    }
     conn.execute(
         "UPDATE tags SET name = ?1, color = ?2 WHERE id = ?3",
         params![name, color, id],
@@ -761,10 +2574,35 @@ pub fn update_tag(
 }
 
 pub fn delete_tag(app_handle: &AppHandle, id: u32) -> Result<()> {
-    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    drop_tag(&mut conn, id)
+}
+
+// Deletes a tag with its descendants; their links go with them via the cascade
+fn drop_tag(conn: &mut Connection, id: u32) -> Result<()> {
+    ensure_unlocked(conn, id)?;
+    ensure_no_locked_descendant(conn, id)?;
     let _ = conn.execute("PRAGMA foreign_keys = ON", [])?;
-    conn.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
-    conn.execute(
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let tx = conn.transaction()?;
+    // The cascade leaves no trace in the files' history, so it's written first
+    tx.execute(
+        "WITH RECURSIVE doomed(id) AS (
+            SELECT ?1 UNION ALL SELECT t.id FROM tags t JOIN doomed d ON t.parent_id = d.id
+        )
+        INSERT INTO tag_history (file_path, tag_id, tag_name, action, at)
+        SELECT f.path, t.id, t.name, 'removed', ?2
+        FROM file_tags ft
+        JOIN files f ON f.id = ft.file_id
+        JOIN tags t ON t.id = ft.tag_id
+        WHERE ft.tag_id IN doomed",
+        params![id, now],
+    )?;
+    tx.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
+    tx.execute(
         "DELETE FROM files WHERE id IN (
             SELECT f.id FROM files f
             LEFT JOIN file_tags ft ON f.id = ft.file_id
@@ -772,17 +2610,187 @@ pub fn delete_tag(app_handle: &AppHandle, id: u32) -> Result<()> {
         )",
         [],
     )?;
+    tx.commit()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagHook {
+    pub tag_id: u32,
+    pub command: String,
+}
+
+pub fn get_tag_hooks(app_handle: &AppHandle) -> Result<Vec<TagHook>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT tag_id, command FROM tag_hooks ORDER BY tag_id")?;
+    let hooks = stmt
+        .query_map([], |row| Ok(TagHook { tag_id: row.get(0)?, command: row.get(1)? }))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(hooks)
+}
+
+// An empty command removes the tag's hook
+pub fn set_tag_hook(app_handle: &AppHandle, tag_id: u32, command: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let command = command.trim();
+    if command.is_empty() {
+        conn.execute("DELETE FROM tag_hooks WHERE tag_id = ?1", params![tag_id])?;
+        return Ok(());
+    }
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    conn.execute(
+        "INSERT INTO tag_hooks (tag_id, command, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(tag_id) DO UPDATE SET command = excluded.command",
+        params![tag_id, command, now],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagExample {
+    pub tag_id: u32,
+    pub file_path: String,
+    pub positive: bool,
+}
+
+// Examples of one tag, or of every tag with `None`; positives first
+pub fn get_tag_examples(app_handle: &AppHandle, tag_id: Option<u32>) -> Result<Vec<TagExample>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT tag_id, file_path, positive FROM tag_examples
+         WHERE ?1 IS NULL OR tag_id = ?1
+         ORDER BY tag_id, positive DESC, created_at",
+    )?;
+    let examples = stmt
+        .query_map(params![tag_id], |row| {
+            Ok(TagExample { tag_id: row.get(0)?, file_path: row.get(1)?, positive: row.get(2)? })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(examples)
+}
+
+// Adding a file that is already an example of the tag flips it to `positive`
+pub fn add_tag_examples(app_handle: &AppHandle, tag_id: u32, file_paths: Vec<String>, positive: bool) -> Result<()> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let tx = conn.transaction()?;
+    for path in file_paths {
+        tx.execute(
+            "INSERT INTO tag_examples (tag_id, file_path, positive, created_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(tag_id, file_path) DO UPDATE SET positive = excluded.positive",
+            params![tag_id, path, positive, now],
+        )?;
+    }
+    tx.commit()
+}
+
+pub fn remove_tag_example(app_handle: &AppHandle, tag_id: u32, file_path: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("DELETE FROM tag_examples WHERE tag_id = ?1 AND file_path = ?2", params![tag_id, file_path])?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedFile {
+    pub path: String,
+    pub is_directory: bool,
+    // Not on disk (anymore); kept pinned in case its drive comes back
+    pub missing: bool,
+    pub tags: Vec<TagInfo>,
+}
+
+// Pins after the existing ones, in the given order; pinned paths stay put
+pub fn pin_files(app_handle: &AppHandle, paths: &[String]) -> Result<()> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let tx = conn.transaction()?;
+    for path in paths {
+        tx.execute(
+            "INSERT OR IGNORE INTO pinned_files (path, position, pinned_at)
+             VALUES (?1, (SELECT COALESCE(MAX(position), -1) + 1 FROM pinned_files), ?2)",
+            params![path, now],
+        )?;
+    }
+    tx.commit()
+}
+
+pub fn unpin_file(app_handle: &AppHandle, path: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("DELETE FROM pinned_files WHERE path = ?1", params![path])?;
+    Ok(())
+}
+
+pub fn get_pinned_files(app_handle: &AppHandle) -> Result<Vec<PinnedFile>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT p.path, f.id FROM pinned_files p
+         LEFT JOIN files f ON f.path = p.path AND f.missing_since IS NULL
+         ORDER BY p.position",
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<u32>>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut pinned = Vec::with_capacity(rows.len());
+    for (path, file_id) in rows {
+        let tags = match file_id {
+            Some(id) => get_file_tags(app_handle, id)?,
+            None => Vec::new(),
+        };
+        let on_disk = Path::new(&path);
+        pinned.push(PinnedFile { is_directory: on_disk.is_dir(), missing: !on_disk.exists(), path, tags });
+    }
+    Ok(pinned)
+}
+
+pub fn store_recommendations(app_handle: &AppHandle, file_path: &str, items: &str) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    conn.execute(
+        "INSERT INTO stored_recommendations (file_path, items, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(file_path) DO UPDATE SET items = excluded.items, created_at = excluded.created_at",
+        params![file_path, items, now],
+    )?;
     Ok(())
 }
 
+// Stored suggestions by path, pruning those of files that have been tagged since
+pub fn get_stored_recommendations(app_handle: &AppHandle) -> Result<Vec<(String, String)>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute(
+        "DELETE FROM stored_recommendations WHERE file_path IN (
+            SELECT f.path FROM files f JOIN file_tags ft ON ft.file_id = f.id
+        )",
+        [],
+    )?;
+    let mut stmt = conn.prepare("SELECT file_path, items FROM stored_recommendations")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
 // Helper function to reorder tags after a move
 fn reorder_tags_in_parent(conn: &Connection, parent_id: Option<u32>) -> Result<()> {
     eprintln!("🔧 [DB] reorder_tags_in_parent: parent={:?}", parent_id);
     // Get all tags in this parent, ordered by current position
     let mut stmt = if parent_id.is_some() {
-        conn.prepare("SELECT id FROM tags WHERE parent_id = ?1 ORDER BY position")?
+        conn.prepare("SELECT id FROM tags WHERE parent_id = ?1 ORDER BY position, id")?
     } else {
-        conn.prepare("SELECT id FROM tags WHERE parent_id IS NULL ORDER BY position")?
+        conn.prepare("SELECT id FROM tags WHERE parent_id IS NULL ORDER BY position, id")?
     };
 
     let tag_ids: Vec<u32> = if let Some(pid) = parent_id {
@@ -814,6 +2822,22 @@ pub fn move_tag(
 ) -> Result<()> {
     eprintln!("🔄 [DB] move_tag called: id={}, new_parent={:?}, target_pos={}", id, new_parent_id, target_position);
     let conn = Connection::open(get_db_path(app_handle))?;
+    relocate_tag(&conn, id, new_parent_id, target_position)?;
+    eprintln!("🔄 [DB] move_tag completed successfully");
+    Ok(())
+}
+
+// Puts the tag at index `target_position` (clamped) among the children of
+// `new_parent_id`, keeping sibling positions contiguous in both parents
+fn relocate_tag(conn: &Connection, id: u32, new_parent_id: Option<u32>, target_position: i32) -> Result<()> {
+    // Walk up from the new parent: moving under a descendant would create a cycle
+    let mut cursor = new_parent_id;
+    while let Some(pid) = cursor {
+        if pid == id {
+            return Err(rusqlite::Error::InvalidParameterName("Cannot move a tag into itself or its own descendant".to_string()));
+        }
+        cursor = conn.query_row("SELECT parent_id FROM tags WHERE id = ?1", params![pid], |row| row.get(0))?;
+    }
 
     // Get current parent
     let old_parent_id: Option<u32> = conn.query_row(
@@ -824,6 +2848,20 @@ pub fn move_tag(
 
     eprintln!("🔄 [DB] Current parent of tag {}: {:?}", id, old_parent_id);
 
+    if old_parent_id != new_parent_id {
+        // Reordering among siblings leaves a locked tag where it is
+        ensure_unlocked(conn, id)?;
+        let name: String = conn.query_row("SELECT name FROM tags WHERE id = ?1", params![id], |row| row.get(0))?;
+        if let Some((_, existing)) = find_tag_collision(conn, &name, new_parent_id, Some(id))? {
+            return Err(tag_exists_error(&existing));
+        }
+        // Make room at the target so the moved tag lands before the sibling there
+        conn.execute(
+            "UPDATE tags SET position = position + 1 WHERE parent_id IS ?1 AND position >= ?2",
+            params![new_parent_id, target_position],
+        )?;
+    }
+
     // If moving within the same parent, shift positions of affected tags
     if old_parent_id == new_parent_id {
         eprintln!("🔄 [DB] Moving within same parent, shifting positions");
@@ -870,21 +2908,51 @@ pub fn move_tag(
 
     eprintln!("🔄 [DB] Updated tag {} to parent {:?}, position {}", id, new_parent_id, target_position);
 
-    // Reorder tags in both old and new parents (only if different parents)
+    // Renumber from 0: also closes gaps left by an out-of-range target
     if old_parent_id != new_parent_id {
         eprintln!("🔄 [DB] Reordering old parent {:?}", old_parent_id);
-        reorder_tags_in_parent(&conn, old_parent_id)?;
-        eprintln!("🔄 [DB] Reordering new parent {:?}", new_parent_id);
-        reorder_tags_in_parent(&conn, new_parent_id)?;
+        reorder_tags_in_parent(conn, old_parent_id)?;
     }
+    eprintln!("🔄 [DB] Reordering new parent {:?}", new_parent_id);
+    reorder_tags_in_parent(conn, new_parent_id)?;
+    Ok(())
+}
 
-    eprintln!("🔄 [DB] move_tag completed successfully");
+// Merges `source` into `target`. Children that now share a name with one of
+// target's children are folded together as well.
+pub fn merge_tags(app_handle: &AppHandle, source_id: u32, target_id: u32) -> Result<()> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    merge_tag(&mut conn, source_id, target_id)?;
+    eprintln!("🏷️  [DB] Merged tag {} into {}", source_id, target_id);
     Ok(())
 }
 
+fn merge_tag(conn: &mut Connection, source_id: u32, target_id: u32) -> Result<()> {
+    if source_id == target_id {
+        return Err(rusqlite::Error::InvalidParameterName("Cannot merge a tag into itself".to_string()));
+    }
+    // Merging deletes the source and moves its children
+    ensure_unlocked(conn, source_id)?;
+    ensure_no_locked_descendant(conn, source_id)?;
+    // Walk up from the target: merging into a descendant would create a cycle
+    let mut cursor = Some(target_id);
+    while let Some(id) = cursor {
+        if id == source_id {
+            return Err(rusqlite::Error::InvalidParameterName("Cannot merge a tag into its own descendant".to_string()));
+        }
+        cursor = conn.query_row("SELECT parent_id FROM tags WHERE id = ?1", params![id], |row| row.get(0))?;
+    }
+
+    let tx = conn.transaction()?;
+    merge_tag_into(&tx, source_id, target_id)?;
+    merge_duplicate_tags(&tx)?;
+    tx.commit()
+}
+
 // File-tag relationship operations
 // Now accepts file_path instead of file_id - will hash and insert file if needed
-pub fn add_file_tag(app_handle: &AppHandle, file_path: String, tag_id: u32) -> Result<()> {
+// Returns whether the tag was newly applied
+pub fn add_file_tag(app_handle: &AppHandle, file_path: String, tag_id: u32) -> Result<bool> {
     let file_id = hash_and_insert_file(app_handle, file_path)?;
     
     // Now add the tag relationship
@@ -894,21 +2962,108 @@ pub fn add_file_tag(app_handle: &AppHandle, file_path: String, tag_id: u32) -> R
         .unwrap()
         .as_secs() as i64;
 
-    conn.execute(
+    let added = conn.execute(
         "INSERT OR IGNORE INTO file_tags (file_id, tag_id, created_at) VALUES (?1, ?2, ?3)",
         params![file_id, tag_id, now],
     )?;
+    if added > 0 {
+        record_tag_history(&conn, file_id, tag_id, "added", now)?;
+    }
     
     eprintln!("✅ Tag {} added to file {}", tag_id, file_id);
-    Ok(())
+    Ok(added > 0)
+}
+
+// Applies every tag to every file in one transaction; returns the new links as
+// (path, tag id).
+pub fn add_tags_to_files(app_handle: &AppHandle, file_paths: Vec<String>, tag_ids: Vec<u32>) -> Result<Vec<(String, u32)>> {
+    add_tags_journaled(app_handle, &file_paths, &tag_ids, None)
+}
+
+// Files tagged per transaction by a journaled run
+pub const JOURNAL_CHUNK: usize = 200;
+
+// add_tags_to_files for a batch journal entry: `batch` is (journal id, index of
+// file_paths[0] in the whole batch). Each chunk commits together with the
+// journal's progress, so a killed run resumes after the last committed chunk.
+pub fn add_tags_journaled(
+    app_handle: &AppHandle,
+    file_paths: &[String],
+    tag_ids: &[u32],
+    batch: Option<(i64, usize)>,
+) -> Result<Vec<(String, u32)>> {
+    let chunk_size = if batch.is_some() { JOURNAL_CHUNK } else { file_paths.len().max(1) };
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let mut added = Vec::new();
+    for (i, chunk) in file_paths.chunks(chunk_size).enumerate() {
+        let mut file_ids = Vec::new();
+        for file_path in chunk {
+            file_ids.push((hash_and_insert_file(app_handle, file_path.clone())?, file_path.clone()));
+        }
+        let tx = conn.transaction()?;
+        let progress = batch.map(|(id, offset)| (id, offset + i * chunk_size + chunk.len()));
+        added.extend(link_tags_chunk(&tx, &file_ids, tag_ids, now, progress)?);
+        tx.commit()?;
+    }
+
+    eprintln!("✅ {} tag link(s) added across {} file(s)", added.len(), file_paths.len());
+    Ok(added)
+}
+
+// One chunk of a tagging run plus, for a journaled run, the journal's new
+// progress. Callers wrap it in a transaction.
+pub fn link_tags_chunk(
+    conn: &Connection,
+    files: &[(u32, String)],
+    tag_ids: &[u32],
+    now: i64,
+    progress: Option<(i64, usize)>,
+) -> Result<Vec<(String, u32)>> {
+    let added = link_tags(conn, files, tag_ids, now)?;
+    if let Some((id, done)) = progress {
+        batch_progress(conn, id, done)?;
+    }
+    Ok(added)
+}
+
+// Links every tracked file to every tag, recording history; returns the new
+// links as (path, tag id). Callers wrap it in a transaction.
+pub fn link_tags(conn: &Connection, files: &[(u32, String)], tag_ids: &[u32], now: i64) -> Result<Vec<(String, u32)>> {
+    let mut added = Vec::new();
+    for (file_id, file_path) in files {
+        for tag_id in tag_ids {
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO file_tags (file_id, tag_id, created_at) VALUES (?1, ?2, ?3)",
+                params![file_id, tag_id, now],
+            )?;
+            if inserted > 0 {
+                record_tag_history(conn, *file_id, *tag_id, "added", now)?;
+                added.push((file_path.clone(), *tag_id));
+            }
+        }
+    }
+    Ok(added)
 }
 
 pub fn remove_file_tag(app_handle: &AppHandle, file_id: u32, tag_id: u32) -> Result<()> {
     let conn = Connection::open(get_db_path(app_handle))?;
-    conn.execute(
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    // History reads the path from the file row, so it's recorded before that
+    // row is deleted along with the file's last tag below
+    let removed = conn.execute(
         "DELETE FROM file_tags WHERE file_id = ?1 AND tag_id = ?2",
         params![file_id, tag_id],
     )?;
+    if removed > 0 {
+        record_tag_history(&conn, file_id, tag_id, "removed", now)?;
+    }
     let remaining: i64 = conn.query_row(
         "SELECT COUNT(*) FROM file_tags WHERE file_id = ?1",
         params![file_id],
@@ -921,17 +3076,56 @@ pub fn remove_file_tag(app_handle: &AppHandle, file_id: u32, tag_id: u32) -> Res
     Ok(())
 }
 
+fn record_tag_history(conn: &Connection, file_id: u32, tag_id: u32, action: &str, at: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO tag_history (file_path, tag_id, tag_name, action, at)
+         SELECT f.path, t.id, t.name, ?3, ?4 FROM files f, tags t WHERE f.id = ?1 AND t.id = ?2",
+        params![file_id, tag_id, action, at],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagHistoryEntry {
+    pub tag_id: u32,
+    pub tag_name: String,
+    pub action: String,
+    pub at: i64,
+}
+
+// Newest first
+pub fn get_tag_history(app_handle: &AppHandle, file_path: String) -> Result<Vec<TagHistoryEntry>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT tag_id, tag_name, action, at FROM tag_history
+         WHERE file_path = ?1
+         ORDER BY at DESC, id DESC",
+    )?;
+    let entries = stmt
+        .query_map(params![file_path], |row| {
+            Ok(TagHistoryEntry {
+                tag_id: row.get(0)?,
+                tag_name: row.get(1)?,
+                action: row.get(2)?,
+                at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
 pub fn get_file_tags(app_handle: &AppHandle, file_id: u32) -> Result<Vec<TagInfo>> {
     let conn = Connection::open(get_db_path(app_handle))?;
     let mut stmt = conn.prepare(
-        "SELECT t.id, t.name, t.parent_id, t.color, t.position
+        "SELECT t.id, t.name, t.parent_id, t.color, t.position, t.icon, t.archived, t.locked
          FROM tags t
          JOIN file_tags ft ON t.id = ft.tag_id
          WHERE ft.file_id = ?1
          ORDER BY t.name",
     )?;
 
-    let tags = stmt
+    let mut tags = stmt
         .query_map(params![file_id], |row| {
             Ok(TagInfo {
                 id: row.get(0)?,
@@ -939,32 +3133,139 @@ pub fn get_file_tags(app_handle: &AppHandle, file_id: u32) -> Result<Vec<TagInfo
                 parent_id: row.get(2)?,
                 color: row.get(3)?,
                 position: row.get(4)?,
+                icon: row.get(5)?,
+                archived: row.get::<_, i64>(6)? != 0,
+                locked: row.get::<_, i64>(7)? != 0,
+                shade: None,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
+    apply_shades(app_handle, &conn, &mut tags)?;
 
     Ok(tags)
 }
 
+// Tags of every file in one query, keyed by file id
+pub fn get_all_file_tags(app_handle: &AppHandle) -> Result<std::collections::HashMap<u32, Vec<TagInfo>>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    file_tags_map(app_handle, &conn, "", [])
+}
+
+// Tags per file id, for the file links matched by `filter` (a WHERE clause on `ft`)
+fn file_tags_map(
+    app_handle: &AppHandle,
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<std::collections::HashMap<u32, Vec<TagInfo>>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ft.file_id, t.id, t.name, t.parent_id, t.color, t.position, t.icon, t.archived, t.locked
+         FROM file_tags ft
+         JOIN tags t ON t.id = ft.tag_id
+         {}
+         ORDER BY ft.file_id, t.name",
+        filter
+    ))?;
+    let mut map: std::collections::HashMap<u32, Vec<TagInfo>> = std::collections::HashMap::new();
+    let rows = stmt.query_map(params, |row| {
+        Ok((
+            row.get::<_, u32>(0)?,
+            TagInfo {
+                id: row.get(1)?,
+                name: row.get(2)?,
+                parent_id: row.get(3)?,
+                color: row.get(4)?,
+                position: row.get(5)?,
+                icon: row.get(6)?,
+                archived: row.get::<_, i64>(7)? != 0,
+                locked: row.get::<_, i64>(8)? != 0,
+                shade: None,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (file_id, tag) = row?;
+        map.entry(file_id).or_default().push(tag);
+    }
+    for tags in map.values_mut() {
+        apply_shades(app_handle, conn, tags)?;
+    }
+    Ok(map)
+}
+
+// Up to `limit` (file name, tag names) pairs from the user's own library, used
+// as few-shot examples in LLM prompts. Recently tagged files sharing words with
+// `title` come first; `exclude` is the file being tagged.
+pub fn few_shot_examples(
+    app_handle: &AppHandle,
+    title: &str,
+    exclude: Option<&str>,
+    // Files under these roots are left out
+    blocked_roots: &[String],
+    limit: usize,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT f.path, group_concat(t.name, char(31)), max(ft.created_at) AS tagged
+         FROM file_tags ft
+         JOIN files f ON f.id = ft.file_id
+         JOIN tags t ON t.id = ft.tag_id
+         GROUP BY f.id
+         ORDER BY tagged DESC
+         LIMIT 200",
+    )?;
+    let words = |text: &str| -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() > 1)
+            .map(str::to_string)
+            .collect()
+    };
+    let wanted = words(title);
+    let mut candidates = Vec::new();
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for (i, row) in rows.enumerate() {
+        let (path, tags) = row?;
+        if exclude == Some(path.as_str())
+            || tagme_paths::longest_root(&path, blocked_roots.iter().map(String::as_str)).is_some()
+        {
+            continue;
+        }
+        let name = Path::new(&path).file_name().and_then(|n| n.to_str()).unwrap_or(&path).to_string();
+        let shared = words(&name).iter().filter(|w| wanted.contains(w)).count();
+        let tags: Vec<String> = tags.split('\u{1f}').map(str::to_string).collect();
+        candidates.push((shared, i, name, tags));
+    }
+    // Most shared words first, then most recently tagged
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    Ok(candidates.into_iter().take(limit).map(|(_, _, name, tags)| (name, tags)).collect())
+}
+
 pub fn get_files_by_tags(
     app_handle: &AppHandle,
     tag_ids: Vec<u32>,
     use_and_logic: bool,
 ) -> Result<Vec<FileInfo>> {
-    let conn = Connection::open(get_db_path(app_handle))?;
-
     if tag_ids.is_empty() {
         return get_all_files(app_handle);
     }
+    let conn = Connection::open(get_db_path(app_handle))?;
+    files_by_tags(&conn, &tag_ids, use_and_logic)
+}
 
+// Present files carrying all (AND) or any of `tag_ids`, in path order
+pub fn files_by_tags(conn: &Connection, tag_ids: &[u32], use_and_logic: bool) -> Result<Vec<FileInfo>> {
     let query = if use_and_logic {
-        // AND logic: files must have ALL selected tags
+        // AND logic: files must have ALL selected tags, counted in one pass over
+        // the matching links instead of a subquery per file
         format!(
-            "SELECT DISTINCT f.id, f.path, f.content_hash, f.size_bytes, f.last_modified, f.is_directory
+            "SELECT f.id, f.path, f.content_hash, f.size_bytes, f.last_modified, f.is_directory,
+                    (SELECT MAX(created_at) FROM file_tags WHERE file_id = f.id), f.open_count, f.last_opened_at
              FROM files f
-             WHERE (SELECT COUNT(DISTINCT ft.tag_id) 
-                    FROM file_tags ft 
-                    WHERE ft.file_id = f.id AND ft.tag_id IN ({})) = {}
+             JOIN file_tags ft ON f.id = ft.file_id
+             WHERE f.missing_since IS NULL AND ft.tag_id IN ({})
+             GROUP BY f.id
+             HAVING COUNT(DISTINCT ft.tag_id) = {}
              ORDER BY f.path",
             tag_ids.iter().map(|_| "?").collect::<Vec<_>>().join(","),
             tag_ids.len()
@@ -972,10 +3273,11 @@ pub fn get_files_by_tags(
     } else {
         // OR logic: files must have ANY selected tag
         format!(
-            "SELECT DISTINCT f.id, f.path, f.content_hash, f.size_bytes, f.last_modified, f.is_directory
+            "SELECT DISTINCT f.id, f.path, f.content_hash, f.size_bytes, f.last_modified, f.is_directory,
+                    (SELECT MAX(created_at) FROM file_tags WHERE file_id = f.id), f.open_count, f.last_opened_at
              FROM files f
              JOIN file_tags ft ON f.id = ft.file_id
-             WHERE ft.tag_id IN ({})
+             WHERE f.missing_since IS NULL AND ft.tag_id IN ({})
              ORDER BY f.path",
             tag_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         )
@@ -993,6 +3295,9 @@ pub fn get_files_by_tags(
                 size_bytes: row.get::<_, i64>(3)? as u64,
                 last_modified: row.get(4)?,
                 is_directory: row.get::<_, i64>(5)? != 0,
+                last_tagged: row.get(6)?,
+                open_count: row.get(7)?,
+                last_opened_at: row.get(8)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -1000,6 +3305,282 @@ pub fn get_files_by_tags(
     Ok(files)
 }
 
+// File ids per tag lookup in query_files_with_tags
+const QUERY_TAGS_CHUNK: usize = 500;
+
+// Filtered file list with tags already attached, so callers don't have to join
+// files and tags themselves. An empty filter returns every file; a non-empty
+// `roots` keeps only files owned by one of those roots.
+pub fn query_files_with_tags(app_handle: &AppHandle, filter: TagFilter, roots: Vec<String>) -> Result<Vec<FileWithTags>> {
+    let mut files = get_files_by_tags(app_handle, filter.tag_ids, filter.use_and)?;
+    if !roots.is_empty() {
+        let all_roots = get_root_directories(app_handle)?;
+        files.retain(|f| {
+            tagme_paths::longest_root(&f.path, all_roots.iter().map(|r| r.as_str()))
+                .is_some_and(|owner| roots.iter().any(|r| tagme_paths::same_path(r, owner)))
+        });
+    }
+    // Tags of the matched files only, a chunk at a time to stay under SQLite's
+    // limit on bound parameters
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let ids: Vec<u32> = files.iter().map(|f| f.id).collect();
+    let mut tags_map = std::collections::HashMap::new();
+    for chunk in ids.chunks(QUERY_TAGS_CHUNK) {
+        let marks = vec!["?"; chunk.len()].join(",");
+        tags_map.extend(file_tags_map(
+            app_handle,
+            &conn,
+            &format!("WHERE ft.file_id IN ({})", marks),
+            rusqlite::params_from_iter(chunk.iter()),
+        )?);
+    }
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            let tags = tags_map.remove(&file.id).unwrap_or_default();
+            FileWithTags { file, tags }
+        })
+        .collect())
+}
+
+// Orphan cleanup. Each WHERE clause is shared by the report and its fix so both
+// always agree on what a category contains.
+const UNUSED_TAGS_WHERE: &str = "NOT EXISTS (SELECT 1 FROM file_tags ft WHERE ft.tag_id = tags.id)
+     AND NOT EXISTS (SELECT 1 FROM tags c WHERE c.parent_id = tags.id)";
+const UNTAGGED_FILES_WHERE: &str = "NOT EXISTS (SELECT 1 FROM file_tags ft WHERE ft.file_id = files.id)";
+const ORPHANED_FILES_WHERE: &str = "root_id IS NULL OR root_id NOT IN (SELECT id FROM roots)";
+const MISSING_FILES_WHERE: &str = "missing_since IS NOT NULL";
+const DANGLING_LINKS_WHERE: &str = "file_id NOT IN (SELECT id FROM files) OR tag_id NOT IN (SELECT id FROM tags)";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    // Leaf tags with no assignments
+    pub unused_tags: Vec<TagInfo>,
+    pub untagged_files: Vec<String>,
+    // File rows whose root was removed
+    pub orphaned_files: Vec<String>,
+    // Tagged files that vanished from disk, kept for re-linking
+    pub missing_files: Vec<String>,
+    pub dangling_links: u32,
+}
+
+fn query_paths(conn: &Connection, where_clause: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("SELECT path FROM files WHERE {} ORDER BY path", where_clause))?;
+    let paths = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(paths)
+}
+
+pub fn cleanup_report(app_handle: &AppHandle) -> Result<CleanupReport> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, parent_id, color, position, icon, archived, locked FROM tags WHERE {} ORDER BY name",
+        UNUSED_TAGS_WHERE
+    ))?;
+    let unused_tags = stmt
+        .query_map([], |row| {
+            Ok(TagInfo {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                parent_id: row.get(2)?,
+                color: row.get(3)?,
+                position: row.get(4)?,
+                icon: row.get(5)?,
+                archived: row.get::<_, i64>(6)? != 0,
+                locked: row.get::<_, i64>(7)? != 0,
+                shade: None,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let dangling_links: u32 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM file_tags WHERE {}", DANGLING_LINKS_WHERE),
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(CleanupReport {
+        unused_tags,
+        untagged_files: query_paths(&conn, UNTAGGED_FILES_WHERE)?,
+        orphaned_files: query_paths(&conn, ORPHANED_FILES_WHERE)?,
+        missing_files: query_paths(&conn, MISSING_FILES_WHERE)?,
+        dangling_links,
+    })
+}
+
+// Fixes one report category; returns the number of rows removed
+pub fn apply_cleanup(app_handle: &AppHandle, category: &str) -> Result<usize> {
+    let mut conn = Connection::open(get_db_path(app_handle))?;
+    let tx = conn.transaction()?;
+    let affected = match category {
+        "unusedTags" => tx.execute(&format!("DELETE FROM tags WHERE {}", UNUSED_TAGS_WHERE), [])?,
+        "untaggedFiles" => tx.execute(&format!("DELETE FROM files WHERE {}", UNTAGGED_FILES_WHERE), [])?,
+        "orphanedFiles" => {
+            // foreign_keys is off on these connections, so drop the links explicitly
+            tx.execute(
+                &format!("DELETE FROM file_tags WHERE file_id IN (SELECT id FROM files WHERE {})", ORPHANED_FILES_WHERE),
+                [],
+            )?;
+            tx.execute(&format!("DELETE FROM files WHERE {}", ORPHANED_FILES_WHERE), [])?
+        }
+        "missingFiles" => {
+            tx.execute(
+                &format!("DELETE FROM file_tags WHERE file_id IN (SELECT id FROM files WHERE {})", MISSING_FILES_WHERE),
+                [],
+            )?;
+            tx.execute(&format!("DELETE FROM files WHERE {}", MISSING_FILES_WHERE), [])?
+        }
+        "danglingLinks" => tx.execute(&format!("DELETE FROM file_tags WHERE {}", DANGLING_LINKS_WHERE), [])?,
+        other => {
+            return Err(rusqlite::Error::InvalidParameterName(format!("unknown cleanup category {}", other)));
+        }
+    };
+    tx.commit()?;
+    eprintln!("🧹 [DB] Cleanup {} removed {} row(s)", category, affected);
+    Ok(affected)
+}
+
+// Timings and cache hit rates (see metrics.rs)
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Metric {
+    pub name: String,
+    // "timing" or "cache"
+    pub kind: String,
+    // Timed runs, or cache lookups
+    pub count: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    // Lookups served from the cache
+    pub hits: u64,
+    pub updated_at: i64,
+}
+
+fn bump_timing(conn: &Connection, name: &str, ms: f64) -> Result<()> {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
+    conn.execute(
+        "INSERT INTO metrics (name, kind, count, total_ms, max_ms, last_ms, updated_at)
+         VALUES (?1, 'timing', 1, ?2, ?2, ?2, ?3)
+         ON CONFLICT(name) DO UPDATE SET
+            count = count + 1,
+            total_ms = total_ms + excluded.total_ms,
+            max_ms = MAX(max_ms, excluded.max_ms),
+            last_ms = excluded.last_ms,
+            updated_at = excluded.updated_at",
+        params![name, ms, now],
+    )?;
+    Ok(())
+}
+
+fn bump_cache(conn: &Connection, name: &str, hit: bool) -> Result<()> {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
+    conn.execute(
+        "INSERT INTO metrics (name, kind, count, hits, updated_at)
+         VALUES (?1, 'cache', 1, ?2, ?3)
+         ON CONFLICT(name) DO UPDATE SET
+            count = count + 1,
+            hits = hits + excluded.hits,
+            updated_at = excluded.updated_at",
+        params![name, hit as i64, now],
+    )?;
+    Ok(())
+}
+
+pub fn record_timing(app_handle: &AppHandle, name: &str, ms: f64) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    bump_timing(&conn, name, ms)
+}
+
+pub fn record_cache(app_handle: &AppHandle, name: &str, hit: bool) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    bump_cache(&conn, name, hit)
+}
+
+pub fn get_metrics(app_handle: &AppHandle) -> Result<Vec<Metric>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare(
+        "SELECT name, kind, count, total_ms, max_ms, last_ms, hits, updated_at FROM metrics ORDER BY kind DESC, name",
+    )?;
+    let metrics = stmt
+        .query_map([], |row| {
+            Ok(Metric {
+                name: row.get(0)?,
+                kind: row.get(1)?,
+                count: row.get::<_, i64>(2)? as u64,
+                total_ms: row.get(3)?,
+                max_ms: row.get(4)?,
+                last_ms: row.get(5)?,
+                hits: row.get::<_, i64>(6)? as u64,
+                updated_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(metrics)
+}
+
+pub fn reset_metrics(app_handle: &AppHandle) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("DELETE FROM metrics", [])?;
+    Ok(())
+}
+
+// A batch journal row; `args` is the job's JSON-encoded input
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub id: i64,
+    pub kind: String,
+    pub args: String,
+    pub total: usize,
+    pub done: usize,
+    pub started_at: i64,
+}
+
+pub fn begin_batch(app_handle: &AppHandle, kind: &str, args: &str, total: usize) -> Result<i64> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as i64;
+    conn.execute(
+        "INSERT INTO batch_journal (kind, args, total, done, started_at) VALUES (?1, ?2, ?3, 0, ?4)",
+        params![kind, args, total as i64, now],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn batch_progress(conn: &Connection, id: i64, done: usize) -> Result<()> {
+    conn.execute("UPDATE batch_journal SET done = ?2 WHERE id = ?1", params![id, done as i64])?;
+    Ok(())
+}
+
+pub fn set_batch_progress(app_handle: &AppHandle, id: i64, done: usize) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    batch_progress(&conn, id, done)
+}
+
+pub fn end_batch(app_handle: &AppHandle, id: i64) -> Result<()> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    conn.execute("DELETE FROM batch_journal WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn get_batches(app_handle: &AppHandle) -> Result<Vec<BatchEntry>> {
+    let conn = Connection::open(get_db_path(app_handle))?;
+    let mut stmt = conn.prepare("SELECT id, kind, args, total, done, started_at FROM batch_journal ORDER BY id")?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(BatchEntry {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                args: row.get(2)?,
+                total: row.get::<_, i64>(3)? as usize,
+                done: row.get::<_, i64>(4)? as usize,
+                started_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
 // Window state management
 pub fn save_window_state(
     app_handle: &AppHandle,
@@ -1041,3 +3622,311 @@ pub fn load_window_state(app_handle: &AppHandle) -> Result<Option<WindowState>>
     }
 }
 fn map_tag_id(row: &rusqlite::Row) -> rusqlite::Result<u32> { row.get(0) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    // (id, parent_id, position) for every tag
+    fn tree(conn: &Connection) -> Vec<(u32, Option<u32>, i32)> {
+        let mut stmt = conn.prepare("SELECT id, parent_id, position FROM tags ORDER BY id").unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn children(tags: &[(u32, Option<u32>, i32)], parent: Option<u32>) -> Vec<u32> {
+        let mut kids: Vec<_> = tags.iter().filter(|t| t.1 == parent).collect();
+        kids.sort_by_key(|t| (t.2, t.0));
+        kids.into_iter().map(|t| t.0).collect()
+    }
+
+    fn is_under(tags: &[(u32, Option<u32>, i32)], id: u32, ancestor: u32) -> bool {
+        let mut cursor = Some(id);
+        while let Some(cur) = cursor {
+            if cur == ancestor {
+                return true;
+            }
+            cursor = tags.iter().find(|t| t.0 == cur).and_then(|t| t.1);
+        }
+        false
+    }
+
+    // A database whose only tags are `parents.len()` tags with ids from 1; each
+    // tag's parent is an earlier tag or none
+    fn seeded(parents: &[Option<prop::sample::Index>]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute("DELETE FROM tags", []).unwrap();
+        let mut counts: HashMap<Option<u32>, i32> = HashMap::new();
+        for (i, parent) in parents.iter().enumerate() {
+            let parent_id = parent.filter(|_| i > 0).map(|p| p.index(i) as u32 + 1);
+            let position = counts.entry(parent_id).or_default();
+            conn.execute(
+                "INSERT INTO tags (id, name, parent_id, position, created_at) VALUES (?1, ?2, ?3, ?4, 0)",
+                params![i as u32 + 1, format!("t{}", i), parent_id, *position],
+            )
+            .unwrap();
+            *position += 1;
+        }
+        conn
+    }
+
+    proptest! {
+        #[test]
+        fn moves_keep_positions_contiguous_and_the_tree_acyclic(
+            parents in prop::collection::vec(prop::option::of(any::<prop::sample::Index>()), 1..10),
+            moves in prop::collection::vec(
+                (any::<prop::sample::Index>(), prop::option::of(any::<prop::sample::Index>()), -2i32..14),
+                1..8,
+            ),
+        ) {
+            let conn = seeded(&parents);
+            for (id, parent, target) in moves {
+                let before = tree(&conn);
+                let id = id.index(before.len()) as u32 + 1;
+                let parent = parent.map(|p| p.index(before.len()) as u32 + 1);
+                let result = relocate_tag(&conn, id, parent, target);
+                let after = tree(&conn);
+                if parent.is_some_and(|p| is_under(&before, p, id)) {
+                    prop_assert!(result.is_err());
+                    prop_assert_eq!(&before, &after);
+                    continue;
+                }
+                prop_assert!(result.is_ok());
+
+                for parent in std::iter::once(None).chain(after.iter().map(|t| Some(t.0))) {
+                    // Positions run 0..n under every parent
+                    let mut positions: Vec<i32> = after.iter().filter(|t| t.1 == parent).map(|t| t.2).collect();
+                    positions.sort();
+                    prop_assert_eq!(positions.clone(), (0..positions.len() as i32).collect::<Vec<_>>());
+                    // The other tags keep their order
+                    let others = |tags: &[(u32, Option<u32>, i32)]| {
+                        children(tags, parent).into_iter().filter(|t| *t != id).collect::<Vec<_>>()
+                    };
+                    prop_assert_eq!(others(&before), others(&after));
+                }
+                for tag in &after {
+                    prop_assert!(tag.1.is_none_or(|p| !is_under(&after, p, tag.0)), "cycle through {}", tag.0);
+                }
+                // The moved tag lands at the (clamped) target index
+                let siblings = children(&after, parent);
+                let expected = target.clamp(0, siblings.len() as i32 - 1) as usize;
+                prop_assert_eq!(siblings.iter().position(|t| *t == id), Some(expected));
+            }
+        }
+
+        #[test]
+        fn reorder_renumbers_without_changing_order(positions in prop::collection::vec(-3i32..6, 1..10)) {
+            let conn = seeded(&[]);
+            for (i, position) in positions.iter().enumerate() {
+                conn.execute(
+                    "INSERT INTO tags (id, name, parent_id, position, created_at) VALUES (?1, ?2, NULL, ?3, 0)",
+                    params![i as u32 + 1, format!("t{}", i), position],
+                )
+                .unwrap();
+            }
+            let before = children(&tree(&conn), None);
+            reorder_tags_in_parent(&conn, None).unwrap();
+            let after = tree(&conn);
+            prop_assert_eq!(children(&after, None), before);
+            let mut renumbered: Vec<i32> = after.iter().map(|t| t.2).collect();
+            renumbered.sort();
+            prop_assert_eq!(renumbered, (0..positions.len() as i32).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn journal_progress_commits_with_its_chunk() {
+        let mut conn = seeded(&[None]);
+        conn.execute(
+            "INSERT INTO files (id, path, content_hash, size_bytes, last_modified, created_at, updated_at)
+             VALUES (1, '/a', 'h', 0, 0, 0, 0), (2, '/b', 'h', 0, 0, 0, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO batch_journal (id, kind, args, total, started_at) VALUES (7, 'tag_files', '{}', 2, 0)", [])
+            .unwrap();
+        let state = |conn: &Connection| -> (i64, i64) {
+            let links = conn.query_row("SELECT COUNT(*) FROM file_tags", [], |row| row.get(0)).unwrap();
+            let done = conn.query_row("SELECT done FROM batch_journal WHERE id = 7", [], |row| row.get(0)).unwrap();
+            (links, done)
+        };
+
+        // Killed before the commit: neither the links nor the progress stick
+        let tx = conn.transaction().unwrap();
+        link_tags_chunk(&tx, &[(1, "/a".to_string())], &[1], 0, Some((7, 1))).unwrap();
+        drop(tx);
+        assert_eq!(state(&conn), (0, 0));
+
+        let tx = conn.transaction().unwrap();
+        link_tags_chunk(&tx, &[(1, "/a".to_string())], &[1], 0, Some((7, 1))).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(state(&conn), (1, 1));
+
+        // A resumed run redoing a committed chunk adds nothing twice
+        let tx = conn.transaction().unwrap();
+        let added = link_tags_chunk(&tx, &[(1, "/a".to_string()), (2, "/b".to_string())], &[1], 0, Some((7, 2))).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(added, vec![("/b".to_string(), 1)]);
+        assert_eq!(state(&conn), (2, 2));
+    }
+
+    #[test]
+    fn pins_follow_rebased_paths() {
+        let conn = seeded(&[]);
+        conn.execute(
+            "INSERT INTO files (id, path, content_hash, size_bytes, last_modified, created_at, updated_at)
+             VALUES (1, '/old/a', 'h', 0, 0, 0, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO pinned_files (path, position, pinned_at) VALUES ('/old/a', 0, 0), ('/elsewhere', 1, 0)", [])
+            .unwrap();
+        rebase_file_paths(&conn, "/old", "/new").unwrap();
+        let mut stmt = conn.prepare("SELECT path FROM pinned_files ORDER BY position").unwrap();
+        let pins: Vec<String> = stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(pins, vec!["/new/a".to_string(), "/elsewhere".to_string()]);
+    }
+
+    // Two top-level tags that differ only by case, each with a "Reports/Q1"
+    // subtree, and a file tagged in each copy
+    fn twin_subtrees() -> Connection {
+        let conn = seeded(&[]);
+        conn.execute_batch(
+            "INSERT INTO tags (id, name, parent_id, position, created_at) VALUES
+                (1, 'Work', NULL, 0, 0), (2, 'work', NULL, 1, 0),
+                (3, 'Reports', 1, 0, 0), (4, 'Reports', 2, 0, 0),
+                (5, 'Q1', 3, 0, 0), (6, 'Q1', 4, 0, 0), (7, 'Drafts', 4, 1, 0);
+             INSERT INTO files (id, path, content_hash, size_bytes, last_modified, created_at, updated_at) VALUES
+                (1, '/a', 'h', 0, 0, 0, 0), (2, '/b', 'h', 0, 0, 0, 0);
+             INSERT INTO file_tags (file_id, tag_id, created_at) VALUES (1, 4, 0), (2, 6, 0);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn links(conn: &Connection) -> Vec<(u32, u32)> {
+        let mut stmt = conn.prepare("SELECT file_id, tag_id FROM file_tags ORDER BY file_id").unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn merging_duplicates_folds_children_with_the_same_name() {
+        let conn = twin_subtrees();
+        merge_duplicate_tags(&conn).unwrap();
+        let ids: Vec<u32> = tree(&conn).into_iter().map(|t| t.0).collect();
+        assert_eq!(ids, vec![1, 3, 5, 7]);
+        assert_eq!(children(&tree(&conn), Some(3)), vec![5, 7]);
+        assert_eq!(links(&conn), vec![(1, 3), (2, 5)]);
+    }
+
+    #[test]
+    fn merging_a_tag_folds_children_with_the_same_name() {
+        let mut conn = twin_subtrees();
+        merge_tag(&mut conn, 2, 1).unwrap();
+        let ids: Vec<u32> = tree(&conn).into_iter().map(|t| t.0).collect();
+        assert_eq!(ids, vec![1, 3, 5, 7]);
+        assert_eq!(children(&tree(&conn), Some(1)), vec![3]);
+        assert_eq!(links(&conn), vec![(1, 3), (2, 5)]);
+        assert!(merge_tag(&mut conn, 1, 5).is_err(), "merging into a descendant is refused");
+    }
+
+    fn history(conn: &Connection) -> Vec<(String, u32, String)> {
+        let mut stmt = conn.prepare("SELECT file_path, tag_id, action FROM tag_history ORDER BY id").unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn merging_and_deleting_tags_is_recorded_in_history() {
+        let entry = |path: &str, tag: u32, action: &str| (path.to_string(), tag, action.to_string());
+        let mut conn = twin_subtrees();
+        merge_tag(&mut conn, 2, 1).unwrap();
+        assert_eq!(
+            history(&conn),
+            vec![entry("/a", 3, "added"), entry("/a", 4, "removed"), entry("/b", 5, "added"), entry("/b", 6, "removed")]
+        );
+
+        let mut conn = twin_subtrees();
+        conn.execute("INSERT INTO file_tags (file_id, tag_id, created_at) VALUES (1, 3, 0)", []).unwrap();
+        drop_tag(&mut conn, 2).unwrap();
+        assert_eq!(history(&conn), vec![entry("/a", 4, "removed"), entry("/b", 6, "removed")]);
+        assert_eq!(links(&conn), vec![(1, 3)]);
+    }
+
+    #[test]
+    fn root_defaults_only_tag_newly_discovered_files() {
+        let conn = seeded(&[None, None, None]);
+        conn.execute("INSERT INTO roots (id, path, created_at) VALUES (1, '/scans', 0), (2, '/scans/deep', 0)", [])
+            .unwrap();
+        set_default_tags(&conn, "/scans", &[1, 2], &["/scans/old.pdf".to_string()]).unwrap();
+        set_default_tags(&conn, "/scans/deep", &[3, 99], &[]).unwrap();
+
+        let claimed = claim_new_files(&conn, ["/scans/old.pdf", "/scans/new.pdf", "/scans/deep/x.pdf", "/other/y.pdf"]).unwrap();
+        assert_eq!(
+            claimed,
+            vec![(vec![1, 2], vec!["/scans/new.pdf".to_string()]), (vec![3], vec!["/scans/deep/x.pdf".to_string()])]
+        );
+        // Seen once is enough, even if the file later loses its tags
+        assert!(claim_new_files(&conn, ["/scans/new.pdf", "/scans/deep/x.pdf"]).unwrap().is_empty());
+
+        // Clearing a rule forgets what it saw
+        set_default_tags(&conn, "/scans", &[], &[]).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM discovered_files WHERE root_id = 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 0);
+        assert!(claim_new_files(&conn, ["/scans/later.pdf"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_matches_path_fragments_and_follows_the_files_table() {
+        let conn = seeded(&[None]);
+        conn.execute_batch(
+            "INSERT INTO files (id, path, content_hash, size_bytes, last_modified, created_at, updated_at) VALUES
+                (1, '/docs/Annual Report Q1.pdf', 'h', 0, 0, 0, 0),
+                (2, '/docs/q1_notes.txt', 'h', 0, 0, 0, 0),
+                (3, '/photos/report-cover.png', 'h', 0, 0, 0, 0);
+             INSERT INTO file_tags (file_id, tag_id, created_at) VALUES (3, 1, 0);",
+        )
+        .unwrap();
+        let paths = |query: &str, tag_ids: Vec<u32>| -> Vec<String> {
+            search(&conn, query, &TagFilter { tag_ids, use_and: true }).unwrap().into_iter().map(|f| f.path).collect()
+        };
+
+        assert_eq!(paths("REPORT", vec![]), vec!["/docs/Annual Report Q1.pdf", "/photos/report-cover.png"]);
+        assert_eq!(paths("nual q1", vec![]), vec!["/docs/Annual Report Q1.pdf"]);
+        assert_eq!(paths("q1_", vec![]), vec!["/docs/q1_notes.txt"]);
+        assert_eq!(paths("report", vec![1]), vec!["/photos/report-cover.png"]);
+        assert!(paths("\"report", vec![]).is_empty());
+        assert!(paths("  ", vec![]).is_empty());
+
+        conn.execute("UPDATE files SET path = '/photos/cover.png' WHERE id = 3", []).unwrap();
+        conn.execute("DELETE FROM files WHERE id = 1", []).unwrap();
+        assert!(paths("report", vec![]).is_empty());
+        assert_eq!(paths("cover", vec![]), vec!["/photos/cover.png"]);
+    }
+
+    #[test]
+    fn schema_upgrades_files_without_root_id() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE files (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                content_hash TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                last_modified INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+        )
+        .unwrap();
+        create_schema(&conn).unwrap();
+        let indexed: bool = conn
+            .query_row("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'idx_files_root')", [], |row| row.get(0))
+            .unwrap();
+        assert!(indexed);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+
+use crate::{db, journal};
+
+// Imports a digiKam library (its digikam4.db) so switching keeps the work put
+// into it: the tag hierarchy becomes tagme tags and every image's tags are
+// applied to the file on disk. digiKam's own bookkeeping tags (color and pick
+// labels, face detection state) are skipped. Existing tags are reused, so
+// importing again only adds what's new.
+
+// Parent of digiKam's bookkeeping tags
+const INTERNAL_ROOT: &str = "_Digikam_Internal_Tags_";
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DigikamReport {
+    pub tags_created: usize,
+    pub tags_existing: usize,
+    pub files_tagged: usize,
+    pub links_added: usize,
+    // Images whose file isn't on disk (anymore)
+    pub files_missing: usize,
+    // Album roots whose folder couldn't be found; their images are skipped
+    pub unresolved_roots: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Tag {
+    id: i64,
+    // None for top-level tags (pid 0 in digiKam)
+    parent: Option<i64>,
+    name: String,
+}
+
+#[derive(Debug, Default)]
+struct Library {
+    // Parents before children
+    tags: Vec<Tag>,
+    // Absolute path and digiKam tag ids of every tagged image
+    images: Vec<(PathBuf, Vec<i64>)>,
+    unresolved_roots: Vec<String>,
+}
+
+pub fn import(app_handle: &tauri::AppHandle, db_path: &Path) -> Result<DigikamReport, String> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("{}: {}", db_path.display(), e))?;
+    let library = read(&conn, |p| p.is_dir()).map_err(|e| format!("Not a digiKam database? {}", e))?;
+    let mut report = DigikamReport { unresolved_roots: library.unresolved_roots, ..Default::default() };
+
+    let before: HashSet<u32> = db::get_all_tags(app_handle)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    let mut ids: HashMap<i64, u32> = HashMap::new();
+    for tag in &library.tags {
+        // A tag whose parent failed is skipped along with its subtree
+        let parent = match tag.parent {
+            Some(p) => match ids.get(&p) {
+                Some(&id) => Some(id),
+                None => continue,
+            },
+            None => None,
+        };
+        match db::create_tag(app_handle, tag.name.clone(), parent, None, None) {
+            Ok(id) => {
+                if before.contains(&id) {
+                    report.tags_existing += 1;
+                } else {
+                    report.tags_created += 1;
+                }
+                ids.insert(tag.id, id);
+            }
+            Err(e) => report.errors.push(format!("Tag \"{}\": {}", tag.name, e)),
+        }
+    }
+
+    // Files with the same tags are tagged in one batch
+    let mut batches: BTreeMap<Vec<u32>, Vec<String>> = BTreeMap::new();
+    for (path, tags) in library.images {
+        let tag_ids: BTreeSet<u32> = tags.iter().filter_map(|t| ids.get(t).copied()).collect();
+        if tag_ids.is_empty() {
+            continue;
+        }
+        if !path.is_file() {
+            report.files_missing += 1;
+            continue;
+        }
+        batches.entry(tag_ids.into_iter().collect()).or_default().push(path.to_string_lossy().into_owned());
+    }
+    for (tag_ids, paths) in batches {
+        let count = paths.len();
+        match journal::tag_files(app_handle, paths, tag_ids) {
+            Ok(added) => {
+                report.files_tagged += count;
+                report.links_added += added.len();
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    eprintln!(
+        "🏷️  [DIGIKAM] {} tag(s) created, {} existing, {} file(s) tagged, {} missing, {} root(s) not found",
+        report.tags_created,
+        report.tags_existing,
+        report.files_tagged,
+        report.files_missing,
+        report.unresolved_roots.len()
+    );
+    Ok(report)
+}
+
+// Tags and tagged images of a digiKam database; `exists` checks candidate album
+// root folders
+fn read(conn: &Connection, exists: impl Fn(&Path) -> bool) -> rusqlite::Result<Library> {
+    let mut library = Library::default();
+
+    let mut stmt = conn.prepare("SELECT id, pid, name FROM Tags ORDER BY id")?;
+    let rows: Vec<(i64, i64, String)> =
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?.collect::<Result<_, _>>()?;
+    // Walked down from the top, so parents come first and the internal subtree
+    // (or anything orphaned) is never reached
+    let mut children: HashMap<i64, Vec<(i64, String)>> = HashMap::new();
+    for (id, pid, name) in rows {
+        children.entry(pid).or_default().push((id, name));
+    }
+    let mut stack: Vec<i64> = vec![0];
+    while let Some(pid) = stack.pop() {
+        for (id, name) in children.remove(&pid).unwrap_or_default() {
+            if pid == 0 && name == INTERNAL_ROOT {
+                continue;
+            }
+            library.tags.push(Tag { id, parent: (pid != 0).then_some(pid), name });
+            stack.push(id);
+        }
+    }
+
+    let mut roots: HashMap<i64, PathBuf> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT id, label, identifier, specificPath FROM AlbumRoots")?;
+    let rows: Vec<(i64, Option<String>, String, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<Result<_, _>>()?;
+    for (id, label, identifier, specific) in rows {
+        let specific = specific.unwrap_or_default();
+        match root_candidates(&identifier, &specific).into_iter().find(|p| exists(p)) {
+            Some(path) => {
+                roots.insert(id, path);
+            }
+            None => library.unresolved_roots.push(label.filter(|l| !l.is_empty()).unwrap_or(specific)),
+        }
+    }
+
+    let tagged: HashSet<i64> = library.tags.iter().map(|t| t.id).collect();
+    // Removed images keep their row but lose their album
+    let mut stmt = conn.prepare(
+        "SELECT i.id, a.albumRoot, a.relativePath, i.name, t.tagid
+         FROM Images i
+         JOIN Albums a ON a.id = i.album
+         JOIN ImageTags t ON t.imageid = i.id
+         ORDER BY i.id, t.tagid",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?, row.get::<_, i64>(4)?))
+    })?;
+    let mut last_image = None;
+    for row in rows {
+        let (image, root, relative, name, tag) = row?;
+        let Some(root) = roots.get(&root) else { continue };
+        if !tagged.contains(&tag) {
+            continue;
+        }
+        if last_image != Some(image) {
+            let path = root.join(relative.trim_start_matches('/')).join(&name);
+            library.images.push((path, Vec::new()));
+            last_image = Some(image);
+        }
+        if let Some((_, tags)) = library.images.last_mut() {
+            tags.push(tag);
+        }
+    }
+    Ok(library)
+}
+
+// Folders an album root may be at. digiKam names the volume by path
+// ("volumeid:?path=..."), by network mount ("networkshareid:?mountpath=...") or
+// by UUID, where `specificPath` is relative to wherever the volume is mounted:
+// tried as-is (the system volume) and, on Windows, on every drive letter.
+fn root_candidates(identifier: &str, specific: &str) -> Vec<PathBuf> {
+    let query = identifier.split_once('?').map(|(_, q)| q).unwrap_or_default();
+    let param = |key: &str| {
+        query
+            .split('&')
+            .filter_map(|kv| kv.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| crate::remote::percent_decode(v))
+    };
+    if let Some(path) = param("path").or_else(|| param("mountpath")) {
+        return vec![PathBuf::from(path)];
+    }
+    if specific.is_empty() {
+        return Vec::new();
+    }
+    let mut candidates = vec![PathBuf::from(specific)];
+    if cfg!(windows) {
+        candidates.extend((b'C'..=b'Z').map(|d| PathBuf::from(format!("{}:{}", d as char, specific))));
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The parts of digiKam's schema the importer reads
+    fn library() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE AlbumRoots (id INTEGER PRIMARY KEY, label TEXT, status INTEGER, type INTEGER, identifier TEXT, specificPath TEXT);
+             CREATE TABLE Albums (id INTEGER PRIMARY KEY, albumRoot INTEGER, relativePath TEXT);
+             CREATE TABLE Images (id INTEGER PRIMARY KEY, album INTEGER, name TEXT, status INTEGER);
+             CREATE TABLE Tags (id INTEGER PRIMARY KEY, pid INTEGER, name TEXT);
+             CREATE TABLE ImageTags (imageid INTEGER, tagid INTEGER);
+             INSERT INTO AlbumRoots VALUES
+                (1, 'Pictures', 0, 1, 'volumeid:?path=%2Fhome%2Fme%2FPictures', '/'),
+                (2, 'USB', 0, 1, 'volumeid:?uuid=1234', '/photos');
+             INSERT INTO Albums VALUES (1, 1, '/'), (2, 1, '/2023/Trip'), (3, 2, '/');
+             INSERT INTO Tags VALUES
+                (1, 0, 'Places'), (2, 1, 'Italy'), (3, 0, '_Digikam_Internal_Tags_'), (4, 3, 'Color Label Red'),
+                (5, 0, 'People'), (6, 5, 'Ada');
+             INSERT INTO Images VALUES (1, 1, 'cat.jpg', 1), (2, 2, 'rome.jpg', 1), (3, 3, 'usb.jpg', 1), (4, NULL, 'gone.jpg', 3);
+             INSERT INTO ImageTags VALUES (1, 6), (2, 2), (2, 6), (2, 4), (3, 2), (4, 2);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn reads_the_hierarchy_without_internal_tags() {
+        let lib = read(&library(), |_| true).unwrap();
+        let names: Vec<&str> = lib.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names.len(), 4);
+        assert!(!names.iter().any(|n| n.starts_with("_Digikam") || n.starts_with("Color")));
+        for tag in &lib.tags {
+            let parent_index = tag.parent.map(|p| lib.tags.iter().position(|t| t.id == p).expect("parent imported"));
+            let index = lib.tags.iter().position(|t| t.id == tag.id).unwrap();
+            assert!(parent_index.is_none_or(|p| p < index), "{} comes after its parent", tag.name);
+        }
+        assert_eq!(lib.tags.iter().find(|t| t.name == "Italy").unwrap().parent, Some(1));
+    }
+
+    #[test]
+    fn resolves_image_paths_and_skips_removed_images() {
+        let lib = read(&library(), |p| p == Path::new("/home/me/Pictures")).unwrap();
+        assert_eq!(
+            lib.images,
+            vec![
+                (PathBuf::from("/home/me/Pictures/cat.jpg"), vec![6]),
+                (PathBuf::from("/home/me/Pictures/2023/Trip/rome.jpg"), vec![2, 6]),
+            ]
+        );
+        assert_eq!(lib.unresolved_roots, vec!["USB".to_string()]);
+    }
+
+    #[test]
+    fn root_candidates_follow_the_identifier() {
+        assert_eq!(root_candidates("volumeid:?path=%2Fmnt%2Fa%20b", "/"), vec![PathBuf::from("/mnt/a b")]);
+        assert_eq!(root_candidates("networkshareid:?mountpath=/mnt/nas", "/x"), vec![PathBuf::from("/mnt/nas")]);
+        assert_eq!(root_candidates("volumeid:?uuid=1234&fileuuid=5", "/photos")[0], PathBuf::from("/photos"));
+        assert!(root_candidates("volumeid:?uuid=1234", "").is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+// Resolves duplicate files the user reviewed in the dialog. For each group the
+// plan names the copy to keep and whether to copy every copy's tags onto the
+// others and/or move the other copies to the system trash. Groups are re-read
+// at run time, and a copy is only trashed when both it and the kept file still
+// hash to the group's content.
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateAction {
+    pub content_hash: String,
+    pub keep_id: u32,
+    pub union_tags: bool,
+    pub trash_others: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    // Tag links added by tag unions
+    pub tagged: usize,
+    pub trashed: usize,
+    pub freed_bytes: u64,
+    // "path: reason" for copies that were left alone
+    pub errors: Vec<String>,
+}
+
+// Runs the plan; `progress` gets (done, total) after each group. Returns the
+// report and the new tag links, for tag hooks.
+pub fn resolve(
+    app_handle: &tauri::AppHandle,
+    actions: Vec<DuplicateAction>,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<(DuplicateReport, Vec<(String, u32)>), String> {
+    let groups = db::duplicate_files(app_handle).map_err(|e| e.to_string())?;
+    let mut report = DuplicateReport::default();
+    let mut added = Vec::new();
+    let total = actions.len();
+    for (i, action) in actions.into_iter().enumerate() {
+        let group = groups.iter().find(|g| g.content_hash == action.content_hash);
+        let keep = group.and_then(|g| g.files.iter().find(|f| f.id == action.keep_id));
+        let (Some(group), Some(keep)) = (group, keep) else {
+            report.errors.push(format!("{}: no longer a duplicate", action.content_hash));
+            progress(i + 1, total);
+            continue;
+        };
+
+        if action.union_tags {
+            let tag_ids: BTreeSet<u32> = group.files.iter().flat_map(|f| f.tag_ids.iter().copied()).collect();
+            // Copies about to be trashed don't need the tags
+            let targets: Vec<String> = group
+                .files
+                .iter()
+                .filter(|f| !action.trash_others || f.id == keep.id)
+                .map(|f| f.path.clone())
+                .collect();
+            match db::add_tags_to_files(app_handle, targets, tag_ids.into_iter().collect()) {
+                Ok(links) => {
+                    report.tagged += links.len();
+                    added.extend(links);
+                }
+                Err(e) => report.errors.push(format!("{}: {}", keep.path, e)),
+            }
+        }
+
+        if action.trash_others {
+            if !same_content(&keep.path, &group.content_hash) {
+                report.errors.push(format!("{}: kept copy changed or is missing, nothing trashed", keep.path));
+            } else {
+                for file in group.files.iter().filter(|f| f.id != keep.id) {
+                    if !same_content(&file.path, &group.content_hash) {
+                        report.errors.push(format!("{}: content changed since the scan", file.path));
+                        continue;
+                    }
+                    if let Err(e) = trash::delete(&file.path) {
+                        report.errors.push(format!("{}: {}", file.path, e));
+                        continue;
+                    }
+                    if let Err(e) = db::forget_file(app_handle, file.id) {
+                        report.errors.push(format!("{}: {}", file.path, e));
+                    }
+                    report.trashed += 1;
+                    report.freed_bytes += group.size_bytes;
+                }
+            }
+        }
+        progress(i + 1, total);
+    }
+    eprintln!(
+        "🧹 [DUPLICATES] {} tag link(s) added, {} copy(ies) trashed, {} skipped",
+        report.tagged,
+        report.trashed,
+        report.errors.len()
+    );
+    Ok((report, added))
+}
+
+fn same_content(path: &str, content_hash: &str) -> bool {
+    db::hash_file_content(Path::new(path)).is_ok_and(|hash| hash == content_hash)
+}
@@ -0,0 +1,67 @@
+use crate::db;
+
+// What may leave the machine: in offline mode only local model endpoints are
+// used at all, and files under roots marked "never send to cloud" (their names,
+// contents or pixels) only ever go to local ones.
+
+pub fn offline_mode(app_handle: &tauri::AppHandle) -> bool {
+    db::get_setting(app_handle, "offline_mode").ok().flatten().as_deref() == Some("true")
+}
+
+pub fn set_offline_mode(app_handle: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(app_handle, "offline_mode", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+// Whether images lose their EXIF/GPS metadata before going to a cloud model; on
+// unless turned off
+pub fn strip_image_metadata(app_handle: &tauri::AppHandle) -> bool {
+    db::get_setting(app_handle, "strip_image_metadata").ok().flatten().as_deref() != Some("false")
+}
+
+pub fn set_strip_image_metadata(app_handle: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(app_handle, "strip_image_metadata", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+// Images bound for `base_url` are re-encoded without metadata; local models get the original
+pub fn strip_images_for(app_handle: &tauri::AppHandle, base_url: Option<String>) -> bool {
+    strip_image_metadata(app_handle) && !is_local(&llm_flow::resolve_base_url(base_url))
+}
+
+// Loopback endpoints (Ollama, LM Studio, llama.cpp) never leave the machine
+pub fn is_local(base_url: &str) -> bool {
+    let rest = base_url.split_once("://").map(|(_, r)| r).unwrap_or(base_url);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    };
+    let host = host.to_ascii_lowercase();
+    host == "localhost" || host.ends_with(".localhost") || host == "::1" || host.starts_with("127.")
+}
+
+// Roots whose files may not be sent to `base_url`; none for a local endpoint
+pub fn blocked_roots(app_handle: &tauri::AppHandle, base_url: Option<String>) -> Result<Vec<String>, String> {
+    if is_local(&llm_flow::resolve_base_url(base_url)) {
+        return Ok(Vec::new());
+    }
+    db::get_local_only_roots(app_handle).map_err(|e| e.to_string())
+}
+
+// Err when a request about `paths` (possibly none) may not go to `base_url`
+pub fn check(app_handle: &tauri::AppHandle, base_url: Option<String>, paths: &[&str]) -> Result<(), String> {
+    let base = llm_flow::resolve_base_url(base_url);
+    if is_local(&base) {
+        return Ok(());
+    }
+    if offline_mode(app_handle) {
+        return Err("Offline mode is on: only local models can be used".to_string());
+    }
+    let roots = db::get_local_only_roots(app_handle).map_err(|e| e.to_string())?;
+    for path in paths {
+        if let Some(root) = tagme_paths::longest_root(path, roots.iter().map(String::as_str)) {
+            return Err(format!("{} is never sent to cloud models (set on {})", path, root));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+// Local embedding backend (any OpenAI-compatible /embeddings endpoint, e.g.
+// Ollama) used to match file names to tags by meaning. Stored as JSON under the
+// "embedding_backend" settings key.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmbeddingSettings {
+    pub enabled: bool,
+    pub base_url: String,
+    pub model: String,
+    // Cosine similarity a tag needs to count as a match
+    pub min_score: f32,
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "http://localhost:11434/v1".to_string(),
+            model: "nomic-embed-text".to_string(),
+            min_score: 0.5,
+        }
+    }
+}
+
+pub fn load(app_handle: &tauri::AppHandle) -> EmbeddingSettings {
+    db::get_setting(app_handle, "embedding_backend")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app_handle: &tauri::AppHandle, settings: &EmbeddingSettings) -> Result<(), String> {
+    let raw = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::set_setting(app_handle, "embedding_backend", &raw).map_err(|e| e.to_string())
+}
+
+// Tag-name vectors keyed by (model, name); tags rarely change, file names always do
+type VectorCache = HashMap<(String, String), Vec<f32>>;
+static TAG_VECTORS: Mutex<Option<VectorCache>> = Mutex::new(None);
+
+// Similarity of `title` to each tag name at or above `min_score`, best first.
+// None when the backend is off or unreachable, so callers fall back to rules.
+pub async fn score_tags(settings: &EmbeddingSettings, title: &str, tag_names: &[String]) -> Option<Vec<(String, f32)>> {
+    if !settings.enabled || title.trim().is_empty() || tag_names.is_empty() {
+        return None;
+    }
+    let key = |name: &String| (settings.model.clone(), name.clone());
+    let missing: Vec<String> = {
+        let cache = TAG_VECTORS.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = cache.as_ref();
+        tag_names.iter().filter(|n| cache.is_none_or(|c| !c.contains_key(&key(n)))).cloned().collect()
+    };
+    // One request for the title plus any tags not seen yet
+    let mut input = vec![title.to_string()];
+    input.extend(missing.iter().cloned());
+    let vectors = match llm_flow::embed(input, settings.base_url.clone(), settings.model.clone()).await {
+        Ok(v) if v.len() == missing.len() + 1 => v,
+        Ok(_) => return None,
+        Err(e) => {
+            eprintln!("[EMBED] backend unavailable: {}", e);
+            return None;
+        }
+    };
+    let mut vectors = vectors.into_iter();
+    let title_vec = vectors.next()?;
+    let mut cache = TAG_VECTORS.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    for (name, vec) in missing.iter().zip(vectors) {
+        cache.insert(key(name), vec);
+    }
+    let mut scores: Vec<(String, f32)> = tag_names
+        .iter()
+        .filter_map(|name| cache.get(&key(name)).map(|v| (name.clone(), cosine(&title_vec, v))))
+        .filter(|(_, s)| *s >= settings.min_score)
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Some(scores)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
@@ -0,0 +1,118 @@
+use std::process::{Command, Output};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::db;
+
+// Hooks run one at a time, in the order tags were applied, so a batch tag of
+// many files doesn't start dozens of conversions at once
+static RUNNING: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookFailure {
+    pub path: String,
+    pub command: String,
+    pub reason: String,
+}
+
+// Runs the hooks of the given tags for the files they were just applied to, as
+// (path, tag id), in the background. Failures are emitted as "tag-hook-failed".
+pub fn run(app_handle: &tauri::AppHandle, links: Vec<(String, u32)>) {
+    if links.is_empty() {
+        return;
+    }
+    let hooks = match db::get_tag_hooks(app_handle) {
+        Ok(hooks) if !hooks.is_empty() => hooks,
+        _ => return,
+    };
+    let jobs: Vec<(String, String)> = links
+        .into_iter()
+        .filter_map(|(path, tag_id)| {
+            let hook = hooks.iter().find(|h| h.tag_id == tag_id)?;
+            Some((hook.command.clone(), path))
+        })
+        .collect();
+    if jobs.is_empty() {
+        return;
+    }
+    let app = app_handle.clone();
+    std::thread::spawn(move || {
+        let _guard = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        for (command, path) in jobs {
+            eprintln!("🪝 [HOOK] {} \"{}\"", command, path);
+            let reason = match shell(&command, &path).output() {
+                Ok(output) if output.status.success() => continue,
+                Ok(output) => failure_reason(&output),
+                Err(e) => e.to_string(),
+            };
+            eprintln!("⚠️ [HOOK] Failed for {}: {}", path, reason);
+            let _ = app.emit("tag-hook-failed", HookFailure { path, command, reason });
+        }
+    });
+}
+
+// `command` is a shell command line; the file path is appended as its last
+// argument. Also used to start tagger plugins.
+pub fn shell(command: &str, path: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let mut cmd = Command::new("cmd");
+        cmd.raw_arg(cmd_line(command)).env(PATH_VAR, path).creation_flags(CREATE_NO_WINDOW);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(format!("{} \"$1\"", command)).arg("tagme-hook").arg(path);
+        cmd
+    }
+}
+
+// The path reaches cmd through this variable: the value of an expanded
+// variable isn't expanded again, so `%` and `&` in file names stay literal
+#[cfg_attr(not(windows), allow(dead_code))]
+const PATH_VAR: &str = "TAGME_FILE";
+
+// With /S, cmd strips exactly the outer pair of quotes and runs the rest as
+// typed, so a quoted program path in `command` keeps its own quotes
+#[cfg_attr(not(windows), allow(dead_code))]
+fn cmd_line(command: &str) -> String {
+    format!("/S /C \"{} \"%{}%\"\"", command, PATH_VAR)
+}
+
+// Last line of stderr, or the exit status when the hook printed nothing
+fn failure_reason(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l.trim().to_string())
+        .unwrap_or_else(|| output.status.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cmd_line_keeps_quoted_programs_intact() {
+        assert_eq!(
+            cmd_line(r#""C:\Program Files\ffmpeg\ffmpeg.exe" -i"#),
+            r#"/S /C ""C:\Program Files\ffmpeg\ffmpeg.exe" -i "%TAGME_FILE%"""#
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn path_is_passed_literally() {
+        let path = "/tmp/a b/$HOME %PATH% 'q' \"d\".txt";
+        let output = shell("printf %s", path).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), path);
+    }
+}
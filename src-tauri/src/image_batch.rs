@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use llm_flow::RecommendItem;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::proxy;
+
+// Upper bound on simultaneous vision requests, whatever the setting says
+pub const MAX_CONCURRENCY: usize = 8;
+
+static CANCEL: AtomicBool = AtomicBool::new(false);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageRequest {
+    pub image_path: String,
+    // Tags the image already has, rated by the model as well
+    #[serde(default)]
+    pub existing: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageResult {
+    pub image_path: String,
+    pub items: Vec<RecommendItem>,
+    pub error: Option<String>,
+}
+
+// Emitted as "image-tag-status" when an image starts ("running") and when it
+// finishes ("done" or "failed")
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ImageStatus<'a> {
+    image_path: &'a str,
+    status: &'a str,
+    error: Option<&'a str>,
+}
+
+pub struct BatchOptions {
+    pub labels: Vec<String>,
+    pub top_k: usize,
+    pub threshold: f32,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub concurrency: usize,
+    pub strip_metadata: bool,
+}
+
+// Stops a running batch after the requests already in flight
+pub fn cancel() {
+    CANCEL.store(true, Ordering::SeqCst);
+}
+
+// Tags the images with up to `concurrency` vision requests at a time. Results
+// come back in completion order; images skipped by a cancel are left out.
+pub async fn run(app_handle: tauri::AppHandle, requests: Vec<ImageRequest>, options: BatchOptions) -> Vec<ImageResult> {
+    CANCEL.store(false, Ordering::SeqCst);
+    let workers = options.concurrency.clamp(1, MAX_CONCURRENCY).min(requests.len());
+    let proxy = proxy::proxy_url(&app_handle);
+    let queue = Arc::new(Mutex::new(VecDeque::from(requests)));
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let options = Arc::new(options);
+
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let (app, queue, results, options, proxy) =
+                (app_handle.clone(), queue.clone(), results.clone(), options.clone(), proxy.clone());
+            tauri::async_runtime::spawn(async move {
+                while !CANCEL.load(Ordering::SeqCst) {
+                    let Some(req) = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front() else {
+                        break;
+                    };
+                    let status = ImageStatus { image_path: &req.image_path, status: "running", error: None };
+                    let _ = app.emit("image-tag-status", status);
+                    let outcome = llm_flow::generate_image_tags_llm(
+                        req.image_path.clone(),
+                        options.labels.clone(),
+                        req.existing,
+                        options.top_k,
+                        options.threshold,
+                        options.base_url.clone(),
+                        options.model.clone(),
+                        proxy.clone(),
+                        options.strip_metadata,
+                    )
+                    .await;
+                    let (items, error) = match outcome {
+                        Ok(items) => (items, None),
+                        Err(e) => (Vec::new(), Some(e)),
+                    };
+                    let status = ImageStatus {
+                        image_path: &req.image_path,
+                        status: if error.is_some() { "failed" } else { "done" },
+                        error: error.as_deref(),
+                    };
+                    let _ = app.emit("image-tag-status", status);
+                    let result = ImageResult { image_path: req.image_path, items, error };
+                    results.lock().unwrap_or_else(|e| e.into_inner()).push(result);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        let _ = handle.await;
+    }
+    let mut results = results.lock().unwrap_or_else(|e| e.into_inner());
+    std::mem::take(&mut *results)
+}
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{db, manifest, tag_import};
+
+// Resumable batch jobs: mass tagging, tag imports and manifest exports write a
+// `batch_journal` row with their input before they start and drop it when they
+// end. A row still there at startup is a run that was killed; the UI offers to
+// resume it from its recorded progress or to discard it.
+
+pub const TAG_FILES: &str = "tag_files";
+pub const IMPORT_TAGS: &str = "import_tags";
+pub const EXPORT_MANIFEST: &str = "export_manifest";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TagFiles {
+    file_paths: Vec<String>,
+    tag_ids: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportTags {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportManifest {
+    root: Option<String>,
+    tag_ids: Vec<u32>,
+    use_and: bool,
+    dest: PathBuf,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBatch {
+    pub id: i64,
+    pub kind: String,
+    pub description: String,
+    pub total: usize,
+    pub done: usize,
+    pub started_at: i64,
+}
+
+pub fn tag_files(app_handle: &tauri::AppHandle, file_paths: Vec<String>, tag_ids: Vec<u32>) -> Result<Vec<(String, u32)>, String> {
+    let args = TagFiles { file_paths, tag_ids };
+    let id = begin(app_handle, TAG_FILES, &args, args.file_paths.len())?;
+    finish(app_handle, id, run_tag_files(app_handle, id, &args, 0))
+}
+
+pub fn import_tags(app_handle: &tauri::AppHandle, text: String) -> Result<tag_import::ImportReport, String> {
+    let args = ImportTags { text };
+    let id = begin(app_handle, IMPORT_TAGS, &args, tag_import::row_count(&args.text))?;
+    finish(app_handle, id, run_import(app_handle, id, &args, 0))
+}
+
+pub fn export_manifest(
+    app_handle: &tauri::AppHandle,
+    root: Option<String>,
+    tag_ids: Vec<u32>,
+    use_and: bool,
+    dest: PathBuf,
+) -> Result<usize, String> {
+    let args = ExportManifest { root, tag_ids, use_and, dest };
+    let id = begin(app_handle, EXPORT_MANIFEST, &args, 1)?;
+    finish(app_handle, id, run_export(app_handle, &args))
+}
+
+// Runs left over from a previous session, oldest first
+pub fn pending(app_handle: &tauri::AppHandle) -> Result<Vec<PendingBatch>, String> {
+    let entries = db::get_batches(app_handle).map_err(|e| e.to_string())?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| PendingBatch {
+            description: describe(&entry),
+            id: entry.id,
+            kind: entry.kind,
+            total: entry.total,
+            done: entry.done,
+            started_at: entry.started_at,
+        })
+        .collect())
+}
+
+pub fn discard(app_handle: &tauri::AppHandle, id: i64) -> Result<(), String> {
+    db::end_batch(app_handle, id).map_err(|e| e.to_string())
+}
+
+// Finishes an interrupted run; returns a summary for the UI. Tagging resumes
+// after its last committed chunk and imports after their last imported row;
+// every step is idempotent, so redoing the few that raced the crash is harmless.
+// Manifest exports start over.
+pub fn resume(app_handle: &tauri::AppHandle, id: i64) -> Result<String, String> {
+    let entry = db::get_batches(app_handle)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or("That batch operation is no longer pending")?;
+    match entry.kind.as_str() {
+        TAG_FILES => {
+            let args: TagFiles = decode(&entry)?;
+            let added = finish(app_handle, id, run_tag_files(app_handle, id, &args, entry.done))?;
+            crate::hooks::run(app_handle, added.clone());
+            Ok(format!("Added {} tag link(s)", added.len()))
+        }
+        IMPORT_TAGS => {
+            let args: ImportTags = decode(&entry)?;
+            let report = finish(app_handle, id, run_import(app_handle, id, &args, entry.done))?;
+            Ok(format!("Imported {} more tag(s)", report.created))
+        }
+        EXPORT_MANIFEST => {
+            let args: ExportManifest = decode(&entry)?;
+            let listed = finish(app_handle, id, run_export(app_handle, &args))?;
+            Ok(format!("Wrote {} file(s) to {}", listed, args.dest.display()))
+        }
+        other => {
+            discard(app_handle, id)?;
+            Err(format!("Unknown batch operation: {}", other))
+        }
+    }
+}
+
+fn run_tag_files(app_handle: &tauri::AppHandle, id: i64, args: &TagFiles, done: usize) -> Result<Vec<(String, u32)>, String> {
+    let rest = args.file_paths.get(done..).unwrap_or_default();
+    db::add_tags_journaled(app_handle, rest, &args.tag_ids, Some((id, done))).map_err(|e| e.to_string())
+}
+
+fn run_import(app_handle: &tauri::AppHandle, id: i64, args: &ImportTags, done: usize) -> Result<tag_import::ImportReport, String> {
+    tag_import::import(app_handle, &args.text, done, |rows| {
+        if let Err(e) = db::set_batch_progress(app_handle, id, rows) {
+            eprintln!("⚠️ [JOURNAL] Failed to record import progress: {}", e);
+        }
+    })
+}
+
+fn run_export(app_handle: &tauri::AppHandle, args: &ExportManifest) -> Result<usize, String> {
+    manifest::export(app_handle, args.root.as_deref(), args.tag_ids.clone(), args.use_and, &args.dest)
+}
+
+fn begin(app_handle: &tauri::AppHandle, kind: &str, args: &impl Serialize, total: usize) -> Result<i64, String> {
+    let raw = serde_json::to_string(args).map_err(|e| e.to_string())?;
+    db::begin_batch(app_handle, kind, &raw, total).map_err(|e| e.to_string())
+}
+
+// Drops the journal row once the run is over. Failed runs are dropped too: the
+// error was already reported and resuming would most likely hit it again.
+fn finish<T>(app_handle: &tauri::AppHandle, id: i64, result: Result<T, String>) -> Result<T, String> {
+    if let Err(e) = db::end_batch(app_handle, id) {
+        eprintln!("⚠️ [JOURNAL] Failed to close batch {}: {}", id, e);
+    }
+    result
+}
+
+fn decode<T: DeserializeOwned>(entry: &db::BatchEntry) -> Result<T, String> {
+    serde_json::from_str(&entry.args).map_err(|e| format!("Unreadable batch operation: {}", e))
+}
+
+fn describe(entry: &db::BatchEntry) -> String {
+    match entry.kind.as_str() {
+        TAG_FILES => match decode::<TagFiles>(entry) {
+            Ok(args) => format!("Tagging {} file(s) with {} tag(s)", args.file_paths.len(), args.tag_ids.len()),
+            Err(_) => "Tagging files".to_string(),
+        },
+        IMPORT_TAGS => format!("Importing {} tag line(s)", entry.total),
+        EXPORT_MANIFEST => match decode::<ExportManifest>(entry) {
+            Ok(args) => format!("Exporting a checksum manifest to {}", args.dest.display()),
+            Err(_) => "Exporting a checksum manifest".to_string(),
+        },
+        other => other.to_string(),
+    }
+}
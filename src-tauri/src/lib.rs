@@ -1,15 +1,70 @@
 use tauri::{Emitter, Manager};
-use updater_flow::UpdateInfo;
+use updater_flow::{UpdateChannel, UpdateInfo};
+use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_dialog::DialogExt;
 
 use notify::{Event, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 mod ai;
+mod archives;
+mod clipboard;
+mod data_dir;
+mod digikam;
 mod db;
+mod duplicates;
+mod egress;
+mod embeddings;
+mod hooks;
+mod image_batch;
+mod journal;
+mod manifest;
+mod obsidian;
+mod metrics;
+mod pdf;
+mod plugins;
+mod pricing;
+mod proxy;
+mod remote;
+mod safety;
+mod scripting;
+mod tag_export;
+mod tag_import;
+mod watch_events;
 
-// Global file watcher state
-static WATCHERS: Mutex<Vec<Arc<Mutex<notify::RecommendedWatcher>>>> = Mutex::new(Vec::new());
+// Connection-level database functions for the criterion benchmarks
+// (benches/db.rs); not part of the app
+#[cfg(feature = "bench")]
+pub mod bench {
+    pub use crate::db::{create_schema, files_by_tags, link_tags, scan_directory_limited};
+}
+
+// Global file watcher state, keyed by canonical root path; the value keeps the root
+// as the user configured it alongside its watcher.
+static WATCHERS: Mutex<BTreeMap<String, (String, Arc<Mutex<notify::RecommendedWatcher>>)>> = Mutex::new(BTreeMap::new());
+// Roots muted by the user; in-memory only, so a restart resumes everything
+static PAUSED_ROOTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// Last watcher failure per root, cleared once the root is watched again
+static WATCH_ERRORS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+// Roots with a self-heal thread in flight
+static HEALING_ROOTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// Roots whose folder is gone or doesn't answer, usually an unplugged drive or a
+// disconnected network share; see monitor_volumes
+static OFFLINE_ROOTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// Roots with a reachability probe still running, possibly stuck on a dead share
+static PROBING_ROOTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// Per-root listing limit raised by "load more"; roots not present use db::SCAN_PAGE_SIZE
+static SCAN_LIMITS: Mutex<BTreeMap<String, usize>> = Mutex::new(BTreeMap::new());
+// Paths this process was launched with, until the frontend picks them up
+static LAUNCH_PATHS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// tagme:// link this process was launched with, until the frontend picks it up
+static LAUNCH_LINK: Mutex<Option<String>> = Mutex::new(None);
+// Set while an integrity check is running; a second request is refused
+static VERIFYING: AtomicBool = AtomicBool::new(false);
+// Set while a duplicate cleanup is running; a second request is refused
+static RESOLVING_DUPLICATES: AtomicBool = AtomicBool::new(false);
 
 // Window management commands
 #[tauri::command]
@@ -47,6 +102,9 @@ fn toggle_maximize(window: tauri::Window) {
 }
 
 // Root directory commands
+
+// Picks a folder and returns it canonicalized; adding it is a separate step so the
+// UI can confirm nesting first
 #[tauri::command]
 async fn select_root_directory(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
     let dialog = app_handle.dialog().file();
@@ -54,9 +112,7 @@ async fn select_root_directory(app_handle: tauri::AppHandle) -> Result<Option<St
     if let Some(file_path) = dialog.blocking_pick_folder() {
         if let Some(path) = file_path.as_path() {
             if let Some(path_str) = path.to_str() {
-                db::add_root_directory(&app_handle, path_str.to_string())
-                    .map_err(|e| e.to_string())?;
-                return Ok(Some(path_str.to_string()));
+                return Ok(Some(db::canonical_root(path_str)));
             }
         }
         Err("Invalid path encoding".to_string())
@@ -65,6 +121,11 @@ async fn select_root_directory(app_handle: tauri::AppHandle) -> Result<Option<St
     }
 }
 
+#[tauri::command]
+fn add_root_directory(app_handle: tauri::AppHandle, path: String, allow_nested: bool) -> Result<String, String> {
+    db::add_root_directory(&app_handle, path, allow_nested).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_root_directory(app_handle: tauri::AppHandle) -> Option<String> {
     db::get_root_directory(&app_handle).ok().flatten()
@@ -77,20 +138,155 @@ fn get_root_directories(app_handle: tauri::AppHandle) -> Result<Vec<String>, Str
 
 #[tauri::command]
 fn remove_root_directory(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    remote::forget(&app_handle, &path)?;
     db::remove_root_directory(&app_handle, path).map_err(|e| e.to_string())
 }
 
+// Adds a WebDAV or S3 location as a root once a listing with `settings` works
+#[tauri::command]
+async fn add_remote_root(
+    app_handle: tauri::AppHandle,
+    url: String,
+    settings: remote::RemoteSettings,
+) -> Result<String, String> {
+    let url = tagme_paths::normalize(url.trim());
+    if !remote::is_remote(&url) {
+        return Err("Use a dav://, davs:// or s3:// address".to_string());
+    }
+    let app = app_handle.clone();
+    let root = url.clone();
+    tauri::async_runtime::spawn_blocking(move || remote::list_with(&app, &root, &settings).map(|_| settings))
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|settings| remote::save_settings(&app_handle, &url, settings))?;
+    db::add_root_directory(&app_handle, url, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn reorder_root_directories(app_handle: tauri::AppHandle, paths: Vec<String>) -> Result<(), String> {
+    db::reorder_root_directories(&app_handle, paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_default_root(app_handle: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
+    db::set_default_root(&app_handle, path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_default_root(app_handle: tauri::AppHandle) -> Option<String> {
+    db::get_default_root(&app_handle).ok().flatten()
+}
+
+// Files under a local-only root are only sent to local models
+#[tauri::command]
+fn set_root_local_only(app_handle: tauri::AppHandle, path: String, local_only: bool) -> Result<(), String> {
+    db::set_root_local_only(&app_handle, &path, local_only).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_local_only_roots(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    db::get_local_only_roots(&app_handle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_root_default_tags(app_handle: tauri::AppHandle) -> Result<std::collections::HashMap<String, Vec<u32>>, String> {
+    db::get_root_default_tags(&app_handle).map_err(|e| e.to_string())
+}
+
+// Files already in the root when the rule is saved aren't new and keep their tags
+#[tauri::command]
+fn set_root_default_tags(app_handle: tauri::AppHandle, path: String, tag_ids: Vec<u32>) -> Result<(), String> {
+    let existing: Vec<String> = if tag_ids.is_empty() || remote::is_remote(&path) {
+        Vec::new()
+    } else {
+        db::scan_directory_lightweight(path.clone())
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|i| !i.is_directory)
+            .map(|i| i.path)
+            .collect()
+    };
+    db::set_root_default_tags(&app_handle, &path, tag_ids, &existing).map_err(|e| e.to_string())
+}
+
+// Gives files a scan finds for the first time the default tags of their root
+fn apply_root_defaults(app_handle: &tauri::AppHandle, items: &[db::FileListItem]) {
+    let batches = match db::claim_discovered(app_handle, items) {
+        Ok(batches) => batches,
+        Err(e) => {
+            eprintln!("⚠️ [TAURI] Failed to check root default tags: {}", e);
+            return;
+        }
+    };
+    for (tag_ids, paths) in batches {
+        let count = paths.len();
+        match journal::tag_files(app_handle, paths, tag_ids) {
+            Ok(added) => {
+                eprintln!("🏷️  [TAURI] Root default tags applied to {} new file(s)", count);
+                hooks::run(app_handle, added);
+            }
+            Err(e) => eprintln!("⚠️ [TAURI] Failed to apply root default tags: {}", e),
+        }
+    }
+}
+
+#[tauri::command]
+fn get_offline_mode(app_handle: tauri::AppHandle) -> bool {
+    egress::offline_mode(&app_handle)
+}
+
+#[tauri::command]
+fn set_offline_mode(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    egress::set_offline_mode(&app_handle, enabled)
+}
+
+#[tauri::command]
+fn get_strip_image_metadata(app_handle: tauri::AppHandle) -> bool {
+    egress::strip_image_metadata(&app_handle)
+}
+
+#[tauri::command]
+fn set_strip_image_metadata(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    egress::set_strip_image_metadata(&app_handle, enabled)
+}
+
+#[tauri::command]
+fn get_manual_refresh(app_handle: tauri::AppHandle) -> bool {
+    db::manual_refresh_enabled(&app_handle)
+}
+
+// Turning manual mode on drops every watcher; turning it off watches the roots again
+#[tauri::command]
+fn set_manual_refresh(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "manual_refresh", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())?;
+    let roots = db::get_root_directories(&app_handle).map_err(|e| e.to_string())?;
+    reconcile_watchers(app_handle, roots)
+}
+
+#[tauri::command]
+fn get_expand_archives(app_handle: tauri::AppHandle) -> bool {
+    archives::expand_enabled(&app_handle)
+}
+
+#[tauri::command]
+fn set_expand_archives(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    archives::set_expand_enabled(&app_handle, enabled)
+}
 
 #[tauri::command]
-fn purge_files_under_root(app_handle: tauri::AppHandle, path: String) -> Result<u32, String> {
+fn purge_files_under_root(app_handle: tauri::AppHandle, path: String, confirmation: Option<String>) -> Result<u32, String> {
+    safety::check_typed(&app_handle, Some(&path), confirmation.as_deref())?;
+    safety::before_destructive(&app_handle, "purge_root")?;
     db::delete_files_under_root(&app_handle, path)
         .map(|n| n as u32)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-fn purge_all_files(app_handle: tauri::AppHandle) -> Result<u32, String> {
+fn purge_all_files(app_handle: tauri::AppHandle, confirmation: Option<String>) -> Result<u32, String> {
     eprintln!("[TAURI] purge_all_files called");
+    safety::check_typed(&app_handle, None, confirmation.as_deref())?;
+    safety::before_destructive(&app_handle, "purge_all")?;
     match db::purge_all_files(&app_handle) {
         Ok(n) => {
             eprintln!("[TAURI] purge_all_files deleted {} rows", n);
@@ -125,7 +321,8 @@ fn scan_files(
     );
 
     // Prune missing files first to keep DB in sync
-    if let Err(e) = db::prune_missing_files(&app_handle) {
+    let offline = OFFLINE_ROOTS.lock().unwrap().clone();
+    if let Err(e) = db::prune_missing_files(&app_handle, &offline) {
         eprintln!("⚠️ [TAURI] Warning: Failed to prune missing files: {}", e);
     }
 
@@ -134,7 +331,8 @@ fn scan_files(
         eprintln!("❌ [TAURI] scan_files failed: {}", err_msg);
         err_msg
     });
-    if result.is_ok() {
+    if let Ok(items) = &result {
+        apply_root_defaults(&app_handle, items);
         eprintln!("✅ [TAURI] scan_files completed successfully");
     }
     result
@@ -149,48 +347,108 @@ fn scan_files_multi(
         "🎯 [TAURI] scan_files_multi command called with paths: {:?}",
         root_paths
     );
-    if let Err(e) = db::prune_missing_files(&app_handle) {
+    // An unreachable share would block the whole scan in read_dir; skip it and
+    // let monitor_volumes bring it back
+    let root_paths = reachable_roots(&app_handle, root_paths);
+    let offline = OFFLINE_ROOTS.lock().unwrap().clone();
+    if let Err(e) = db::prune_missing_files(&app_handle, &offline) {
         eprintln!("⚠️ [TAURI] Warning: Failed to prune missing files: {}", e);
     }
-    let result = db::scan_directories_lightweight(root_paths).map_err(|e| e.to_string());
-    if result.is_ok() {
-        eprintln!("✅ [TAURI] scan_files_multi completed successfully");
+    let limits = SCAN_LIMITS.lock().unwrap().clone();
+    let limit_for = |root: &str| limits.get(root).copied().unwrap_or(db::SCAN_PAGE_SIZE);
+    let (remote_roots, local_roots): (Vec<String>, Vec<String>) = root_paths.into_iter().partition(|r| remote::is_remote(r));
+    let (mut items, mut overflow) = metrics::timed(&app_handle, metrics::SCAN, || db::scan_directories_lightweight(local_roots, limit_for))
+        .map_err(|e| e.to_string())?;
+    if archives::expand_enabled(&app_handle) {
+        let inside: Vec<db::FileListItem> = items
+            .iter()
+            .filter(|i| !i.is_directory && archives::is_archive(&i.path))
+            .flat_map(archives::entries)
+            .take(db::SCAN_MAX_TOTAL.saturating_sub(items.len()))
+            .collect();
+        items.extend(inside);
     }
-    result
+    // Remote roots come from the poller's last listing; an offline one waits for it
+    for root in remote_roots.into_iter().filter(|r| !offline.contains(r)) {
+        match remote::listing(&app_handle, &root) {
+            Ok(mut listed) => {
+                let limit = limit_for(&root).min(db::SCAN_MAX_TOTAL.saturating_sub(items.len()));
+                if listed.len() > limit {
+                    overflow.push(db::ScanOverflow { root: root.clone(), shown: limit, hidden: listed.len() - limit });
+                    listed.truncate(limit);
+                }
+                items.extend(listed);
+            }
+            Err(reason) => {
+                eprintln!("⚠️ [REMOTE] Listing {} failed: {}", root, reason);
+                if mark_offline(&app_handle, &root) {
+                    emit_watch_status(&app_handle);
+                }
+            }
+        }
+    }
+    apply_root_defaults(&app_handle, &items);
+    // The list pages through tracked files on its own; the scan only adds new ones
+    if db::large_library_enabled(&app_handle) {
+        let tracked = db::tracked_paths(&app_handle).map_err(|e| e.to_string())?;
+        items.retain(|i| !tracked.contains(&i.path));
+    }
+    eprintln!("✅ [TAURI] scan_files_multi completed successfully");
+    // Always sent, so the UI clears stale "…and N more" rows after a scan that fits
+    let _ = app_handle.emit("scan-overflow", &overflow);
+    Ok(items)
+}
+
+// Lists another page of a root that a scan cut short; the caller rescans afterwards
+#[tauri::command]
+fn load_more_scan(root: String) {
+    let mut limits = SCAN_LIMITS.lock().unwrap();
+    let limit = limits.entry(root).or_insert(db::SCAN_PAGE_SIZE);
+    *limit += db::SCAN_PAGE_SIZE;
 }
 
 // File watching commands
 #[tauri::command]
 fn start_watching(app_handle: tauri::AppHandle, root_path: String) -> Result<(), String> {
-    use notify::EventKind;
-
     eprintln!("🔍 [TAURI] start_watching called for: {}", root_path);
+    if db::manual_refresh_enabled(&app_handle) {
+        emit_watch_status(&app_handle);
+        return Ok(());
+    }
+    if PAUSED_ROOTS.lock().unwrap().contains(&root_path) {
+        eprintln!("⏸️ [TAURI] Root is paused, not watching: {}", root_path);
+        return Ok(());
+    }
+    // Remote roots are watched by poll_remote_roots
+    if remote::is_remote(&root_path) {
+        emit_watch_status(&app_handle);
+        return Ok(());
+    }
 
-    let path = std::path::PathBuf::from(root_path.clone());
+    match watch_root(&app_handle, &root_path) {
+        Ok(()) => {
+            emit_watch_status(&app_handle);
+            Ok(())
+        }
+        Err(reason) => {
+            report_watcher_error(&app_handle, &root_path, &reason);
+            Err(reason)
+        }
+    }
+}
+
+fn watch_root(app_handle: &tauri::AppHandle, root_path: &str) -> Result<(), String> {
+    let path = std::path::PathBuf::from(root_path);
     let app = app_handle.clone();
+    let root = root_path.to_string();
 
     let watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
         match res {
-            Ok(event) => {
-                eprintln!("📬 [WATCHER] Event received: {:?}", event);
-                // Only emit events for Create, Modify, and Remove
-                match event.kind {
-                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
-                        eprintln!(
-                            "📁 [WATCHER] File change detected: {:?}, paths: {:?}",
-                            event.kind, event.paths
-                        );
-                        match app.emit("file-system-change", ()) {
-                            Ok(_) => eprintln!("✅ [WATCHER] Event emitted successfully"),
-                            Err(e) => eprintln!("❌ [WATCHER] Failed to emit event: {:?}", e),
-                        }
-                    }
-                    _ => {
-                        eprintln!("⏭️ [WATCHER] Ignoring event kind: {:?}", event.kind);
-                    }
-                }
+            Ok(event) => handle_watch_event(&app, &event),
+            Err(e) => {
+                eprintln!("❌ [WATCHER] Error: {:?}", e);
+                report_watcher_error(&app, &root, &e.to_string());
             }
-            Err(e) => eprintln!("❌ [WATCHER] Error: {:?}", e),
         }
     })
     .map_err(|e| e.to_string())?;
@@ -204,32 +462,425 @@ fn start_watching(app_handle: tauri::AppHandle, root_path: String) -> Result<(),
         .watch(&path, RecursiveMode::NonRecursive)
         .map_err(|e| e.to_string())?;
 
-    WATCHERS.lock().unwrap().push(watcher_arc);
+    // Replacing an existing entry drops the old watcher, so a root is never watched twice
+    let replaced = WATCHERS.lock().unwrap().insert(watch_key(root_path), (root_path.to_string(), watcher_arc));
+    drop(replaced);
+    WATCH_ERRORS.lock().unwrap().retain(|(r, _)| r != root_path);
 
     eprintln!("✅ [TAURI] File watching started for: {}", root_path);
     eprintln!("📊 [TAURI] Watching mode: NonRecursive");
     Ok(())
 }
 
+// Emits what a watcher event calls for (see watch_events): `file-updated` per
+// patched row, re-hashed off the watcher thread, or `file-system-change`
+fn handle_watch_event(app: &tauri::AppHandle, event: &Event) {
+    eprintln!("📬 [WATCHER] Event received: {:?}", event);
+    let outcomes = watch_events::process(event, |path| db::refresh_file_stat(app, path));
+    if outcomes.is_empty() {
+        eprintln!("⏭️ [WATCHER] Nothing to do for: {:?}", event.kind);
+    }
+    for outcome in outcomes {
+        match outcome {
+            watch_events::Outcome::Updated(info) => {
+                eprintln!("🔄 [WATCHER] Refreshed tracked file: {}", info.path);
+                let _ = app.emit("file-updated", &info);
+                // Emits again once the new hash is stored
+                let app = app.clone();
+                let id = info.id;
+                std::thread::spawn(move || {
+                    if let Ok(Some(info)) = db::rehash_file(&app, id) {
+                        let _ = app.emit("file-updated", &info);
+                    }
+                });
+            }
+            watch_events::Outcome::Rescan => {
+                eprintln!("📁 [WATCHER] File change detected: {:?}, paths: {:?}", event.kind, event.paths);
+                match app.emit("file-system-change", ()) {
+                    Ok(_) => eprintln!("✅ [WATCHER] Event emitted successfully"),
+                    Err(e) => eprintln!("❌ [WATCHER] Failed to emit event: {:?}", e),
+                }
+            }
+        }
+    }
+}
+
+// Debug builds only: runs a synthetic filesystem event ("create", "modify",
+// "metadata", "remove" or "rename" with old and new path) through the watcher
+// pipeline, as if a watched root had reported it
+#[tauri::command]
+fn inject_fs_event(app_handle: tauri::AppHandle, kind: String, paths: Vec<String>) -> Result<(), String> {
+    if !cfg!(debug_assertions) {
+        return Err("Synthetic filesystem events are only available in debug builds".to_string());
+    }
+    let event = watch_events::synthetic(&kind, paths)?;
+    handle_watch_event(&app_handle, &event);
+    Ok(())
+}
+
+// Watchers are dropped after the map lock is released: a watcher's callback may be
+// waiting on that lock (via `emit_watch_status`) while the watcher shuts down.
+fn remove_watchers(remove: impl Fn(&str, &str) -> bool) {
+    let removed: Vec<_> = {
+        let mut watchers = WATCHERS.lock().unwrap();
+        let keys: Vec<String> = watchers
+            .iter()
+            .filter(|(key, (root, _))| remove(key, root))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.into_iter().filter_map(|k| watchers.remove(&k)).collect()
+    };
+    drop(removed);
+}
+
+fn watch_key(root: &str) -> String {
+    std::fs::canonicalize(root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| root.to_string())
+}
+
+const WATCH_HEAL_ATTEMPTS: u32 = 5;
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WatcherError {
+    root: String,
+    reason: String,
+}
+
+// Records the failure, tells the UI, and schedules re-registration unless one is
+// already pending for this root.
+fn report_watcher_error(app_handle: &tauri::AppHandle, root: &str, reason: &str) {
+    {
+        let mut errors = WATCH_ERRORS.lock().unwrap();
+        errors.retain(|(r, _)| r != root);
+        errors.push((root.to_string(), reason.to_string()));
+    }
+    let _ = app_handle.emit("watcher-error", WatcherError { root: root.to_string(), reason: reason.to_string() });
+    emit_watch_status(app_handle);
+
+    {
+        let mut healing = HEALING_ROOTS.lock().unwrap();
+        if healing.iter().any(|r| r == root) {
+            return;
+        }
+        healing.push(root.to_string());
+    }
+    let app = app_handle.clone();
+    let root = root.to_string();
+    // Runs off the notify thread: dropping a watcher from its own callback would deadlock
+    std::thread::spawn(move || {
+        for attempt in 0..WATCH_HEAL_ATTEMPTS {
+            std::thread::sleep(std::time::Duration::from_secs(5 << attempt));
+            // An offline root is re-attached by monitor_volumes once it's back
+            let still_wanted = db::get_root_directories(&app).unwrap_or_default().contains(&root)
+                && !db::manual_refresh_enabled(&app)
+                && !PAUSED_ROOTS.lock().unwrap().contains(&root)
+                && !OFFLINE_ROOTS.lock().unwrap().contains(&root);
+            if !still_wanted {
+                WATCH_ERRORS.lock().unwrap().retain(|(r, _)| r != &root);
+                break;
+            }
+            remove_watchers(|_, r| r == root);
+            match watch_root(&app, &root) {
+                Ok(()) => {
+                    eprintln!("🩹 [WATCHER] Re-registered watcher for {} (attempt {})", root, attempt + 1);
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("❌ [WATCHER] Re-register attempt {} failed for {}: {}", attempt + 1, root, e);
+                    WATCH_ERRORS.lock().unwrap().retain(|(r, _)| r != &root);
+                    WATCH_ERRORS.lock().unwrap().push((root.clone(), e));
+                }
+            }
+        }
+        HEALING_ROOTS.lock().unwrap().retain(|r| r != &root);
+        emit_watch_status(&app);
+    });
+}
+
+// Seconds between checks for root folders that disappeared or came back
+const VOLUME_POLL_SECS: u64 = 10;
+// How long a root folder gets to answer before it counts as unreachable
+const ROOT_PROBE_TIMEOUT_MS: u64 = 3000;
+
+// Whether each of `roots` can be listed, all probed at once and given at most
+// ROOT_PROBE_TIMEOUT_MS together. A read_dir on a disconnected SMB/NFS mount
+// can block for minutes, so it runs on its own thread; a probe that never
+// returns is left behind, and its root stays unreachable without another
+// thread being spawned until that probe finishes.
+fn probe_roots(roots: &[String]) -> Vec<bool> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut pending = 0;
+    for (i, root) in roots.iter().enumerate() {
+        // Remote roots answer (or not) when they are listed
+        if remote::is_remote(root) {
+            continue;
+        }
+        {
+            let mut probing = PROBING_ROOTS.lock().unwrap();
+            if probing.contains(root) {
+                continue;
+            }
+            probing.push(root.clone());
+        }
+        pending += 1;
+        let (tx, root) = (tx.clone(), root.clone());
+        std::thread::spawn(move || {
+            let ok = std::fs::read_dir(&root).map(|mut entries| entries.next()).is_ok();
+            PROBING_ROOTS.lock().unwrap().retain(|r| r != &root);
+            let _ = tx.send((i, ok));
+        });
+    }
+    let mut reachable: Vec<bool> = roots.iter().map(|r| remote::is_remote(r)).collect();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(ROOT_PROBE_TIMEOUT_MS);
+    while pending > 0 {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        match rx.recv_timeout(left) {
+            Ok((i, ok)) => {
+                reachable[i] = ok;
+                pending -= 1;
+            }
+            Err(_) => break,
+        }
+    }
+    reachable
+}
+
+// Drops the watcher of a root that can't be reached and reports it offline;
+// false if it already was
+fn mark_offline(app_handle: &tauri::AppHandle, root: &str) -> bool {
+    {
+        let mut offline = OFFLINE_ROOTS.lock().unwrap();
+        if offline.iter().any(|r| r == root) {
+            return false;
+        }
+        offline.push(root.to_string());
+    }
+    eprintln!("⏏️ [VOLUMES] Root went offline: {}", root);
+    remove_watchers(|_, r| r == root);
+    WATCH_ERRORS.lock().unwrap().retain(|(r, _)| r != root);
+    true
+}
+
+// The roots among `roots` that answer a probe. The rest are marked offline,
+// except ones not (or no longer) configured, which are simply left out.
+fn reachable_roots(app_handle: &tauri::AppHandle, roots: Vec<String>) -> Vec<String> {
+    let known = db::get_root_directories(app_handle).unwrap_or_default();
+    let mut changed = false;
+    let mut reachable = Vec::new();
+    for (root, ok) in roots.iter().zip(probe_roots(&roots)) {
+        if ok {
+            reachable.push(root.clone());
+        } else {
+            eprintln!("⚠️ [VOLUMES] Root not reachable, skipping: {}", root);
+            if known.contains(root) {
+                changed |= mark_offline(app_handle, root);
+            }
+        }
+    }
+    if changed {
+        emit_watch_status(app_handle);
+    }
+    reachable
+}
+
+// Polls every root's folder so removable drives and network shares can come
+// and go: a root that vanished or stopped answering loses its watcher, and once
+// its folder is back the watcher is re-created and "root-online" tells the UI
+// to rescan.
+fn monitor_volumes(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        let mut roots = db::get_root_directories(&app_handle).unwrap_or_default();
+        OFFLINE_ROOTS.lock().unwrap().retain(|r| roots.contains(r));
+        roots.retain(|r| !remote::is_remote(r));
+        let present = probe_roots(&roots);
+        for (root, present) in roots.into_iter().zip(present) {
+            let was_offline = OFFLINE_ROOTS.lock().unwrap().contains(&root);
+            if !present && !was_offline {
+                mark_offline(&app_handle, &root);
+                emit_watch_status(&app_handle);
+            } else if present && was_offline {
+                eprintln!("🔌 [VOLUMES] Root back online: {}", root);
+                OFFLINE_ROOTS.lock().unwrap().retain(|r| r != &root);
+                if !PAUSED_ROOTS.lock().unwrap().contains(&root) && !db::manual_refresh_enabled(&app_handle) {
+                    remove_watchers(|_, r| r == root);
+                    if let Err(reason) = watch_root(&app_handle, &root) {
+                        report_watcher_error(&app_handle, &root, &reason);
+                    }
+                }
+                emit_watch_status(&app_handle);
+                let _ = app_handle.emit("root-online", &root);
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_secs(VOLUME_POLL_SECS));
+    });
+}
+
+// Seconds between listings of each remote root
+const REMOTE_POLL_SECS: u64 = 60;
+
+// Remote roots have no change notifications: each one is listed again every
+// REMOTE_POLL_SECS, and a changed listing counts as a file-system change. A
+// root whose listing fails is offline until a listing succeeds again.
+fn poll_remote_roots(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(REMOTE_POLL_SECS));
+        if db::manual_refresh_enabled(&app_handle) {
+            continue;
+        }
+        let roots = db::get_root_directories(&app_handle).unwrap_or_default();
+        for root in roots.into_iter().filter(|r| remote::is_remote(r)) {
+            if PAUSED_ROOTS.lock().unwrap().contains(&root) {
+                continue;
+            }
+            let was_offline = OFFLINE_ROOTS.lock().unwrap().contains(&root);
+            match remote::refresh(&app_handle, &root) {
+                Ok(_) if was_offline => {
+                    eprintln!("🔌 [REMOTE] Root back online: {}", root);
+                    OFFLINE_ROOTS.lock().unwrap().retain(|r| r != &root);
+                    emit_watch_status(&app_handle);
+                    let _ = app_handle.emit("root-online", &root);
+                }
+                Ok(true) => {
+                    let _ = app_handle.emit("file-system-change", ());
+                }
+                Ok(false) => {}
+                Err(reason) => {
+                    eprintln!("⚠️ [REMOTE] Listing {} failed: {}", root, reason);
+                    if mark_offline(&app_handle, &root) {
+                        emit_watch_status(&app_handle);
+                    }
+                }
+            }
+        }
+    });
+}
+
 #[tauri::command]
-fn stop_watching() -> Result<(), String> {
+fn stop_watching(app_handle: tauri::AppHandle) -> Result<(), String> {
     eprintln!("🛑 [TAURI] stop_watching called");
 
-    let mut list = WATCHERS.lock().unwrap();
-    list.clear();
+    remove_watchers(|_, _| true);
 
     eprintln!("✅ [TAURI] File watching stopped");
+    emit_watch_status(&app_handle);
+    Ok(())
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WatchStatus {
+    root: String,
+    watching: bool,
+    paused: bool,
+    // The folder is unreachable; watching resumes when it comes back
+    offline: bool,
+    error: Option<String>,
+    // Manual refresh mode: nothing is watched on purpose
+    manual: bool,
+}
+
+fn watch_status(app_handle: &tauri::AppHandle) -> Vec<WatchStatus> {
+    let roots = db::get_root_directories(app_handle).unwrap_or_default();
+    let watchers = WATCHERS.lock().unwrap();
+    let paused = PAUSED_ROOTS.lock().unwrap();
+    let errors = WATCH_ERRORS.lock().unwrap();
+    let offline = OFFLINE_ROOTS.lock().unwrap();
+    let manual = db::manual_refresh_enabled(app_handle);
+    roots
+        .into_iter()
+        .map(|root| WatchStatus {
+            watching: watchers.values().any(|(r, _)| r == &root)
+                || (remote::is_remote(&root) && !manual && !paused.contains(&root) && !offline.contains(&root)),
+            paused: paused.contains(&root),
+            offline: offline.contains(&root),
+            error: errors.iter().find(|(r, _)| r == &root).map(|(_, e)| e.clone()),
+            manual,
+            root,
+        })
+        .collect()
+}
+
+fn emit_watch_status(app_handle: &tauri::AppHandle) {
+    let _ = app_handle.emit("watch-status-changed", watch_status(app_handle));
+}
+
+#[tauri::command]
+fn get_watch_status(app_handle: tauri::AppHandle) -> Vec<WatchStatus> {
+    watch_status(&app_handle)
+}
+
+#[tauri::command]
+fn pause_watching(app_handle: tauri::AppHandle, root: String) -> Result<(), String> {
+    {
+        let mut paused = PAUSED_ROOTS.lock().unwrap();
+        if !paused.contains(&root) {
+            paused.push(root.clone());
+        }
+    }
+    // Dropping the watcher releases the OS handles for the root
+    remove_watchers(|_, r| r == root);
+    eprintln!("⏸️ [TAURI] Watching paused for: {}", root);
+    emit_watch_status(&app_handle);
     Ok(())
 }
 
+#[tauri::command]
+fn resume_watching(app_handle: tauri::AppHandle, root: String) -> Result<(), String> {
+    PAUSED_ROOTS.lock().unwrap().retain(|r| r != &root);
+    let already = WATCHERS.lock().unwrap().contains_key(&watch_key(&root));
+    if already {
+        emit_watch_status(&app_handle);
+        return Ok(());
+    }
+    start_watching(app_handle, root)
+}
+
 #[tauri::command]
 fn start_watching_multi(
     app_handle: tauri::AppHandle,
     root_paths: Vec<String>,
 ) -> Result<(), String> {
-    for p in root_paths {
-        let _ = start_watching(app_handle.clone(), p);
+    reconcile_watchers(app_handle, root_paths)
+}
+
+// Brings the watcher set in line with `roots`: drops watchers for roots no longer
+// listed, starts missing ones, and leaves healthy ones untouched. Safe to call
+// repeatedly with the same list.
+#[tauri::command]
+fn reconcile_watchers(app_handle: tauri::AppHandle, roots: Vec<String>) -> Result<(), String> {
+    if db::manual_refresh_enabled(&app_handle) {
+        remove_watchers(|_, _| true);
+        emit_watch_status(&app_handle);
+        return Ok(());
+    }
+    let paused = PAUSED_ROOTS.lock().unwrap().clone();
+    let offline = OFFLINE_ROOTS.lock().unwrap().clone();
+    let candidates: Vec<String> = roots
+        .into_iter()
+        .filter(|r| !paused.contains(r) && !offline.contains(r) && !remote::is_remote(r))
+        .collect();
+    let wanted: Vec<(String, String)> = reachable_roots(&app_handle, candidates)
+        .into_iter()
+        .map(|r| (watch_key(&r), r))
+        .collect();
+
+    remove_watchers(|key, _| !wanted.iter().any(|(k, _)| k == key));
+
+    let mut seen: Vec<String> = Vec::new();
+    for (key, root) in wanted {
+        // Two spellings of the same directory only get one watcher
+        if seen.contains(&key) || WATCHERS.lock().unwrap().contains_key(&key) {
+            seen.push(key);
+            continue;
+        }
+        seen.push(key);
+        if let Err(reason) = watch_root(&app_handle, &root) {
+            report_watcher_error(&app_handle, &root, &reason);
+        }
     }
+    emit_watch_status(&app_handle);
     Ok(())
 }
 
@@ -245,8 +896,79 @@ fn create_tag(
     name: String,
     parent_id: Option<u32>,
     color: Option<String>,
+    icon: Option<String>,
 ) -> Result<u32, String> {
-    db::create_tag(&app_handle, name, parent_id, color).map_err(|e| e.to_string())
+    db::create_tag(&app_handle, name, parent_id, color, icon).map_err(|e| e.to_string())
+}
+
+// Creates the tags in a pasted list or CSV (see `tag_import`)
+#[tauri::command]
+fn import_tags(app_handle: tauri::AppHandle, text: String) -> Result<tag_import::ImportReport, String> {
+    journal::import_tags(&app_handle, text)
+}
+
+// Markdown notes for Obsidian (see obsidian.rs): "notes" into a vault folder
+// the user picks, "index" as one note. None when the dialog was cancelled.
+#[tauri::command]
+async fn export_obsidian(app_handle: tauri::AppHandle, mode: String) -> Result<Option<obsidian::ObsidianReport>, String> {
+    let dialog = app_handle.dialog().file();
+    let picked = if mode == "index" {
+        dialog.set_title("Export index note").set_file_name(obsidian::INDEX_NOTE).blocking_save_file()
+    } else {
+        dialog.set_title("Export notes into vault").blocking_pick_folder()
+    };
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let dest = picked.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || obsidian::export(&app_handle, &mode, &dest))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+}
+
+// Tags and tag assignments from a digiKam database the user picks; None when
+// the dialog was cancelled
+#[tauri::command]
+async fn import_digikam(app_handle: tauri::AppHandle) -> Result<Option<digikam::DigikamReport>, String> {
+    let Some(picked) = app_handle
+        .dialog()
+        .file()
+        .set_title("Import digiKam database")
+        .add_filter("digiKam database", &["db"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = picked.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || digikam::import(&app_handle, &path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+}
+
+// The tag tree as a Markdown or OPML outline with usage counts
+#[tauri::command]
+fn export_tags(app_handle: tauri::AppHandle, format: String) -> Result<String, String> {
+    tag_export::export(&app_handle, &format)
+}
+
+// Writes the outline to a file the user picks; None when the dialog was cancelled
+#[tauri::command]
+async fn save_tag_outline(app_handle: tauri::AppHandle, format: String) -> Result<Option<String>, String> {
+    let outline = tag_export::export(&app_handle, &format)?;
+    let Some(dest) = app_handle
+        .dialog()
+        .file()
+        .set_title("Export tags")
+        .set_file_name(format!("tags.{}", tag_export::extension(&format)))
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let dest = dest.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+    std::fs::write(&dest, outline).map_err(|e| e.to_string())?;
+    Ok(Some(dest.to_string_lossy().into_owned()))
 }
 
 #[tauri::command]
@@ -254,6 +976,73 @@ fn get_all_tags(app_handle: tauri::AppHandle) -> Result<Vec<db::TagInfo>, String
     db::get_all_tags(&app_handle).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn get_tag_sort_mode(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let mode = db::get_setting(&app_handle, "tag_sort_mode").map_err(|e| e.to_string())?;
+    Ok(mode.unwrap_or_else(|| "manual".to_string()))
+}
+
+#[tauri::command]
+fn set_tag_sort_mode(app_handle: tauri::AppHandle, mode: String) -> Result<(), String> {
+    if !db::TAG_SORT_MODES.contains(&mode.as_str()) {
+        return Err(format!("Unknown tag sort mode: {}", mode));
+    }
+    db::set_setting(&app_handle, "tag_sort_mode", &mode).map_err(|e| e.to_string())
+}
+
+// How file rows render tag badges: plain names, full ancestry, or only the
+// deepest tags of each branch
+const TAG_BADGE_MODES: &[&str] = &["name", "path", "leaf"];
+
+#[tauri::command]
+fn get_tag_badge_mode(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let mode = db::get_setting(&app_handle, "tag_badge_mode").map_err(|e| e.to_string())?;
+    Ok(mode.unwrap_or_else(|| "name".to_string()))
+}
+
+#[tauri::command]
+fn set_tag_badge_mode(app_handle: tauri::AppHandle, mode: String) -> Result<(), String> {
+    if !TAG_BADGE_MODES.contains(&mode.as_str()) {
+        return Err(format!("Unknown tag badge mode: {}", mode));
+    }
+    db::set_setting(&app_handle, "tag_badge_mode", &mode).map_err(|e| e.to_string())
+}
+
+// Quick-created tags get a palette color unless the user turned this off
+#[tauri::command]
+fn get_auto_color_tags(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let value = db::get_setting(&app_handle, "auto_color_tags").map_err(|e| e.to_string())?;
+    Ok(value.as_deref() != Some("false"))
+}
+
+#[tauri::command]
+fn set_auto_color_tags(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "auto_color_tags", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+// Child tags without a color show a shade of their parent's (`TagInfo::shade`)
+#[tauri::command]
+fn get_inherit_tag_colors(app_handle: tauri::AppHandle) -> bool {
+    db::inherit_colors_enabled(&app_handle)
+}
+
+#[tauri::command]
+fn set_inherit_tag_colors(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "inherit_tag_colors", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+// Hides a retired tag from the tree, sidebar and suggestions, or brings it back
+#[tauri::command]
+fn set_tag_archived(app_handle: tauri::AppHandle, id: u32, archived: bool) -> Result<(), String> {
+    db::set_tag_archived(&app_handle, id, archived).map_err(|e| e.to_string())
+}
+
+// Locked tags and their subtrees refuse rename, move and delete until unlocked
+#[tauri::command]
+fn set_tag_locked(app_handle: tauri::AppHandle, id: u32, locked: bool) -> Result<(), String> {
+    db::set_tag_locked(&app_handle, id, locked).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn update_tag(
     app_handle: tauri::AppHandle,
@@ -264,8 +1053,14 @@ fn update_tag(
     db::update_tag(&app_handle, id, name, color).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn merge_tags(app_handle: tauri::AppHandle, source_id: u32, target_id: u32) -> Result<(), String> {
+    db::merge_tags(&app_handle, source_id, target_id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn delete_tag(app_handle: tauri::AppHandle, id: u32) -> Result<(), String> {
+    safety::before_destructive(&app_handle, "delete_tag")?;
     db::delete_tag(&app_handle, id).map_err(|e| e.to_string())
 }
 
@@ -286,19 +1081,369 @@ fn add_file_tag(
     file_path: String,
     tag_id: u32,
 ) -> Result<(), String> {
-    db::add_file_tag(&app_handle, file_path, tag_id).map_err(|e| e.to_string())
+    let added = db::add_file_tag(&app_handle, file_path.clone(), tag_id).map_err(|e| e.to_string())?;
+    if added {
+        hooks::run(&app_handle, vec![(file_path, tag_id)]);
+    }
+    Ok(())
 }
 
 #[tauri::command]
-fn remove_file_tag(app_handle: tauri::AppHandle, file_id: u32, tag_id: u32) -> Result<(), String> {
+fn add_tags_to_files(
+    app_handle: tauri::AppHandle,
+    file_paths: Vec<String>,
+    tag_ids: Vec<u32>,
+) -> Result<usize, String> {
+    let added = journal::tag_files(&app_handle, file_paths, tag_ids)?;
+    let count = added.len();
+    hooks::run(&app_handle, added);
+    Ok(count)
+}
+
+// Script console. A real run can remove tags, so it takes the safety backup first.
+#[tauri::command]
+async fn run_script(app_handle: tauri::AppHandle, source: String, dry_run: bool) -> Result<scripting::ScriptResult, String> {
+    if !dry_run {
+        safety::before_destructive(&app_handle, "script")?;
+    }
+    tauri::async_runtime::spawn_blocking(move || scripting::run(&app_handle, &source, dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn get_tag_hooks(app_handle: tauri::AppHandle) -> Result<Vec<db::TagHook>, String> {
+    db::get_tag_hooks(&app_handle).map_err(|e| e.to_string())
+}
+
+// Shell command run with the file's path whenever `tag_id` is applied; empty removes it
+#[tauri::command]
+fn set_tag_hook(app_handle: tauri::AppHandle, tag_id: u32, command: String) -> Result<(), String> {
+    db::set_tag_hook(&app_handle, tag_id, &command).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn remove_file_tag(app_handle: tauri::AppHandle, file_id: u32, tag_id: u32) -> Result<(), String> {
     db::remove_file_tag(&app_handle, file_id, tag_id).map_err(|e| e.to_string())
 }
 
+// The "Pinned" strip above the file list
+#[tauri::command]
+fn get_pinned_files(app_handle: tauri::AppHandle) -> Result<Vec<db::PinnedFile>, String> {
+    db::get_pinned_files(&app_handle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn pin_files(app_handle: tauri::AppHandle, paths: Vec<String>) -> Result<(), String> {
+    db::pin_files(&app_handle, &paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn unpin_file(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    db::unpin_file(&app_handle, &path).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_file_tags(app_handle: tauri::AppHandle, file_id: u32) -> Result<Vec<db::TagInfo>, String> {
     db::get_file_tags(&app_handle, file_id).map_err(|e| e.to_string())
 }
 
+// File list layout: one table per root (default) or a single flat table
+#[tauri::command]
+fn get_flat_view(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let value = db::get_setting(&app_handle, "file_list_flat_view").map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+#[tauri::command]
+fn set_flat_view(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "file_list_flat_view", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+// Files show (and match on) the tags of tagged folders above them
+#[tauri::command]
+fn get_inherit_folder_tags(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let value = db::get_setting(&app_handle, "inherit_folder_tags").map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+#[tauri::command]
+fn set_inherit_folder_tags(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "inherit_folder_tags", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_all_file_tags(
+    app_handle: tauri::AppHandle,
+) -> Result<std::collections::HashMap<u32, Vec<db::TagInfo>>, String> {
+    db::get_all_file_tags(&app_handle).map_err(|e| e.to_string())
+}
+
+// Tracked files whose path contains every word of `query`, ANDed with the tag filter
+#[tauri::command]
+fn search_files(app_handle: tauri::AppHandle, query: String, filter: Option<db::TagFilter>) -> Result<Vec<db::FileInfo>, String> {
+    db::search_files(&app_handle, &query, filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn query_files_with_tags(
+    app_handle: tauri::AppHandle,
+    filter: Option<db::TagFilter>,
+    roots: Option<Vec<String>>,
+) -> Result<Vec<db::FileWithTags>, String> {
+    metrics::timed(&app_handle, metrics::QUERY_FILTER, || {
+        db::query_files_with_tags(&app_handle, filter.unwrap_or_default(), roots.unwrap_or_default())
+    })
+    .map_err(|e| e.to_string())
+}
+
+// `limit` files from `offset` with their tags, plus the library size, for the
+// paged startup load
+#[tauri::command]
+fn get_files_page(app_handle: tauri::AppHandle, offset: usize, limit: usize) -> Result<db::FilesPage, String> {
+    metrics::timed(&app_handle, metrics::QUERY_PAGE, || db::files_page(&app_handle, offset, limit)).map_err(|e| e.to_string())
+}
+
+// One window of the (filtered) file list after the path `after`, for large
+// library mode
+#[tauri::command]
+fn get_files_window(
+    app_handle: tauri::AppHandle,
+    after: Option<String>,
+    limit: usize,
+    filter: Option<db::TagFilter>,
+    roots: Option<Vec<String>>,
+) -> Result<db::FilesWindow, String> {
+    metrics::timed(&app_handle, metrics::QUERY_WINDOW, || {
+        db::files_window(&app_handle, after, limit, filter.unwrap_or_default(), roots.unwrap_or_default())
+    })
+    .map_err(|e| e.to_string())
+}
+
+// Local diagnostics counters for the diagnostics panel
+#[tauri::command]
+fn get_metrics(app_handle: tauri::AppHandle) -> Result<Vec<db::Metric>, String> {
+    db::get_metrics(&app_handle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn reset_metrics(app_handle: tauri::AppHandle) -> Result<(), String> {
+    db::reset_metrics(&app_handle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_large_library(app_handle: tauri::AppHandle) -> bool {
+    db::large_library_enabled(&app_handle)
+}
+
+#[tauri::command]
+fn set_large_library(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    db::set_setting(&app_handle, "large_library", if enabled { "true" } else { "false" }).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_tag_history(app_handle: tauri::AppHandle, file_path: String) -> Result<Vec<db::TagHistoryEntry>, String> {
+    db::get_tag_history(&app_handle, file_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn cleanup_report(app_handle: tauri::AppHandle) -> Result<db::CleanupReport, String> {
+    db::cleanup_report(&app_handle).map_err(|e| e.to_string())
+}
+
+// Two tags that look like the same one; merging moves the less used into the other
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateTags {
+    source_id: u32,
+    source_name: String,
+    source_count: u32,
+    target_id: u32,
+    target_name: String,
+    target_count: u32,
+    similarity: f32,
+}
+
+// Likely duplicate tag names, most similar first
+#[tauri::command]
+fn near_duplicate_tags(app_handle: tauri::AppHandle) -> Result<Vec<DuplicateTags>, String> {
+    let tags = db::get_all_tags(&app_handle).map_err(|e| e.to_string())?;
+    let counts = db::tag_usage_counts(&app_handle).map_err(|e| e.to_string())?;
+    let count = |id: u32| counts.get(&id).copied().unwrap_or(0);
+    let mut pairs = Vec::new();
+    for (i, a) in tags.iter().enumerate() {
+        for b in &tags[i + 1..] {
+            let Some(similarity) = llm_flow::matching::near_duplicate(&a.name, &b.name) else { continue };
+            let (source, target) = if count(a.id) <= count(b.id) { (a, b) } else { (b, a) };
+            pairs.push(DuplicateTags {
+                source_id: source.id,
+                source_name: source.name.clone(),
+                source_count: count(source.id),
+                target_id: target.id,
+                target_name: target.name.clone(),
+                target_count: count(target.id),
+                similarity,
+            });
+        }
+    }
+    pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    Ok(pairs)
+}
+
+#[tauri::command]
+fn apply_cleanup(app_handle: tauri::AppHandle, category: String) -> Result<usize, String> {
+    safety::before_destructive(&app_handle, &format!("cleanup_{}", category))?;
+    db::apply_cleanup(&app_handle, &category).map_err(|e| e.to_string())
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct VerifyProgress {
+    checked: usize,
+    total: usize,
+}
+
+// Re-hashes tracked files on a background thread. Emits `verify-progress` while
+// running and `verify-finished` with the report (or `verify-failed`) at the end.
+#[tauri::command]
+fn verify_files(app_handle: tauri::AppHandle, root: Option<String>) -> Result<(), String> {
+    if VERIFYING.swap(true, Ordering::SeqCst) {
+        return Err("An integrity check is already running".to_string());
+    }
+    std::thread::spawn(move || {
+        let result = db::verify_files(&app_handle, root, |checked, total| {
+            if checked % 50 == 0 || checked == total {
+                let _ = app_handle.emit("verify-progress", VerifyProgress { checked, total });
+            }
+        });
+        VERIFYING.store(false, Ordering::SeqCst);
+        match result {
+            Ok(report) => {
+                let _ = app_handle.emit("verify-finished", &report);
+            }
+            Err(e) => {
+                let _ = app_handle.emit("verify-failed", e.to_string());
+            }
+        }
+    });
+    Ok(())
+}
+
+// Writes a checksum manifest of `root`, or of the files with the given tags, to
+// a file the user picks. None when the save dialog was cancelled.
+#[tauri::command]
+async fn export_manifest(
+    app_handle: tauri::AppHandle,
+    root: Option<String>,
+    tag_ids: Vec<u32>,
+    use_and: bool,
+) -> Result<Option<usize>, String> {
+    let Some(dest) = app_handle
+        .dialog()
+        .file()
+        .set_title("Export checksum manifest")
+        .set_file_name("manifest.tsv")
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let dest = dest.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+    journal::export_manifest(&app_handle, root, tag_ids, use_and, dest).map(Some)
+}
+
+// Batch operations a previous session didn't finish (see journal.rs)
+#[tauri::command]
+fn get_pending_batches(app_handle: tauri::AppHandle) -> Result<Vec<journal::PendingBatch>, String> {
+    journal::pending(&app_handle)
+}
+
+#[tauri::command]
+async fn resume_batch(app_handle: tauri::AppHandle, id: i64) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || journal::resume(&app_handle, id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn discard_batch(app_handle: tauri::AppHandle, id: i64) -> Result<(), String> {
+    journal::discard(&app_handle, id)
+}
+
+// Checks the files listed in a manifest the user picks against their hashes
+#[tauri::command]
+async fn verify_manifest(app_handle: tauri::AppHandle) -> Result<Option<manifest::ManifestReport>, String> {
+    let Some(picked) = app_handle.dialog().file().set_title("Verify checksum manifest").blocking_pick_file() else {
+        return Ok(None);
+    };
+    let picked = picked.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+    manifest::verify(&picked).map(Some)
+}
+
+// Tracked files with identical content, across all roots
+#[tauri::command]
+async fn find_duplicate_files(app_handle: tauri::AppHandle) -> Result<Vec<db::DuplicateGroup>, String> {
+    tauri::async_runtime::spawn_blocking(move || db::duplicate_files(&app_handle).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateProgress {
+    done: usize,
+    total: usize,
+}
+
+// Applies the reviewed duplicate plan on a background thread. Emits
+// `duplicates-progress` while running and `duplicates-finished` with the report
+// (or `duplicates-failed`) at the end.
+#[tauri::command]
+fn resolve_duplicates(app_handle: tauri::AppHandle, actions: Vec<duplicates::DuplicateAction>) -> Result<(), String> {
+    if actions.iter().any(|a| a.trash_others) {
+        safety::before_destructive(&app_handle, "trash_duplicates")?;
+    }
+    if RESOLVING_DUPLICATES.swap(true, Ordering::SeqCst) {
+        return Err("A duplicate cleanup is already running".to_string());
+    }
+    std::thread::spawn(move || {
+        let result = duplicates::resolve(&app_handle, actions, |done, total| {
+            let _ = app_handle.emit("duplicates-progress", DuplicateProgress { done, total });
+        });
+        RESOLVING_DUPLICATES.store(false, Ordering::SeqCst);
+        match result {
+            Ok((report, added)) => {
+                hooks::run(&app_handle, added);
+                let _ = app_handle.emit("duplicates-finished", &report);
+            }
+            Err(e) => {
+                let _ = app_handle.emit("duplicates-failed", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn accept_file_hashes(app_handle: tauri::AppHandle, file_ids: Vec<u32>) -> Result<usize, String> {
+    db::accept_file_hashes(&app_handle, file_ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_missing_files(app_handle: tauri::AppHandle) -> Result<Vec<db::MissingFile>, String> {
+    db::get_missing_files(&app_handle).map_err(|e| e.to_string())
+}
+
+// Async so hashing a large folder doesn't block the main thread
+#[tauri::command]
+async fn find_relink_candidates(app_handle: tauri::AppHandle, dirs: Vec<String>) -> Result<Vec<db::RelinkCandidate>, String> {
+    db::find_relink_candidates(&app_handle, dirs).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn apply_relinks(app_handle: tauri::AppHandle, links: Vec<db::RelinkCandidate>) -> Result<usize, String> {
+    db::apply_relinks(&app_handle, links).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn filter_files_by_tags(
     app_handle: tauri::AppHandle,
@@ -326,14 +1471,64 @@ fn load_window_state(app_handle: tauri::AppHandle) -> Option<db::WindowState> {
     db::load_window_state(&app_handle).ok().flatten()
 }
 
+// A file on disk with the contents of `path`: the path itself, or the
+// extracted or downloaded copy of an archive entry or remote file
+fn local_file(app_handle: &tauri::AppHandle, path: &str) -> Result<std::path::PathBuf, String> {
+    if archives::is_virtual(path) {
+        archives::extract(app_handle, path)
+    } else if remote::is_remote(path) {
+        remote::local_copy(app_handle, path)
+    } else {
+        Ok(std::path::PathBuf::from(path))
+    }
+}
+
 #[tauri::command]
-fn open_file(path: String) -> Result<(), String> {
+fn open_file(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
     eprintln!("📂 Opening file: {}", path);
+    let local = local_file(&app_handle, &path)?.to_string_lossy().into_owned();
 
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
-            .arg(&path)
+            .arg(&local)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(&local)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(&local)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Counted for the "frequently opened" sort; a failure here doesn't undo the open
+    if let Err(e) = db::record_open(&app_handle, &path) {
+        eprintln!("⚠️ Failed to record the open of {}: {}", path, e);
+    }
+    Ok(())
+}
+
+// Shows `path` selected in Explorer/Finder; other platforms open its folder
+#[tauri::command]
+fn reveal_file(path: String) -> Result<(), String> {
+    // An archive entry is shown as its archive
+    let path = archives::split(&path).map(|(archive, _)| archive.to_string()).unwrap_or(path);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path))
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -341,6 +1536,7 @@ fn open_file(path: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
+            .arg("-R")
             .arg(&path)
             .spawn()
             .map_err(|e| e.to_string())?;
@@ -348,52 +1544,656 @@ fn open_file(path: String) -> Result<(), String> {
 
     #[cfg(target_os = "linux")]
     {
+        let dir = std::path::Path::new(&path).parent().ok_or("File has no parent folder")?;
         std::process::Command::new("xdg-open")
+            .arg(dir)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Lets the user choose the program to open `path` with: the system "Open with"
+// dialog on Windows, a program picker elsewhere
+#[tauri::command]
+async fn open_file_with(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    let path = {
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || local_file(&app, &path))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = &app_handle;
+        std::process::Command::new("rundll32")
+            .arg("shell32.dll,OpenAs_RunDLL")
             .arg(&path)
             .spawn()
             .map_err(|e| e.to_string())?;
     }
 
+    #[cfg(not(target_os = "windows"))]
+    {
+        let Some(program) = app_handle.dialog().file().set_title("Open with").blocking_pick_file() else {
+            return Ok(());
+        };
+        let program = program.as_path().ok_or("Invalid path encoding")?.to_path_buf();
+        #[cfg(target_os = "macos")]
+        let mut command = {
+            let mut c = std::process::Command::new("open");
+            c.arg("-a").arg(&program);
+            c
+        };
+        #[cfg(not(target_os = "macos"))]
+        let mut command = std::process::Command::new(&program);
+        command.arg(&path).spawn().map_err(|e| e.to_string())?;
+    }
+
     Ok(())
 }
 
+// The asset protocol starts with an empty scope; each previewed file is allowed
+// individually so the webview can load it. Returns the local file to load,
+// which for an archive entry or remote file is its extracted or downloaded copy.
+#[tauri::command]
+async fn allow_preview(app_handle: tauri::AppHandle, path: String) -> Result<String, String> {
+    let app = app_handle.clone();
+    let local = tauri::async_runtime::spawn_blocking(move || local_file(&app, &path))
+        .await
+        .map_err(|e| e.to_string())??;
+    if !local.is_file() {
+        return Err("File not found".to_string());
+    }
+    app_handle.asset_protocol_scope().allow_file(&local).map_err(|e| e.to_string())?;
+    Ok(local.to_string_lossy().into_owned())
+}
+
+// What double-clicking a file does, chosen per extension; unlisted extensions open
+const OPEN_ACTIONS: [&str; 4] = ["open", "preview", "reveal", "open-with"];
+
+#[tauri::command]
+fn get_open_actions(app_handle: tauri::AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let raw = db::get_setting(&app_handle, "open_actions").map_err(|e| e.to_string())?;
+    Ok(raw.and_then(|r| serde_json::from_str(&r).ok()).unwrap_or_default())
+}
+
+// Extensions are stored lowercase without the dot
+#[tauri::command]
+fn set_open_actions(app_handle: tauri::AppHandle, actions: BTreeMap<String, String>) -> Result<(), String> {
+    let mut clean = BTreeMap::new();
+    for (ext, action) in actions {
+        if !OPEN_ACTIONS.contains(&action.as_str()) {
+            return Err(format!("Unknown open action: {}", action));
+        }
+        let ext = ext.trim().trim_start_matches('.').to_lowercase();
+        if !ext.is_empty() {
+            clean.insert(ext, action);
+        }
+    }
+    let raw = serde_json::to_string(&clean).map_err(|e| e.to_string())?;
+    db::set_setting(&app_handle, "open_actions", &raw).map_err(|e| e.to_string())
+}
+
 type RecommendItem = llm_flow::RecommendItem;
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn generate_tags_llm(
+    app_handle: tauri::AppHandle,
     title: String,
+    // The file being tagged, left out of the few-shot examples
+    file_path: Option<String>,
+    labels: Vec<String>,
+    existing: Option<Vec<String>>,
+    top_k: usize,
+    threshold: f32,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Result<Vec<RecommendItem>, String> {
+    let paths: Vec<&str> = file_path.as_deref().into_iter().collect();
+    egress::check(&app_handle, base_url.clone(), &paths)?;
+    let blocked = egress::blocked_roots(&app_handle, base_url.clone())?;
+    let proxy = proxy::proxy_url(&app_handle);
+    let existing = existing.unwrap_or_default();
+    let title = match file_path.as_deref() {
+        Some(path) => prompt_title(&app_handle, path, title),
+        None => title,
+    };
+    let examples = few_shot_examples(&app_handle, &title, file_path.as_deref(), &blocked);
+    let request = llm_flow::generate_tags_llm(title, labels, existing, examples, top_k, threshold, base_url, model, proxy);
+    metrics::timed_async(&app_handle, metrics::LLM_TAGS, request).await
+}
+
+// Scanned PDFs are often named like "scan_0042.pdf"; their embedded title
+// says more about them than `fallback` (the file name)
+fn prompt_title(app_handle: &tauri::AppHandle, path: &str, fallback: String) -> String {
+    if !pdf::is_pdf(path) {
+        return fallback;
+    }
+    // Files tracked before PDF metadata was stored are read directly
+    let stored = db::pdf_title(app_handle, path).ok().flatten();
+    let title = stored.or_else(|| {
+        let local = (!archives::is_virtual(path) && !remote::is_remote(path)).then(|| std::path::Path::new(path))?;
+        pdf::metadata(local)?.title
+    });
+    title.unwrap_or(fallback)
+}
+
+// Tagged files from the user's library shown to the text model as examples
+const FEW_SHOT_EXAMPLES: usize = 5;
+// Per-tag example files sent for each side (positive/negative)
+const TAG_EXAMPLES_PER_SIDE: usize = 3;
+
+// Files under `blocked` roots are kept out of the prompt
+fn few_shot_examples(
+    app_handle: &tauri::AppHandle,
+    title: &str,
+    exclude: Option<&str>,
+    blocked: &[String],
+) -> llm_flow::PromptExamples {
+    let files = db::few_shot_examples(app_handle, title, exclude, blocked, FEW_SHOT_EXAMPLES).unwrap_or_else(|e| {
+        eprintln!("[TAURI] few-shot examples unavailable: {}", e);
+        Vec::new()
+    });
+    let names: std::collections::HashMap<u32, String> = db::get_all_tags(app_handle)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| (t.id, t.name))
+        .collect();
+    let mut tags: Vec<llm_flow::TagExamples> = Vec::new();
+    for example in db::get_tag_examples(app_handle, None).unwrap_or_default() {
+        // The file being tagged would only echo its own answer
+        if exclude == Some(example.file_path.as_str())
+            || tagme_paths::longest_root(&example.file_path, blocked.iter().map(String::as_str)).is_some()
+        {
+            continue;
+        }
+        let Some(name) = names.get(&example.tag_id) else { continue };
+        let file_name = std::path::Path::new(&example.file_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&example.file_path)
+            .to_string();
+        let entry = match tags.iter().position(|t| &t.name == name) {
+            Some(i) => &mut tags[i],
+            None => {
+                tags.push(llm_flow::TagExamples { name: name.clone(), ..Default::default() });
+                tags.last_mut().unwrap()
+            }
+        };
+        let side = if example.positive { &mut entry.positive } else { &mut entry.negative };
+        if side.len() < TAG_EXAMPLES_PER_SIDE {
+            side.push(file_name);
+        }
+    }
+    llm_flow::PromptExamples { files, tags }
+}
+
+#[tauri::command]
+fn get_tag_examples(app_handle: tauri::AppHandle, tag_id: u32) -> Result<Vec<db::TagExample>, String> {
+    db::get_tag_examples(&app_handle, Some(tag_id)).map_err(|e| e.to_string())
+}
+
+// Marks files as what `tag_id` looks like (`positive`) or doesn't
+#[tauri::command]
+fn add_tag_examples(app_handle: tauri::AppHandle, tag_id: u32, file_paths: Vec<String>, positive: bool) -> Result<(), String> {
+    db::add_tag_examples(&app_handle, tag_id, file_paths, positive).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn remove_tag_example(app_handle: tauri::AppHandle, tag_id: u32, file_path: String) -> Result<(), String> {
+    db::remove_tag_example(&app_handle, tag_id, &file_path).map_err(|e| e.to_string())
+}
+
+// Suggestions from the background indexer, kept as the recommender's items
+#[tauri::command]
+fn store_recommendations(app_handle: tauri::AppHandle, file_path: String, items: serde_json::Value) -> Result<(), String> {
+    db::store_recommendations(&app_handle, &file_path, &items.to_string()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_stored_recommendations(app_handle: tauri::AppHandle) -> Result<std::collections::HashMap<String, serde_json::Value>, String> {
+    let rows = db::get_stored_recommendations(&app_handle).map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(path, items)| serde_json::from_str(&items).ok().map(|v| (path, v)))
+        .collect())
+}
+
+// Text and code files: the model also sees the start of the file
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn generate_content_tags_llm(
+    app_handle: tauri::AppHandle,
+    file_path: String,
     labels: Vec<String>,
+    existing: Option<Vec<String>>,
     top_k: usize,
     threshold: f32,
     base_url: Option<String>,
     model: Option<String>,
 ) -> Result<Vec<RecommendItem>, String> {
-    llm_flow::generate_tags_llm(title, labels, top_k, threshold, base_url, model).await
+    egress::check(&app_handle, base_url.clone(), &[&file_path])?;
+    let blocked = egress::blocked_roots(&app_handle, base_url.clone())?;
+    let proxy = proxy::proxy_url(&app_handle);
+    let existing = existing.unwrap_or_default();
+    let title = std::path::Path::new(&file_path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let title = prompt_title(&app_handle, &file_path, title);
+    let examples = few_shot_examples(&app_handle, &title, Some(&file_path), &blocked);
+    let request = llm_flow::generate_content_tags_llm(file_path, labels, existing, examples, top_k, threshold, base_url, model, proxy);
+    metrics::timed_async(&app_handle, metrics::LLM_CONTENT, request).await
+}
+
+// "People also tagged": tags most often applied together with `tag_id`, as (tag id, shared files)
+#[tauri::command]
+fn co_occurring_tags(app_handle: tauri::AppHandle, tag_id: u32, limit: usize) -> Result<Vec<(u32, u32)>, String> {
+    db::co_occurring_tags(&app_handle, tag_id, limit).map_err(|e| e.to_string())
+}
+
+// Proposed merges, renames and groupings for the whole tag tree. Nothing is
+// changed here; the UI applies the accepted ones through the tag commands.
+#[tauri::command]
+async fn suggest_taxonomy(
+    app_handle: tauri::AppHandle,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Result<Vec<llm_flow::TaxonomyChange>, String> {
+    egress::check(&app_handle, base_url.clone(), &[])?;
+    let tags = tags_with_usage(&app_handle)?;
+    let proxy = proxy::proxy_url(&app_handle);
+    metrics::timed_async(&app_handle, metrics::LLM_TAXONOMY, llm_flow::suggest_taxonomy(tags, base_url, model, proxy)).await
+}
+
+// Natural-language search: the LLM turns `query` into a filter that the UI
+// shows for confirmation before applying it
+#[tauri::command]
+async fn translate_query(
+    app_handle: tauri::AppHandle,
+    query: String,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Result<llm_flow::NlFilter, String> {
+    if query.trim().is_empty() {
+        return Err("Empty query".to_string());
+    }
+    egress::check(&app_handle, base_url.clone(), &[])?;
+    let tags = tags_with_usage(&app_handle)?;
+    let proxy = proxy::proxy_url(&app_handle);
+    metrics::timed_async(&app_handle, metrics::LLM_QUERY, llm_flow::translate_query(query, tags, base_url, model, proxy)).await
+}
+
+fn tags_with_usage(app_handle: &tauri::AppHandle) -> Result<Vec<llm_flow::TaxonomyTag>, String> {
+    let counts = db::tag_usage_counts(app_handle).map_err(|e| e.to_string())?;
+    Ok(db::get_all_tags(app_handle)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|t| llm_flow::TaxonomyTag {
+            count: counts.get(&t.id).copied().unwrap_or(0),
+            id: t.id,
+            name: t.name,
+            parent_id: t.parent_id,
+        })
+        .collect())
+}
+
+// Suggestions from external tagger plugins, merged with the LLM ones by the UI
+#[tauri::command]
+async fn run_tagger_plugins(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    labels: Vec<String>,
+) -> Result<Vec<RecommendItem>, String> {
+    tauri::async_runtime::spawn_blocking(move || plugins::suggest(&app_handle, &file_path, &labels))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Per-source enable/weight/threshold for recommendations. They are applied by
+// the UI's recommendation pipeline (leptos-recommender), so the JSON is stored as-is.
+#[tauri::command]
+fn get_recommend_sources(app_handle: tauri::AppHandle) -> Result<Option<serde_json::Value>, String> {
+    let raw = db::get_setting(&app_handle, "recommend_sources").map_err(|e| e.to_string())?;
+    Ok(raw.and_then(|r| serde_json::from_str(&r).ok()))
+}
+
+#[tauri::command]
+fn set_recommend_sources(app_handle: tauri::AppHandle, sources: serde_json::Value) -> Result<(), String> {
+    if !sources.is_object() {
+        return Err("Invalid recommendation settings".to_string());
+    }
+    db::set_setting(&app_handle, "recommend_sources", &sources.to_string()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_embedding_settings(app_handle: tauri::AppHandle) -> embeddings::EmbeddingSettings {
+    embeddings::load(&app_handle)
+}
+
+#[tauri::command]
+fn set_embedding_settings(app_handle: tauri::AppHandle, settings: embeddings::EmbeddingSettings) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&settings.min_score) {
+        return Err("Minimum similarity must be between 0 and 1".to_string());
+    }
+    embeddings::save(&app_handle, &settings)
+}
+
+#[tauri::command]
+fn get_llm_pricing(app_handle: tauri::AppHandle) -> pricing::LlmPricing {
+    pricing::load(&app_handle)
+}
+
+#[tauri::command]
+fn set_llm_pricing(app_handle: tauri::AppHandle, pricing: pricing::LlmPricing) -> Result<(), String> {
+    let valid = |p: f64| p.is_finite() && p >= 0.0;
+    if !valid(pricing.input_per_million) || !valid(pricing.output_per_million) {
+        return Err("Prices must be zero or more".to_string());
+    }
+    pricing::save(&app_handle, &pricing)
+}
+
+// Token, payload and cost estimate for a batch recommendation run, shown before
+// "Recommend All" when pricing is configured. Reads file sizes, so it runs off the main thread.
+#[tauri::command]
+async fn estimate_recommendation(
+    app_handle: tauri::AppHandle,
+    images: Vec<String>,
+    contents: Vec<String>,
+    titles: Vec<String>,
+    labels: Vec<String>,
+) -> Result<pricing::BatchEstimate, String> {
+    let prices = pricing::load(&app_handle);
+    tauri::async_runtime::spawn_blocking(move || pricing::estimate(&prices, &images, &contents, &titles, &labels))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+fn get_tagger_plugins(app_handle: tauri::AppHandle) -> Vec<plugins::TaggerPlugin> {
+    plugins::load(&app_handle)
+}
+
+#[tauri::command]
+fn set_tagger_plugins(app_handle: tauri::AppHandle, plugins: Vec<plugins::TaggerPlugin>) -> Result<(), String> {
+    let mut clean = Vec::new();
+    for mut plugin in plugins {
+        plugin.name = plugin.name.trim().to_string();
+        plugin.command = plugin.command.trim().to_string();
+        if plugin.name.is_empty() || plugin.command.is_empty() {
+            return Err("Plugins need a name and a command".to_string());
+        }
+        plugin.extensions = plugin
+            .extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+        clean.push(plugin);
+    }
+    plugins::save(&app_handle, &clean)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn generate_image_tags_llm(
+    app_handle: tauri::AppHandle,
     image_path: String,
     labels: Vec<String>,
+    existing: Option<Vec<String>>,
     top_k: usize,
     threshold: f32,
     base_url: Option<String>,
     model: Option<String>,
 ) -> Result<Vec<RecommendItem>, String> {
-    llm_flow::generate_image_tags_llm(image_path, labels, top_k, threshold, base_url, model).await
+    egress::check(&app_handle, base_url.clone(), &[&image_path])?;
+    let strip = egress::strip_images_for(&app_handle, base_url.clone());
+    let proxy = proxy::proxy_url(&app_handle);
+    let existing = existing.unwrap_or_default();
+    llm_flow::generate_image_tags_llm(image_path, labels, existing, top_k, threshold, base_url, model, proxy, strip).await
+}
+
+// File-name suggestions for an image while the vision source is off; nothing
+// leaves the machine
+#[tauri::command]
+fn rule_tags(file_path: String, labels: Vec<String>, top_k: usize) -> Vec<RecommendItem> {
+    llm_flow::rule_tags(&file_path, &labels, top_k)
+}
+
+// Vision tagging of many images with several requests in flight; progress is
+// emitted per image as "image-tag-status"
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn generate_image_tags_llm_batch(
+    app_handle: tauri::AppHandle,
+    requests: Vec<image_batch::ImageRequest>,
+    labels: Vec<String>,
+    top_k: usize,
+    threshold: f32,
+    base_url: Option<String>,
+    model: Option<String>,
+    concurrency: usize,
+) -> Result<Vec<image_batch::ImageResult>, String> {
+    // Images that may not go to this endpoint fail up front
+    let mut refused = Vec::new();
+    let mut allowed = Vec::new();
+    for req in requests {
+        match egress::check(&app_handle, base_url.clone(), &[&req.image_path]) {
+            Ok(()) => allowed.push(req),
+            Err(e) => refused.push(image_batch::ImageResult { image_path: req.image_path, items: Vec::new(), error: Some(e) }),
+        }
+    }
+    let strip_metadata = egress::strip_images_for(&app_handle, base_url.clone());
+    let options = image_batch::BatchOptions { labels, top_k, threshold, base_url, model, concurrency, strip_metadata };
+    let mut results = image_batch::run(app_handle, allowed, options).await;
+    results.extend(refused);
+    Ok(results)
+}
+
+#[tauri::command]
+fn cancel_image_tagging() {
+    image_batch::cancel();
+}
+
+#[tauri::command]
+fn get_tag_filter(app_handle: tauri::AppHandle, root: Option<String>) -> Result<Option<db::TagFilter>, String> {
+    db::get_tag_filter(&app_handle, root).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_tag_filter(app_handle: tauri::AppHandle, root: Option<String>, filter: db::TagFilter) -> Result<(), String> {
+    db::set_tag_filter(&app_handle, root, filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_safety_policy(app_handle: tauri::AppHandle) -> safety::SafetyPolicy {
+    safety::load(&app_handle)
+}
+
+#[tauri::command]
+fn set_safety_policy(app_handle: tauri::AppHandle, policy: safety::SafetyPolicy) -> Result<(), String> {
+    safety::save(&app_handle, &policy)
+}
+
+// Where the database, backups and caches live (see data_dir.rs)
+#[tauri::command]
+fn get_data_location(app_handle: tauri::AppHandle) -> data_dir::DataLocation {
+    data_dir::location(&app_handle)
+}
+
+// Copies the library to a folder the user picks, or back to the default
+// location, for use from the next start. None when the dialog was cancelled.
+#[tauri::command]
+async fn move_data_dir(app_handle: tauri::AppHandle, to_default: bool) -> Result<Option<data_dir::MoveReport>, String> {
+    let target = if to_default {
+        None
+    } else {
+        let Some(picked) = app_handle.dialog().file().set_title("Move TagMe data to").blocking_pick_folder() else {
+            return Ok(None);
+        };
+        Some(picked.as_path().ok_or("Invalid path encoding")?.to_path_buf())
+    };
+    tauri::async_runtime::spawn_blocking(move || data_dir::move_to(&app_handle, target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+}
+
+#[tauri::command]
+fn restart_app(app_handle: tauri::AppHandle) {
+    app_handle.restart();
+}
+
+// "Back up now" in the settings dialog; returns the backup file
+#[tauri::command]
+fn backup_database(app_handle: tauri::AppHandle) -> Result<String, String> {
+    db::backup_database(&app_handle, "manual")
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+// Passed by the login item so the app starts hidden in the tray
+const AUTOSTART_ARG: &str = "--autostart";
+
+// Login autostart lives in the OS (registry Run key, LaunchAgent, XDG autostart),
+// so its state is read from there rather than from our settings
+#[tauri::command]
+fn get_autostart(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    app_handle.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_autostart(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app_handle.autolaunch();
+    let result = if enabled { autolaunch.enable() } else { autolaunch.disable() };
+    result.map_err(|e| e.to_string())
+}
+
+// Existing files and folders named on a command line, resolved against `cwd` and
+// canonicalized like roots so they match scanned paths. The program name and
+// flags are skipped.
+fn paths_from_args(args: &[String], cwd: &str) -> Vec<String> {
+    args.iter()
+        .skip(1)
+        .filter(|a| !a.starts_with("--") && !tagme_paths::link::is_link(a))
+        .map(|a| std::path::Path::new(cwd).join(a))
+        .filter(|p| p.exists())
+        .map(|p| db::canonical_root(&p.to_string_lossy()))
+        .collect()
+}
+
+// Hands paths from "Open with TagMe" (or a second launch) to the UI, which
+// selects them for tagging
+fn open_paths(app_handle: &tauri::AppHandle, paths: Vec<String>) {
+    show_main_window(app_handle);
+    if !paths.is_empty() {
+        let _ = app_handle.emit("open-paths", &paths);
+    }
+}
+
+#[tauri::command]
+fn take_launch_paths() -> Vec<String> {
+    std::mem::take(&mut *LAUNCH_PATHS.lock().unwrap())
+}
+
+// A tagme:// link the OS started us with (see windows/hooks.nsh)
+fn link_from_args(args: &[String]) -> Option<String> {
+    args.iter().skip(1).find(|a| tagme_paths::link::is_link(a)).cloned()
+}
+
+// Hands a clicked filter link to the UI, which applies it
+fn open_link(app_handle: &tauri::AppHandle, link: String) {
+    show_main_window(app_handle);
+    let _ = app_handle.emit("open-link", &link);
+}
+
+#[tauri::command]
+fn take_launch_link() -> Option<String> {
+    LAUNCH_LINK.lock().unwrap().take()
+}
+
+fn show_main_window(app_handle: &tauri::AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+// Tray icon for reopening the window after an autostart (or any time it's hidden)
+fn build_tray(app: &tauri::App) -> tauri::Result<()> {
+    use tauri::menu::{Menu, MenuItem};
+    use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+
+    let show = MenuItem::with_id(app, "show", "Show TagMe", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &quit])?;
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip("TagMe")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => show_main_window(app),
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+// Offer to tag files whose paths are copied to the clipboard
+#[tauri::command]
+fn get_clipboard_watch(app_handle: tauri::AppHandle) -> bool {
+    clipboard::is_enabled(&app_handle)
+}
+
+#[tauri::command]
+fn set_clipboard_watch(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    clipboard::set_enabled(&app_handle, enabled)
+}
+
+#[tauri::command]
+fn copy_text(text: String) -> Result<(), String> {
+    clipboard::copy_text(&text)
+}
+
+#[tauri::command]
+fn get_proxy_settings(app_handle: tauri::AppHandle) -> proxy::ProxySettings {
+    proxy::load(&app_handle)
+}
+
+#[tauri::command]
+fn set_proxy_settings(app_handle: tauri::AppHandle, settings: proxy::ProxySettings) -> Result<(), String> {
+    if settings.enabled && settings.url().is_none() {
+        return Err("Proxy host is required".to_string());
+    }
+    proxy::save(&app_handle, &settings)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            let _ = app
-                .get_webview_window("main")
-                .expect("no main window")
-                .set_focus();
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            match link_from_args(&args) {
+                Some(link) => open_link(app, link),
+                None => open_paths(app, paths_from_args(&args, &cwd)),
+            }
         }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![AUTOSTART_ARG]),
+        ))
         .setup(|_app| Ok(()))
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) = event {
@@ -432,7 +2232,17 @@ pub fn run() {
             }
         })
         .setup(|app| {
+            data_dir::finish_move(app.handle());
             db::init_db(app.handle())?;
+            build_tray(app)?;
+            clipboard::apply(app.handle(), clipboard::is_enabled(app.handle()));
+            monitor_volumes(app.handle().clone());
+            poll_remote_roots(app.handle().clone());
+            let args: Vec<String> = std::env::args().collect();
+            *LAUNCH_LINK.lock().unwrap() = link_from_args(&args);
+            if let Ok(cwd) = std::env::current_dir() {
+                *LAUNCH_PATHS.lock().unwrap() = paths_from_args(&args, &cwd.to_string_lossy());
+            }
 
             // Restore window state
             if let Some(window) = app.get_webview_window("main") {
@@ -447,6 +2257,11 @@ pub fn run() {
                     }));
                     let _ = window.set_always_on_top(state.pinned);
                 }
+                // Launched at login: stay in the tray. The webview still loads, so
+                // the frontend starts the watchers as usual.
+                if std::env::args().any(|a| a == AUTOSTART_ARG) {
+                    let _ = window.hide();
+                }
             }
             Ok(())
         })
@@ -457,42 +2272,168 @@ pub fn run() {
             start_drag,
             toggle_maximize,
             select_root_directory,
+            add_root_directory,
             get_root_directory,
             get_root_directories,
             remove_root_directory,
+            get_manual_refresh,
+            set_manual_refresh,
+            get_expand_archives,
+            set_expand_archives,
+            add_remote_root,
+            reorder_root_directories,
+            set_default_root,
+            get_default_root,
+            set_root_local_only,
+            get_local_only_roots,
+            get_root_default_tags,
+            set_root_default_tags,
+            get_offline_mode,
+            set_offline_mode,
+            get_strip_image_metadata,
+            set_strip_image_metadata,
             purge_files_under_root,
             purge_all_files,
             get_db_path,
             get_files_count,
             scan_files,
             scan_files_multi,
+            load_more_scan,
             start_watching,
+            inject_fs_event,
             start_watching_multi,
             stop_watching,
+            reconcile_watchers,
+            get_watch_status,
+            pause_watching,
+            resume_watching,
             get_all_files,
             create_tag,
+            import_tags,
+            import_digikam,
+            export_obsidian,
+            export_tags,
+            save_tag_outline,
             get_all_tags,
+            get_tag_sort_mode,
+            set_tag_sort_mode,
+            get_tag_badge_mode,
+            set_tag_badge_mode,
+            get_auto_color_tags,
+            set_auto_color_tags,
+            get_inherit_tag_colors,
+            set_inherit_tag_colors,
+            get_flat_view,
+            set_flat_view,
+            get_inherit_folder_tags,
+            set_inherit_folder_tags,
             update_tag,
+            set_tag_archived,
+            set_tag_locked,
             delete_tag,
+            merge_tags,
             move_tag,
             add_file_tag,
+            add_tags_to_files,
+            get_pending_batches,
+            resume_batch,
+            discard_batch,
+            get_tag_hooks,
+            get_tag_examples,
+            co_occurring_tags,
+            store_recommendations,
+            get_stored_recommendations,
+            add_tag_examples,
+            remove_tag_example,
+            run_script,
+            set_tag_hook,
             remove_file_tag,
+            get_pinned_files,
+            pin_files,
+            unpin_file,
             get_file_tags,
+            get_all_file_tags,
+            query_files_with_tags,
+            search_files,
+            get_files_page,
+            get_files_window,
+            get_large_library,
+            set_large_library,
+            get_metrics,
+            reset_metrics,
+            get_tag_history,
+            cleanup_report,
+            apply_cleanup,
+            near_duplicate_tags,
+            verify_files,
+            accept_file_hashes,
+            find_duplicate_files,
+            resolve_duplicates,
+            export_manifest,
+            verify_manifest,
+            get_missing_files,
+            find_relink_candidates,
+            apply_relinks,
             filter_files_by_tags,
             recommend_tags_by_title,
             generate_tags_llm,
+            generate_content_tags_llm,
             generate_image_tags_llm,
+            rule_tags,
+            generate_image_tags_llm_batch,
+            cancel_image_tagging,
+            suggest_taxonomy,
+            translate_query,
+            run_tagger_plugins,
+            get_recommend_sources,
+            set_recommend_sources,
+            get_embedding_settings,
+            set_embedding_settings,
+            get_llm_pricing,
+            set_llm_pricing,
+            estimate_recommendation,
+            get_tagger_plugins,
+            set_tagger_plugins,
             save_window_state,
             load_window_state,
             open_file,
+            reveal_file,
+            open_file_with,
+            allow_preview,
+            get_open_actions,
+            set_open_actions,
             updater_check,
-            updater_install
+            updater_install,
+            get_update_channel,
+            set_update_channel,
+            skip_update_version,
+            snooze_update,
+            get_tag_filter,
+            set_tag_filter,
+            get_proxy_settings,
+            set_proxy_settings,
+            get_safety_policy,
+            get_autostart,
+            take_launch_paths,
+            take_launch_link,
+            get_clipboard_watch,
+            copy_text,
+            set_clipboard_watch,
+            set_autostart,
+            set_safety_policy,
+            backup_database,
+            get_data_location,
+            move_data_dir,
+            restart_app,
+            get_post_update_info,
+            run_migration,
+            acknowledge_update
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 #[tauri::command]
-fn recommend_tags_by_title(
+async fn recommend_tags_by_title(
     app_handle: tauri::AppHandle,
     file_path: String,
     top_k: usize,
@@ -523,41 +2464,146 @@ fn recommend_tags_by_title(
         }
         return Ok(out);
     }
-    let lname = name.to_lowercase();
-    let tokens: Vec<String> = lname
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect();
-    let mut scored: Vec<(db::TagInfo, i32)> = Vec::new();
-    for t in tags {
-        let tname = t.name.to_lowercase();
-        let mut score = 0;
-        if !tname.is_empty() {
-            if lname.contains(&tname) {
-                score += 10;
-            }
-            if tokens.iter().any(|w| w == &tname) {
-                score += 8;
-            }
-            if lname.starts_with(&tname) || lname.ends_with(&tname) {
-                score += 4;
+    // Embeddings catch synonyms and other languages that substring rules miss
+    let settings = embeddings::load(&app_handle);
+    // A remote embedding endpoint is a cloud model like any other
+    let scores = match egress::check(&app_handle, Some(settings.base_url.clone()), &[&file_path]) {
+        Ok(()) => embeddings::score_tags(&settings, &name, &tag_names).await,
+        Err(_) => None,
+    };
+    if let Some(scores) = scores.filter(|s| !s.is_empty()) {
+        let mut out: Vec<db::TagInfo> = Vec::new();
+        for (tag_name, _) in scores {
+            for t in tags.iter().filter(|t| t.name == tag_name) {
+                if out.len() < top_k {
+                    out.push(t.clone());
+                }
             }
         }
-        if score > 0 {
-            scored.push((t, score));
-        }
+        return Ok(out);
     }
+    // Folded, CJK-tokenized and pinyin-aware, shared with the LLM pre-filter
+    let mut scored: Vec<(db::TagInfo, i32)> = tags
+        .into_iter()
+        .map(|t| {
+            let score = llm_flow::matching::score(&name, &t.name);
+            (t, score)
+        })
+        .filter(|(_, score)| *score > 0)
+        .collect();
     scored.sort_by(|a, b| b.1.cmp(&a.1));
     Ok(scored.into_iter().take(top_k).map(|(t, _)| t).collect())
 }
 
+fn current_update_channel(app_handle: &tauri::AppHandle) -> UpdateChannel {
+    db::get_setting(app_handle, "update_channel")
+        .ok()
+        .flatten()
+        .map(|v| UpdateChannel::parse(&v))
+        .unwrap_or_default()
+}
+
+// A skipped version stays quiet forever; a snoozed one until its deadline. Either
+// only matches that exact version, so a newer release notifies again.
+fn is_update_suppressed(app_handle: &tauri::AppHandle, version: &str) -> bool {
+    let skipped = db::get_setting(app_handle, "update_skipped_version").ok().flatten();
+    if skipped.as_deref() == Some(version) {
+        return true;
+    }
+    let snoozed = db::get_setting(app_handle, "update_snoozed").ok().flatten().unwrap_or_default();
+    if let Some((snoozed_version, until)) = snoozed.split_once('|') {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        return snoozed_version == version && until.parse::<i64>().map(|u| now < u).unwrap_or(false);
+    }
+    false
+}
+
 #[tauri::command]
 async fn updater_check(app_handle: tauri::AppHandle) -> Result<UpdateInfo, String> {
-    updater_flow::check(app_handle).await
+    let channel = current_update_channel(&app_handle);
+    let proxy = proxy::proxy_url(&app_handle);
+    let mut info = updater_flow::check(app_handle.clone(), channel, proxy).await?;
+    if let Some(latest) = info.latest.as_deref() {
+        info.suppressed = is_update_suppressed(&app_handle, latest);
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+fn skip_update_version(app_handle: tauri::AppHandle, version: String) -> Result<(), String> {
+    db::set_setting(&app_handle, "update_skipped_version", &version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn snooze_update(app_handle: tauri::AppHandle, version: String, hours: u32) -> Result<(), String> {
+    let until = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+        + hours as i64 * 3600;
+    db::set_setting(&app_handle, "update_snoozed", &format!("{}|{}", version, until)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn updater_install(app_handle: tauri::AppHandle) -> Result<(), String> {
-    updater_flow::install(app_handle).await
+    let channel = current_update_channel(&app_handle);
+    let proxy = proxy::proxy_url(&app_handle);
+    let app = app_handle.clone();
+    updater_flow::install(app_handle, channel, proxy, move |version, notes| {
+        // Kept for the "What's new" screen on the next launch
+        let saved = serde_json::json!({ "version": version, "notes": notes }).to_string();
+        let _ = db::set_setting(&app, "release_notes", &saved);
+    })
+    .await
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostUpdateInfo {
+    previous_version: Option<String>,
+    current_version: String,
+    updated: bool,
+    notes: Option<String>,
+    pending_migrations: Vec<db::PendingMigration>,
+}
+
+#[tauri::command]
+fn get_post_update_info(app_handle: tauri::AppHandle) -> Result<PostUpdateInfo, String> {
+    let current_version = app_handle.package_info().version.to_string();
+    let previous_version = db::get_setting(&app_handle, "last_run_version").map_err(|e| e.to_string())?;
+    // A missing value means a fresh install (or a pre-tracking build): nothing to announce
+    let updated = previous_version.as_deref().map(|v| v != current_version).unwrap_or(false);
+    let notes = db::get_setting(&app_handle, "release_notes")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .filter(|v| v["version"].as_str() == Some(current_version.as_str()))
+        .and_then(|v| v["notes"].as_str().map(|s| s.to_string()));
+    let pending_migrations = db::pending_migrations(&app_handle).map_err(|e| e.to_string())?;
+    Ok(PostUpdateInfo { previous_version, current_version, updated, notes, pending_migrations })
+}
+
+#[tauri::command]
+fn run_migration(app_handle: tauri::AppHandle, version: i64) -> Result<(), String> {
+    db::run_migration(&app_handle, version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn acknowledge_update(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let current_version = app_handle.package_info().version.to_string();
+    db::set_setting(&app_handle, "last_run_version", &current_version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_update_channel(app_handle: tauri::AppHandle) -> String {
+    current_update_channel(&app_handle).as_str().to_string()
+}
+
+#[tauri::command]
+fn set_update_channel(app_handle: tauri::AppHandle, channel: String) -> Result<(), String> {
+    let channel = UpdateChannel::parse(&channel);
+    db::set_setting(&app_handle, "update_channel", channel.as_str()).map_err(|e| e.to_string())
 }
@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::db;
+
+// Checksum manifests for archival hand-offs and backups: a tab-separated file
+// with the SHA-256, size and path of each file. A manifest of a root stores
+// paths relative to it and records the root in its header.
+
+const HEADER: &str = "# tagme manifest";
+const ROOT_PREFIX: &str = "# root: ";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestIssue {
+    pub path: String,
+    // "missing", "modified" or "unreadable"
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestReport {
+    // Folder relative paths were checked against
+    pub base: Option<String>,
+    pub checked: usize,
+    pub issues: Vec<ManifestIssue>,
+}
+
+// Writes the manifest of every file under `root`, or of the files tagged with
+// `tag_ids` when no root is given, to `dest`. Returns how many files it lists.
+pub fn export(
+    app_handle: &tauri::AppHandle,
+    root: Option<&str>,
+    tag_ids: Vec<u32>,
+    use_and: bool,
+    dest: &Path,
+) -> Result<usize, String> {
+    let files: Vec<PathBuf> = match root {
+        Some(root) if crate::remote::is_remote(root) => {
+            return Err("Manifests can't be made for remote roots".to_string());
+        }
+        Some(root) => {
+            let mut out = Vec::new();
+            db::walk_files(Path::new(root), &mut out);
+            out.sort();
+            out
+        }
+        None if tag_ids.is_empty() => return Err("Pick a root or some tags for the manifest".to_string()),
+        None => db::get_files_by_tags(app_handle, tag_ids, use_and)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|f| !f.is_directory && !crate::remote::is_remote(&f.path) && !crate::archives::is_virtual(&f.path))
+            .map(|f| PathBuf::from(f.path))
+            .collect(),
+    };
+    // Hashes of tracked files are reused while their size and mtime still match
+    let stored = db::stored_hashes(app_handle).map_err(|e| e.to_string())?;
+
+    let mut text = format!("{}\n", HEADER);
+    if let Some(root) = root {
+        text.push_str(&format!("{}{}\n", ROOT_PREFIX, root));
+    }
+    let mut listed = 0;
+    for path in files.iter().filter(|p| p.as_path() != dest) {
+        let path_str = path.to_string_lossy().into_owned();
+        let name = match root.and_then(|r| path.strip_prefix(r).ok()) {
+            Some(rel) => rel.to_string_lossy().replace('\\', "/"),
+            None => path_str.clone(),
+        };
+        if name.contains(['\t', '\n', '\r']) {
+            eprintln!("⚠️ [MANIFEST] Skipping {}: tabs and line breaks can't be listed", path_str);
+            continue;
+        }
+        let Ok(meta) = fs::metadata(path) else {
+            eprintln!("⚠️ [MANIFEST] Skipping {}: it disappeared", path_str);
+            continue;
+        };
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+        let hash = match stored.get(&path_str) {
+            Some((hash, size, stored_mtime)) if *size == meta.len() && Some(*stored_mtime) == mtime => hash.clone(),
+            _ => db::hash_file_content(path).map_err(|e| format!("{}: {}", path_str, e))?,
+        };
+        text.push_str(&format!("{}\t{}\t{}\n", hash, meta.len(), name));
+        listed += 1;
+    }
+    // Written next to `dest` and renamed, so a killed export never leaves half a manifest
+    let partial = dest.with_extension("partial");
+    fs::write(&partial, text).map_err(|e| e.to_string())?;
+    fs::rename(&partial, dest).map_err(|e| e.to_string())?;
+    eprintln!("🧾 [MANIFEST] Wrote {} file(s) to {}", listed, dest.display());
+    Ok(listed)
+}
+
+// Re-hashes the files listed in `manifest`. Relative paths are checked against
+// the recorded root while it exists, otherwise against the manifest's folder,
+// so a copied folder can be checked with the manifest placed at its top.
+pub fn verify(manifest: &Path) -> Result<ManifestReport, String> {
+    let text = fs::read_to_string(manifest).map_err(|e| e.to_string())?;
+    if !text.starts_with(HEADER) {
+        return Err(format!("{} is not a tagme manifest", manifest.display()));
+    }
+    let mut root = None;
+    let mut entries = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if let Some(r) = line.strip_prefix(ROOT_PREFIX) {
+            root = Some(r.to_string());
+            continue;
+        }
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let mut parts = line.splitn(3, '\t');
+        let (Some(hash), Some(Ok(size)), Some(path)) = (parts.next(), parts.next().map(str::parse::<u64>), parts.next()) else {
+            return Err(format!("Line {} is not a manifest entry", n + 1));
+        };
+        entries.push((hash.to_string(), size, path.to_string()));
+    }
+    let base = root
+        .map(PathBuf::from)
+        .filter(|r| r.is_dir())
+        .or_else(|| manifest.parent().map(Path::to_path_buf));
+
+    let mut issues = Vec::new();
+    for (hash, size, path) in &entries {
+        let full = match &base {
+            Some(base) if !Path::new(path).is_absolute() => base.join(path),
+            _ => PathBuf::from(path),
+        };
+        let kind = match fs::metadata(&full) {
+            Err(_) => Some("missing"),
+            // A different size is a different file; no need to hash it
+            Ok(meta) if meta.len() != *size => Some("modified"),
+            Ok(_) => match db::hash_file_content(&full) {
+                Ok(current) if current == *hash => None,
+                Ok(_) => Some("modified"),
+                Err(e) => {
+                    eprintln!("⚠️ [MANIFEST] Could not read {}: {}", full.display(), e);
+                    Some("unreadable")
+                }
+            },
+        };
+        if let Some(kind) = kind {
+            issues.push(ManifestIssue { path: path.clone(), kind: kind.to_string() });
+        }
+    }
+    eprintln!("🧾 [MANIFEST] Checked {} file(s), {} issue(s)", entries.len(), issues.len());
+    Ok(ManifestReport { base: base.map(|b| b.to_string_lossy().into_owned()), checked: entries.len(), issues })
+}
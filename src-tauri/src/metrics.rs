@@ -0,0 +1,48 @@
+use std::future::Future;
+use std::time::Instant;
+
+use crate::db;
+
+// Local-only counters for the diagnostics panel: how long scans, LLM calls and
+// list queries take, and how often the caches hit. They live in the `metrics`
+// table and are only read by get_metrics; nothing is ever sent anywhere.
+
+pub const SCAN: &str = "scan";
+pub const LLM_TAGS: &str = "llm.tags";
+pub const LLM_CONTENT: &str = "llm.content";
+pub const LLM_QUERY: &str = "llm.query";
+pub const LLM_TAXONOMY: &str = "llm.taxonomy";
+pub const QUERY_FILTER: &str = "query.filter";
+pub const QUERY_PAGE: &str = "query.page";
+pub const QUERY_WINDOW: &str = "query.window";
+// Stored hash reused because size and mtime didn't change
+pub const HASH_CACHE: &str = "cache.hash";
+pub const ARCHIVE_CACHE: &str = "cache.archive";
+pub const REMOTE_CACHE: &str = "cache.remote";
+
+pub fn timed<T>(app_handle: &tauri::AppHandle, name: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    record(app_handle, name, start);
+    out
+}
+
+pub async fn timed_async<T>(app_handle: &tauri::AppHandle, name: &str, f: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let out = f.await;
+    record(app_handle, name, start);
+    out
+}
+
+pub fn cache(app_handle: &tauri::AppHandle, name: &str, hit: bool) {
+    if let Err(e) = db::record_cache(app_handle, name, hit) {
+        eprintln!("⚠️ [METRICS] {}: {}", name, e);
+    }
+}
+
+fn record(app_handle: &tauri::AppHandle, name: &str, start: Instant) {
+    let ms = start.elapsed().as_secs_f64() * 1000.0;
+    if let Err(e) = db::record_timing(app_handle, name, ms) {
+        eprintln!("⚠️ [METRICS] {}: {}", name, e);
+    }
+}
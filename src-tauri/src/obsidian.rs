@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::db::{self, TagInfo};
+
+// Markdown for browsing tagged files from Obsidian. "notes" writes a TagMe
+// folder into a vault with one note per tag (nested like the tag tree) that
+// links its parent and subtags and lists its files as file:// links, each with
+// the file's other tags, so Obsidian's backlinks and graph connect tags that
+// share files. "index" writes all of it as one note. Generated notes carry
+// `generator: tagme` in their front matter; re-exporting replaces them and
+// removes the ones whose tag is gone.
+
+pub const MODES: &[&str] = &["notes", "index"];
+// Vault subfolder the notes are written to
+pub const FOLDER: &str = "TagMe";
+pub const INDEX_NOTE: &str = "TagMe index.md";
+const MARKER: &str = "generator: tagme";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianReport {
+    pub notes: usize,
+    pub files: usize,
+    pub removed: usize,
+    pub path: String,
+}
+
+// A tag in tree order with its note's path segments under FOLDER/Tags (just
+// its own name until Vault::new places it)
+struct Entry<'a> {
+    tag: &'a TagInfo,
+    depth: usize,
+    note: Vec<String>,
+}
+
+struct Vault<'a> {
+    entries: Vec<Entry<'a>>,
+    // Note link target per tag id
+    links: HashMap<u32, String>,
+    // (path, tag ids) of every file per tag id
+    files: HashMap<u32, Vec<(&'a str, &'a [u32])>>,
+    file_count: usize,
+}
+
+pub fn export(app_handle: &tauri::AppHandle, mode: &str, dest: &Path) -> Result<ObsidianReport, String> {
+    if !MODES.contains(&mode) {
+        return Err(format!("Unknown export mode: {}", mode));
+    }
+    let tags = db::get_all_tags(app_handle).map_err(|e| e.to_string())?;
+    let by_file = db::get_all_file_tags(app_handle).map_err(|e| e.to_string())?;
+    let files: Vec<(String, Vec<u32>)> = db::get_all_files(app_handle)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|f| {
+            let ids: Vec<u32> = by_file.get(&f.id)?.iter().map(|t| t.id).collect();
+            (!ids.is_empty()).then_some((f.path, ids))
+        })
+        .collect();
+    let vault = Vault::new(&tags, &files);
+
+    if mode == "index" {
+        let path = if dest.is_dir() { dest.join(INDEX_NOTE) } else { dest.to_path_buf() };
+        fs::write(&path, vault.index()).map_err(|e| e.to_string())?;
+        return Ok(ObsidianReport {
+            notes: 1,
+            files: vault.file_count,
+            removed: 0,
+            path: path.to_string_lossy().into_owned(),
+        });
+    }
+
+    let root = dest.join(FOLDER);
+    let mut written = HashSet::new();
+    for entry in &vault.entries {
+        let mut path = root.join("Tags");
+        for segment in &entry.note {
+            path.push(segment);
+        }
+        path.set_extension("md");
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, vault.note(entry)).map_err(|e| format!("{}: {}", path.display(), e))?;
+        written.insert(path);
+    }
+    let index = root.join(INDEX_NOTE);
+    fs::write(&index, vault.contents()).map_err(|e| e.to_string())?;
+
+    // Notes of tags that were renamed, moved or deleted since the last export
+    let mut existing = Vec::new();
+    db::walk_files(&root.join("Tags"), &mut existing);
+    let mut removed = 0;
+    for path in existing {
+        let generated = path.extension().is_some_and(|e| e == "md")
+            && fs::read_to_string(&path).is_ok_and(|text| text.lines().take(4).any(|l| l == MARKER));
+        if generated && !written.contains(&path) && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    eprintln!("📝 [OBSIDIAN] {} note(s) written to {}, {} stale note(s) removed", written.len() + 1, root.display(), removed);
+    Ok(ObsidianReport {
+        notes: written.len() + 1,
+        files: vault.file_count,
+        removed,
+        path: root.to_string_lossy().into_owned(),
+    })
+}
+
+impl<'a> Vault<'a> {
+    fn new(tags: &'a [TagInfo], files: &'a [(String, Vec<u32>)]) -> Self {
+        let ids: HashSet<u32> = tags.iter().map(|t| t.id).collect();
+        // Tags whose parent is gone go to the top level, as in the outline export
+        let mut children: HashMap<Option<u32>, Vec<&TagInfo>> = HashMap::new();
+        for tag in tags {
+            children.entry(tag.parent_id.filter(|p| ids.contains(p))).or_default().push(tag);
+        }
+        let mut entries = Vec::new();
+        let mut seen = HashSet::new();
+        walk(&children, None, 0, &mut seen, &mut entries);
+
+        let mut links = HashMap::new();
+        // Sibling names that only differ in characters notes can't have still
+        // need their own notes; children go under their parent's final name
+        let mut taken: HashSet<Vec<String>> = HashSet::new();
+        let mut notes: HashMap<u32, Vec<String>> = HashMap::new();
+        for entry in &mut entries {
+            let mut note = entry.tag.parent_id.and_then(|p| notes.get(&p).cloned()).unwrap_or_default();
+            let base = entry.note.last().cloned().unwrap_or_default();
+            let mut name = base.clone();
+            let mut n = 2;
+            while taken.contains(&[note.clone(), vec![name.clone()]].concat()) {
+                name = format!("{} {}", base, n);
+                n += 1;
+            }
+            note.push(name);
+            taken.insert(note.clone());
+            links.insert(entry.tag.id, format!("{}/Tags/{}", FOLDER, note.join("/")));
+            notes.insert(entry.tag.id, note.clone());
+            entry.note = note;
+        }
+
+        let mut by_tag: HashMap<u32, Vec<(&str, &[u32])>> = HashMap::new();
+        for (path, tag_ids) in files {
+            for id in tag_ids {
+                by_tag.entry(*id).or_default().push((path.as_str(), tag_ids.as_slice()));
+            }
+        }
+        for list in by_tag.values_mut() {
+            list.sort_by_key(|(path, _)| *path);
+        }
+        Vault { entries, links, files: by_tag, file_count: files.len() }
+    }
+
+    fn link(&self, id: u32) -> Option<String> {
+        let target = self.links.get(&id)?;
+        let name = self.entries.iter().find(|e| e.tag.id == id).map(|e| e.tag.name.as_str()).unwrap_or_default();
+        Some(format!("[[{}|{}]]", target, label(name)))
+    }
+
+    fn note(&self, entry: &Entry) -> String {
+        let tag = entry.tag;
+        let mut out = format!("---\n{}\ntag: \"{}\"\naliases: [\"{}\"]\n---\n", MARKER, yaml(&self.path_name(entry)), yaml(&tag.name));
+        out.push_str(&format!("# {}{}\n\n", tag.icon.as_deref().map(|i| format!("{} ", i)).unwrap_or_default(), tag.name));
+        if let Some(up) = tag.parent_id.and_then(|p| self.link(p)) {
+            out.push_str(&format!("Up: {}\n", up));
+        }
+        let subtags: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|e| e.tag.parent_id == Some(tag.id))
+            .filter_map(|e| self.link(e.tag.id))
+            .collect();
+        if !subtags.is_empty() {
+            out.push_str(&format!("Subtags: {}\n", subtags.join(", ")));
+        }
+        out.push('\n');
+        out.push_str(&self.file_list(tag.id, "##"));
+        out
+    }
+
+    // The one-note export: every tag as a heading nested like the tree
+    fn index(&self) -> String {
+        let mut out = format!("---\n{}\n---\n# Tags\n\n", MARKER);
+        for entry in &self.entries {
+            let level = "#".repeat((entry.depth + 2).min(6));
+            out.push_str(&format!("{} {}\n\n", level, entry.tag.name));
+            out.push_str(&self.file_list(entry.tag.id, ""));
+        }
+        out
+    }
+
+    // The notes export's entry point: the tag tree as nested links
+    fn contents(&self) -> String {
+        let mut out = format!("---\n{}\n---\n# Tags\n\n", MARKER);
+        for entry in &self.entries {
+            let count = self.files.get(&entry.tag.id).map_or(0, Vec::len);
+            out.push_str(&format!(
+                "{}- {} ({})\n",
+                "  ".repeat(entry.depth),
+                self.link(entry.tag.id).unwrap_or_default(),
+                count
+            ));
+        }
+        out
+    }
+
+    // "Files (n)" and a bullet per file; `heading` empty for a plain label
+    fn file_list(&self, id: u32, heading: &str) -> String {
+        let files = self.files.get(&id).map(Vec::as_slice).unwrap_or_default();
+        if files.is_empty() {
+            return "*No files*\n\n".to_string();
+        }
+        let mut out = if heading.is_empty() {
+            format!("Files ({}):\n\n", files.len())
+        } else {
+            format!("{} Files ({})\n\n", heading, files.len())
+        };
+        for (path, tag_ids) in files {
+            let others: Vec<String> = tag_ids.iter().filter(|t| **t != id).filter_map(|t| self.link(*t)).collect();
+            let also = if others.is_empty() { String::new() } else { format!(" · {}", others.join(" ")) };
+            out.push_str(&format!("- {}{}\n", file_link(path), also));
+        }
+        out.push('\n');
+        out
+    }
+
+    fn path_name(&self, entry: &Entry) -> String {
+        let mut names = vec![entry.tag.name.clone()];
+        let mut cursor = entry.tag.parent_id;
+        while let Some(e) = cursor.and_then(|p| self.entries.iter().find(|e| e.tag.id == p)) {
+            if names.len() > self.entries.len() {
+                break;
+            }
+            names.push(e.tag.name.clone());
+            cursor = e.tag.parent_id;
+        }
+        names.reverse();
+        names.join("/")
+    }
+}
+
+fn walk<'a>(
+    children: &HashMap<Option<u32>, Vec<&'a TagInfo>>,
+    parent: Option<u32>,
+    depth: usize,
+    seen: &mut HashSet<u32>,
+    out: &mut Vec<Entry<'a>>,
+) {
+    for tag in children.get(&parent).into_iter().flatten() {
+        if !seen.insert(tag.id) {
+            continue;
+        }
+        out.push(Entry { tag, depth, note: vec![file_name(&tag.name)] });
+        walk(children, Some(tag.id), depth + 1, seen, out);
+    }
+}
+
+// A tag name as a note name: no characters Obsidian or the filesystem reject
+fn file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if "*\"\\/<>:|?#^[]".contains(c) || c.is_control() { '-' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').to_string();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned
+    }
+}
+
+// Text shown for a wikilink; `|` and brackets would end it early
+fn label(name: &str) -> String {
+    name.replace('|', "-").replace('[', "(").replace(']', ")")
+}
+
+fn yaml(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// A Markdown link that opens a local file; remote and in-archive paths, which
+// the OS can't open, are listed as code
+fn file_link(path: &str) -> String {
+    let name = tagme_paths::file_name(path).unwrap_or(path);
+    if crate::remote::is_remote(path) || crate::archives::is_virtual(path) {
+        return format!("{} `{}`", label(name), path);
+    }
+    let slashed = path.replace('\\', "/");
+    // UNC paths (//server/share) keep the server as the URL's host
+    let (mut url, rest) = match slashed.strip_prefix("//") {
+        Some(unc) => (String::from("file://"), unc),
+        None => (String::from("file:///"), slashed.trim_start_matches('/')),
+    };
+    for b in rest.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => url.push(b as char),
+            _ => url.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    format!("[{}]({})", label(name), url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(id: u32, name: &str, parent_id: Option<u32>) -> TagInfo {
+        TagInfo {
+            id,
+            name: name.to_string(),
+            parent_id,
+            color: None,
+            position: id as i32,
+            icon: None,
+            archived: false,
+            locked: false,
+            shade: None,
+        }
+    }
+
+    #[test]
+    fn file_links_are_percent_encoded() {
+        assert_eq!(file_link("/home/me/My Photos/a#1.jpg"), "[a#1.jpg](file:///home/me/My%20Photos/a%231.jpg)");
+        assert_eq!(file_link("C:\\Users\\me\\x.pdf"), "[x.pdf](file:///C:/Users/me/x.pdf)");
+        assert_eq!(file_link("\\\\nas\\share\\y.txt"), "[y.txt](file://nas/share/y.txt)");
+    }
+
+    #[test]
+    fn notes_nest_like_the_tree_and_link_shared_files() {
+        let tags = vec![tag(1, "Projects", None), tag(2, "Client: A", Some(1)), tag(3, "Client? A", Some(1)), tag(4, "urgent", None)];
+        let files = vec![("/w/spec.pdf".to_string(), vec![2, 4])];
+        let vault = Vault::new(&tags, &files);
+        let notes: Vec<String> = vault.entries.iter().map(|e| e.note.join("/")).collect();
+        assert_eq!(notes, vec!["Projects", "Projects/Client- A", "Projects/Client- A 2", "urgent"]);
+
+        let client = vault.note(&vault.entries[1]);
+        assert!(client.contains("tag: \"Projects/Client: A\""));
+        assert!(client.contains("Up: [[TagMe/Tags/Projects|Projects]]"));
+        assert!(client.contains("- [spec.pdf](file:///w/spec.pdf) · [[TagMe/Tags/urgent|urgent]]"));
+        assert!(vault.note(&vault.entries[0]).contains("Subtags: [[TagMe/Tags/Projects/Client- A|Client: A]], [[TagMe/Tags/Projects/Client- A 2|Client? A]]"));
+        assert!(vault.index().contains("### Client: A"));
+
+        let tags = vec![tag(1, "a:b", None), tag(2, "a?b", None), tag(3, "x", Some(2))];
+        let vault = Vault::new(&tags, &[]);
+        assert_eq!(vault.entries[2].note, vec!["a-b 2".to_string(), "x".to_string()]);
+    }
+}
@@ -0,0 +1,458 @@
+use std::io::Read;
+use std::path::Path;
+
+// Title, author and page count of a PDF, read without a PDF library: the
+// Info dictionary the last trailer points to (falling back to the XMP
+// dc:title), and the largest /Count of a /Pages node. Objects packed into
+// Flate-compressed object streams are found too. Encrypted files only give
+// their page count.
+
+// Larger files aren't read just for their metadata
+const PDF_MAX_BYTES: u64 = 64 * 1024 * 1024;
+// Decompressed object streams of one file, all together; a few KB of Flate
+// data can inflate to gigabytes
+const OBJSTM_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PdfMeta {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub page_count: Option<u32>,
+}
+
+pub fn is_pdf(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+}
+
+pub fn metadata(path: &Path) -> Option<PdfMeta> {
+    if std::fs::metadata(path).ok()?.len() > PDF_MAX_BYTES {
+        return None;
+    }
+    let data = std::fs::read(path).ok()?;
+    data.starts_with(b"%PDF").then(|| parse(&data))
+}
+
+fn parse(data: &[u8]) -> PdfMeta {
+    let streams = object_streams(data);
+    let encrypted = find(data, b"/Encrypt").is_some();
+    let info = (!encrypted).then(|| info_dict(data, &streams)).flatten();
+    let field = |key: &[u8]| info.and_then(|d| dict_string(d, key)).filter(|s| !s.is_empty());
+    let page_count = std::iter::once(data)
+        .chain(streams.iter().map(|s| s.data.as_slice()))
+        .filter_map(page_count)
+        .max();
+    PdfMeta {
+        title: field(b"/Title").or_else(|| (!encrypted).then(|| xmp_title(data)).flatten()),
+        author: field(b"/Author"),
+        page_count,
+    }
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).rposition(|w| w == needle)
+}
+
+fn is_delimiter(b: u8) -> bool {
+    b.is_ascii_whitespace() || b"()<>[]{}/%".contains(&b)
+}
+
+// The unsigned integer following `key` in `dict`
+fn dict_number(dict: &[u8], key: &[u8]) -> Option<usize> {
+    let at = find(dict, key)? + key.len();
+    let digits: String = dict[at..]
+        .iter()
+        .skip_while(|b| b.is_ascii_whitespace())
+        .take_while(|b| b.is_ascii_digit())
+        .map(|&b| b as char)
+        .collect();
+    digits.parse().ok()
+}
+
+// A decompressed /ObjStm: "number offset" pairs, then the objects from /First on
+struct ObjectStream {
+    first: usize,
+    data: Vec<u8>,
+}
+
+impl ObjectStream {
+    fn object(&self, number: u32) -> Option<&[u8]> {
+        let header = String::from_utf8_lossy(self.data.get(..self.first)?).into_owned();
+        let values: Vec<usize> = header.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        let index = values.chunks(2).position(|pair| pair[0] == number as usize)?;
+        // Offsets come from the file; absurd ones just miss
+        let start = self.first.checked_add(*values.get(index * 2 + 1)?)?;
+        let end = match values.get(index * 2 + 3) {
+            Some(next) => self.first.checked_add(*next)?,
+            None => self.data.len(),
+        };
+        self.data.get(start..end.min(self.data.len()))
+    }
+}
+
+fn object_streams(data: &[u8]) -> Vec<ObjectStream> {
+    let mut out = Vec::new();
+    let mut budget = OBJSTM_MAX_BYTES;
+    let mut pos = 0;
+    while let Some(i) = find(&data[pos..], b"/ObjStm").map(|i| pos + i) {
+        if budget == 0 {
+            break;
+        }
+        pos = i + 1;
+        let Some(start) = find(&data[i..], b"stream").map(|s| i + s + b"stream".len()) else { break };
+        let dict_start = enclosing_dict(data, i).unwrap_or(i);
+        let dict = &data[dict_start..start];
+        let Some(first) = dict_number(dict, b"/First") else { continue };
+        if find(dict, b"/FlateDecode").is_none() {
+            continue;
+        }
+        // The stream keyword ends with CRLF or LF
+        let body_start = match data.get(start..start + 2) {
+            Some(b"\r\n") => start + 2,
+            _ => start + 1,
+        };
+        let Some(end) = data.get(body_start..).and_then(|rest| find(rest, b"endstream")).map(|e| body_start + e) else { break };
+        let mut decoded = Vec::new();
+        // The end-of-line bytes before endstream don't stop what decoded so far
+        let _ = flate2::read::ZlibDecoder::new(&data[body_start..end]).take(budget).read_to_end(&mut decoded);
+        budget -= decoded.len() as u64;
+        out.push(ObjectStream { first, data: decoded });
+        pos = end;
+    }
+    out
+}
+
+// The Info dictionary named by the last trailer (or cross-reference stream),
+// stored as "N G obj" or packed in an object stream
+fn info_dict<'a>(data: &'a [u8], streams: &'a [ObjectStream]) -> Option<&'a [u8]> {
+    let at = rfind(data, b"/Info")?;
+    let rest = &data[at + b"/Info".len()..];
+    let text = String::from_utf8_lossy(&rest[..rest.len().min(32)]).into_owned();
+    let mut words = text.split(|c: char| c.is_whitespace() || c == '/' || c == '>').filter(|w| !w.is_empty());
+    let number: u32 = words.next()?.parse().ok()?;
+    let generation: u32 = words.next()?.parse().ok()?;
+    let header = format!("{} {} obj", number, generation);
+    // Incremental updates append newer versions; the last one counts
+    let mut found = None;
+    let mut pos = 0;
+    while let Some(i) = find(&data[pos..], header.as_bytes()).map(|i| pos + i) {
+        pos = i + 1;
+        if i == 0 || is_delimiter(data[i - 1]) {
+            found = Some(i + header.len());
+        }
+    }
+    let body = match found {
+        Some(i) => &data[i..],
+        None => streams.iter().find_map(|s| s.object(number))?,
+    };
+    let start = find(body, b"<<")?;
+    Some(&body[start..start + dict_end(&body[start..])])
+}
+
+// Start of the innermost dictionary around `at`
+fn enclosing_dict(data: &[u8], at: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = at;
+    while i >= 2 {
+        match &data[i - 2..i] {
+            b">>" => {
+                depth += 1;
+                i -= 2;
+            }
+            b"<<" if depth == 0 => return Some(i - 2),
+            b"<<" => {
+                depth -= 1;
+                i -= 2;
+            }
+            _ => i -= 1,
+        }
+    }
+    None
+}
+
+// Length of the dictionary at the start of `data`, nested ones included
+fn dict_end(data: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while i + 1 < data.len() {
+        match &data[i..i + 2] {
+            b"<<" => {
+                depth += 1;
+                i += 2;
+            }
+            b">>" => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ if data[i] == b'(' => i += literal_len(&data[i..]),
+            _ => i += 1,
+        }
+    }
+    data.len()
+}
+
+// Length of the literal string at the start of `data`, parentheses balanced
+fn literal_len(data: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    data.len()
+}
+
+// The string value of `key` in `dict`
+fn dict_string(dict: &[u8], key: &[u8]) -> Option<String> {
+    let mut pos = 0;
+    while let Some(i) = find(&dict[pos..], key).map(|i| pos + i) {
+        pos = i + key.len();
+        // "/Title" must not match "/TitleX"
+        if dict.get(pos).is_some_and(|&b| !is_delimiter(b)) {
+            continue;
+        }
+        let value = &dict[pos..];
+        let value = &value[value.iter().position(|b| !b.is_ascii_whitespace())?..];
+        return match value.first()? {
+            b'(' => {
+                let len = literal_len(value).max(2);
+                Some(decode_text(&unescape_literal(&value[1..len - 1])))
+            }
+            b'<' if value.get(1) != Some(&b'<') => {
+                let end = value.iter().position(|&b| b == b'>')?;
+                let hex: Vec<u8> = value[1..end].iter().copied().filter(|b| b.is_ascii_hexdigit()).collect();
+                // An odd final digit is followed by an implied 0
+                let bytes: Vec<u8> = hex
+                    .chunks(2)
+                    .map(|pair| {
+                        let digit = |b: u8| (b as char).to_digit(16).unwrap_or(0) as u8;
+                        digit(pair[0]) * 16 + pair.get(1).map_or(0, |&b| digit(b))
+                    })
+                    .collect();
+                Some(decode_text(&bytes))
+            }
+            _ => None,
+        };
+    }
+    None
+}
+
+fn unescape_literal(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] != b'\\' {
+            out.push(raw[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let Some(&c) = raw.get(i) else { break };
+        match c {
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b't' => out.push(b'\t'),
+            b'b' => out.push(8),
+            b'f' => out.push(12),
+            b'0'..=b'7' => {
+                let digits = raw[i..].iter().take(3).take_while(|d| (b'0'..=b'7').contains(d)).count();
+                let value = raw[i..i + digits].iter().fold(0u32, |v, d| v * 8 + u32::from(d - b'0'));
+                out.push(value as u8);
+                i += digits;
+                continue;
+            }
+            // A backslash before a line break continues the string
+            b'\r' if raw.get(i + 1) == Some(&b'\n') => i += 1,
+            b'\r' | b'\n' => {}
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+// UTF-16BE or UTF-8 with a byte order mark, otherwise PDFDocEncoding (close
+// enough to Latin-1 for titles)
+fn decode_text(bytes: &[u8]) -> String {
+    let text = if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16.chunks(2).map(|p| u16::from_be_bytes([p[0], *p.get(1).unwrap_or(&0)])).collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(utf8).into_owned()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    };
+    text.trim().to_string()
+}
+
+// <dc:title><rdf:Alt><rdf:li xml:lang="x-default">…</rdf:li></rdf:Alt></dc:title>
+fn xmp_title(data: &[u8]) -> Option<String> {
+    let start = find(data, b"<dc:title")?;
+    let end = start + find(&data[start..], b"</dc:title>")?;
+    let block = String::from_utf8_lossy(&data[start..end]).into_owned();
+    let li = block.find("<rdf:li")?;
+    let text_start = li + block[li..].find('>')? + 1;
+    let text_end = text_start + block[text_start..].find("</rdf:li>")?;
+    let title = block[text_start..text_end]
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+// The root of the page tree counts every page; it has the largest /Count of
+// any /Pages node
+fn page_count(data: &[u8]) -> Option<u32> {
+    let mut best: Option<u32> = None;
+    let mut pos = 0;
+    while let Some(i) = find(&data[pos..], b"/Type").map(|i| pos + i) {
+        pos = i + b"/Type".len();
+        let value = &data[pos..];
+        let value = &value[value.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(0)..];
+        // "/Pages" but not "/Page"
+        if !value.starts_with(b"/Pages") || value.get(6).is_some_and(|&b| !is_delimiter(b)) {
+            continue;
+        }
+        let Some(start) = enclosing_dict(data, i) else { continue };
+        let dict = &data[start..start + dict_end(&data[start..])];
+        if let Some(n) = dict_number(dict, b"/Count").and_then(|n| u32::try_from(n).ok()) {
+            best = Some(best.map_or(n, |b| b.max(n)));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // A PDF body: objects as (number, dictionary) and the trailer dictionary.
+    // The parser doesn't read the xref table, so none is written.
+    fn pdf(objects: &[(u32, &str)], trailer: &str) -> Vec<u8> {
+        let mut out = b"%PDF-1.7\n".to_vec();
+        for (number, body) in objects {
+            out.extend(format!("{} 0 obj\n{}\nendobj\n", number, body).bytes());
+        }
+        out.extend(format!("trailer\n{}\n%%EOF\n", trailer).bytes());
+        out
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    // An /ObjStm object holding `objects`, followed by an xref stream whose
+    // dictionary stands in for the trailer
+    fn packed_pdf(objects: &[(u32, &str)], xref: &str) -> Vec<u8> {
+        let mut header = String::new();
+        let mut body = String::new();
+        for (number, object) in objects {
+            header.push_str(&format!("{} {} ", number, body.len()));
+            body.push_str(object);
+            body.push(' ');
+        }
+        let stream = zlib(format!("{}{}", header, body).as_bytes());
+        let mut out = b"%PDF-1.7\n".to_vec();
+        out.extend(
+            format!("10 0 obj\n<< /Type /ObjStm /N {} /First {} /Length {} /Filter /FlateDecode >>\nstream\r\n", objects.len(), header.len(), stream.len())
+                .bytes(),
+        );
+        out.extend(&stream);
+        out.extend(b"\r\nendstream\nendobj\n");
+        out.extend(format!("11 0 obj\n{}\nstream\n\nendstream\nendobj\n%%EOF\n", xref).bytes());
+        out
+    }
+
+    const CATALOG: (u32, &str) = (1, "<< /Type /Catalog /Pages 2 0 R >>");
+    const PAGES: (u32, &str) = (2, "<< /Type /Pages /Kids [3 0 R 5 0 R 6 0 R] /Count 3 >>");
+
+    #[test]
+    fn reads_the_info_dictionary() {
+        let data = pdf(
+            &[CATALOG, PAGES, (4, r"<< /Title (Annual \(draft\)\040report) /Author <FEFF004100640061> /TitleX (no) >>")],
+            "<< /Root 1 0 R /Info 4 0 R >>",
+        );
+        assert_eq!(
+            parse(&data),
+            PdfMeta { title: Some("Annual (draft) report".into()), author: Some("Ada".into()), page_count: Some(3) }
+        );
+    }
+
+    #[test]
+    fn incremental_updates_win() {
+        let mut data = pdf(&[CATALOG, PAGES, (4, "<< /Title (Draft) >>")], "<< /Root 1 0 R /Info 4 0 R >>");
+        data.extend(pdf(&[(4, "<< /Title (Final) >>")], "<< /Root 1 0 R /Info 4 0 R /Prev 9 >>").split_off(9));
+        assert_eq!(parse(&data).title.as_deref(), Some("Final"));
+    }
+
+    #[test]
+    fn falls_back_to_the_xmp_title() {
+        let xmp = "<< /Type /Metadata /Subtype /XML >>\nstream\n<x:xmpmeta><dc:title><rdf:Alt>\
+                   <rdf:li xml:lang=\"x-default\"> Tom &amp; Jerry &lt;3 </rdf:li></rdf:Alt></dc:title></x:xmpmeta>\nendstream";
+        let data = pdf(&[CATALOG, PAGES, (4, "<< /Producer (x) >>"), (7, xmp)], "<< /Root 1 0 R /Info 4 0 R >>");
+        assert_eq!(parse(&data).title.as_deref(), Some("Tom & Jerry <3"));
+    }
+
+    #[test]
+    fn finds_objects_in_object_streams() {
+        let data = packed_pdf(
+            &[CATALOG, PAGES, (4, "<< /Title (Packed) /Author (Bo) >>")],
+            "<< /Type /XRef /Root 1 0 R /Info 4 0 R /Size 12 >>",
+        );
+        assert_eq!(
+            parse(&data),
+            PdfMeta { title: Some("Packed".into()), author: Some("Bo".into()), page_count: Some(3) }
+        );
+    }
+
+    #[test]
+    fn encrypted_files_only_give_their_page_count() {
+        let data = pdf(
+            &[CATALOG, PAGES, (4, "<< /Title <8A3F9C> >>"), (8, "<< /Filter /Standard /V 2 >>")],
+            "<< /Root 1 0 R /Info 4 0 R /Encrypt 8 0 R >>",
+        );
+        assert_eq!(parse(&data), PdfMeta { title: None, author: None, page_count: Some(3) });
+    }
+
+    #[test]
+    fn object_streams_are_inflated_up_to_the_limit() {
+        let bomb = vec![b' '; OBJSTM_MAX_BYTES as usize + 4096];
+        let stream = zlib(&bomb);
+        let mut data = b"%PDF-1.7\n10 0 obj\n<< /Type /ObjStm /First 4 /Filter /FlateDecode >>\nstream\n".to_vec();
+        data.extend(&stream);
+        data.extend(b"\nendstream\nendobj\n");
+        let streams = object_streams(&data);
+        assert_eq!(streams.iter().map(|s| s.data.len() as u64).sum::<u64>(), OBJSTM_MAX_BYTES);
+    }
+
+    #[test]
+    fn absurd_object_offsets_miss_instead_of_overflowing() {
+        let header = format!("4 {} 5 0 ", usize::MAX);
+        let stream = ObjectStream { first: header.len(), data: header.into_bytes() };
+        assert_eq!(stream.object(4), None);
+        let stream = ObjectStream { first: usize::MAX, data: b"4 0".to_vec() };
+        assert_eq!(stream.object(4), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::{Read, Write};
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use llm_flow::RecommendItem;
+use serde::{Deserialize, Serialize};
+
+use crate::{db, hooks};
+
+// A plugin that takes longer than this for one file is killed
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// External tagger: an executable started once per file with the file's path as
+// its last argument. It gets {"path", "labels"} as JSON on stdin and answers with
+// [{"name", "score"}] on stdout, scores between 0 and 1.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaggerPlugin {
+    pub name: String,
+    pub command: String,
+    // Lowercase, without the dot; empty means every file
+    pub extensions: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    path: &'a str,
+    labels: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct PluginSuggestion {
+    name: String,
+    score: f32,
+}
+
+pub fn load(app_handle: &tauri::AppHandle) -> Vec<TaggerPlugin> {
+    db::get_setting(app_handle, "tagger_plugins")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app_handle: &tauri::AppHandle, plugins: &[TaggerPlugin]) -> Result<(), String> {
+    let raw = serde_json::to_string(plugins).map_err(|e| e.to_string())?;
+    db::set_setting(app_handle, "tagger_plugins", &raw).map_err(|e| e.to_string())
+}
+
+// Suggestions from every enabled plugin that handles `path`'s extension, tagged
+// with source "plugin:<name>". A failing plugin is logged and skipped.
+pub fn suggest(app_handle: &tauri::AppHandle, path: &str, labels: &[String]) -> Vec<RecommendItem> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut items = Vec::new();
+    for plugin in load(app_handle) {
+        if !plugin.enabled || plugin.command.trim().is_empty() {
+            continue;
+        }
+        if !plugin.extensions.is_empty() && !plugin.extensions.contains(&ext) {
+            continue;
+        }
+        match run(&plugin, path, labels) {
+            Ok(list) => items.extend(list.into_iter().filter(|s| !s.name.trim().is_empty()).map(|s| RecommendItem {
+                name: s.name.trim().to_string(),
+                score: s.score.clamp(0.0, 1.0),
+                source: format!("plugin:{}", plugin.name),
+            })),
+            Err(e) => eprintln!("⚠️ [PLUGIN] {} failed for {}: {}", plugin.name, path, e),
+        }
+    }
+    items
+}
+
+fn run(plugin: &TaggerPlugin, path: &str, labels: &[String]) -> Result<Vec<PluginSuggestion>, String> {
+    let request = serde_json::to_vec(&PluginRequest { path, labels }).map_err(|e| e.to_string())?;
+    let mut child = hooks::shell(&plugin.command, path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // Both pipes are serviced on their own threads so a plugin that ignores stdin
+    // or writes a lot can't deadlock against us
+    let stdin = child.stdin.take();
+    std::thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(&request);
+        }
+    });
+    // Sent over a channel rather than joined: a background process the plugin
+    // started can keep stdout open long after the plugin itself exited
+    let stdout = child.stdout.take();
+    let (sender, output) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut out = String::new();
+        if let Some(mut stdout) = stdout {
+            let _ = stdout.read_to_string(&mut out);
+        }
+        let _ = sender.send(out);
+    });
+
+    let deadline = Instant::now() + PLUGIN_TIMEOUT;
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            None => {
+                // Reaped so a killed plugin doesn't linger as a zombie
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("timed out".to_string());
+            }
+        }
+    };
+    if !status.success() {
+        return Err(format!("exited with {}", status));
+    }
+    let out = output
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .map_err(|_| "timed out waiting for its output".to_string())?;
+    serde_json::from_str(out.trim()).map_err(|e| format!("invalid output: {}", e))
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+// Rough per-request figures for the recommendation prompts in llm-flow
+const PROMPT_OVERHEAD_TOKENS: u64 = 150;
+const OUTPUT_TOKENS: u64 = 120;
+// Vision models downscale images, so a flat figure is closer than one based on file size
+const IMAGE_TOKENS: u64 = 1_200;
+// The text prompt only carries the best-matching labels
+const TEXT_LABELS_SENT: usize = 20;
+// Start of a text file sent to the content model (see llm-flow)
+const CONTENT_EXCERPT_BYTES: u64 = 6000;
+
+// Prices of the configured LLM endpoint, used for the estimate shown before
+// "Recommend All". Stored as JSON under the "llm_pricing" settings key; zero
+// prices mean the endpoint is free and no confirmation is asked.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LlmPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub currency: String,
+}
+
+impl LlmPricing {
+    pub fn is_priced(&self) -> bool {
+        self.input_per_million > 0.0 || self.output_per_million > 0.0
+    }
+}
+
+pub fn load(app_handle: &tauri::AppHandle) -> LlmPricing {
+    db::get_setting(app_handle, "llm_pricing")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app_handle: &tauri::AppHandle, pricing: &LlmPricing) -> Result<(), String> {
+    let raw = serde_json::to_string(pricing).map_err(|e| e.to_string())?;
+    db::set_setting(app_handle, "llm_pricing", &raw).map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEstimate {
+    // Files that would be sent to a model
+    pub files: usize,
+    pub image_files: usize,
+    // Base64 payload of the images, as uploaded
+    pub image_bytes: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+    pub currency: String,
+    pub priced: bool,
+}
+
+// CJK text runs about a token per character, ASCII about four characters per token
+fn approx_tokens(text: &str) -> u64 {
+    let (wide, narrow) = text.chars().fold((0u64, 0u64), |(w, n), c| if c.is_ascii() { (w, n + 1) } else { (w + 1, n) });
+    wide + narrow.div_ceil(4)
+}
+
+// Estimate for recommending files against `labels`, grouped by the model they
+// go to (vision, content or title). Plugins and file-name rules are free.
+pub fn estimate(
+    pricing: &LlmPricing,
+    images: &[String],
+    contents: &[String],
+    titles: &[String],
+    labels: &[String],
+) -> BatchEstimate {
+    let all_labels = approx_tokens(&serde_json::to_string(labels).unwrap_or_default());
+    let text_labels = approx_tokens(
+        &serde_json::to_string(&labels[..labels.len().min(TEXT_LABELS_SENT)]).unwrap_or_default(),
+    );
+    let title_tokens = |path: &str| {
+        approx_tokens(std::path::Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or(""))
+    };
+    let size = |path: &str| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut est = BatchEstimate { currency: pricing.currency.clone(), priced: pricing.is_priced(), ..Default::default() };
+    for path in images {
+        est.image_files += 1;
+        est.image_bytes += size(path).div_ceil(3) * 4;
+        est.input_tokens += PROMPT_OVERHEAD_TOKENS + all_labels + IMAGE_TOKENS;
+    }
+    for path in contents {
+        // Text runs about four bytes per token
+        let excerpt = size(path).min(CONTENT_EXCERPT_BYTES).div_ceil(4);
+        est.input_tokens += PROMPT_OVERHEAD_TOKENS + text_labels + title_tokens(path) + excerpt;
+    }
+    for path in titles {
+        est.input_tokens += PROMPT_OVERHEAD_TOKENS + text_labels + title_tokens(path);
+    }
+    est.files = images.len() + contents.len() + titles.len();
+    est.output_tokens = est.files as u64 * OUTPUT_TOKENS;
+    est.cost = (est.input_tokens as f64 * pricing.input_per_million + est.output_tokens as f64 * pricing.output_per_million)
+        / 1_000_000.0;
+    est
+}
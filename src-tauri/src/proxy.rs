@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+// Network proxy shared by the updater and the LLM client. Stored as JSON under
+// the "proxy" settings key.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    pub enabled: bool,
+    // "http", "https" or "socks5"
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+}
+
+impl ProxySettings {
+    // Credentials travel as URL userinfo, which both reqwest and the updater understand.
+    pub fn url(&self) -> Option<String> {
+        if !self.enabled || self.host.trim().is_empty() {
+            return None;
+        }
+        let scheme = match self.scheme.as_str() {
+            "https" | "socks5" | "socks5h" => self.scheme.as_str(),
+            _ => "http",
+        };
+        let auth = if self.username.is_empty() {
+            String::new()
+        } else if self.password.is_empty() {
+            format!("{}@", encode_userinfo(&self.username))
+        } else {
+            format!("{}:{}@", encode_userinfo(&self.username), encode_userinfo(&self.password))
+        };
+        let port = if self.port == 0 { String::new() } else { format!(":{}", self.port) };
+        Some(format!("{}://{}{}{}", scheme, auth, self.host.trim(), port))
+    }
+}
+
+fn encode_userinfo(value: &str) -> String {
+    let mut out = String::new();
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+pub fn load(app_handle: &tauri::AppHandle) -> ProxySettings {
+    db::get_setting(app_handle, "proxy")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app_handle: &tauri::AppHandle, settings: &ProxySettings) -> Result<(), String> {
+    let raw = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    db::set_setting(app_handle, "proxy", &raw).map_err(|e| e.to_string())
+}
+
+pub fn proxy_url(app_handle: &tauri::AppHandle) -> Option<String> {
+    load(app_handle).url()
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db;
+
+// Guard rails for operations that delete data: deleting tags, purging a root or
+// everything, cleanup fixes and pruning vanished files. Stored as JSON under the
+// "safety_policy" settings key.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafetyPolicy {
+    // Ask before every destructive action (enforced by the UI)
+    pub confirm: bool,
+    // Purges must be confirmed by typing the root's folder name, or "all"
+    pub type_to_confirm: bool,
+    // Snapshot the database before anything is deleted
+    pub backup: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self { confirm: true, type_to_confirm: false, backup: false }
+    }
+}
+
+pub fn load(app_handle: &tauri::AppHandle) -> SafetyPolicy {
+    db::get_setting(app_handle, "safety_policy")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(app_handle: &tauri::AppHandle, policy: &SafetyPolicy) -> Result<(), String> {
+    let raw = serde_json::to_string(policy).map_err(|e| e.to_string())?;
+    db::set_setting(app_handle, "safety_policy", &raw).map_err(|e| e.to_string())
+}
+
+// What has to be typed to purge `root` (its last folder name) or, without a root,
+// every file
+pub fn purge_phrase(root: Option<&str>) -> String {
+    match root {
+        Some(root) => tagme_paths::file_name(root).unwrap_or(root).to_string(),
+        None => "all".to_string(),
+    }
+}
+
+// Rejects a purge whose typed confirmation doesn't match, when the policy asks for one
+pub fn check_typed(app_handle: &tauri::AppHandle, root: Option<&str>, typed: Option<&str>) -> Result<(), String> {
+    if !load(app_handle).type_to_confirm {
+        return Ok(());
+    }
+    let expected = purge_phrase(root);
+    if typed.map(str::trim) == Some(expected.as_str()) {
+        Ok(())
+    } else {
+        Err(format!("Type \"{}\" to confirm", expected))
+    }
+}
+
+// Called right before `op` deletes anything; takes a backup when enabled. A failed
+// backup aborts the operation.
+pub fn before_destructive(app_handle: &tauri::AppHandle, op: &str) -> Result<(), String> {
+    if !load(app_handle).backup {
+        return Ok(());
+    }
+    let path = db::backup_database(app_handle, op).map_err(|e| format!("Backup failed, nothing was deleted: {}", e))?;
+    eprintln!("💾 [SAFETY] Backed up database before {}: {}", op, path.display());
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use serde::Serialize;
+
+use crate::{db, hooks};
+
+// Bounds a runaway loop; scripts that hit it stop with an error
+const MAX_OPERATIONS: u64 = 50_000_000;
+// Lines of print/debug output kept for the console
+const MAX_OUTPUT: usize = 5000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptResult {
+    pub output: Vec<String>,
+    // Tags added or removed and files renamed (or that would be, in a dry run)
+    pub changes: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Default)]
+struct State {
+    output: Vec<String>,
+    changes: usize,
+    // All tags, loaded on first use and again after create_tag
+    tags: Option<Vec<db::TagInfo>>,
+}
+
+impl State {
+    fn log(&mut self, line: String) {
+        if self.output.len() < MAX_OUTPUT {
+            self.output.push(line);
+        }
+    }
+}
+
+type FnResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn err(msg: impl std::fmt::Display) -> Box<EvalAltResult> {
+    msg.to_string().into()
+}
+
+fn with_tags<T>(app_handle: &tauri::AppHandle, state: &RefCell<State>, f: impl FnOnce(&[db::TagInfo]) -> T) -> FnResult<T> {
+    let mut s = state.borrow_mut();
+    let tags = match &mut s.tags {
+        Some(tags) => tags,
+        slot => slot.insert(db::get_all_tags(app_handle).map_err(err)?),
+    };
+    Ok(f(tags))
+}
+
+// A tag by name, or by "Parent/Child" path when names are ambiguous
+fn find_tag(app_handle: &tauri::AppHandle, state: &RefCell<State>, wanted: &str) -> FnResult<db::TagInfo> {
+    with_tags(app_handle, state, |tags| pick_tag(tags, wanted))?
+}
+
+fn pick_tag(tags: &[db::TagInfo], wanted: &str) -> FnResult<db::TagInfo> {
+    let path_of = |tag: &db::TagInfo| {
+        let mut parts = vec![tag.name.clone()];
+        let mut parent = tag.parent_id;
+        while let Some(id) = parent {
+            let Some(p) = tags.iter().find(|t| t.id == id) else { break };
+            parts.push(p.name.clone());
+            parent = p.parent_id;
+            if parts.len() > tags.len() {
+                break;
+            }
+        }
+        parts.reverse();
+        parts.join("/")
+    };
+    if let Some(tag) = tags.iter().find(|t| path_of(t) == wanted) {
+        return Ok(tag.clone());
+    }
+    let named: Vec<&db::TagInfo> = tags.iter().filter(|t| t.name == wanted).collect();
+    match named.as_slice() {
+        [tag] => Ok((*tag).clone()),
+        [] => Err(err(format!("No tag named \"{}\"", wanted))),
+        _ => Err(err(format!("Several tags are named \"{}\"; use its path, e.g. \"Parent/{}\"", wanted, wanted))),
+    }
+}
+
+fn file_map(file: &db::FileInfo, tags: &[db::TagInfo]) -> Dynamic {
+    let path = Path::new(&file.path);
+    let mut map = Map::new();
+    map.insert("id".into(), (file.id as i64).into());
+    map.insert("path".into(), file.path.clone().into());
+    map.insert("name".into(), path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default().into());
+    map.insert(
+        "ext".into(),
+        path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default().into(),
+    );
+    map.insert("size".into(), (file.size_bytes as i64).into());
+    map.insert("modified".into(), file.last_modified.into());
+    map.insert("is_dir".into(), file.is_directory.into());
+    let names: Array = tags.iter().map(|t| t.name.clone().into()).collect();
+    map.insert("tags".into(), names.into());
+    map.into()
+}
+
+// Tracked files with their tags, optionally only those carrying `tag_id`
+fn list_files(app_handle: &tauri::AppHandle, tag_id: Option<u32>) -> FnResult<Array> {
+    let files = db::get_all_files(app_handle).map_err(err)?;
+    let tags_map = db::get_all_file_tags(app_handle).map_err(err)?;
+    let none = Vec::new();
+    Ok(files
+        .iter()
+        .filter_map(|f| {
+            let tags = tags_map.get(&f.id).unwrap_or(&none);
+            let keep = tag_id.is_none_or(|id| tags.iter().any(|t| t.id == id));
+            keep.then(|| file_map(f, tags))
+        })
+        .collect())
+}
+
+// Runs a Rhai script against the library. The engine has no file or process
+// access; scripts only see the functions registered here:
+//   files(), files_with_tag(tag), tags()
+//   add_tag(path, tag), remove_tag(path, tag), create_tag(name), create_tag(name, parent)
+//   rename(path, new_name), print(..)
+// rename() only touches tracked files under a root. In a dry run every change
+// is reported instead of made.
+pub fn run(app_handle: &tauri::AppHandle, source: &str, dry_run: bool) -> Result<ScriptResult, String> {
+    let state = Rc::new(RefCell::new(State::default()));
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let st = state.clone();
+    engine.on_print(move |s| st.borrow_mut().log(s.to_string()));
+    let st = state.clone();
+    engine.on_debug(move |s, _, pos| st.borrow_mut().log(format!("[{}] {}", pos, s)));
+
+    let app = app_handle.clone();
+    engine.register_fn("files", move || list_files(&app, None));
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    engine.register_fn("files_with_tag", move |tag: &str| {
+        let tag = find_tag(&app, &st, tag)?;
+        list_files(&app, Some(tag.id))
+    });
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    engine.register_fn("tags", move || -> FnResult<Array> {
+        with_tags(&app, &st, |tags| tags.iter().map(|t| t.name.clone().into()).collect())
+    });
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    engine.register_fn("add_tag", move |path: &str, tag: &str| -> FnResult<bool> {
+        let tag = find_tag(&app, &st, tag)?;
+        if dry_run {
+            let has_tag = match db::file_id_by_path(&app, path).map_err(err)? {
+                Some(file_id) => db::get_file_tags(&app, file_id).map_err(err)?.iter().any(|t| t.id == tag.id),
+                None => false,
+            };
+            if has_tag {
+                return Ok(false);
+            }
+            let mut s = st.borrow_mut();
+            s.log(format!("would add \"{}\" to {}", tag.name, path));
+            s.changes += 1;
+            return Ok(true);
+        }
+        let added = db::add_file_tag(&app, path.to_string(), tag.id).map_err(err)?;
+        if added {
+            hooks::run(&app, vec![(path.to_string(), tag.id)]);
+            st.borrow_mut().changes += 1;
+        }
+        Ok(added)
+    });
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    engine.register_fn("remove_tag", move |path: &str, tag: &str| -> FnResult<bool> {
+        let tag = find_tag(&app, &st, tag)?;
+        let Some(file_id) = db::file_id_by_path(&app, path).map_err(err)? else {
+            return Ok(false);
+        };
+        let has_tag = db::get_file_tags(&app, file_id).map_err(err)?.iter().any(|t| t.id == tag.id);
+        if !has_tag {
+            return Ok(false);
+        }
+        if dry_run {
+            st.borrow_mut().log(format!("would remove \"{}\" from {}", tag.name, path));
+        } else {
+            db::remove_file_tag(&app, file_id, tag.id).map_err(err)?;
+        }
+        st.borrow_mut().changes += 1;
+        Ok(true)
+    });
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    let create = move |name: &str, parent: Option<&str>| -> FnResult<()> {
+        let parent_id = parent.map(|p| find_tag(&app, &st, p)).transpose()?.map(|t| t.id);
+        if dry_run {
+            // Stands in for the new tag so the rest of the script can use it;
+            // counted down from u32::MAX, clear of real ids
+            let count = with_tags(&app, &st, |tags| tags.len())?;
+            let mut s = st.borrow_mut();
+            s.log(format!("would create tag \"{}\"", name));
+            if let Some(tags) = s.tags.as_mut() {
+                tags.push(db::TagInfo {
+                    id: u32::MAX - count as u32,
+                    name: name.to_string(),
+                    parent_id,
+                    color: None,
+                    position: 0,
+                    icon: None,
+                    archived: false,
+                    locked: false,
+                    shade: None,
+                });
+            }
+            return Ok(());
+        }
+        db::create_tag(&app, name.to_string(), parent_id, None, None).map_err(err)?;
+        st.borrow_mut().tags = None;
+        Ok(())
+    };
+    let create_top = create.clone();
+    engine.register_fn("create_tag", move |name: &str| create_top(name, None));
+    engine.register_fn("create_tag", move |name: &str, parent: &str| create(name, Some(parent)));
+
+    let (app, st) = (app_handle.clone(), state.clone());
+    engine.register_fn("rename", move |path: &str, new_name: &str| -> FnResult<String> {
+        let tracked = db::file_id_by_path(&app, path).map_err(err)?.is_some();
+        let roots = db::get_root_directories(&app).map_err(err)?;
+        if !tracked || !roots.iter().any(|root| tagme_paths::is_under(path, root)) {
+            return Err(err(format!("{} isn't a tracked file under one of the roots", path)));
+        }
+        if dry_run {
+            let mut s = st.borrow_mut();
+            s.log(format!("would rename {} to {}", path, new_name));
+            s.changes += 1;
+            let parent = Path::new(path).parent().unwrap_or(Path::new(""));
+            return Ok(parent.join(new_name).to_string_lossy().to_string());
+        }
+        let new_path = db::rename_file(&app, path, new_name).map_err(err)?;
+        st.borrow_mut().changes += 1;
+        Ok(new_path)
+    });
+
+    let outcome = engine.run(source);
+    let State { mut output, changes, .. } = state.take();
+    if let Err(e) = outcome {
+        output.push(format!("Error: {}", e));
+    }
+    Ok(ScriptResult { output, changes, dry_run })
+}
@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::db::{self, TagInfo};
+
+// The tag tree as an outline, in tree order with each tag's file count:
+// nested Markdown bullets, or OPML for outliners (the count goes in a `usage`
+// attribute so the outline text stays the tag name).
+
+pub const FORMATS: &[&str] = &["markdown", "opml"];
+
+pub fn export(app_handle: &tauri::AppHandle, format: &str) -> Result<String, String> {
+    if !FORMATS.contains(&format) {
+        return Err(format!("Unknown outline format: {}", format));
+    }
+    let tags = db::get_all_tags(app_handle).map_err(|e| e.to_string())?;
+    let counts = db::tag_usage_counts(app_handle).map_err(|e| e.to_string())?;
+    Ok(render(&tags, &counts, format))
+}
+
+// File extension for a saved outline
+pub fn extension(format: &str) -> &'static str {
+    if format == "opml" {
+        "opml"
+    } else {
+        "md"
+    }
+}
+
+fn render(tags: &[TagInfo], counts: &HashMap<u32, u32>, format: &str) -> String {
+    let ids: HashSet<u32> = tags.iter().map(|t| t.id).collect();
+    // Tags whose parent is gone are shown at the top level
+    let mut children: HashMap<Option<u32>, Vec<&TagInfo>> = HashMap::new();
+    for tag in tags {
+        let parent = tag.parent_id.filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(tag);
+    }
+    let mut out = String::new();
+    if format == "opml" {
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+        out.push_str("  <head>\n    <title>TagMe tags</title>\n  </head>\n  <body>\n");
+        opml(&children, counts, None, 2, &mut HashSet::new(), &mut out);
+        out.push_str("  </body>\n</opml>\n");
+    } else {
+        out.push_str("# Tags\n\n");
+        markdown(&children, counts, None, 0, &mut HashSet::new(), &mut out);
+    }
+    out
+}
+
+fn markdown(
+    children: &HashMap<Option<u32>, Vec<&TagInfo>>,
+    counts: &HashMap<u32, u32>,
+    parent: Option<u32>,
+    depth: usize,
+    seen: &mut HashSet<u32>,
+    out: &mut String,
+) {
+    for tag in children.get(&parent).into_iter().flatten() {
+        if !seen.insert(tag.id) {
+            continue;
+        }
+        let icon = tag.icon.as_deref().map(|i| format!("{} ", i)).unwrap_or_default();
+        let archived = if tag.archived { " *(archived)*" } else { "" };
+        out.push_str(&format!(
+            "{}- {}{} ({}){}\n",
+            "  ".repeat(depth),
+            icon,
+            tag.name,
+            counts.get(&tag.id).copied().unwrap_or(0),
+            archived
+        ));
+        markdown(children, counts, Some(tag.id), depth + 1, seen, out);
+    }
+}
+
+fn opml(
+    children: &HashMap<Option<u32>, Vec<&TagInfo>>,
+    counts: &HashMap<u32, u32>,
+    parent: Option<u32>,
+    depth: usize,
+    seen: &mut HashSet<u32>,
+    out: &mut String,
+) {
+    for tag in children.get(&parent).into_iter().flatten() {
+        if !seen.insert(tag.id) {
+            continue;
+        }
+        let indent = "  ".repeat(depth);
+        let mut attrs = format!(
+            "text=\"{}\" usage=\"{}\"",
+            escape(&tag.name),
+            counts.get(&tag.id).copied().unwrap_or(0)
+        );
+        if let Some(color) = &tag.color {
+            attrs.push_str(&format!(" color=\"{}\"", escape(color)));
+        }
+        if tag.archived {
+            attrs.push_str(" archived=\"true\"");
+        }
+        if children.get(&Some(tag.id)).is_some_and(|c| !c.is_empty()) {
+            out.push_str(&format!("{}<outline {}>\n", indent, attrs));
+            opml(children, counts, Some(tag.id), depth + 1, seen, out);
+            out.push_str(&format!("{}</outline>\n", indent));
+        } else {
+            out.push_str(&format!("{}<outline {}/>\n", indent, attrs));
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::db;
+
+// Seeds the tag tree from pasted text. Each line is either a tag path
+// ("Projects/Client A") nested under the closest less-indented line above it,
+// or a CSV row "name, parent path, color". Blank lines and lines starting with
+// '#' are skipped, as is a "name,parent,color" header. Existing tags are
+// reused, so importing the same list twice changes nothing.
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub created: usize,
+    pub existing: usize,
+    // "Line N: ..." for lines that were skipped
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Row {
+    line: usize,
+    path: Vec<String>,
+    color: Option<String>,
+}
+
+// Tag rows in `text`; the progress `import` reports counts these
+pub fn row_count(text: &str) -> usize {
+    parse(text).0.len()
+}
+
+// Imports the rows after the first `skip` (already imported by a run that was
+// interrupted), calling `on_row` with the number of rows done after each one
+pub fn import(
+    app_handle: &tauri::AppHandle,
+    text: &str,
+    skip: usize,
+    mut on_row: impl FnMut(usize),
+) -> Result<ImportReport, String> {
+    let (rows, errors) = parse(text);
+    let before: HashSet<u32> = db::get_all_tags(app_handle)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    let mut report = ImportReport { errors, ..Default::default() };
+    // Ids by path, so shared parents are looked up once
+    let mut ids: HashMap<Vec<String>, u32> = HashMap::new();
+    let mut seen: HashSet<u32> = HashSet::new();
+    for (i, row) in rows.into_iter().enumerate().skip(skip) {
+        let mut parent_id = None;
+        for depth in 1..=row.path.len() {
+            let key = row.path[..depth].to_vec();
+            let id = match ids.get(&key) {
+                Some(&id) => id,
+                None => {
+                    let color = (depth == row.path.len()).then(|| row.color.clone()).flatten();
+                    match db::create_tag(app_handle, key[depth - 1].clone(), parent_id, color, None) {
+                        Ok(id) => id,
+                        Err(e) => {
+                            report.errors.push(format!("Line {}: {}", row.line, e));
+                            break;
+                        }
+                    }
+                }
+            };
+            ids.insert(key, id);
+            if seen.insert(id) {
+                if before.contains(&id) {
+                    report.existing += 1;
+                } else {
+                    report.created += 1;
+                }
+            }
+            parent_id = Some(id);
+        }
+        on_row(i + 1);
+    }
+    eprintln!(
+        "🏷️  [IMPORT] {} tag(s) created, {} already there, {} line(s) skipped",
+        report.created,
+        report.existing,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+fn parse(text: &str) -> (Vec<Row>, Vec<String>) {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    // (indent, path) of the plain lines a deeper line can nest under
+    let mut stack: Vec<(usize, Vec<String>)> = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.contains(',') {
+            let fields = csv_fields(trimmed);
+            let name = fields.first().map(|f| f.trim()).unwrap_or_default();
+            if rows.is_empty() && errors.is_empty() && name.eq_ignore_ascii_case("name") {
+                continue;
+            }
+            if name.is_empty() {
+                errors.push(format!("Line {}: missing tag name", line));
+                continue;
+            }
+            let color = fields.get(2).map(|c| c.trim()).filter(|c| !c.is_empty());
+            if let Some(c) = color.filter(|c| !is_hex_color(c)) {
+                errors.push(format!("Line {}: \"{}\" is not a #rrggbb color", line, c));
+                continue;
+            }
+            let mut path = split_path(fields.get(1).map(String::as_str).unwrap_or_default());
+            path.push(name.to_string());
+            rows.push(Row { line, path, color: color.map(str::to_string) });
+            continue;
+        }
+        let indent: usize = raw
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let mut path = stack.last().map(|(_, p)| p.clone()).unwrap_or_default();
+        path.extend(split_path(trimmed));
+        stack.push((indent, path.clone()));
+        rows.push(Row { line, path, color: None });
+    }
+    (rows, errors)
+}
+
+// "A/B" or "A ▸ B" (the tree's own path format) into its segments
+fn split_path(path: &str) -> Vec<String> {
+    path.split(['/', '▸']).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+}
+
+// Comma-separated fields; double quotes allow commas, "" is a literal quote
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|h| (h.len() == 3 || h.len() == 6) && h.chars().all(|c| c.is_ascii_hexdigit()))
+}
@@ -0,0 +1,186 @@
+use notify::event::{CreateKind, DataChange, MetadataKind, ModifyKind, RemoveKind, RenameMode};
+use notify::{Event, EventKind};
+
+use crate::db::{FileInfo, StatRefresh};
+
+// What the watcher does with one filesystem event. Content changes to tracked
+// files patch their rows and emit `file-updated`; any other change, or one to
+// an untracked or vanished path, emits `file-system-change` so the UI rescans.
+
+#[derive(Debug)]
+pub enum Outcome {
+    Updated(FileInfo),
+    Rescan,
+}
+
+// Outcomes for `event`, in emit order; `refresh` re-stats a tracked path
+// (db::refresh_file_stat). Empty when nothing needs to be emitted.
+pub fn process(event: &Event, mut refresh: impl FnMut(&str) -> rusqlite::Result<StatRefresh>) -> Vec<Outcome> {
+    match event.kind {
+        EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Metadata(_) | ModifyKind::Any) if !event.paths.is_empty() => {
+            let mut outcomes = Vec::new();
+            let mut rescan = false;
+            for p in &event.paths {
+                let path = p.to_string_lossy().to_string();
+                match refresh(&path) {
+                    Ok(StatRefresh::Updated(info)) => outcomes.push(Outcome::Updated(info)),
+                    Ok(StatRefresh::Unchanged) => {}
+                    Ok(StatRefresh::Untracked) => rescan = true,
+                    Err(e) => {
+                        eprintln!("❌ [WATCHER] Failed to refresh {}: {}", path, e);
+                        rescan = true;
+                    }
+                }
+            }
+            if rescan {
+                outcomes.push(Outcome::Rescan);
+            }
+            outcomes
+        }
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => vec![Outcome::Rescan],
+        _ => Vec::new(),
+    }
+}
+
+pub const KINDS: &[&str] = &["create", "modify", "metadata", "remove", "rename"];
+
+// A synthetic event as a platform watcher would report it, for the debug
+// inject_fs_event command and the tests. "rename" takes the old and new path.
+pub fn synthetic(kind: &str, paths: Vec<String>) -> Result<Event, String> {
+    let kind = match kind {
+        "create" => EventKind::Create(CreateKind::File),
+        "modify" => EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+        "metadata" => EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)),
+        "remove" => EventKind::Remove(RemoveKind::File),
+        "rename" if paths.len() == 2 => EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+        "rename" => return Err("A rename takes the old and the new path".to_string()),
+        other => return Err(format!("Unknown event kind: {} (expected one of {})", other, KINDS.join(", "))),
+    };
+    Ok(paths.into_iter().fold(Event::new(kind), |event, path| event.add_path(path.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_schema, refresh_stat};
+    use rusqlite::{params, Connection};
+    use std::path::{Path, PathBuf};
+
+    // A temp root with one tracked file, `a.txt`, whose row matches the disk
+    struct Fixture {
+        root: PathBuf,
+        conn: Connection,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("tagme-watch-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(&root).unwrap();
+            let conn = Connection::open_in_memory().unwrap();
+            create_schema(&conn).unwrap();
+            let fixture = Fixture { root, conn };
+            std::fs::write(fixture.path("a.txt"), b"hello").unwrap();
+            let mtime = std::fs::metadata(fixture.path("a.txt"))
+                .unwrap()
+                .modified()
+                .unwrap()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            fixture
+                .conn
+                .execute(
+                    "INSERT INTO files (path, content_hash, size_bytes, last_modified, created_at, updated_at)
+                     VALUES (?1, 'hash', 5, ?2, 0, 0)",
+                    params![fixture.path("a.txt"), mtime],
+                )
+                .unwrap();
+            fixture
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.root.join(name).to_string_lossy().to_string()
+        }
+
+        fn run(&self, kind: &str, names: &[&str]) -> Vec<Outcome> {
+            let event = synthetic(kind, names.iter().map(|n| self.path(n)).collect()).unwrap();
+            process(&event, |path| refresh_stat(&self.conn, path))
+        }
+
+        fn stored_size(&self, name: &str) -> Option<i64> {
+            self.conn
+                .query_row("SELECT size_bytes FROM files WHERE path = ?1", params![self.path(name)], |row| row.get(0))
+                .ok()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn is_rescan(outcomes: &[Outcome]) -> bool {
+        matches!(outcomes, [Outcome::Rescan])
+    }
+
+    #[test]
+    fn modified_tracked_file_is_patched_in_place() {
+        let fx = Fixture::new("modify");
+        std::fs::write(fx.path("a.txt"), b"hello, world").unwrap();
+        let outcomes = fx.run("modify", &["a.txt"]);
+        assert!(matches!(&outcomes[..], [Outcome::Updated(info)] if info.size_bytes == 12 && Path::new(&info.path).ends_with("a.txt")));
+        assert_eq!(fx.stored_size("a.txt"), Some(12));
+    }
+
+    #[test]
+    fn unchanged_metadata_emits_nothing() {
+        let fx = Fixture::new("metadata");
+        assert!(fx.run("metadata", &["a.txt"]).is_empty());
+        assert_eq!(fx.stored_size("a.txt"), Some(5));
+    }
+
+    #[test]
+    fn untracked_changes_fall_back_to_a_rescan() {
+        let fx = Fixture::new("untracked");
+        std::fs::write(fx.path("b.txt"), b"new").unwrap();
+        assert!(is_rescan(&fx.run("create", &["b.txt"])));
+        assert!(is_rescan(&fx.run("modify", &["b.txt"])));
+        assert_eq!(fx.stored_size("b.txt"), None);
+    }
+
+    #[test]
+    fn deleted_file_is_left_to_the_rescan() {
+        let fx = Fixture::new("remove");
+        std::fs::remove_file(fx.path("a.txt")).unwrap();
+        // Some platforms report the deletion as a modify first
+        assert!(is_rescan(&fx.run("modify", &["a.txt"])));
+        assert!(is_rescan(&fx.run("remove", &["a.txt"])));
+        assert_eq!(fx.stored_size("a.txt"), Some(5));
+    }
+
+    #[test]
+    fn rename_triggers_a_rescan() {
+        let fx = Fixture::new("rename");
+        std::fs::rename(fx.path("a.txt"), fx.path("c.txt")).unwrap();
+        assert!(is_rescan(&fx.run("rename", &["a.txt", "c.txt"])));
+        assert!(synthetic("rename", vec![fx.path("c.txt")]).is_err());
+    }
+
+    #[test]
+    fn mixed_paths_patch_tracked_and_rescan_for_the_rest() {
+        let fx = Fixture::new("mixed");
+        std::fs::write(fx.path("a.txt"), b"hi").unwrap();
+        std::fs::write(fx.path("b.txt"), b"new").unwrap();
+        let outcomes = fx.run("modify", &["a.txt", "b.txt"]);
+        assert!(matches!(&outcomes[..], [Outcome::Updated(info), Outcome::Rescan] if info.size_bytes == 2));
+    }
+
+    #[test]
+    fn access_events_are_ignored() {
+        let event = Event::new(EventKind::Access(notify::event::AccessKind::Any)).add_path("/tmp/x".into());
+        assert!(process(&event, |_| panic!("access events are never re-statted")).is_empty());
+        assert!(synthetic("chmod", Vec::new()).is_err());
+    }
+}
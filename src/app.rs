@@ -7,20 +7,50 @@ pub mod api;
 pub mod components;
 pub mod drag_drop;
 pub mod files;
+mod indexer;
 pub mod resizing;
 pub mod types;
 mod update;
 pub mod utils;
 
-use crate::app::api::invoke;
+use crate::app::api::{invoke, try_invoke};
+use crate::app::components::add_tag_dialog::*;
+use crate::app::components::batch_estimate::*;
+use crate::app::components::cleanup_dialog::*;
+use crate::app::components::duplicates_dialog::*;
+use crate::app::components::verify_dialog::*;
+use crate::app::components::view_tabs::*;
+use crate::app::components::diagnostics_dialog::*;
+use crate::app::components::resume_batch::*;
 use crate::app::components::file_list::*;
+use crate::app::components::import_tags_dialog::*;
+use crate::app::components::nl_query::*;
+use crate::app::components::pinned_files::*;
+use crate::app::components::preview_dialog::*;
+use crate::app::components::recommend_review::*;
+use crate::app::components::relink_dialog::*;
+use crate::app::components::remote_root_dialog::*;
+use crate::app::components::root_defaults_dialog::*;
+use crate::app::components::script_console::*;
+use crate::app::components::settings_dialog::*;
+use crate::app::components::split_pane::*;
+use crate::app::components::tag_context_menu::*;
+use crate::app::components::tag_examples::*;
+use crate::app::components::tag_history::*;
 use crate::app::components::tag_tree::*;
+use crate::app::components::taxonomy_dialog::*;
+use crate::app::components::toast::*;
+use crate::app::components::clipboard_prompt::*;
+use crate::app::components::whats_new::*;
 use crate::app::drag_drop::*;
 use crate::app::files::*;
 use crate::app::resizing::*;
 use crate::app::types::*;
 use crate::app::utils::*;
-use leptos_recommender::RecommendItem;
+use leptos_recommender::{Diff, RecommendItem, RecommendSources, Route};
+
+// "Often tagged with" chips shown after a tag is added in the sidebar
+const CO_TAG_CHIPS: usize = 6;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -28,8 +58,19 @@ pub fn App() -> impl IntoView {
     let (scanned_files, set_scanned_files) = signal(Vec::<FileListItem>::new());
     let (all_files, set_all_files) = signal(Vec::<FileInfo>::new());
     let (all_tags, set_all_tags) = signal(Vec::<TagInfo>::new());
+    // Archived tags stay hidden in the tree and sidebar unless this is on
+    let (show_archived, set_show_archived) = signal(false);
     let (selected_tag_ids, set_selected_tag_ids) = signal(Vec::<u32>::new());
     let (use_and_logic, set_use_and_logic) = signal(true);
+    // Natural-language search: the typed query, the translated filter awaiting
+    // confirmation, and the applied one's limits beyond tags
+    let (nl_query, set_nl_query) = signal(String::new());
+    // Name/path search box and the paths of the tracked files it matched
+    let (search_text, set_search_text) = signal(String::new());
+    let (search_hits, set_search_hits) = signal(None::<std::collections::HashSet<String>>);
+    let (nl_pending, set_nl_pending) = signal(None::<NlFilter>);
+    let (nl_busy, set_nl_busy) = signal(false);
+    let (nl_refine, set_nl_refine) = signal(None::<NlFilter>);
     let (displayed_files, set_displayed_files) = signal(Vec::<FileInfo>::new());
     let (file_tags_map, set_file_tags_map) =
         signal(std::collections::HashMap::<u32, Vec<TagInfo>>::new());
@@ -40,6 +81,10 @@ pub fn App() -> impl IntoView {
     let (file_recommended_info_map, set_file_recommended_info_map) =
         signal(std::collections::HashMap::<String, Vec<RecommendItem>>::new());
     let (show_recommended, set_show_recommended) = signal(false);
+    let (recommend_sources, set_recommend_sources) = signal(RecommendSources::default());
+    // Suggestions scoring at least this are applied by "Apply all" and pre-checked in the review
+    let (accept_threshold, set_accept_threshold) = signal(0.8f32);
+    let (show_review, set_show_review) = signal(false);
     let (batch_running, set_batch_running) = signal(false);
     let (batch_progress, set_batch_progress) = signal(0usize);
     let (batch_total, set_batch_total) = signal(0usize);
@@ -75,12 +120,15 @@ pub fn App() -> impl IntoView {
             web_sys::console::log_1(&"[Overlay] off".into());
         }
     });
-    let recommend_all = move |_| {
-        if batch_running.get() {
+    let run_recommend_all = move || {
+        if batch_running.get_untracked() {
             return;
         }
-        let files = displayed_files.get();
+        let sources = recommend_sources.get_untracked();
+        // Files of a type switched off in the settings are skipped
+        let files: Vec<FileInfo> = displayed_files.get().into_iter().filter(|f| sources.includes(&f.path)).collect();
         let tags = all_tags.get();
+        let file_tags = file_tags_map.get_untracked();
         let set_map = set_file_recommended_tags_map;
         let set_info = set_file_recommended_info_map;
         let set_show = set_show_recommended;
@@ -97,41 +145,55 @@ pub fn App() -> impl IntoView {
             set_map.set(std::collections::HashMap::new());
             let mut info_map = std::collections::HashMap::new();
             let mut tag_map = std::collections::HashMap::new();
-            for (i, f) in files.iter().enumerate() {
+            let label_names: Vec<String> = active_tags(&tags).into_iter().map(|t| t.name).collect();
+            let tk = core::cmp::min(label_names.len(), 8);
+            let existing_of = |f: &FileInfo| -> Vec<String> {
+                file_tags.get(&f.id).map(|t| t.iter().map(|t| t.name.clone()).collect()).unwrap_or_default()
+            };
+            let tags_of = |list: &[RecommendItem]| -> Vec<TagInfo> {
+                list.iter().filter_map(|item| tags.iter().find(|x| x.name == item.name).cloned()).collect()
+            };
+            // Images go to the vision model in parallel; progress comes from "image-tag-status"
+            let (images, others): (Vec<&FileInfo>, Vec<&FileInfo>) =
+                files.iter().partition(|f| sources.route(&f.path) == Some(Route::Vision));
+            if !images.is_empty() {
+                let batch = images.iter().map(|f| (f.path.clone(), existing_of(f))).collect();
+                let results = leptos_recommender::generate_for_images(
+                    batch,
+                    label_names.clone(),
+                    tk,
+                    &sources,
+                    Some(String::from("https://api.siliconflow.cn/v1")),
+                    None,
+                )
+                .await;
+                for (path, list) in results {
+                    if let Some(f) = images.iter().find(|f| f.path == path).filter(|_| !list.is_empty()) {
+                        tag_map.insert(f.id, tags_of(&list));
+                        info_map.insert(path, list);
+                    }
+                }
+                set_prog.set(images.len());
+            }
+            for (i, f) in others.into_iter().enumerate() {
                 if cancel_sig.get_untracked() {
                     break;
                 }
-                let path = f.path.clone();
-                let label_names: Vec<String> = tags.iter().map(|t| t.name.clone()).collect();
-                let tk = core::cmp::min(label_names.len(), 8);
-                let list_ext = leptos_recommender::generate_for_file(
-                    path.clone(),
+                let list = leptos_recommender::generate_for_file(
+                    f.path.clone(),
                     label_names.clone(),
+                    existing_of(f),
                     tk,
-                    0.6,
+                    &sources,
                     Some(String::from("https://api.siliconflow.cn/v1")),
                     None,
                 )
                 .await;
-                if !list_ext.is_empty() {
-                    let list: Vec<RecommendItem> = list_ext
-                        .into_iter()
-                        .map(|ri| RecommendItem {
-                            name: ri.name,
-                            score: ri.score,
-                            source: ri.source,
-                        })
-                        .collect();
-                    info_map.insert(path.clone(), list.clone());
-                    let mut out: Vec<TagInfo> = Vec::new();
-                    for item in list {
-                        if let Some(t) = tags.iter().find(|x| x.name == item.name) {
-                            out.push(t.clone());
-                        }
-                    }
-                    tag_map.insert(f.id, out);
+                if !list.is_empty() {
+                    tag_map.insert(f.id, tags_of(&list));
+                    info_map.insert(f.path.clone(), list);
                 }
-                set_prog.set(i + 1);
+                set_prog.update(|p| *p += 1);
                 if i % 5 == 4 {
                     set_map.set(tag_map.clone());
                     set_info.set(info_map.clone());
@@ -143,17 +205,93 @@ pub fn App() -> impl IntoView {
             set_batch_cancel.set(false);
         });
     };
+    let (batch_estimate, set_batch_estimate) = signal(None::<BatchEstimate>);
+    // On a priced endpoint the estimated cost is shown first and the run starts on confirm
+    let recommend_all = move |_| {
+        if batch_running.get_untracked() {
+            return;
+        }
+        let sources = recommend_sources.get_untracked();
+        let mut args = EstimateRecommendationArgs {
+            labels: active_tags(&all_tags.get_untracked()).into_iter().map(|t| t.name).collect(),
+            ..Default::default()
+        };
+        for f in displayed_files.get_untracked() {
+            match sources.route(&f.path) {
+                Some(Route::Vision) => args.images.push(f.path),
+                Some(Route::Content) => args.contents.push(f.path),
+                Some(Route::Title) => args.titles.push(f.path),
+                // Matched locally, nothing to pay for
+                Some(Route::Rule) | None => {}
+            }
+        }
+        spawn_local(async move {
+            let estimate = try_invoke("estimate_recommendation", serde_wasm_bindgen::to_value(&args).unwrap())
+                .await
+                .ok()
+                .and_then(|v| serde_wasm_bindgen::from_value::<BatchEstimate>(v).ok());
+            match estimate {
+                Some(est) if est.priced && est.files > 0 => set_batch_estimate.set(Some(est)),
+                _ => run_recommend_all(),
+            }
+        });
+    };
+    let indexer_args = indexer::IndexerArgs {
+        recommend_sources,
+        scanned_files,
+        all_files,
+        all_tags,
+        file_tags_map,
+        batch_running,
+        recommended_info_map: file_recommended_info_map,
+        set_recommended_info_map: set_file_recommended_info_map,
+        set_recommended_tags_map: set_file_recommended_tags_map,
+        set_show_recommended,
+    };
+    indexer::init_background_indexer(indexer_args);
     let (scanning, set_scanning) = signal(false);
     let (show_add_tag_dialog, set_show_add_tag_dialog) = signal(false);
-    let (new_tag_name, set_new_tag_name) = signal(String::new());
     let (new_tag_parent, set_new_tag_parent) = signal(None::<u32>);
+    let (tag_menu, set_tag_menu) = signal(None::<TagMenuTarget>);
+    let (examples_tag, set_examples_tag) = signal(None::<u32>);
     let (new_tag_input_sidebar, set_new_tag_input_sidebar) = signal(String::new());
     let (show_purge_confirm, set_show_purge_confirm) = signal(false);
     let (show_delete_tag_confirm, set_show_delete_tag_confirm) = signal(false);
     let (delete_target_tag_id, set_delete_target_tag_id) = signal(None::<u32>);
     let (show_update_modal, set_show_update_modal) = signal(false);
+    let (show_settings, set_show_settings) = signal(false);
+    let (show_cleanup, set_show_cleanup) = signal(false);
+    let (show_verify, set_show_verify) = signal(false);
+    let (show_diagnostics, set_show_diagnostics) = signal(false);
+    let (show_taxonomy, set_show_taxonomy) = signal(false);
+    let (show_relink, set_show_relink) = signal(false);
+    let (show_duplicates, set_show_duplicates) = signal(false);
+    let (show_remote_root, set_show_remote_root) = signal(false);
+    let (show_import_tags, set_show_import_tags) = signal(false);
+    let (show_script, set_show_script) = signal(false);
+    let (safety_policy, set_safety_policy) = signal(SafetyPolicy::default());
+    // (loaded, total) while the rest of the library loads after startup
+    let (hydrating, set_hydrating) = signal(None::<(usize, usize)>);
+    // Manual refresh mode: no watchers and no automatic scans
+    let (manual_refresh, set_manual_refresh) = signal(false);
+    // Large library mode: the window of files currently loaded
+    let file_window = RwSignal::new(None::<FileWindow>);
+    let (large_library, set_large_library) = signal(false);
+    let (purge_typed, set_purge_typed) = signal(String::new());
+    let (tag_sort_mode, set_tag_sort_mode) = signal(String::from("manual"));
+    let (tag_click_mode, set_tag_click_mode) = signal(TagClickMode::Filter);
+    let (flat_view, set_flat_view) = signal(false);
+    let (inherit_folder_tags, set_inherit_folder_tags) = signal(false);
+    let (tag_badge_mode, set_tag_badge_mode) = signal(String::from("name"));
+    let (open_actions, set_open_actions) = signal(std::collections::BTreeMap::<String, String>::new());
+    let (preview_path, set_preview_path) = signal(None::<String>);
+    let (toast, set_toast) = signal(None::<String>);
+    let (scan_overflow, set_scan_overflow) = signal(Vec::<ScanOverflow>::new());
     let (update_current, set_update_current) = signal(String::new());
     let (update_latest, set_update_latest) = signal(String::new());
+    let (update_notes, set_update_notes) = signal(String::new());
+    let (update_channel, set_update_channel) = signal(String::from("stable"));
+    let (update_suppressed, set_update_suppressed) = signal(false);
     let (update_has, set_update_has) = signal(false);
     let (update_loading, set_update_loading) = signal(false);
     let (update_downloading, set_update_downloading) = signal(false);
@@ -170,6 +308,12 @@ pub fn App() -> impl IntoView {
         set_update_current,
         update_latest,
         set_update_latest,
+        update_notes,
+        set_update_notes,
+        update_channel,
+        set_update_channel,
+        update_suppressed,
+        set_update_suppressed,
         update_has,
         set_update_has,
         update_error,
@@ -187,7 +331,26 @@ pub fn App() -> impl IntoView {
     // Sorting state
     let (sort_column, set_sort_column) = signal(SortColumn::Name);
     let (sort_direction, set_sort_direction) = signal(SortDirection::Asc);
-    let (active_root_filter, set_active_root_filter) = signal(None::<String>);
+    let (opened_filter, set_opened_filter) = signal(OpenedFilter::All);
+    // Roots the file list is scoped to; empty means all roots
+    let (active_root_filter, set_active_root_filter) = signal(Vec::<String>::new());
+    let (default_root, set_default_root) = signal(None::<String>);
+    // Roots whose files only go to local models
+    let (local_only_roots, set_local_only_roots) = signal(Vec::<String>::new());
+    // Tags given to new files per root, and the root whose defaults are being edited
+    let (root_defaults, set_root_defaults) = signal(std::collections::HashMap::<String, Vec<u32>>::new());
+    let (editing_root_defaults, set_editing_root_defaults) = signal(None::<String>);
+    let saved_root_defaults = move |root: String, tag_ids: Vec<u32>| {
+        set_root_defaults.update(|m| {
+            if tag_ids.is_empty() {
+                m.remove(&root);
+            } else {
+                m.insert(root, tag_ids);
+            }
+        });
+    };
+    let (watch_status, set_watch_status) = signal(Vec::<WatchStatus>::new());
+    let (dragging_root, set_dragging_root) = signal(None::<String>);
 
     // Panel resizing state
     let (left_panel_width, set_left_panel_width) = signal(300.0);
@@ -204,8 +367,23 @@ pub fn App() -> impl IntoView {
         let mut display_files: Vec<DisplayFile> = Vec::new();
         let mut seen_paths = std::collections::HashSet::new();
 
+        // Folder inheritance: files show the tags of tagged folders above them and
+        // can match the tag filter through them, so every row is a candidate
+        let has_tag_filter = !selected_tag_ids.get().is_empty();
+        let folders = if inherit_folder_tags.get() {
+            tagged_folders(&all_files.get(), &tags_map)
+        } else {
+            Vec::new()
+        };
+        let extra_db: Vec<FileInfo> = if has_tag_filter && !folders.is_empty() {
+            let shown: std::collections::HashSet<String> = db.iter().map(|f| f.path.clone()).collect();
+            all_files.get().into_iter().filter(|f| !shown.contains(&f.path)).collect()
+        } else {
+            Vec::new()
+        };
+
         // Add DB files first
-        for file in db {
+        for file in db.into_iter().chain(extra_db) {
             let path_obj = std::path::Path::new(&file.path);
             let name = path_obj
                 .file_name()
@@ -228,12 +406,18 @@ pub fn App() -> impl IntoView {
                 db_id: Some(file.id),
                 tags: tags_map.get(&file.id).cloned().unwrap_or_default(),
                 is_directory: file.is_directory,
+                last_tagged: file.last_tagged,
+                open_count: file.open_count,
+                last_opened_at: file.last_opened_at,
+                inherited_tags: Vec::new(),
             });
         }
 
-        // Add scanned files that are not in DB (only when no tag filter is active)
-        let has_tag_filter = !selected_tag_ids.get().is_empty();
-        if !has_tag_filter {
+        // Add scanned files that are not in DB (only when no tag filter is active,
+        // unless they may match it through a folder); windows past the first
+        // leave them out
+        let past_first_window = file_window.with(|w| w.as_ref().is_some_and(|w| w.start.is_some()));
+        if (!has_tag_filter || !folders.is_empty()) && !past_first_window {
             for file in scanned {
                 if !seen_paths.contains(&file.path) {
                     let path_obj = std::path::Path::new(&file.path);
@@ -257,22 +441,59 @@ pub fn App() -> impl IntoView {
                         db_id: None,
                         tags: Vec::new(),
                         is_directory: file.is_directory,
+                        last_tagged: None,
+                        open_count: 0,
+                        last_opened_at: None,
+                        inherited_tags: Vec::new(),
                     });
                 }
             }
         }
 
+        if !folders.is_empty() {
+            for f in display_files.iter_mut() {
+                f.inherited_tags = inherited_tags(&f.path, &f.tags, &folders);
+            }
+            if has_tag_filter {
+                let wanted = selected_tag_ids.get();
+                let use_and = use_and_logic.get();
+                display_files.retain(|f| {
+                    let has = |id: &u32| f.tags.iter().chain(&f.inherited_tags).any(|t| t.id == *id);
+                    if use_and { wanted.iter().all(has) } else { wanted.iter().any(has) }
+                });
+            }
+        }
+
+        // Scope to the selected roots; a file counts for the deepest root containing it
+        let scope = active_root_filter.get();
+        if !scope.is_empty() {
+            let roots = root_directories.get();
+            display_files.retain(|f| in_root_scope(&f.path, &roots, &scope));
+        }
+        if let Some(refine) = nl_refine.get() {
+            display_files.retain(|f| refine.matches(&f.name, &f.extension, f.last_modified));
+        }
+        if opened_filter.get() != OpenedFilter::All {
+            display_files.retain(|f| f.open_count > 0);
+        }
+        if let Some(hits) = search_hits.get() {
+            // Untracked files aren't in the search index; they match on their path here
+            let words: Vec<String> = search_text.get().split_whitespace().map(|w| w.to_lowercase()).collect();
+            display_files.retain(|f| match f.db_id {
+                Some(_) => hits.contains(&f.path),
+                None => {
+                    let path = f.path.to_lowercase();
+                    words.iter().all(|w| path.contains(w.as_str()))
+                }
+            });
+        }
+
         // Sort
         let col = sort_column.get();
         let dir = sort_direction.get();
 
         display_files.sort_by(|a, b| {
-            let cmp = match col {
-                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                SortColumn::Size => a.size_bytes.cmp(&b.size_bytes),
-                SortColumn::Date => a.last_modified.cmp(&b.last_modified),
-                SortColumn::Type => a.extension.to_lowercase().cmp(&b.extension.to_lowercase()),
-            };
+            let cmp = col.compare(a, b);
 
             match dir {
                 SortDirection::Asc => cmp,
@@ -302,6 +523,8 @@ pub fn App() -> impl IntoView {
     let (drop_target_tag_id, set_drop_target_tag_id) = signal(None::<u32>);
     let (drop_position, set_drop_position) = signal(0.5f64); // 0.0=top, 1.0=bottom
     let (drag_just_ended, set_drag_just_ended) = signal(false);
+    let (selected_tag_nodes, set_selected_tag_nodes) = signal(Vec::<u32>::new());
+    let (active_drop_zone, set_active_drop_zone) = signal(None::<String>);
     let dnd = leptos_dragdrop::DndSignals {
         dragging_id_read: dragging_tag_id,
         dragging_id_write: set_dragging_tag_id,
@@ -311,11 +534,22 @@ pub fn App() -> impl IntoView {
         drop_position_write: set_drop_position,
         drag_just_ended_read: drag_just_ended,
         drag_just_ended_write: set_drag_just_ended,
+        selected_ids_read: selected_tag_nodes,
+        selected_ids_write: set_selected_tag_nodes,
+        active_zone_read: active_drop_zone,
+        active_zone_write: set_active_drop_zone,
     };
     let (reload_tags_trigger, set_reload_tags_trigger) = signal(0u32);
     let (last_click_time, set_last_click_time) = signal(0.0);
     let (is_maximized, set_is_maximized) = signal(false);
 
+    // Escape or a release outside the tree cancels the drag and drops the highlight
+    let on_drag_cancel = move || {
+        set_selected_tag_nodes.set(Vec::new());
+        set_drop_position.set(0.5);
+    };
+    leptos_dragdrop::bind_escape_cancel(dnd.clone(), on_drag_cancel);
+
     // Global mouse up handler for drag and drop
     setup_drag_drop(
         dragging_tag_id,
@@ -325,10 +559,251 @@ pub fn App() -> impl IntoView {
         drop_position,
         set_drop_position,
         set_drag_just_ended,
+        selected_tag_nodes,
         all_tags,
         set_reload_tags_trigger,
+        on_drag_cancel,
     );
 
+    // Recent and Frequent show opened files only, most recent or most opened first
+    let set_opened_view = move |filter: OpenedFilter| {
+        set_opened_filter.set(filter);
+        match filter {
+            OpenedFilter::All => return,
+            OpenedFilter::Recent => set_sort_column.set(SortColumn::LastOpened),
+            OpenedFilter::Frequent => set_sort_column.set(SortColumn::Opens),
+        }
+        set_sort_direction.set(SortDirection::Desc);
+    };
+
+    // Double-click on a file: the action configured for its extension, else open
+    let open_with_action = move |path: String, extension: String| {
+        let action = open_actions.with_untracked(|a| a.get(&extension.to_lowercase()).cloned());
+        let command = match action.as_deref() {
+            Some("preview") => {
+                set_preview_path.set(Some(path));
+                return;
+            }
+            Some("reveal") => "reveal_file",
+            Some("open-with") => "open_file_with",
+            _ => "open_file",
+        };
+        spawn_local(async move {
+            let args = OpenFileArgs { path: path.clone() };
+            match try_invoke(command, serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) if command == "open_file" => {
+                    // The backend counted the open; mirror it without reloading the library
+                    let now = (js_sys::Date::now() / 1000.0) as i64;
+                    set_all_files.update(|files| {
+                        if let Some(f) = files.iter_mut().find(|f| f.path == path) {
+                            f.open_count += 1;
+                            f.last_opened_at = Some(now);
+                        }
+                    });
+                    set_displayed_files.update(|files| {
+                        if let Some(f) = files.iter_mut().find(|f| f.path == path) {
+                            f.open_count += 1;
+                            f.last_opened_at = Some(now);
+                        }
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => set_toast.set(Some(format!("Couldn't open file: {}", e.as_string().unwrap_or_default()))),
+            }
+        });
+    };
+
+    // Applies one file's suggestions at or above the accept threshold and drops them from its chips
+    let apply_recommended = move |path: String| {
+        let threshold = accept_threshold.get_untracked();
+        let items = file_recommended_info_map.get_untracked().get(&path).cloned().unwrap_or_default();
+        let (accepted, rest): (Vec<RecommendItem>, Vec<RecommendItem>) =
+            items.into_iter().partition(|i| i.diff == Diff::New && i.score >= threshold);
+        let tags = all_tags.get_untracked();
+        let tag_ids: Vec<u32> = accepted
+            .iter()
+            .filter_map(|i| tags.iter().find(|t| t.name == i.name).map(|t| t.id))
+            .collect();
+        if tag_ids.is_empty() {
+            return;
+        }
+        set_file_recommended_info_map.update(|m| {
+            m.insert(path.clone(), rest);
+        });
+        spawn_local(async move {
+            let args = AddTagsToFilesArgs { file_paths: vec![path], tag_ids };
+            if let Err(e) = try_invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_toast.set(Some(format!("Couldn't apply tags: {}", e.as_string().unwrap_or_default())));
+            }
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+        });
+    };
+
+    // Paths handed over by the OS ("Tag with TagMe", a second launch) become the
+    // selection, with the tag input focused so tagging can start right away
+    // Explorer launches once per selected item, so paths arriving in quick
+    // succession add to the selection instead of replacing it
+    let (last_open_at, set_last_open_at) = signal(0.0f64);
+    let open_paths = move |paths: Vec<String>| {
+        if paths.is_empty() {
+            return;
+        }
+        let now = js_sys::Date::now();
+        let burst = now - last_open_at.get_untracked() < 1500.0;
+        set_last_open_at.set(now);
+        set_last_selected_file_path.set(paths.first().cloned());
+        if burst {
+            set_selected_file_paths.update(|sel| {
+                for p in paths {
+                    if !sel.contains(&p) {
+                        sel.push(p);
+                    }
+                }
+            });
+        } else {
+            set_selected_file_paths.set(paths);
+        }
+        set_timeout(
+            || {
+                if let Some(input) = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.get_element_by_id("sidebar-tag-input"))
+                    .and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok())
+                {
+                    let _ = input.focus();
+                }
+            },
+            std::time::Duration::from_millis(50),
+        );
+    };
+
+    // Shows a tag filter over a root selection. Switching roots restores the
+    // filter saved for them, so it's saved there first.
+    let show_filter = move |tag_ids: Vec<u32>, use_and: bool, scope: Vec<String>| {
+        spawn_local(async move {
+            let args = SetTagFilterArgs { root: tag_filter_key(&scope), filter: TagFilter { tag_ids: tag_ids.clone(), use_and } };
+            let _ = invoke("set_tag_filter", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            if scope != active_root_filter.get_untracked() {
+                set_active_root_filter.set(scope);
+            } else {
+                set_selected_tag_ids.set(tag_ids.clone());
+                set_use_and_logic.set(use_and);
+                filter_files(tag_ids, use_and, set_displayed_files, all_files.get_untracked(), scope);
+            }
+        });
+    };
+
+    // A tagme:// filter link replaces the tag filter, root selection and search
+    // limits. Tags are found by their path in the tree and roots by path; what
+    // this library doesn't have is left out and reported.
+    let open_link = move |url: String| {
+        let Some(link) = tagme_paths::link::FilterLink::parse(&url) else {
+            set_toast.set(Some("Not a TagMe filter link".to_string()));
+            return;
+        };
+        let tags = all_tags.get_untracked();
+        let mut missing = Vec::new();
+        let mut tag_ids = Vec::new();
+        for path in &link.tags {
+            match find_tag_by_path(path, &tags) {
+                Some(id) if !tag_ids.contains(&id) => tag_ids.push(id),
+                Some(_) => {}
+                None => missing.push(format!("tag \"{}\"", path.join(" ▸ "))),
+            }
+        }
+        let roots = root_directories.get_untracked();
+        let mut scope: Vec<String> = Vec::new();
+        for root in &link.roots {
+            match roots.iter().find(|r| tagme_paths::same_path(r, root)) {
+                Some(r) if !scope.contains(r) => scope.push(r.clone()),
+                Some(_) => {}
+                None => missing.push(format!("folder {}", root)),
+            }
+        }
+        if !missing.is_empty() {
+            set_toast.set(Some(format!("Not in this library: {}", missing.join(", "))));
+        }
+        let use_and = link.use_and;
+        let refine = NlFilter {
+            tag_ids: tag_ids.clone(),
+            use_and,
+            extensions: link.extensions,
+            name_contains: link.name_contains,
+            modified_after: link.modified_after,
+            modified_before: link.modified_before,
+        };
+        set_nl_refine.set(refine.has_refinements().then_some(refine));
+        show_filter(tag_ids, use_and, scope);
+    };
+
+    // View tabs. The active tab is the live filter, sort and selection signals;
+    // the others are snapshots, swapped in when switched to.
+    let (view_tabs, set_view_tabs) = signal(vec![ViewTab::new(0)]);
+    let (active_tab, set_active_tab) = signal(0u32);
+    let (next_tab_id, set_next_tab_id) = signal(1u32);
+    let current_view = move || ViewTab {
+        id: active_tab.get(),
+        tag_ids: selected_tag_ids.get(),
+        use_and: use_and_logic.get(),
+        roots: active_root_filter.get(),
+        refine: nl_refine.get(),
+        sort_column: sort_column.get(),
+        sort_direction: sort_direction.get(),
+        selected_file_paths: selected_file_paths.get(),
+    };
+    let tab_list = Signal::derive(move || {
+        let live = current_view();
+        view_tabs.get().into_iter().map(|t| if t.id == live.id { live.clone() } else { t }).collect::<Vec<_>>()
+    });
+    let stash_active_tab = move || {
+        let live = untrack(current_view);
+        set_view_tabs.update(|tabs| {
+            if let Some(tab) = tabs.iter_mut().find(|t| t.id == live.id) {
+                *tab = live;
+            }
+        });
+    };
+    let restore_tab = move |tab: ViewTab| {
+        set_active_tab.set(tab.id);
+        set_sort_column.set(tab.sort_column);
+        set_sort_direction.set(tab.sort_direction);
+        set_last_selected_file_path.set(tab.selected_file_paths.first().cloned());
+        set_selected_file_paths.set(tab.selected_file_paths);
+        set_nl_refine.set(tab.refine);
+        show_filter(tab.tag_ids, tab.use_and, tab.roots);
+    };
+    let select_tab = move |id: u32| {
+        if id == active_tab.get_untracked() {
+            return;
+        }
+        stash_active_tab();
+        if let Some(tab) = view_tabs.get_untracked().into_iter().find(|t| t.id == id) {
+            restore_tab(tab);
+        }
+    };
+    let new_tab = move || {
+        stash_active_tab();
+        let id = next_tab_id.get_untracked();
+        set_next_tab_id.set(id + 1);
+        let tab = ViewTab::new(id);
+        set_view_tabs.update(|tabs| tabs.push(tab.clone()));
+        restore_tab(tab);
+    };
+    // The last tab stays; closing the active one switches to its neighbour
+    let close_tab = move |id: u32| {
+        let Some(index) = view_tabs.get_untracked().iter().position(|t| t.id == id) else { return };
+        if view_tabs.get_untracked().len() < 2 {
+            return;
+        }
+        set_view_tabs.update(|tabs| {
+            tabs.remove(index);
+        });
+        if id == active_tab.get_untracked() {
+            let tabs = view_tabs.get_untracked();
+            restore_tab(tabs[index.min(tabs.len() - 1)].clone());
+        }
+    };
+
     // Global mouse handlers for panel resizing
     setup_resizing(
         is_resizing_left,
@@ -379,11 +854,71 @@ pub fn App() -> impl IntoView {
                 }
             }
 
+            if let Ok(roots) = serde_wasm_bindgen::from_value::<Vec<String>>(invoke("get_local_only_roots", JsValue::NULL).await) {
+                set_local_only_roots.set(roots);
+            }
+            if let Ok(defaults) = serde_wasm_bindgen::from_value(invoke("get_root_default_tags", JsValue::NULL).await) {
+                set_root_defaults.set(defaults);
+            }
+            // Restore the pinned root as the active filter
+            let pinned: Option<String> =
+                serde_wasm_bindgen::from_value(invoke("get_default_root", JsValue::NULL).await)
+                    .unwrap_or(None);
+            if let Some(p) = pinned {
+                if root_directories.get_untracked().contains(&p) {
+                    set_active_root_filter.set(vec![p.clone()]);
+                }
+                set_default_root.set(Some(p));
+            }
+
+            if let Ok(flat) = serde_wasm_bindgen::from_value::<bool>(invoke("get_flat_view", JsValue::NULL).await) {
+                set_flat_view.set(flat);
+            }
+            if let Ok(inherit) = serde_wasm_bindgen::from_value::<bool>(invoke("get_inherit_folder_tags", JsValue::NULL).await) {
+                set_inherit_folder_tags.set(inherit);
+            }
+            if let Ok(mode) = serde_wasm_bindgen::from_value::<String>(invoke("get_tag_badge_mode", JsValue::NULL).await) {
+                set_tag_badge_mode.set(mode);
+            }
+            if let Ok(policy) = serde_wasm_bindgen::from_value::<SafetyPolicy>(invoke("get_safety_policy", JsValue::NULL).await) {
+                set_safety_policy.set(policy);
+            }
+            if let Ok(manual) = serde_wasm_bindgen::from_value::<bool>(invoke("get_manual_refresh", JsValue::NULL).await) {
+                set_manual_refresh.set(manual);
+            }
+            if let Ok(large) = serde_wasm_bindgen::from_value::<bool>(invoke("get_large_library", JsValue::NULL).await) {
+                set_large_library.set(large);
+            }
+            if let Ok(actions) = serde_wasm_bindgen::from_value(invoke("get_open_actions", JsValue::NULL).await) {
+                set_open_actions.set(actions);
+            }
+            if let Ok(Some(sources)) = serde_wasm_bindgen::from_value(invoke("get_recommend_sources", JsValue::NULL).await) {
+                set_recommend_sources.set(sources);
+            }
+
             // Load tags
+            if let Ok(mode) = serde_wasm_bindgen::from_value::<String>(invoke("get_tag_sort_mode", JsValue::NULL).await) {
+                set_tag_sort_mode.set(mode);
+            }
             load_tags(set_all_tags).await;
 
-            // Load all files
-            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+            // First page of files now; the rest is loaded once it's on screen,
+            // or paged through in large library mode
+            register_window(WindowSignals { window: file_window, set_all_files, set_displayed_files, set_file_tags_map });
+            let total_files = if large_library.get_untracked() {
+                file_window.set(Some(FileWindow::default()));
+                load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                None
+            } else {
+                Some(load_first_page(set_all_files, set_displayed_files, set_file_tags_map).await)
+            };
+            indexer::load_stored_recommendations(&indexer_args).await;
+            if let Ok(paths) = serde_wasm_bindgen::from_value::<Vec<String>>(invoke("take_launch_paths", JsValue::NULL).await) {
+                open_paths(paths);
+            }
+            if let Ok(Some(link)) = serde_wasm_bindgen::from_value::<Option<String>>(invoke("take_launch_link", JsValue::NULL).await) {
+                open_link(link);
+            }
 
             // Load window state
             let state_value = invoke("load_window_state", JsValue::NULL).await;
@@ -394,23 +929,29 @@ pub fn App() -> impl IntoView {
                 spawn_local(async move {
                     #[derive(Serialize)]
                     #[serde(rename_all = "camelCase")]
-                    struct StartWatchingMultiArgs {
-                        root_paths: Vec<String>,
+                    struct ReconcileWatchersArgs {
+                        roots: Vec<String>,
                     }
-                    let args = StartWatchingMultiArgs {
-                        root_paths: list.clone(),
+                    let args = ReconcileWatchersArgs {
+                        roots: list.clone(),
                     };
                     let _ = invoke(
-                        "start_watching_multi",
+                        "reconcile_watchers",
                         serde_wasm_bindgen::to_value(&args).unwrap(),
                     )
                     .await;
+                    if let Ok(status) = serde_wasm_bindgen::from_value::<Vec<WatchStatus>>(
+                        invoke("get_watch_status", JsValue::NULL).await,
+                    ) {
+                        set_watch_status.set(status);
+                    }
                 });
             }
 
+            // Scanning waits until the first page has been painted
             let list2 = root_directories.get_untracked();
-            if !list2.is_empty() {
-                spawn_local(async move {
+            if !list2.is_empty() && !manual_refresh.get_untracked() {
+                request_animation_frame(move || set_timeout(move || spawn_local(async move {
                     #[derive(Serialize)]
                     #[serde(rename_all = "camelCase")]
                     struct ScanFilesMultiArgs {
@@ -426,10 +967,21 @@ pub fn App() -> impl IntoView {
                         )
                         .await,
                     ) {
-                        set_scanned_files.set(files);
+                        apply_scan(set_scanned_files, files);
+                        // Pruning may have dropped rows; a full reload also ends hydration
                         load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
                     }
-                });
+                }), std::time::Duration::ZERO));
+            }
+            if let Some(total_files) = total_files {
+                spawn_local(hydrate_files(
+                    total_files,
+                    all_files,
+                    set_all_files,
+                    set_displayed_files,
+                    set_file_tags_map,
+                    set_hydrating,
+                ));
             }
 
             // Setup file system change listener
@@ -443,6 +995,9 @@ pub fn App() -> impl IntoView {
                         window.dispatchEvent(new CustomEvent('tauri-fs-change'));
                         console.log('✅ [FRONTEND] Custom event dispatched');
                     });
+                    window.__TAURI__.event.listen('file-updated', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-file-updated', { detail: evt ? evt.payload : null }));
+                    });
                     window.__TAURI__.event.listen('update-download-progress', (evt) => {
                         const payload = evt && evt.payload ? evt.payload : {};
                         window.dispatchEvent(new CustomEvent('tauri-update-progress', { detail: payload }));
@@ -450,6 +1005,53 @@ pub fn App() -> impl IntoView {
                     window.__TAURI__.event.listen('update-download-complete', () => {
                         window.dispatchEvent(new CustomEvent('tauri-update-complete'));
                     });
+                    window.__TAURI__.event.listen('watcher-error', (evt) => {
+                        const payload = evt && evt.payload ? evt.payload : {};
+                        console.warn('⚠️ [WATCHER] ' + payload.root + ': ' + payload.reason);
+                    });
+                    window.__TAURI__.event.listen('scan-overflow', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-scan-overflow', { detail: evt ? evt.payload : [] }));
+                    });
+                    window.__TAURI__.event.listen('open-paths', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-open-paths', { detail: evt ? evt.payload : [] }));
+                    });
+                    window.__TAURI__.event.listen('open-link', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-open-link', { detail: evt ? evt.payload : '' }));
+                    });
+                    window.__TAURI__.event.listen('tag-hook-failed', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-tag-hook-failed', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('image-tag-status', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-image-tag-status', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('clipboard-paths', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-clipboard-paths', { detail: evt ? evt.payload : [] }));
+                    });
+                    window.__TAURI__.event.listen('verify-progress', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-verify-progress', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('verify-finished', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-verify-finished', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('verify-failed', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-verify-failed', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('duplicates-progress', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-duplicates-progress', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('duplicates-finished', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-duplicates-finished', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('duplicates-failed', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-duplicates-failed', { detail: evt ? evt.payload : null }));
+                    });
+                    window.__TAURI__.event.listen('root-online', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-root-online', { detail: evt ? evt.payload : null }));
+                        window.dispatchEvent(new CustomEvent('tauri-fs-change'));
+                    });
+                    window.__TAURI__.event.listen('watch-status-changed', (evt) => {
+                        window.dispatchEvent(new CustomEvent('tauri-watch-status', { detail: evt ? evt.payload : [] }));
+                    });
                     console.log('✅ [FRONTEND] Tauri event listener registered');
                 } else {
                     console.error('❌ [FRONTEND] Tauri event API not available');
@@ -472,6 +1074,9 @@ pub fn App() -> impl IntoView {
             .unwrap_or(false);
         if !flag {
             let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+                if manual_refresh.get_untracked() {
+                    return;
+                }
                 web_sys::console::log_1(
                     &"📥 [FRONTEND] Custom event received, refreshing file list...".into(),
                 );
@@ -494,7 +1099,7 @@ pub fn App() -> impl IntoView {
                             )
                             .await,
                         ) {
-                            set_scanned_files.set(files);
+                            apply_scan(set_scanned_files, files);
                             load_all_files(set_all_files, set_displayed_files, set_file_tags_map)
                                 .await;
                         }
@@ -516,98 +1121,374 @@ pub fn App() -> impl IntoView {
         }
     });
 
+    // Patch a single refreshed row in place instead of reloading the whole list
     Effect::new(move |_| {
         let window = web_sys::window().expect("no window");
-        let flag = js_sys::Reflect::get(
-            &window,
-            &JsValue::from_str("__TAGME_UPDATE_PROGRESS_LISTENER_SET"),
-        )
-        .ok()
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_FILE_UPDATED_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
         if !flag {
             let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
-                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
-                    let detail = ce.detail();
-                    let rec = js_sys::Reflect::get(&detail, &JsValue::from_str("received"))
-                        .ok()
-                        .and_then(|v| v.as_f64())
-                        .map(|x| x as usize)
-                        .unwrap_or(0usize);
-                    let tot = js_sys::Reflect::get(&detail, &JsValue::from_str("total"))
-                        .ok()
-                        .and_then(|v| {
-                            if v.is_null() || v.is_undefined() {
-                                None
-                            } else {
-                                v.as_f64().map(|x| x as u64)
-                            }
-                        });
-                    set_update_received.set(rec);
-                    set_update_total.set(tot);
-                    set_update_downloading.set(true);
-                }
+                let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() else { return };
+                let Ok(info) = serde_wasm_bindgen::from_value::<FileInfo>(ce.detail()) else { return };
+                let patch = |files: &mut Vec<FileInfo>| {
+                    if let Some(f) = files.iter_mut().find(|f| f.id == info.id) {
+                        // The event carries no tag or open data, so keep what's known
+                        *f = FileInfo {
+                            last_tagged: f.last_tagged,
+                            open_count: f.open_count,
+                            last_opened_at: f.last_opened_at,
+                            ..info.clone()
+                        };
+                    }
+                };
+                set_all_files.update(patch);
+                set_displayed_files.update(patch);
+                set_scanned_files.update(|files| {
+                    if let Some(f) = files.iter_mut().find(|f| f.path == info.path) {
+                        f.size_bytes = info.size_bytes;
+                        f.last_modified = info.last_modified;
+                    }
+                });
             }) as Box<dyn FnMut(_)>);
             let _ = window.add_event_listener_with_callback(
-                "tauri-update-progress",
+                "tauri-file-updated",
                 closure.as_ref().unchecked_ref(),
             );
             let _ = js_sys::Reflect::set(
                 &window,
-                &JsValue::from_str("__TAGME_UPDATE_PROGRESS_LISTENER_SET"),
+                &JsValue::from_str("__TAGME_FILE_UPDATED_LISTENER_SET"),
                 &JsValue::from_bool(true),
             );
             closure.forget();
         }
     });
 
+    // A root's drive was reconnected; the bridge also asks for a rescan
     Effect::new(move |_| {
         let window = web_sys::window().expect("no window");
-        let flag = js_sys::Reflect::get(
-            &window,
-            &JsValue::from_str("__TAGME_UPDATE_COMPLETE_LISTENER_SET"),
-        )
-        .ok()
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_ROOT_ONLINE_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
         if !flag {
-            let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
-                set_update_downloading.set(false);
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(root) = ev.dyn_ref::<web_sys::CustomEvent>().and_then(|ce| ce.detail().as_string()) {
+                    set_toast.set(Some(format!("{} is back online", root)));
+                }
             }) as Box<dyn FnMut(_)>);
             let _ = window.add_event_listener_with_callback(
-                "tauri-update-complete",
+                "tauri-root-online",
                 closure.as_ref().unchecked_ref(),
             );
             let _ = js_sys::Reflect::set(
                 &window,
-                &JsValue::from_str("__TAGME_UPDATE_COMPLETE_LISTENER_SET"),
+                &JsValue::from_str("__TAGME_ROOT_ONLINE_LISTENER_SET"),
                 &JsValue::from_bool(true),
             );
             closure.forget();
         }
     });
 
-    Effect::new(move || {
-        spawn_local(async move {
-            // 启动时进行一次后台检查，加入 8 秒超时控制，避免网络不佳时卡住体验
-            let window = web_sys::window().expect("no window");
-            // done 用于在超时回调中判断异步检查是否已完成
-            let done = std::rc::Rc::new(std::cell::Cell::new(false));
-            let done2 = done.clone();
-            // 8 秒超时：若检查仍未完成，则设置错误与重试信息（10 分钟后重试）
-            let timeout_cb = Closure::wrap(Box::new(move || {
-                if !done2.get() {
-                    set_update_error.set(Some(format!("检查更新超时，将在{}分钟后重试", 10)));
-                    set_update_retry_in.set(Some(600));
+    // A tag's automation hook exited with an error
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_TAG_HOOK_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(failure) = serde_wasm_bindgen::from_value::<HookFailure>(ce.detail()) {
+                        let name = tagme_paths::file_name(&failure.path).unwrap_or(&failure.path).to_string();
+                        set_toast.set(Some(format!("Hook failed for {}: {}", name, failure.reason)));
+                    }
                 }
-            }) as Box<dyn FnMut()>);
-            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
-                timeout_cb.as_ref().unchecked_ref(),
-                8000,
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-tag-hook-failed",
+                closure.as_ref().unchecked_ref(),
             );
-            timeout_cb.forget();
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_TAG_HOOK_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
 
-            // 实际检查更新：成功则更新版本信息；失败则提示并设置重试
+    // Per-image progress of a parallel vision batch
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_IMAGE_TAG_STATUS_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(status) = serde_wasm_bindgen::from_value::<ImageTagStatus>(ce.detail()) {
+                        if status.status != "running" && batch_running.get_untracked() {
+                            set_batch_progress.update(|p| *p += 1);
+                        }
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-image-tag-status",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_IMAGE_TAG_STATUS_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+    // Cancelling a batch also stops the vision requests not started yet
+    Effect::new(move |_| {
+        if batch_cancel.get() {
+            spawn_local(async move {
+                let _ = invoke("cancel_image_tagging", JsValue::NULL).await;
+            });
+        }
+    });
+
+    // Paths copied to the clipboard while the clipboard watcher is on
+    let (clipboard_paths, set_clipboard_paths) = signal(Vec::<String>::new());
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_CLIPBOARD_PATHS_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(paths) = serde_wasm_bindgen::from_value::<Vec<String>>(ce.detail()) {
+                        set_clipboard_paths.set(paths);
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-clipboard-paths",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_CLIPBOARD_PATHS_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    // Paths forwarded from a second launch
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_OPEN_PATHS_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(paths) = serde_wasm_bindgen::from_value::<Vec<String>>(ce.detail()) {
+                        open_paths(paths);
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-open-paths",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_OPEN_PATHS_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    // Filter links clicked while the app is running
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_OPEN_LINK_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(url) = ev.dyn_ref::<web_sys::CustomEvent>().and_then(|ce| ce.detail().as_string()) {
+                    open_link(url);
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-open-link",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_OPEN_LINK_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    // Roots whose last scan was cut short, for the "…and N more" rows
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_SCAN_OVERFLOW_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(overflow) = serde_wasm_bindgen::from_value::<Vec<ScanOverflow>>(ce.detail()) {
+                        set_scan_overflow.set(overflow);
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-scan-overflow",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_SCAN_OVERFLOW_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    // Keep root chip watch icons in sync with the backend watchers
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_WATCH_STATUS_LISTENER_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    if let Ok(status) = serde_wasm_bindgen::from_value::<Vec<WatchStatus>>(ce.detail()) {
+                        set_watch_status.set(status);
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-watch-status",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_WATCH_STATUS_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(
+            &window,
+            &JsValue::from_str("__TAGME_UPDATE_PROGRESS_LISTENER_SET"),
+        )
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+                if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+                    let detail = ce.detail();
+                    let rec = js_sys::Reflect::get(&detail, &JsValue::from_str("received"))
+                        .ok()
+                        .and_then(|v| v.as_f64())
+                        .map(|x| x as usize)
+                        .unwrap_or(0usize);
+                    let tot = js_sys::Reflect::get(&detail, &JsValue::from_str("total"))
+                        .ok()
+                        .and_then(|v| {
+                            if v.is_null() || v.is_undefined() {
+                                None
+                            } else {
+                                v.as_f64().map(|x| x as u64)
+                            }
+                        });
+                    set_update_received.set(rec);
+                    set_update_total.set(tot);
+                    set_update_downloading.set(true);
+                }
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-update-progress",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_UPDATE_PROGRESS_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(
+            &window,
+            &JsValue::from_str("__TAGME_UPDATE_COMPLETE_LISTENER_SET"),
+        )
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+        if !flag {
+            let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+                set_update_downloading.set(false);
+            }) as Box<dyn FnMut(_)>);
+            let _ = window.add_event_listener_with_callback(
+                "tauri-update-complete",
+                closure.as_ref().unchecked_ref(),
+            );
+            let _ = js_sys::Reflect::set(
+                &window,
+                &JsValue::from_str("__TAGME_UPDATE_COMPLETE_LISTENER_SET"),
+                &JsValue::from_bool(true),
+            );
+            closure.forget();
+        }
+    });
+
+    Effect::new(move || {
+        spawn_local(async move {
+            // 启动时进行一次后台检查，加入 8 秒超时控制，避免网络不佳时卡住体验
+            let window = web_sys::window().expect("no window");
+            // done 用于在超时回调中判断异步检查是否已完成
+            let done = std::rc::Rc::new(std::cell::Cell::new(false));
+            let done2 = done.clone();
+            // 8 秒超时：若检查仍未完成，则设置错误与重试信息（10 分钟后重试）
+            let timeout_cb = Closure::wrap(Box::new(move || {
+                if !done2.get() {
+                    set_update_error.set(Some(format!("检查更新超时，将在{}分钟后重试", 10)));
+                    set_update_retry_in.set(Some(600));
+                }
+            }) as Box<dyn FnMut()>);
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                timeout_cb.as_ref().unchecked_ref(),
+                8000,
+            );
+            timeout_cb.forget();
+
+            // 实际检查更新：成功则更新版本信息；失败则提示并设置重试
             let val = invoke("updater_check", JsValue::NULL).await;
             match serde_wasm_bindgen::from_value::<UpdateInfo>(val.clone()) {
                 Ok(info) => {
@@ -618,6 +1499,8 @@ pub fn App() -> impl IntoView {
                     set_update_current.set(info.current);
                     set_update_latest.set(info.latest.unwrap_or_default());
                     set_update_has.set(info.has_update);
+                    set_update_notes.set(info.notes.unwrap_or_default());
+                    set_update_suppressed.set(info.suppressed);
                 }
                 Err(_) => {
                     // 检查失败，提示失败并设置 10 分钟后重试
@@ -642,6 +1525,8 @@ pub fn App() -> impl IntoView {
             let set_c = set_update_current;
             let set_l = set_update_latest;
             let set_h = set_update_has;
+            let set_n = set_update_notes;
+            let set_s = set_update_suppressed;
             // 后台定时检查也维护错误与重试提示（无加载遮挡）
             let set_err = set_update_error;
             let set_retry = set_update_retry_in;
@@ -649,6 +1534,8 @@ pub fn App() -> impl IntoView {
                 let set_c2 = set_c;
                 let set_l2 = set_l;
                 let set_h2 = set_h;
+                let set_n2 = set_n;
+                let set_s2 = set_s;
                 let set_err2 = set_err;
                 let set_retry2 = set_retry;
                 spawn_local(async move {
@@ -679,6 +1566,8 @@ pub fn App() -> impl IntoView {
                             set_c2.set(info.current);
                             set_l2.set(info.latest.unwrap_or_default());
                             set_h2.set(info.has_update);
+                            set_n2.set(info.notes.unwrap_or_default());
+                            set_s2.set(info.suppressed);
                         }
                         Err(_) => {
                             // 检查失败，设置提示与 10 分钟后重试
@@ -713,6 +1602,7 @@ pub fn App() -> impl IntoView {
             set_file_tags_map,
             active_root_filter,
             set_active_root_filter,
+            set_toast,
         );
     };
 
@@ -727,6 +1617,21 @@ pub fn App() -> impl IntoView {
         );
     };
 
+    let load_more = move |root: String| {
+        spawn_local(async move {
+            let args = LoadMoreScanArgs { root };
+            let _ = invoke("load_more_scan", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            handle_scan_directory(
+                root_directories,
+                set_scanning,
+                set_scanned_files,
+                set_all_files,
+                set_displayed_files,
+                set_file_tags_map,
+            );
+        });
+    };
+
     let close = move |_| {
         spawn_local(async move {
             let _ = invoke("close_window", JsValue::NULL).await;
@@ -800,9 +1705,229 @@ pub fn App() -> impl IntoView {
             )
             .into(),
         );
-        filter_files(current, logic, set_displayed_files, all_files.get());
+        filter_files(current, logic, set_displayed_files, all_files.get(), active_root_filter.get_untracked());
+    };
+
+    // Tree checkboxes show the filter in Filter mode, and the tags shared by
+    // every selected file in Assign mode
+    let (tree_checked_ids, set_tree_checked_ids) = signal(Vec::<u32>::new());
+    Effect::new(move |_| {
+        let ids = match tag_click_mode.get() {
+            TagClickMode::Filter => selected_tag_ids.get(),
+            TagClickMode::Assign => {
+                let paths = selected_file_paths.get();
+                let files = all_files.get();
+                let tags_map = file_tags_map.get();
+                let mut common: Option<Vec<u32>> = None;
+                for p in &paths {
+                    let ids: Vec<u32> = files
+                        .iter()
+                        .find(|f| &f.path == p)
+                        .and_then(|f| tags_map.get(&f.id))
+                        .map(|tags| tags.iter().map(|t| t.id).collect())
+                        .unwrap_or_default();
+                    common = Some(match common {
+                        None => ids,
+                        Some(c) => c.into_iter().filter(|id| ids.contains(id)).collect(),
+                    });
+                }
+                common.unwrap_or_default()
+            }
+        };
+        set_tree_checked_ids.set(ids);
+    });
+
+    let assign_tag_to_selection = move |tag_id: u32| {
+        // Re-sync the checkbox the browser just toggled; real state follows the reload
+        set_tree_checked_ids.update(|_| {});
+        let paths = selected_file_paths.get_untracked();
+        if paths.is_empty() {
+            return;
+        }
+        let remove = tree_checked_ids.get_untracked().contains(&tag_id);
+        let files = all_files.get_untracked();
+        spawn_local(async move {
+            if remove {
+                for p in &paths {
+                    if let Some(f) = files.iter().find(|f| &f.path == p) {
+                        let args = RemoveFileTagArgs { file_id: f.id, tag_id };
+                        let _ = invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                    }
+                }
+            } else {
+                let args = AddTagsToFilesArgs { file_paths: paths, tag_ids: vec![tag_id] };
+                let _ = invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            }
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+        });
     };
 
+    // Sidebar checkboxes patch file_tags_map right away and reconcile with the
+    // backend afterwards; a failed write restores the previous map.
+    // Tags often applied together with the one just added to the selection,
+    // as (tag id, shared files); cleared when the selection changes
+    let (co_tags, set_co_tags) = signal(Vec::<(u32, u32)>::new());
+    let (co_tags_for, set_co_tags_for) = signal(None::<u32>);
+    Effect::new(move |_| {
+        selected_file_paths.track();
+        set_co_tags_for.set(None);
+        set_co_tags.set(Vec::new());
+    });
+    let toggle_tag_on_selection = move |tag_id: u32, add: bool| {
+        let paths = selected_file_paths.get_untracked();
+        if paths.is_empty() {
+            return;
+        }
+        let files = all_files.get_untracked();
+        let file_ids: Vec<u32> = paths
+            .iter()
+            .filter_map(|p| files.iter().find(|f| &f.path == p).map(|f| f.id))
+            .collect();
+        let previous = file_tags_map.get_untracked();
+        if let Some(tag) = all_tags.get_untracked().into_iter().find(|t| t.id == tag_id) {
+            set_file_tags_map.update(|map| {
+                for id in &file_ids {
+                    let tags = map.entry(*id).or_default();
+                    tags.retain(|t| t.id != tag_id);
+                    if add {
+                        tags.push(tag.clone());
+                        tags.sort_by(|a, b| a.name.cmp(&b.name));
+                    }
+                }
+            });
+        }
+        spawn_local(async move {
+            let result = if add {
+                // Paths not yet in the DB get their row created here
+                let args = AddTagsToFilesArgs { file_paths: paths, tag_ids: vec![tag_id] };
+                try_invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await.map(|_| ())
+            } else {
+                let mut result = Ok(());
+                for file_id in file_ids {
+                    let args = RemoveFileTagArgs { file_id, tag_id };
+                    if let Err(e) = try_invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                        result = Err(e);
+                        break;
+                    }
+                }
+                result
+            };
+            if let Err(e) = result {
+                set_file_tags_map.set(previous);
+                let reason = e.as_string().unwrap_or_else(|| "unknown error".to_string());
+                set_toast.set(Some(format!("Couldn't update tag: {}", reason)));
+            } else if add {
+                let args = CoOccurringTagsArgs { tag_id, limit: CO_TAG_CHIPS };
+                if let Ok(list) = serde_wasm_bindgen::from_value(invoke("co_occurring_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await) {
+                    set_co_tags.set(list);
+                    set_co_tags_for.set(Some(tag_id));
+                }
+            }
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+        });
+    };
+
+    // Deletes a tag and its subtree, dropping them from the active filter first
+    let delete_tag_now = move |id: u32| {
+        let mut current = selected_tag_ids.get_untracked();
+        let all = all_tags.get_untracked();
+        let mut stack = vec![id];
+        let mut subtree_ids: Vec<u32> = Vec::new();
+        while let Some(x) = stack.pop() {
+            subtree_ids.push(x);
+            for t in all.iter().filter(|t| t.parent_id == Some(x)) { stack.push(t.id); }
+        }
+        let remove_set: std::collections::HashSet<u32> = subtree_ids.iter().copied().collect();
+        current.retain(|tid| !remove_set.contains(tid));
+        set_selected_tag_ids.set(current.clone());
+        let logic = use_and_logic.get_untracked();
+        filter_files(current.clone(), logic, set_displayed_files, all_files.get_untracked(), active_root_filter.get_untracked());
+        spawn_local(async move {
+            let args = DeleteTagArgs { id };
+            match try_invoke("delete_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => web_sys::console::log_1(&format!("[UI] delete_tag ok: {}", id).into()),
+                Err(e) => set_toast.set(Some(format!("Couldn't delete tag: {}", e.as_string().unwrap_or_default()))),
+            }
+            set_reload_tags_trigger.update(|v| *v += 1);
+        });
+    };
+
+    // With confirmations turned off, a delete request goes straight through
+    Effect::new(move |_| {
+        if show_delete_tag_confirm.get() && !safety_policy.get_untracked().confirm {
+            if let Some(id) = delete_target_tag_id.get_untracked() {
+                delete_tag_now(id);
+            }
+            set_show_delete_tag_confirm.set(false);
+        }
+    });
+
+    let purge_all = move |confirmation: Option<String>| {
+        spawn_local(async move {
+            let dbp = invoke("get_db_path", JsValue::NULL).await;
+            if let Ok(p) = serde_wasm_bindgen::from_value::<String>(dbp.clone()) {
+                web_sys::console::log_1(&format!("[UI] DB path={}", p).into());
+            }
+            let args = PurgeAllFilesArgs { confirmation };
+            match try_invoke("purge_all_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(res) => {
+                    if let Ok(cnt) = serde_wasm_bindgen::from_value::<u32>(res) {
+                        web_sys::console::log_1(&format!("[UI] purge_all_files ok, count={}", cnt).into());
+                    }
+                }
+                Err(e) => set_toast.set(Some(format!("Purge skipped: {}", e.as_string().unwrap_or_default()))),
+            }
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+        });
+    };
+
+    let on_tree_tag_click = move |tag_id: u32| match tag_click_mode.get_untracked() {
+        TagClickMode::Filter => toggle_tag_selection(tag_id),
+        TagClickMode::Assign => assign_tag_to_selection(tag_id),
+    };
+
+    // Each root (and "all roots") remembers its own tag filter. Saving waits until
+    // the filter for the current root has been restored, so a switch never
+    // overwrites the saved filter with the previous root's selection.
+    let (filter_restored_for, set_filter_restored_for) = signal(None::<Option<String>>);
+    Effect::new(move |_| {
+        let root = tag_filter_key(&active_root_filter.get());
+        set_filter_restored_for.set(None);
+        spawn_local(async move {
+            let args = GetTagFilterArgs { root: root.clone() };
+            let saved = serde_wasm_bindgen::from_value::<Option<TagFilter>>(
+                invoke("get_tag_filter", serde_wasm_bindgen::to_value(&args).unwrap()).await,
+            )
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| TagFilter { tag_ids: Vec::new(), use_and: use_and_logic.get_untracked() });
+            let known = all_tags.get_untracked();
+            let tag_ids: Vec<u32> = saved
+                .tag_ids
+                .into_iter()
+                .filter(|id| known.is_empty() || known.iter().any(|t| t.id == *id))
+                .collect();
+            if tag_ids != selected_tag_ids.get_untracked() || saved.use_and != use_and_logic.get_untracked() {
+                set_selected_tag_ids.set(tag_ids.clone());
+                set_use_and_logic.set(saved.use_and);
+            }
+            // Re-run even for an unchanged filter: the query is scoped to the selected roots
+            filter_files(tag_ids, saved.use_and, set_displayed_files, all_files.get_untracked(), active_root_filter.get_untracked());
+            set_filter_restored_for.set(Some(root));
+        });
+    });
+    Effect::new(move |_| {
+        let filter = TagFilter { tag_ids: selected_tag_ids.get(), use_and: use_and_logic.get() };
+        let root = tag_filter_key(&active_root_filter.get_untracked());
+        if filter_restored_for.get() != Some(root.clone()) {
+            return;
+        }
+        spawn_local(async move {
+            let args = SetTagFilterArgs { root, filter };
+            let _ = invoke("set_tag_filter", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+        });
+    });
+
     let toggle_and_or = move |_| {
         let new_logic = !use_and_logic.get();
         set_use_and_logic.set(new_logic);
@@ -811,12 +1936,109 @@ pub fn App() -> impl IntoView {
             new_logic,
             set_displayed_files,
             all_files.get(),
+            active_root_filter.get_untracked(),
         );
     };
 
     let show_all = move |_| {
         set_selected_tag_ids.set(Vec::new());
-        set_displayed_files.set(all_files.get());
+        set_nl_refine.set(None);
+        set_opened_filter.set(OpenedFilter::All);
+        set_search_text.set(String::new());
+        filter_files(Vec::new(), use_and_logic.get_untracked(), set_displayed_files, all_files.get(), active_root_filter.get_untracked());
+    };
+
+    // Searched again when the text, the tag filter or the library changes; a
+    // reply for text that has since been edited is dropped
+    Effect::new(move |_| {
+        let query = search_text.get().trim().to_string();
+        let filter = TagFilter { tag_ids: selected_tag_ids.get(), use_and: use_and_logic.get() };
+        all_files.track();
+        if query.is_empty() {
+            set_search_hits.set(None);
+            return;
+        }
+        spawn_local(async move {
+            let args = SearchFilesArgs { query: query.clone(), filter: Some(filter) };
+            match try_invoke("search_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    if search_text.get_untracked().trim() == query {
+                        let files: Vec<FileInfo> = serde_wasm_bindgen::from_value(v).unwrap_or_default();
+                        set_search_hits.set(Some(files.into_iter().map(|f| f.path).collect()));
+                    }
+                }
+                Err(e) => set_toast.set(Some(format!("Search failed: {}", e.as_string().unwrap_or_default()))),
+            }
+        });
+    });
+
+    let ask_query = move || {
+        let query = nl_query.get_untracked().trim().to_string();
+        if query.is_empty() || nl_busy.get_untracked() {
+            return;
+        }
+        set_nl_busy.set(true);
+        spawn_local(async move {
+            let args = TranslateQueryArgs { query, base_url: None, model: None };
+            match try_invoke("translate_query", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => set_nl_pending.set(serde_wasm_bindgen::from_value::<NlFilter>(v).ok()),
+                Err(e) => set_toast.set(Some(format!("Search failed: {}", e.as_string().unwrap_or_default()))),
+            }
+            set_nl_busy.set(false);
+        });
+    };
+
+    // A confirmed query replaces the tag filter; the rest narrows the list
+    let apply_nl_filter = move |filter: NlFilter| {
+        set_selected_tag_ids.set(filter.tag_ids.clone());
+        set_use_and_logic.set(filter.use_and);
+        filter_files(filter.tag_ids.clone(), filter.use_and, set_displayed_files, all_files.get_untracked(), active_root_filter.get_untracked());
+        set_nl_refine.set(filter.has_refinements().then_some(filter));
+    };
+
+    // The current view as a tagme:// link, for bookmarks or a colleague with the
+    // same tags
+    let copy_filter_link = move |_| {
+        let tags = all_tags.get_untracked();
+        let refine = nl_refine.get_untracked().unwrap_or_default();
+        let link = tagme_paths::link::FilterLink {
+            tags: selected_tag_ids
+                .get_untracked()
+                .iter()
+                .filter_map(|id| tags.iter().find(|t| t.id == *id))
+                .map(|t| tag_name_path(t, &tags))
+                .collect(),
+            use_and: use_and_logic.get_untracked(),
+            roots: active_root_filter.get_untracked(),
+            extensions: refine.extensions,
+            name_contains: refine.name_contains,
+            modified_after: refine.modified_after,
+            modified_before: refine.modified_before,
+        };
+        spawn_local(async move {
+            let args = CopyTextArgs { text: link.to_url() };
+            match try_invoke("copy_text", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => set_toast.set(Some("Filter link copied".to_string())),
+                Err(e) => set_toast.set(Some(format!("Couldn't copy the link: {}", e.as_string().unwrap_or_default()))),
+            }
+        });
+    };
+
+    let can_switch_to_or = move || use_and_logic.get() && selected_tag_ids.get().len() > 1;
+
+    // "12 files" normally, "0 files match 3 tags (AND)" while a tag filter is active
+    let file_count = Memo::new(move |_| sorted_files().len());
+    let filter_summary = move || {
+        let n = file_count.get();
+        let files = if n == 1 { "file" } else { "files" };
+        let k = selected_tag_ids.get().len();
+        if k == 0 {
+            format!("{} {}", n, files)
+        } else {
+            let logic = if use_and_logic.get() { "AND" } else { "OR" };
+            let tags = if k == 1 { "tag" } else { "tags" };
+            format!("{} {} match {} {} ({})", n, files, k, tags, logic)
+        }
     };
 
     let toggle_file_selection = move |file_path: String| {
@@ -843,26 +2065,106 @@ pub fn App() -> impl IntoView {
         });
     };
 
-    let create_tag_action = move |_| {
-        let name = new_tag_name.get();
-        let parent = new_tag_parent.get();
-        if !name.is_empty() {
-            spawn_local(async move {
-                let args = CreateTagArgs {
-                    name,
-                    parent_id: parent,
-                    color: None,
-                };
-                let _ = invoke("create_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                load_tags(set_all_tags).await;
-                set_show_add_tag_dialog.set(false);
-                set_new_tag_name.set(String::new());
-                set_new_tag_parent.set(None);
-            });
+    // Tag nodes dropped on the file panel apply to the current file selection
+    let apply_dragged_tags = move |tag_ids: Vec<u32>| {
+        let file_paths = selected_file_paths.get_untracked();
+        if file_paths.is_empty() {
+            web_sys::console::log_1(&"⚠️ No files selected - select files before dropping a tag".into());
+            return;
+        }
+        spawn_local(async move {
+            let args = AddTagsToFilesArgs { file_paths, tag_ids };
+            let _ = invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+        });
+    };
+    let dnd_files_zone = dnd.clone();
+
+    // Pinned files, reloaded along with the files so their tags stay current
+    let (pinned, set_pinned) = signal(Vec::<PinnedFile>::new());
+    let reload_pinned = move || {
+        spawn_local(async move {
+            if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<PinnedFile>>(invoke("get_pinned_files", JsValue::NULL).await) {
+                set_pinned.set(list);
+            }
+        });
+    };
+    Effect::new(move |_| {
+        all_files.track();
+        reload_pinned();
+    });
+    let pin_selected = move |_| {
+        let paths = selected_file_paths.get_untracked();
+        if paths.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let args = PinFilesArgs { paths };
+            match try_invoke("pin_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => reload_pinned(),
+                Err(e) => set_toast.set(Some(format!("Couldn't pin: {}", e.as_string().unwrap_or_default()))),
+            }
+        });
+    };
+    let unpin_file = move |path: String| {
+        spawn_local(async move {
+            let args = UnpinFileArgs { path };
+            let _ = invoke("unpin_file", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            reload_pinned();
+        });
+    };
+    let select_pinned = move |path: String| {
+        set_last_selected_file_path.set(Some(path.clone()));
+        set_selected_file_paths.set(vec![path]);
+    };
+
+    // Split view: a second file list with its own tag filter. Files dropped on
+    // the other list get its filter tags and, unless Ctrl is held, lose the tags
+    // of the list they came from that the other one doesn't share.
+    let (split_view, set_split_view) = signal(false);
+    let (split_tag_ids, set_split_tag_ids) = signal(Vec::<u32>::new());
+    let (split_use_and, set_split_use_and) = signal(true);
+    let (file_drag, set_file_drag) = signal(None::<FileDrag>);
+    let drop_files = move |to_split: bool, ev: web_sys::DragEvent| {
+        ev.prevent_default();
+        let Some(drag) = file_drag.get_untracked() else { return };
+        set_file_drag.set(None);
+        if drag.from_split == to_split {
+            return;
+        }
+        let (from, to) = if to_split {
+            (selected_tag_ids.get_untracked(), split_tag_ids.get_untracked())
+        } else {
+            (split_tag_ids.get_untracked(), selected_tag_ids.get_untracked())
+        };
+        if to.is_empty() {
+            set_toast.set(Some("Filter that list by a tag first".to_string()));
+            return;
         }
+        let copy = ev.ctrl_key() || ev.meta_key();
+        let remove: Vec<u32> = if copy { Vec::new() } else { from.into_iter().filter(|t| !to.contains(t)).collect() };
+        let files = all_files.get_untracked();
+        spawn_local(async move {
+            let count = drag.paths.len();
+            let args = AddTagsToFilesArgs { file_paths: drag.paths.clone(), tag_ids: to };
+            if let Err(e) = try_invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_toast.set(Some(format!("Couldn't apply tags: {}", e.as_string().unwrap_or_default())));
+                return;
+            }
+            for path in &drag.paths {
+                let Some(file) = files.iter().find(|f| &f.path == path) else { continue };
+                for &tag_id in &remove {
+                    let args = RemoveFileTagArgs { file_id: file.id, tag_id };
+                    let _ = invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                }
+            }
+            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+            set_toast.set(Some(format!("{} {} file(s)", if copy { "Tagged" } else { "Moved" }, count)));
+        });
     };
 
     provide_context(dnd.clone());
+    provide_context(set_tag_menu);
     view! {
         <div class="app">
             <div class="header"
@@ -897,6 +2199,12 @@ pub fn App() -> impl IntoView {
                         set_update_current,
                         update_latest,
                         set_update_latest,
+                        update_notes,
+                        set_update_notes,
+                        update_channel,
+                        set_update_channel,
+                        update_suppressed,
+                        set_update_suppressed,
                         update_has,
                         set_update_has,
                         update_error,
@@ -910,6 +2218,36 @@ pub fn App() -> impl IntoView {
                         update_total,
                         set_update_total,
                     }})}
+                    <button on:click=move |_| set_show_cleanup.set(true) class="header-btn" title="Cleanup">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M15 16h4v2h-4zm0-8h7v2h-7zm0 4h6v2h-6zM3 18c0 1.1.9 2 2 2h6c1.1 0 2-.9 2-2V8H3v10zM14 5h-3l-1-1H6L5 5H2v2h12z"/>
+                        </svg>
+                    </button>
+                    <button on:click=move |_| set_show_taxonomy.set(true) class="header-btn" title="Suggest taxonomy improvements">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M22 11V3h-7v3H9V3H2v8h7V8h2v10h4v3h7v-8h-7v3h-2V8h2v3z"/>
+                        </svg>
+                    </button>
+                    <button on:click=move |_| set_show_verify.set(true) class="header-btn" title="Verify integrity">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M12 1 3 5v6c0 5.55 3.84 10.74 9 12 5.16-1.26 9-6.45 9-12V5l-9-4zm-2 16-4-4 1.41-1.41L10 14.17l6.59-6.59L18 9l-8 8z"/>
+                        </svg>
+                    </button>
+                    <button on:click=move |_| set_show_diagnostics.set(true) class="header-btn" title="Diagnostics">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M3.5 18.49l6-6.01 4 4L22 6.92l-1.41-1.41-7.09 7.97-4-4L2 16.99z"/>
+                        </svg>
+                    </button>
+                    <button on:click=move |_| set_show_script.set(true) class="header-btn" title="Script console">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M20 4H4c-1.11 0-2 .9-2 2v12c0 1.1.89 2 2 2h16c1.1 0 2-.9 2-2V6c0-1.1-.89-2-2-2zm0 14H4V8h16v10zm-2-1h-6v-2h6v2zM7.5 17l-1.41-1.41L8.67 13l-2.59-2.59L7.5 9l4 4-4 4z"/>
+                        </svg>
+                    </button>
+                    <button on:click=move |_| set_show_settings.set(true) class="header-btn" title="Settings">
+                        <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
+                            <path d="M19.14 12.94a7.07 7.07 0 0 0 0-1.88l2.03-1.58a.5.5 0 0 0 .12-.64l-1.92-3.32a.5.5 0 0 0-.6-.22l-2.39.96a7.03 7.03 0 0 0-1.63-.94l-.36-2.54A.5.5 0 0 0 14.3 2h-3.84a.5.5 0 0 0-.49.42l-.36 2.54a7.03 7.03 0 0 0-1.63.94l-2.39-.96a.5.5 0 0 0-.6.22L3.07 8.48a.5.5 0 0 0 .12.64l2.03 1.58a7.07 7.07 0 0 0 0 1.88l-2.03 1.58a.5.5 0 0 0-.12.64l1.92 3.32a.5.5 0 0 0 .6.22l2.39-.96c.5.39 1.05.7 1.63.94l.36 2.54a.5.5 0 0 0 .49.42h3.84a.5.5 0 0 0 .49-.42l.36-2.54a7.03 7.03 0 0 0 1.63-.94l2.39.96a.5.5 0 0 0 .6-.22l1.92-3.32a.5.5 0 0 0-.12-.64l-2.03-1.58zM12.38 15.5a3.5 3.5 0 1 1 0-7 3.5 3.5 0 0 1 0 7z"/>
+                        </svg>
+                    </button>
                     <button on:click=move |_| minimize(()) class="header-btn" title="Minimize">
                         <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor" style="pointer-events: none;">
                             <path d="M19 13H5v-2h14v2z"/>
@@ -940,6 +2278,7 @@ pub fn App() -> impl IntoView {
 
             <div class="toolbar">
                 <button on:click=select_directory>"Select Root Directory"</button>
+                <button title="Add a WebDAV or S3 location as a root" on:click=move |_| set_show_remote_root.set(true)>"☁ Add remote"</button>
                 {move || {
                     let list = root_directories.get();
                     if list.is_empty() { None } else {
@@ -961,13 +2300,26 @@ pub fn App() -> impl IntoView {
                                                 struct RemoveRootArgs { path: String }
                                                 let _ = invoke("remove_root_directory", serde_wasm_bindgen::to_value(&RemoveRootArgs { path: rp2.clone() }).unwrap()).await;
                                                 let do_purge = web_sys::window().and_then(|w| w.confirm_with_message(&format!("Also purge DB records under root?\n{}", rp2)).ok()).unwrap_or(false);
+                                                // With typed confirmation on, the folder name has to be entered as well
+                                                let confirmation = if do_purge && safety_policy.get_untracked().type_to_confirm {
+                                                    let phrase = purge_phrase(Some(&rp2));
+                                                    web_sys::window()
+                                                        .and_then(|w| w.prompt_with_message(&format!("Type \"{}\" to purge DB records under\n{}", phrase, rp2)).ok())
+                                                        .flatten()
+                                                } else {
+                                                    None
+                                                };
                                                 if do_purge {
                                                     #[derive(Serialize)]
                                                     #[serde(rename_all = "camelCase")]
-                                                    struct PurgeArgs { path: String }
-                                                    let res = invoke("purge_files_under_root", serde_wasm_bindgen::to_value(&PurgeArgs { path: rp2.clone() }).unwrap()).await;
-                                                    if let Ok(cnt) = serde_wasm_bindgen::from_value::<u32>(res) {
-                                                        web_sys::console::log_1(&format!("[DB] purged {} files under root", cnt).into());
+                                                    struct PurgeArgs { path: String, confirmation: Option<String> }
+                                                    match try_invoke("purge_files_under_root", serde_wasm_bindgen::to_value(&PurgeArgs { path: rp2.clone(), confirmation }).unwrap()).await {
+                                                        Ok(res) => {
+                                                            if let Ok(cnt) = serde_wasm_bindgen::from_value::<u32>(res) {
+                                                                web_sys::console::log_1(&format!("[DB] purged {} files under root", cnt).into());
+                                                            }
+                                                        }
+                                                        Err(e) => set_toast.set(Some(format!("Purge skipped: {}", e.as_string().unwrap_or_default()))),
                                                     }
                                                 }
                                                 // Reload roots from backend to ensure persistence, then restart watcher and refresh files
@@ -975,14 +2327,15 @@ pub fn App() -> impl IntoView {
                                                 if let Ok(roots) = serde_wasm_bindgen::from_value::<Vec<String>>(roots_val) {
                                                     set_root_directories.set(roots.clone());
                                                     // Clear active filter if it pointed to removed path
-                                                    if active_root_filter.get_untracked().as_ref() == Some(&rp2) {
-                                                        set_active_root_filter.set(None);
+                                                    set_active_root_filter.update(|roots| roots.retain(|r| r != &rp2));
+                                                    if default_root.get_untracked().as_ref() == Some(&rp2) {
+                                                        set_default_root.set(None);
                                                     }
                                                     // Restart watcher
                                                     #[derive(Serialize)]
                                                     #[serde(rename_all = "camelCase")]
-                                                    struct StartWatchingMultiArgs { root_paths: Vec<String> }
-                                                    let _ = invoke("start_watching_multi", serde_wasm_bindgen::to_value(&StartWatchingMultiArgs { root_paths: roots.clone() }).unwrap()).await;
+                                                    struct ReconcileWatchersArgs { roots: Vec<String> }
+                                                    let _ = invoke("reconcile_watchers", serde_wasm_bindgen::to_value(&ReconcileWatchersArgs { roots: roots.clone() }).unwrap()).await;
                                                     // Refresh DB files and displayed files
                                                     load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
                                                 }
@@ -992,29 +2345,172 @@ pub fn App() -> impl IntoView {
                                             spawn_local(async move {
                                                 #[derive(Serialize)]
                                                 #[serde(rename_all = "camelCase")]
-                                                struct StartWatchingMultiArgs { root_paths: Vec<String> }
-                                                let _ = invoke("start_watching_multi", serde_wasm_bindgen::to_value(&StartWatchingMultiArgs { root_paths: updated.clone() }).unwrap()).await;
+                                                struct ReconcileWatchersArgs { roots: Vec<String> }
+                                                let _ = invoke("reconcile_watchers", serde_wasm_bindgen::to_value(&ReconcileWatchersArgs { roots: updated.clone() }).unwrap()).await;
+                                            });
+                                        };
+                                        let rp_drag = rp.clone();
+                                        let rp_drop = rp.clone();
+                                        let on_dragstart = move |_ev: web_sys::DragEvent| {
+                                            set_dragging_root.set(Some(rp_drag.clone()));
+                                        };
+                                        let on_drop = move |ev: web_sys::DragEvent| {
+                                            ev.prevent_default();
+                                            let Some(src) = dragging_root.get_untracked() else { return; };
+                                            set_dragging_root.set(None);
+                                            if src == rp_drop { return; }
+                                            let mut order = root_directories.get_untracked();
+                                            order.retain(|x| x != &src);
+                                            let idx = order.iter().position(|x| x == &rp_drop).unwrap_or(order.len());
+                                            order.insert(idx, src);
+                                            set_root_directories.set(order.clone());
+                                            spawn_local(async move {
+                                                #[derive(Serialize)]
+                                                #[serde(rename_all = "camelCase")]
+                                                struct ReorderRootsArgs { paths: Vec<String> }
+                                                let _ = invoke("reorder_root_directories", serde_wasm_bindgen::to_value(&ReorderRootsArgs { paths: order }).unwrap()).await;
+                                            });
+                                        };
+                                        let rp_pin = rp.clone();
+                                        let rp_pinned = rp.clone();
+                                        let rp_pinned2 = rp.clone();
+                                        let is_pinned = move || default_root.get().as_ref() == Some(&rp_pinned);
+                                        let is_pinned_style = move || default_root.get().as_ref() == Some(&rp_pinned2);
+                                        let toggle_pin = move |ev: web_sys::MouseEvent| {
+                                            ev.stop_propagation();
+                                            let next = if default_root.get_untracked().as_ref() == Some(&rp_pin) { None } else { Some(rp_pin.clone()) };
+                                            set_default_root.set(next.clone());
+                                            spawn_local(async move {
+                                                #[derive(Serialize)]
+                                                #[serde(rename_all = "camelCase")]
+                                                struct SetDefaultRootArgs { path: Option<String> }
+                                                let _ = invoke("set_default_root", serde_wasm_bindgen::to_value(&SetDefaultRootArgs { path: next }).unwrap()).await;
+                                            });
+                                        };
+                                        let rp_lock = rp.clone();
+                                        let rp_locked = rp.clone();
+                                        let rp_locked2 = rp.clone();
+                                        let is_local_only = move || local_only_roots.get().contains(&rp_locked);
+                                        let is_local_only_style = move || local_only_roots.get().contains(&rp_locked2);
+                                        let toggle_local_only = move |ev: web_sys::MouseEvent| {
+                                            ev.stop_propagation();
+                                            let path = rp_lock.clone();
+                                            let local_only = !local_only_roots.get_untracked().contains(&path);
+                                            set_local_only_roots.update(|v| {
+                                                v.retain(|r| r != &path);
+                                                if local_only {
+                                                    v.push(path.clone());
+                                                }
+                                            });
+                                            spawn_local(async move {
+                                                let args = SetRootLocalOnlyArgs { path, local_only };
+                                                if let Err(e) = try_invoke("set_root_local_only", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                                                    set_toast.set(Some(e.as_string().unwrap_or_else(|| "Could not change cloud access".to_string())));
+                                                }
+                                            });
+                                        };
+                                        let rp_defaults = rp.clone();
+                                        let rp_defaults2 = rp.clone();
+                                        let rp_defaults_edit = rp.clone();
+                                        let defaults_of = move || root_defaults.get().get(&rp_defaults).cloned().unwrap_or_default();
+                                        let has_defaults_style = move || root_defaults.get().contains_key(&rp_defaults2);
+                                        let edit_defaults = move |ev: web_sys::MouseEvent| {
+                                            ev.stop_propagation();
+                                            set_editing_root_defaults.set(Some(rp_defaults_edit.clone()));
+                                        };
+                                        let rp_watch = rp.clone();
+                                        let rp_watch_toggle = rp.clone();
+                                        let rp_watch_icon = rp.clone();
+                                        let status_of = move || watch_status.get().into_iter().find(|s| s.root == rp_watch);
+                                        let status_icon = move || watch_status.get().into_iter().find(|s| s.root == rp_watch_icon);
+                                        let toggle_watch = move |ev: web_sys::MouseEvent| {
+                                            ev.stop_propagation();
+                                            if manual_refresh.get_untracked() {
+                                                return;
+                                            }
+                                            let root = rp_watch_toggle.clone();
+                                            let paused = watch_status.get_untracked().iter().any(|s| s.root == root && s.paused);
+                                            spawn_local(async move {
+                                                #[derive(Serialize)]
+                                                #[serde(rename_all = "camelCase")]
+                                                struct WatchRootArgs { root: String }
+                                                let cmd = if paused { "resume_watching" } else { "pause_watching" };
+                                                let _ = invoke(cmd, serde_wasm_bindgen::to_value(&WatchRootArgs { root }).unwrap()).await;
                                             });
                                         };
                                         let rp_filter_src = rp.clone();
                                         let rp_filter = rp_filter_src.clone();
-                                        let is_active = move || active_root_filter.get().as_ref().map(|x| x == &rp_filter).unwrap_or(false);
+                                        let is_active = move || active_root_filter.get().contains(&rp_filter);
                                         let toggle_val = rp_filter_src.clone();
-                                        let toggle_filter = move |_| {
-                                            let current = active_root_filter.get_untracked();
-                                            if current.as_ref() == Some(&toggle_val) {
-                                                set_active_root_filter.set(None);
-                                            } else {
-                                                set_active_root_filter.set(Some(toggle_val.clone()));
-                                            }
+                                        // Click scopes to this root alone; Ctrl/Cmd-click adds or removes it
+                                        let toggle_filter = move |ev: web_sys::MouseEvent| {
+                                            let additive = ev.ctrl_key() || ev.meta_key();
+                                            set_active_root_filter.update(|roots| {
+                                                if additive {
+                                                    if roots.contains(&toggle_val) {
+                                                        roots.retain(|r| r != &toggle_val);
+                                                    } else {
+                                                        roots.push(toggle_val.clone());
+                                                    }
+                                                } else if roots.len() == 1 && roots[0] == toggle_val {
+                                                    roots.clear();
+                                                } else {
+                                                    *roots = vec![toggle_val.clone()];
+                                                }
+                                            });
                                         };
                                         view! {
                                             <span
                                                 class=move || if is_active() { "root-path active" } else { "root-path" }
+                                                title="Click to show only this root; Ctrl-click to add or remove it"
                                                 style="padding:2px 6px; border-radius:4px; display:inline-flex; align-items:center; gap:6px; cursor:pointer;"
+                                                draggable="true"
                                                 on:click=toggle_filter
+                                                on:dragstart=on_dragstart
+                                                on:dragover=|ev: web_sys::DragEvent| ev.prevent_default()
+                                                on:drop=on_drop
+                                                on:dragend=move |_| set_dragging_root.set(None)
                                             >
                                                 {rp_display.clone()}
+                                                <button on:click=toggle_watch
+                                                    title=move || match status_of() {
+                                                        Some(s) if s.manual => "Manual refresh only - use Scan to pick up changes".to_string(),
+                                                        Some(s) if s.offline => "Folder not reachable (drive or network share disconnected?) - watching resumes when it's back".to_string(),
+                                                        Some(s) if s.paused => "Watching paused - click to resume".to_string(),
+                                                        Some(s) if s.watching => "Watching for changes - click to pause".to_string(),
+                                                        Some(WatchStatus { error: Some(e), .. }) => format!("Watcher failed, retrying: {}", e),
+                                                        _ => "Not watched - click to pause".to_string(),
+                                                    }
+                                                    style="border:none; background:transparent; cursor:pointer;"
+                                                >{move || match status_icon() {
+                                                    Some(s) if s.manual => "✋",
+                                                    Some(s) if s.offline => "⏏",
+                                                    Some(s) if s.paused => "⏸",
+                                                    Some(s) if s.watching => "👁",
+                                                    _ => "⚠",
+                                                }}</button>
+                                                <button on:click=toggle_pin title=move || if is_pinned() { "Unpin default root" } else { "Pin as default root" }
+                                                    style=move || format!("border:none; background:transparent; cursor:pointer; opacity:{};", if is_pinned_style() { "1" } else { "0.35" })
+                                                >"📌"</button>
+                                                <button on:click=toggle_local_only
+                                                    title=move || if is_local_only() { "Never sent to cloud models - click to allow" } else { "Click to never send these files to cloud models" }
+                                                    style=move || format!("border:none; background:transparent; cursor:pointer; opacity:{};", if is_local_only_style() { "1" } else { "0.35" })
+                                                >"🔒"</button>
+                                                <button on:click=edit_defaults
+                                                    title=move || {
+                                                        let tags = all_tags.get();
+                                                        let names: Vec<String> = defaults_of()
+                                                            .iter()
+                                                            .filter_map(|id| tags.iter().find(|t| t.id == *id).map(|t| t.name.clone()))
+                                                            .collect();
+                                                        if names.is_empty() {
+                                                            "Set tags that new files in this root get automatically".to_string()
+                                                        } else {
+                                                            format!("New files get: {} - click to change", names.join(", "))
+                                                        }
+                                                    }
+                                                    style=move || format!("border:none; background:transparent; cursor:pointer; opacity:{};", if has_defaults_style() { "1" } else { "0.35" })
+                                                >"🏷"</button>
                                                 <button on:click=remove title="Remove" style="border:none; background:transparent; cursor:pointer; color:#c00;">"×"</button>
                                             </span>
                                         }
@@ -1027,12 +2523,21 @@ pub fn App() -> impl IntoView {
                 <button on:click=scan_directory disabled=move || root_directories.get().is_empty()>
                     {move || if scanning.get() { "Scanning..." } else { "Scan Files" }}
                 </button>
+                {move || hydrating.get().map(|(loaded, total)| view! {
+                    <span class="hydrate-progress">{format!("Loading library... {} / {}", format_count(loaded), format_count(total))}</span>
+                })}
 
                 <button on:mousedown={move |_| {
                         web_sys::console::log_1(&"[UI] Clear DB Files mousedown".into());
                     }}
                     on:click={move |_| {
-                        set_show_purge_confirm.set(true);
+                        let policy = safety_policy.get_untracked();
+                        if policy.confirm || policy.type_to_confirm {
+                            set_purge_typed.set(String::new());
+                            set_show_purge_confirm.set(true);
+                        } else {
+                            purge_all(None);
+                        }
                     }}
                 >
                     "Clear DB Files"
@@ -1043,16 +2548,56 @@ pub fn App() -> impl IntoView {
                 <div class="left-panel" style=move || format!("width: {}px", left_panel_width.get())>
                     <div class="panel-header">
                         <h2>"Tags"</h2>
-                        <button on:click=move |_| set_show_add_tag_dialog.set(true)>"+"</button>
+                        <div class="tag-mode-toggle" title="What clicking a tag does">
+                            <span
+                                class:active=move || tag_click_mode.get() == TagClickMode::Filter
+                                title="Filter files by tag"
+                                on:click=move |_| set_tag_click_mode.set(TagClickMode::Filter)
+                            >"Filter"</span>
+                            <span
+                                class:active=move || tag_click_mode.get() == TagClickMode::Assign
+                                title="Add or remove the tag on the selected files"
+                                on:click=move |_| set_tag_click_mode.set(TagClickMode::Assign)
+                            >"Assign"</span>
+                        </div>
+                        <select
+                            class="tag-sort-select"
+                            title="Sort tags"
+                            prop:value=move || tag_sort_mode.get()
+                            on:change=move |e| {
+                                let mode = event_target_value(&e);
+                                set_tag_sort_mode.set(mode.clone());
+                                spawn_local(async move {
+                                    let args = SetTagSortModeArgs { mode };
+                                    let _ = invoke("set_tag_sort_mode", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                    load_tags(set_all_tags).await;
+                                });
+                            }
+                        >
+                            <option value="manual">"Manual"</option>
+                            <option value="name">"A–Z"</option>
+                            <option value="usage">"Most used"</option>
+                        </select>
+                        <button
+                            class:active=move || show_archived.get()
+                            title=move || if show_archived.get() { "Hide archived tags" } else { "Show archived tags" }
+                            on:click=move |_| set_show_archived.update(|v| *v = !*v)
+                        >"🗄"</button>
+                        <button on:click=move |_| {
+                            set_new_tag_parent.set(None);
+                            set_show_add_tag_dialog.set(true);
+                        }>"+"</button>
+                        <button title="Import tags from a list or CSV, or export the tree as an outline" on:click=move |_| set_show_import_tags.set(true)>"⇣"</button>
                     </div>
                     <TagTree
                         tags=all_tags
-                        selected_tag_ids=selected_tag_ids
+                        show_archived=show_archived
+                        selected_tag_ids=tree_checked_ids
                         set_selected_tag_ids=set_selected_tag_ids
                         use_and_logic=use_and_logic
                         set_displayed_files=set_displayed_files
                         all_files=all_files
-                        on_toggle=toggle_tag_selection
+                        on_toggle=on_tree_tag_click
                         _set_all_tags=set_all_tags
                         dragging_tag_id=dragging_tag_id
                         set_dragging_tag_id=set_dragging_tag_id
@@ -1077,15 +2622,140 @@ pub fn App() -> impl IntoView {
                     }
                 ></div>
 
-                <div class="center-panel">
+                <div
+                    class="center-panel"
+                    class:drop-zone-active=move || leptos_dragdrop::is_zone_active(&dnd_files_zone, "file-list")
+                    on:mouseenter=leptos_dragdrop::make_zone_on_mouseenter(dnd.clone(), "file-list")
+                    on:mouseleave=leptos_dragdrop::make_zone_on_mouseleave(dnd.clone())
+                    on:mouseup=leptos_dragdrop::make_zone_on_mouseup(dnd.clone(), "file-list", apply_dragged_tags)
+                >
+                    <ViewTabs
+                        tabs=tab_list
+                        active=active_tab
+                        all_tags=all_tags
+                        on_select=select_tab
+                        on_new=new_tab
+                        on_close=close_tab
+                    />
                     <div class="panel-header">
                         <h2>"Files"</h2>
                         <div class="file-controls">
                             <button on:click=show_all>"Show All"</button>
+                            <input
+                                class="file-search"
+                                type="search"
+                                placeholder="Search names and paths"
+                                title="Files whose name or path contains every word; combined with the tag filter"
+                                prop:value=move || search_text.get()
+                                on:input=move |e| set_search_text.set(event_target_value(&e))
+                            />
+                            <input
+                                class="nl-query"
+                                type="text"
+                                placeholder="Ask, e.g. screenshots from last month about billing"
+                                prop:value=move || nl_query.get()
+                                prop:disabled=move || nl_busy.get()
+                                on:input=move |e| set_nl_query.set(event_target_value(&e))
+                                on:keydown=move |e| {
+                                    if e.key() == "Enter" {
+                                        ask_query();
+                                    }
+                                }
+                            />
+                            {move || nl_refine.get().is_some().then(|| view! {
+                                <button title="Clear the type, name and date limits of the last search" on:click=move |_| set_nl_refine.set(None)>
+                                    "Search limits ×"
+                                </button>
+                            })}
+                            <span class="file-count">{filter_summary}</span>
                             <button on:click=toggle_and_or>
                                 {move || if use_and_logic.get() { "Filter: AND" } else { "Filter: OR" }}
                             </button>
+                            <select
+                                title="Only files opened through tagme"
+                                prop:value=move || match opened_filter.get() {
+                                    OpenedFilter::All => "all",
+                                    OpenedFilter::Recent => "recent",
+                                    OpenedFilter::Frequent => "frequent",
+                                }
+                                on:change=move |ev| set_opened_view(match event_target_value(&ev).as_str() {
+                                    "recent" => OpenedFilter::Recent,
+                                    "frequent" => OpenedFilter::Frequent,
+                                    _ => OpenedFilter::All,
+                                })
+                            >
+                                <option value="all">"All files"</option>
+                                <option value="recent">"Recently opened"</option>
+                                <option value="frequent">"Frequently opened"</option>
+                            </select>
+                            <button
+                                title="Keep the selected files in the Pinned strip above the list"
+                                disabled=move || selected_file_paths.get().is_empty()
+                                on:click=pin_selected
+                            >
+                                "Pin"
+                            </button>
+                            <button
+                                class:active=move || split_view.get()
+                                title="Show a second file list with its own tag filter, to drag files between tag sets"
+                                on:click=move |_| set_split_view.update(|v| *v = !*v)
+                            >
+                                "Split"
+                            </button>
+                            <button title="Copy a tagme:// link that opens this filter" on:click=copy_filter_link>
+                                "Copy link"
+                            </button>
+                            <button
+                                title="Show all roots in one table instead of one table per root"
+                                on:click=move |_| {
+                                    let enabled = !flat_view.get_untracked();
+                                    set_flat_view.set(enabled);
+                                    spawn_local(async move {
+                                        let args = SetFlatViewArgs { enabled };
+                                        let _ = invoke("set_flat_view", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                    });
+                                }
+                            >
+                                {move || if flat_view.get() { "View: Flat" } else { "View: By root" }}
+                            </button>
+                            <button
+                                title="Show tags of tagged folders on the files inside them"
+                                class:active=move || inherit_folder_tags.get()
+                                on:click=move |_| {
+                                    let enabled = !inherit_folder_tags.get_untracked();
+                                    set_inherit_folder_tags.set(enabled);
+                                    spawn_local(async move {
+                                        let args = SetInheritFolderTagsArgs { enabled };
+                                        let _ = invoke("set_inherit_folder_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                    });
+                                }
+                            >
+                                {move || if inherit_folder_tags.get() { "Folder tags: On" } else { "Folder tags: Off" }}
+                            </button>
+                            <select
+                                class="tag-sort-select"
+                                title="How tags are shown on file rows; hover a badge for its full path"
+                                prop:value=move || tag_badge_mode.get()
+                                on:change=move |e| {
+                                    let mode = event_target_value(&e);
+                                    set_tag_badge_mode.set(mode.clone());
+                                    spawn_local(async move {
+                                        let args = SetTagBadgeModeArgs { mode };
+                                        let _ = invoke("set_tag_badge_mode", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                    });
+                                }
+                            >
+                                <option value="name">"Badges: Name"</option>
+                                <option value="path">"Badges: Full path"</option>
+                                <option value="leaf">"Badges: Leaf only"</option>
+                            </select>
                             <button on:click=recommend_all>"Recommend All"</button>
+                            <button
+                                prop:disabled=move || file_recommended_info_map.get().values().all(|items| items.is_empty())
+                                on:click=move |_| set_show_review.set(true)
+                            >
+                                "Review..."
+                            </button>
                             <button on:click=move |_| {
                                 set_show_recommended.set(false);
                                 set_file_recommended_tags_map.set(std::collections::HashMap::new());
@@ -1096,26 +2766,120 @@ pub fn App() -> impl IntoView {
 
                         </div>
                     </div>
-                    <GroupedFileList
-                        files=sorted_files
-                        roots=root_directories
-                        active_root_filter=active_root_filter
+                    <PinnedFiles
+                        pinned=pinned
                         selected_file_paths=selected_file_paths
-                        on_toggle=toggle_file_selection
-                        sort_column=sort_column
-                        sort_direction=sort_direction
-                        on_sort=toggle_sort
-                        set_selected_file_paths=set_selected_file_paths
-                        last_selected_file_path=last_selected_file_path
-                        set_last_selected_file_path=set_last_selected_file_path
-                        _recommended_map=file_recommended_tags_map
-                        recommended_info_map=file_recommended_info_map
-                        show_recommended=show_recommended
-                        all_tags=all_tags
-                        set_all_files=set_all_files
-                        set_displayed_files=set_displayed_files
-                        set_file_tags_map=set_file_tags_map
+                        on_select=select_pinned
+                        on_open=open_with_action
+                        on_unpin=unpin_file
                     />
+                    <Show when=move || file_count.get() == 0 && !selected_tag_ids.get().is_empty()>
+                        <div class="empty-filter">
+                            <p>{filter_summary}</p>
+                            <div class="empty-filter-actions">
+                                <Show when=can_switch_to_or>
+                                    <button on:click=toggle_and_or>"Match any tag (OR)"</button>
+                                </Show>
+                                <button on:click=show_all>"Clear filters"</button>
+                            </div>
+                        </div>
+                    </Show>
+                    <div class="split-view">
+                        <div
+                            class="split-main"
+                            on:dragover=|ev: web_sys::DragEvent| ev.prevent_default()
+                            on:drop=move |ev: web_sys::DragEvent| drop_files(false, ev)
+                        >
+                        <For
+                            each=move || scan_overflow.get()
+                            key=|o| (o.root.clone(), o.shown)
+                            children=move |o: ScanOverflow| {
+                                let root = o.root.clone();
+                                view! {
+                                    <div class="scan-overflow">
+                                        <span>
+                                            {format!("…and {} more in {}", format_count(o.hidden), o.root)}
+                                        </span>
+                                        <button
+                                            disabled=move || scanning.get()
+                                            on:click=move |_| load_more(root.clone())
+                                        >
+                                            "Load more"
+                                        </button>
+                                    </div>
+                                }
+                            }
+                        />
+                        <GroupedFileList
+                            files=sorted_files
+                            roots=root_directories
+                            active_root_filter=active_root_filter
+                            flat_view=flat_view
+                            badge_mode=tag_badge_mode
+                            selected_file_paths=selected_file_paths
+                            on_toggle=toggle_file_selection
+                            sort_column=sort_column
+                            sort_direction=sort_direction
+                            on_sort=toggle_sort
+                            set_selected_file_paths=set_selected_file_paths
+                            last_selected_file_path=last_selected_file_path
+                            set_last_selected_file_path=set_last_selected_file_path
+                            _recommended_map=file_recommended_tags_map
+                            recommended_info_map=file_recommended_info_map
+                            show_recommended=show_recommended
+                            all_tags=all_tags
+                            set_all_files=set_all_files
+                            set_displayed_files=set_displayed_files
+                            set_file_tags_map=set_file_tags_map
+                            on_open=open_with_action
+                            accept_threshold=accept_threshold
+                            recommend_sources=recommend_sources
+                            on_apply_recommended=apply_recommended
+                            rows_draggable=split_view
+                            on_drag_start=move |path: String| {
+                                let sel = selected_file_paths.get_untracked();
+                                let paths = if sel.contains(&path) { sel } else { vec![path] };
+                                set_file_drag.set(Some(FileDrag { from_split: false, paths }));
+                            }
+                        />
+                        {move || file_window.get().filter(|w| w.total > w.rows).map(|w| {
+                            let first = w.previous.len() * WINDOW_SIZE;
+                            view! {
+                                <div class="scan-overflow window-pager">
+                                    <span>
+                                        {format!(
+                                            "{}–{} of {}",
+                                            format_count(first + 1),
+                                            format_count(first + w.rows),
+                                            format_count(w.total)
+                                        )}
+                                    </span>
+                                    <div>
+                                        <button disabled=w.previous.is_empty() on:click=move |_| step_window(false)>"Previous"</button>
+                                        <button disabled=w.next.is_none() on:click=move |_| step_window(true)>"Next"</button>
+                                    </div>
+                                </div>
+                            }
+                        })}
+                        </div>
+                        {move || split_view.get().then(|| view! {
+                            <div
+                                class="split-side"
+                                on:dragover=|ev: web_sys::DragEvent| ev.prevent_default()
+                                on:drop=move |ev: web_sys::DragEvent| drop_files(true, ev)
+                            >
+                                <SplitPane
+                                    all_tags=all_tags
+                                    all_files=all_files
+                                    tag_ids=split_tag_ids
+                                    set_tag_ids=set_split_tag_ids
+                                    use_and=split_use_and
+                                    set_use_and=set_split_use_and
+                                    on_drag_start=move |paths| set_file_drag.set(Some(FileDrag { from_split: true, paths }))
+                                />
+                            </div>
+                        })}
+                    </div>
                 </div>
 
                 <div
@@ -1140,28 +2904,46 @@ pub fn App() -> impl IntoView {
                                 let set_tot = set_batch_total;
                                 let cancel_sig = batch_cancel;
                                 move |_| {
-                                    let files = sel.get();
+                                    let sources = recommend_sources.get_untracked();
+                                    let files: Vec<String> = sel.get().into_iter().filter(|p| sources.includes(p)).collect();
                                     if files.is_empty() { return; }
                                     let tags = tags_sig.get();
-                                    let label_names: Vec<String> = tags.iter().map(|t| t.name.clone()).collect();
+                                    let label_names: Vec<String> = active_tags(&tags).into_iter().map(|t| t.name).collect();
                                     let tk = core::cmp::min(label_names.len(), 8);
+                                    let known = all_files.get_untracked();
+                                    let file_tags = file_tags_map.get_untracked();
                                     set_tot.set(files.len());
                                     set_prog.set(0);
                                     set_run.set(true);
                                     set_show.set(true);
                                     spawn_local(async move {
-                                        let mut done = 0usize;
-                                        for path in files {
+                                        let existing_of = |path: &str| -> Vec<String> {
+                                            known
+                                                .iter()
+                                                .find(|f| f.path == path)
+                                                .and_then(|f| file_tags.get(&f.id))
+                                                .map(|t| t.iter().map(|t| t.name.clone()).collect())
+                                                .unwrap_or_default()
+                                        };
+                                        let (images, others): (Vec<String>, Vec<String>) =
+                                            files.into_iter().partition(|p| sources.route(p) == Some(Route::Vision));
+                                        if !images.is_empty() {
+                                            let batch = images.iter().map(|p| (p.clone(), existing_of(p))).collect();
+                                            let results = leptos_recommender::generate_for_images(batch, label_names.clone(), tk, &sources, Some(String::from("https://api.siliconflow.cn/v1")), None).await;
+                                            let mut map = file_recommended_info_map.get_untracked();
+                                            map.extend(results.into_iter().filter(|(_, list)| !list.is_empty()));
+                                            set_info.set(map);
+                                            set_prog.set(images.len());
+                                        }
+                                        for path in others {
                                             if cancel_sig.get_untracked() { break; }
-                                            let list_ext = leptos_recommender::generate_for_file(path.clone(), label_names.clone(), tk, 0.6, Some(String::from("https://api.siliconflow.cn/v1")), None).await;
-                                            if !list_ext.is_empty() {
-                                                let list: Vec<RecommendItem> = list_ext.into_iter().map(|ri| RecommendItem { name: ri.name, score: ri.score, source: ri.source }).collect();
+                                            let list = leptos_recommender::generate_for_file(path.clone(), label_names.clone(), existing_of(&path), tk, &sources, Some(String::from("https://api.siliconflow.cn/v1")), None).await;
+                                            if !list.is_empty() {
                                                 let mut map = file_recommended_info_map.get_untracked();
                                                 map.insert(path.clone(), list);
                                                 set_info.set(map);
                                             }
-                                            done += 1;
-                                            set_prog.set(done);
+                                            set_prog.update(|p| *p += 1);
                                         }
                                         set_run.set(false);
                                         set_batch_cancel.set(false);
@@ -1191,6 +2973,7 @@ pub fn App() -> impl IntoView {
                                         <input
                                             type="text"
                                             placeholder="Type tag name and press Enter..."
+                                            id="sidebar-tag-input"
                                             prop:value=new_tag_input_sidebar
                                             on:input=move |e| set_new_tag_input_sidebar.set(event_target_value(&e))
                                             on:keydown=move |e| {
@@ -1198,8 +2981,11 @@ pub fn App() -> impl IntoView {
                                                     let name = new_tag_input_sidebar.get().trim().to_string();
                                                     if !name.is_empty() {
                                                         let paths = selected_file_paths.get();
+                                                        let tags = all_tags.get_untracked();
                                                         spawn_local(async move {
-                                                            let args = CreateTagArgs { name: name.clone(), parent_id: None, color: None };
+                                                            let auto_color = serde_wasm_bindgen::from_value::<bool>(invoke("get_auto_color_tags", JsValue::NULL).await).unwrap_or(true);
+                                                            let color = auto_color.then(|| next_tag_color(&tags));
+                                                            let args = CreateTagArgs { name: name.clone(), parent_id: None, color, icon: None };
                                                             let result = invoke("create_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
 
                                                             if let Ok(tid) = serde_wasm_bindgen::from_value::<u32>(result) {
@@ -1218,9 +3004,46 @@ pub fn App() -> impl IntoView {
                                             }
                                         />
                                     </div>
+                                    {move || {
+                                        let source = co_tags_for.get()?;
+                                        let tags = all_tags.get();
+                                        let files = all_files.get();
+                                        let tags_map = file_tags_map.get();
+                                        let paths = selected_file_paths.get();
+                                        // Already on every selected file: nothing to suggest
+                                        let on_all = |tid: u32| paths.iter().all(|p| {
+                                            files.iter().find(|f| &f.path == p)
+                                                .and_then(|f| tags_map.get(&f.id))
+                                                .is_some_and(|ts| ts.iter().any(|t| t.id == tid))
+                                        });
+                                        let chips: Vec<(TagInfo, u32)> = co_tags.get().into_iter()
+                                            .filter(|(id, _)| !on_all(*id))
+                                            .filter_map(|(id, shared)| tags.iter().find(|t| t.id == id).cloned().map(|t| (t, shared)))
+                                            .collect();
+                                        if chips.is_empty() {
+                                            return None;
+                                        }
+                                        let source_name = tags.iter().find(|t| t.id == source).map(|t| t.name.clone()).unwrap_or_default();
+                                        Some(view! {
+                                            <div class="co-tags">
+                                                <span class="co-tags-label">{format!("Often tagged with \"{}\":", source_name)}</span>
+                                                {chips.into_iter().map(|chip| {
+                                                    let (tag, shared) = chip;
+                                                    let id = tag.id;
+                                                    view! {
+                                                        <button class="co-tag-chip"
+                                                            title=format!("On {} file(s) that have \"{}\"", shared, source_name)
+                                                            style=tag.display_color().map(|c| format!("border-color: {}; color: {}", c, c)).unwrap_or_default()
+                                                            on:click=move |_| toggle_tag_on_selection(id, true)
+                                                        >{format!("+ {}", tag.name)}</button>
+                                                    }
+                                                }).collect_view()}
+                                            </div>
+                                        })
+                                    }}
                                     <div class="tag-list">
                                         <For
-                                            each=move || all_tags.get()
+                                            each=move || if show_archived.get() { all_tags.get() } else { active_tags(&all_tags.get()) }
                                             key=|t| t.id
                                             children=move |t| {
                                                 let tid = t.id;
@@ -1258,39 +3081,10 @@ pub fn App() -> impl IntoView {
                                                             type="checkbox"
                                                             checked=is_checked
                                                             on:change=move |e| {
-                                                                let checked = event_target_checked(&e);
-                                                                let ps = selected_file_paths.get();
-
-                                                                if checked {
-                                                                    // Add tag to all selected file paths (DB entry will be created if missing)
-                                                                    for p in &ps {
-                                                                        let pc = p.clone();
-                                                                        spawn_local(async move {
-                                                                            let args = AddFileTagArgs { file_path: pc, tag_id: tid };
-                                                                            let _ = invoke("add_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                                                        });
-                                                                    }
-                                                                } else {
-                                                                    // Remove tag only from files present in DB
-                                                                    let all_files_info = all_files.get();
-                                                                    for p in &ps {
-                                                                        if let Some(file_info) = all_files_info.iter().find(|f| &f.path == p) {
-                                                                            let file_id = file_info.id;
-                                                                            spawn_local(async move {
-                                                                                let args = RemoveFileTagArgs { file_id, tag_id: tid };
-                                                                                let _ = invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                                                            });
-                                                                        }
-                                                                    }
-                                                                }
-
-                                                                // Reload only the affected files immediately
-                                                                spawn_local(async move {
-                                                                    load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
-                                                                });
+                                                                toggle_tag_on_selection(tid, event_target_checked(&e));
                                                             }
                                                         />
-                                                        <span style=t.color.map(|c| format!("color: {}", c)).unwrap_or_default()>{tname}</span>
+                                                        <span style=t.display_color().map(|c| format!("color: {}", c)).unwrap_or_default()>{tname}</span>
                                                     </label>
                                                 }
                                             }
@@ -1300,60 +3094,242 @@ pub fn App() -> impl IntoView {
                             </div>
                         }
                     }}
+                    <TagHistory selected_file_paths=selected_file_paths file_tags_map=file_tags_map />
                 </div>
             </div>
 
-            {move || show_add_tag_dialog.get().then(|| view! {
-                <div class="modal-overlay" on:click=move |_| set_show_add_tag_dialog.set(false)>
-                    <div class="modal" on:click={|e| e.stop_propagation()}>
-                        <h3>"Add New Tag"</h3>
-                        <input
-                            type="text"
-                            placeholder="Tag name"
-                            prop:value=new_tag_name
-                            on:input=move |e| set_new_tag_name.set(event_target_value(&e))
-                        />
-                        <button on:click=create_tag_action>"Create"</button>
-                        <button on:click=move |_| set_show_add_tag_dialog.set(false)>"Cancel"</button>
-                    </div>
-                </div>
-            })}
+            <SettingsDialog
+                show=show_settings
+                set_show=set_show_settings
+                set_safety_policy=set_safety_policy
+                set_open_actions=set_open_actions
+                set_recommend_sources=set_recommend_sources
+                set_manual_refresh=set_manual_refresh
+                set_large_library=set_large_library
+                on_saved=move || {
+                    // Entering large library mode starts at the first window of the current filter
+                    if large_library.get_untracked() != file_window.get_untracked().is_some() {
+                        let tag_ids = selected_tag_ids.get_untracked();
+                        file_window.set(large_library.get_untracked().then(|| FileWindow {
+                            filter: (!tag_ids.is_empty()).then(|| TagFilter { tag_ids, use_and: use_and_logic.get_untracked() }),
+                            roots: active_root_filter.get_untracked(),
+                            ..Default::default()
+                        }));
+                    }
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <PreviewDialog path=preview_path set_path=set_preview_path />
+            <Toast message=toast set_message=set_toast />
+            <ClipboardPrompt
+                paths=clipboard_paths
+                set_paths=set_clipboard_paths
+                on_tag=move |paths| {
+                    set_clipboard_paths.set(Vec::new());
+                    open_paths(paths);
+                }
+            />
+            <CleanupDialog
+                show=show_cleanup
+                set_show=set_show_cleanup
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+                on_relink=move || set_show_relink.set(true)
+                on_duplicates=move || set_show_duplicates.set(true)
+                safety_policy=safety_policy
+            />
+            <DuplicatesDialog
+                show=show_duplicates
+                set_show=set_show_duplicates
+                all_tags=all_tags
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+                safety_policy=safety_policy
+            />
+            <NlQueryDialog
+                query=nl_query
+                pending=nl_pending
+                set_pending=set_nl_pending
+                all_tags=all_tags
+                on_apply=apply_nl_filter
+            />
+            <TaxonomyDialog
+                show=show_taxonomy
+                set_show=set_show_taxonomy
+                all_tags=all_tags
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <ImportTagsDialog
+                show=show_import_tags
+                set_show=set_show_import_tags
+                on_imported=move || spawn_local(async move {
+                    load_tags(set_all_tags).await;
+                    load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                })
+            />
+            <RootDefaultsDialog
+                root=editing_root_defaults
+                set_root=set_editing_root_defaults
+                all_tags=all_tags
+                defaults=root_defaults
+                on_saved=saved_root_defaults
+            />
+            <RemoteRootDialog
+                show=show_remote_root
+                set_show=set_show_remote_root
+                on_added=move |root| {
+                    spawn_local(async move {
+                        if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<String>>(invoke("get_root_directories", JsValue::NULL).await) {
+                            set_root_directories.set(list);
+                        }
+                        set_toast.set(Some(format!("Added {}", root)));
+                        handle_scan_directory(
+                            root_directories,
+                            set_scanning,
+                            set_scanned_files,
+                            set_all_files,
+                            set_displayed_files,
+                            set_file_tags_map,
+                        );
+                    });
+                }
+            />
+            <RelinkDialog
+                show=show_relink
+                set_show=set_show_relink
+                roots=root_directories
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <DiagnosticsDialog show=show_diagnostics set_show=set_show_diagnostics />
+            <VerifyDialog
+                show=show_verify
+                set_show=set_show_verify
+                roots=root_directories
+                tag_ids=selected_tag_ids
+                use_and=use_and_logic
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <BatchEstimateDialog
+                estimate=batch_estimate
+                set_estimate=set_batch_estimate
+                on_confirm=run_recommend_all
+            />
+            <RecommendReviewDialog
+                show=show_review
+                set_show=set_show_review
+                recommendations=file_recommended_info_map
+                set_recommendations=set_file_recommended_info_map
+                all_tags=all_tags
+                threshold=accept_threshold
+                set_threshold=set_accept_threshold
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <ScriptConsole
+                show=show_script
+                set_show=set_show_script
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <WhatsNew />
+            <ResumeBatchPrompt
+                on_resumed=move |summary| {
+                    set_toast.set(Some(summary));
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+
+            <TagContextMenu
+                target=tag_menu
+                set_target=set_tag_menu
+                all_tags=all_tags
+                on_new_child=move |id| {
+                    set_new_tag_parent.set(Some(id));
+                    set_show_add_tag_dialog.set(true);
+                }
+                on_delete=move |id| {
+                    set_delete_target_tag_id.set(Some(id));
+                    set_show_delete_tag_confirm.set(true);
+                }
+                on_examples=move |id| set_examples_tag.set(Some(id))
+                on_changed=move || {
+                    spawn_local(async move {
+                        load_tags(set_all_tags).await;
+                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                    });
+                }
+            />
+            <TagExamplesDialog
+                tag_id=examples_tag
+                set_tag_id=set_examples_tag
+                all_tags=all_tags
+                selected_paths=selected_file_paths
+            />
+            <AddTagDialog
+                show=show_add_tag_dialog
+                set_show=set_show_add_tag_dialog
+                all_tags=all_tags
+                initial_parent=new_tag_parent
+                on_created=move || spawn_local(async move { load_tags(set_all_tags).await; })
+            />
 
             {move || show_purge_confirm.get().then(|| view! {
                 <div class="modal-overlay" on:click=move |_| set_show_purge_confirm.set(false)>
                     <div class="modal" on:click={|e| e.stop_propagation()}>
                         <h3>"Confirm Purge"</h3>
                         <p>"Purge ALL files in database? This cannot be undone."</p>
+                        {move || safety_policy.get().type_to_confirm.then(|| view! {
+                            <p>
+                                {format!("Type \"{}\" to confirm: ", purge_phrase(None))}
+                                <input
+                                    type="text"
+                                    prop:value=move || purge_typed.get()
+                                    on:input=move |e| set_purge_typed.set(event_target_value(&e))
+                                />
+                            </p>
+                        })}
                         <div style="display:flex; gap:8px;">
-                            <button on:click={
-                                let set_all = set_all_files;
-                                let set_disp = set_displayed_files;
-                                let set_tags_map = set_file_tags_map;
-                                let set_modal = set_show_purge_confirm;
-                                move |_| {
-                                    spawn_local(async move {
-                                        let dbp = invoke("get_db_path", JsValue::NULL).await;
-                                        if let Ok(p) = serde_wasm_bindgen::from_value::<String>(dbp.clone()) {
-                                            web_sys::console::log_1(&format!("[UI] DB path={}", p).into());
-                                        }
-                                        let before = invoke("get_files_count", JsValue::NULL).await;
-                                        if let Ok(cnt) = serde_wasm_bindgen::from_value::<u32>(before.clone()) {
-                                            web_sys::console::log_1(&format!("[UI] files count before purge={}", cnt).into());
-                                        }
-                                        let res = invoke("purge_all_files", JsValue::NULL).await;
-                                        match serde_wasm_bindgen::from_value::<u32>(res.clone()) {
-                                            Ok(cnt) => web_sys::console::log_1(&format!("[UI] purge_all_files ok, count={}", cnt).into()),
-                                            Err(e) => web_sys::console::error_1(&format!("[UI] purge_all_files parse error: {:?}; raw={:?}", e, res).into()),
-                                        }
-                                        let after = invoke("get_files_count", JsValue::NULL).await;
-                                        if let Ok(cnt) = serde_wasm_bindgen::from_value::<u32>(after.clone()) {
-                                            web_sys::console::log_1(&format!("[UI] files count after purge={}", cnt).into());
-                                        }
-                                        load_all_files(set_all, set_disp, set_tags_map).await;
-                                        set_modal.set(false);
-                                    });
+                            <button
+                                prop:disabled=move || safety_policy.get().type_to_confirm && purge_typed.get().trim() != purge_phrase(None)
+                                on:click=move |_| {
+                                    let typed = Some(purge_typed.get_untracked()).filter(|t| !t.is_empty());
+                                    purge_all(typed);
+                                    set_show_purge_confirm.set(false);
                                 }
-                            }>"Confirm"</button>
+                            >"Confirm"</button>
                             <button on:click=move |_| set_show_purge_confirm.set(false)>"Cancel"</button>
                         </div>
                     </div>
@@ -1367,6 +3343,12 @@ pub fn App() -> impl IntoView {
                 set_update_current,
                 update_latest,
                 set_update_latest,
+                update_notes,
+                set_update_notes,
+                update_channel,
+                set_update_channel,
+                update_suppressed,
+                set_update_suppressed,
                 update_has,
                 set_update_has,
                 update_error,
@@ -1381,7 +3363,7 @@ pub fn App() -> impl IntoView {
                 set_update_total,
             }})}
 
-            {move || show_delete_tag_confirm.get().then(|| view! {
+            {move || (show_delete_tag_confirm.get() && safety_policy.get().confirm).then(|| view! {
                 <div class="modal-overlay" on:click=move |_| set_show_delete_tag_confirm.set(false)>
                     <div class="modal" on:click={|e| e.stop_propagation()}>
                         {move || {
@@ -1391,48 +3373,11 @@ pub fn App() -> impl IntoView {
                         }}
                         <p>"This will also delete its child tags and relationships."</p>
                         <div style="display:flex; gap:8px;">
-                            <button on:click={
-                                let set_modal = set_show_delete_tag_confirm;
-                                let set_sel = set_selected_tag_ids;
-                                let sel_ids = selected_tag_ids;
-                                let tags_sig = all_tags;
-                                let use_and = use_and_logic;
-                                let set_disp = set_displayed_files;
-                                let all_files_sig = all_files;
-                                let set_reload = set_reload_tags_trigger;
-                                let del_tid_sig = delete_target_tag_id;
-                                move |_| {
-                                    let maybe_id = del_tid_sig.get_untracked();
-                                    if let Some(id) = maybe_id {
-                                        let mut current = sel_ids.get_untracked();
-                                        let all = tags_sig.get_untracked();
-                                        let mut stack = vec![id];
-                                        let mut subtree_ids: Vec<u32> = Vec::new();
-                                        while let Some(x) = stack.pop() {
-                                            subtree_ids.push(x);
-                                            for t in all.iter().filter(|t| t.parent_id == Some(x)) { stack.push(t.id); }
-                                        }
-                                        let remove_set: std::collections::HashSet<u32> = subtree_ids.iter().copied().collect();
-                                        current.retain(|tid| !remove_set.contains(tid));
-                                        set_sel.set(current.clone());
-                                        let logic = use_and.get_untracked();
-                                        if current.is_empty() {
-                                            set_disp.set(all_files_sig.get_untracked());
-                                        } else {
-                                            filter_files(current.clone(), logic, set_disp, all_files_sig.get_untracked());
-                                        }
-                                        spawn_local(async move {
-                                            let args = DeleteTagArgs { id };
-                                            let res = invoke("delete_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                            match serde_wasm_bindgen::from_value::<()> (res.clone()) {
-                                                Ok(_) => web_sys::console::log_1(&format!("[UI] delete_tag ok: {}", id).into()),
-                                                Err(e) => web_sys::console::error_1(&format!("[UI] delete_tag error: {:?}; raw={:?}", e, res).into()),
-                                            }
-                                        });
-                                        set_reload.update(|v| *v += 1);
-                                        set_modal.set(false);
-                                    }
+                            <button on:click=move |_| {
+                                if let Some(id) = delete_target_tag_id.get_untracked() {
+                                    delete_tag_now(id);
                                 }
+                                set_show_delete_tag_confirm.set(false);
                             }>"Confirm"</button>
                             <button on:click=move |_| set_show_delete_tag_confirm.set(false)>"Cancel"</button>
                         </div>
@@ -1479,4 +3424,8 @@ struct UpdateInfo {
     current: String,
     latest: Option<String>,
     has_update: bool,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    suppressed: bool,
 }
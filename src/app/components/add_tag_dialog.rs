@@ -0,0 +1,172 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::invoke;
+use crate::app::types::{CreateTagArgs, TagInfo};
+use crate::app::utils::TAG_PALETTE;
+
+const TAG_ICONS: &[&str] = &["📁", "⭐", "📌", "🔥", "💼", "🏠", "🎵", "🎬", "📷", "📚", "💡", "✅"];
+
+// Depth-first (id, indented label) list in tree order, for the parent selector
+fn tag_options(tags: &[TagInfo]) -> Vec<(u32, String)> {
+    fn walk(tags: &[TagInfo], parent: Option<u32>, depth: usize, out: &mut Vec<(u32, String)>) {
+        for t in tags.iter().filter(|t| t.parent_id == parent) {
+            out.push((t.id, format!("{}{}", "\u{a0}\u{a0}".repeat(depth), t.name)));
+            walk(tags, Some(t.id), depth + 1, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(tags, None, 0, &mut out);
+    out
+}
+
+#[component]
+pub fn AddTagDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    // Parent preselected when the dialog opens (e.g. "New child tag")
+    initial_parent: ReadSignal<Option<u32>>,
+    // Called after each successful create so the caller can reload tags
+    on_created: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (name, set_name) = signal(String::new());
+    let (parent, set_parent) = signal(None::<u32>);
+    let (color, set_color) = signal(None::<String>);
+    let (icon, set_icon) = signal(None::<String>);
+    let (create_another, set_create_another) = signal(false);
+
+    Effect::new(move |_| {
+        if show.get() {
+            set_parent.set(initial_parent.get_untracked());
+        }
+    });
+
+    let close = move || {
+        set_show.set(false);
+        set_name.set(String::new());
+        set_parent.set(None);
+        set_color.set(None);
+        set_icon.set(None);
+    };
+
+    // "Create another" keeps parent, color and icon so sibling tags can be entered quickly
+    let create = move || {
+        let tag_name = name.get_untracked().trim().to_string();
+        if tag_name.is_empty() {
+            return;
+        }
+        let args = CreateTagArgs {
+            name: tag_name,
+            parent_id: parent.get_untracked(),
+            color: color.get_untracked(),
+            icon: icon.get_untracked(),
+        };
+        let again = create_another.get_untracked();
+        spawn_local(async move {
+            let _ = invoke("create_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            on_created();
+            if again {
+                set_name.set(String::new());
+            } else {
+                close();
+            }
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| close()>
+                <div class="modal add-tag-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Add New Tag"</h3>
+                    <input
+                        type="text"
+                        placeholder="Tag name"
+                        autofocus=true
+                        prop:value=name
+                        on:input=move |e| set_name.set(event_target_value(&e))
+                        on:keydown=move |e| {
+                            if e.key() == "Enter" {
+                                create();
+                            } else if e.key() == "Escape" {
+                                close();
+                            }
+                        }
+                    />
+                    <label class="add-tag-field">
+                        "Parent"
+                        <select
+                            prop:value=move || parent.get().map(|p| p.to_string()).unwrap_or_default()
+                            on:change=move |e| set_parent.set(event_target_value(&e).parse::<u32>().ok())
+                        >
+                            <option value="">"(none)"</option>
+                            {move || tag_options(&all_tags.get()).into_iter().map(|(id, label)| view! {
+                                <option value=id.to_string()>{label}</option>
+                            }).collect_view()}
+                        </select>
+                    </label>
+                    <div class="add-tag-field">
+                        "Color"
+                        <div class="swatches">
+                            <button
+                                class="swatch swatch-none"
+                                class:active=move || color.get().is_none()
+                                title="No color"
+                                on:click=move |_| set_color.set(None)
+                            >"∅"</button>
+                            {TAG_PALETTE.iter().map(|c| {
+                                let c = c.to_string();
+                                let c_active = c.clone();
+                                let c_set = c.clone();
+                                view! {
+                                    <button
+                                        class="swatch"
+                                        class:active=move || color.get().as_deref() == Some(c_active.as_str())
+                                        style=format!("background: {}", c)
+                                        title=c.clone()
+                                        on:click=move |_| set_color.set(Some(c_set.clone()))
+                                    ></button>
+                                }
+                            }).collect_view()}
+                        </div>
+                    </div>
+                    <div class="add-tag-field">
+                        "Icon"
+                        <div class="swatches">
+                            <button
+                                class="swatch swatch-none"
+                                class:active=move || icon.get().is_none()
+                                title="No icon"
+                                on:click=move |_| set_icon.set(None)
+                            >"∅"</button>
+                            {TAG_ICONS.iter().map(|i| {
+                                let i = i.to_string();
+                                let i_active = i.clone();
+                                let i_set = i.clone();
+                                view! {
+                                    <button
+                                        class="swatch"
+                                        class:active=move || icon.get().as_deref() == Some(i_active.as_str())
+                                        on:click=move |_| set_icon.set(Some(i_set.clone()))
+                                    >{i}</button>
+                                }
+                            }).collect_view()}
+                        </div>
+                    </div>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=create_another
+                            on:change=move |e| set_create_another.set(event_target_checked(&e))
+                        />
+                        "Create another"
+                    </label>
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| create()>"Create"</button>
+                        <button on:click=move |_| close()>"Cancel"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,50 @@
+use leptos::prelude::*;
+
+use crate::app::types::BatchEstimate;
+use crate::app::utils::{format_count, format_file_size};
+
+// Confirmation before "Recommend All" on a priced endpoint, with the estimated
+// token use, image upload size and cost of the run.
+#[component]
+pub fn BatchEstimateDialog(
+    estimate: ReadSignal<Option<BatchEstimate>>,
+    set_estimate: WriteSignal<Option<BatchEstimate>>,
+    on_confirm: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        {move || estimate.get().map(|est| {
+            let currency = if est.currency.trim().is_empty() { "$".to_string() } else { est.currency.trim().to_string() };
+            view! {
+                <div class="modal-overlay" on:click=move |_| set_estimate.set(None)>
+                    <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                        <h3>"Recommend All"</h3>
+                        <div class="settings-row">
+                            <span>"Files sent to a model"</span>
+                            <span>{format_count(est.files)}</span>
+                        </div>
+                        <div class="settings-row">
+                            <span>"Images"</span>
+                            <span>{format!("{} ({} upload)", format_count(est.image_files), format_file_size(est.image_bytes))}</span>
+                        </div>
+                        <div class="settings-row">
+                            <span>"Estimated tokens"</span>
+                            <span>{format!("~{} in / ~{} out", est.input_tokens, est.output_tokens)}</span>
+                        </div>
+                        <div class="settings-row">
+                            <span>"Estimated cost"</span>
+                            <span>{format!("~{} {:.4}", currency, est.cost)}</span>
+                        </div>
+                        <p class="settings-hint">"Token counts are approximate; plugins and file-name rules are free and not counted."</p>
+                        <div style="display:flex; gap:8px; margin-top:8px;">
+                            <button on:click=move |_| {
+                                set_estimate.set(None);
+                                on_confirm();
+                            }>"Run"</button>
+                            <button on:click=move |_| set_estimate.set(None)>"Cancel"</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
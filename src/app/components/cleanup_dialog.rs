@@ -0,0 +1,173 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{ApplyCleanupArgs, CleanupReport, DuplicateTags, MergeTagsArgs, SafetyPolicy};
+
+#[component]
+pub fn CleanupDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    // Called after a fix so the caller can reload tags and files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+    // Opens the re-link tool for files that went missing
+    on_relink: impl Fn() + Copy + Send + Sync + 'static,
+    // Opens the duplicate file review
+    on_duplicates: impl Fn() + Copy + Send + Sync + 'static,
+    safety_policy: ReadSignal<SafetyPolicy>,
+) -> impl IntoView {
+    let (report, set_report) = signal(None::<CleanupReport>);
+    let (duplicates, set_duplicates) = signal(Vec::<DuplicateTags>::new());
+    let (status, set_status) = signal(None::<String>);
+
+    let load = move || {
+        spawn_local(async move {
+            let val = invoke("cleanup_report", wasm_bindgen::JsValue::NULL).await;
+            set_report.set(serde_wasm_bindgen::from_value::<CleanupReport>(val).ok());
+            let val = invoke("near_duplicate_tags", wasm_bindgen::JsValue::NULL).await;
+            set_duplicates.set(serde_wasm_bindgen::from_value(val).unwrap_or_default());
+        });
+    };
+
+    // Re-run the report every time the dialog opens
+    Effect::new(move |_| {
+        if show.get() {
+            set_status.set(None);
+            set_report.set(None);
+            load();
+        }
+    });
+
+    let fix = move |category: &'static str| {
+        if safety_policy.get_untracked().confirm {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Remove these items? This cannot be undone.").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+        }
+        spawn_local(async move {
+            let args = ApplyCleanupArgs { category: category.to_string() };
+            match try_invoke("apply_cleanup", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    let n = v.as_f64().unwrap_or(0.0) as usize;
+                    set_status.set(Some(format!("Removed {} item(s)", n)));
+                    on_changed();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Cleanup failed".to_string()))),
+            }
+            load();
+        });
+    };
+
+    let merge = move |pair: DuplicateTags| {
+        if safety_policy.get_untracked().confirm {
+            let message = format!("Merge \"{}\" into \"{}\"?", pair.source_name, pair.target_name);
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&message).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+        }
+        spawn_local(async move {
+            let args = MergeTagsArgs { source_id: pair.source_id, target_id: pair.target_id };
+            match try_invoke("merge_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => {
+                    set_status.set(Some(format!("Merged \"{}\" into \"{}\"", pair.source_name, pair.target_name)));
+                    on_changed();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Merge failed".to_string()))),
+            }
+            load();
+        });
+    };
+
+    // One report row: count, title, up to a few sample names and a fix button
+    let row = move |title: &'static str, category: &'static str, count: usize, samples: Vec<String>| {
+        let more = count.saturating_sub(samples.len());
+        view! {
+            <div class="cleanup-row">
+                <div class="cleanup-info">
+                    <strong>{format!("{} · {}", title, count)}</strong>
+                    <div class="cleanup-samples">
+                        {samples.join(", ")}
+                        {(more > 0).then(|| format!(" and {} more", more))}
+                    </div>
+                </div>
+                <button prop:disabled=count == 0 on:click=move |_| fix(category)>"Fix"</button>
+            </div>
+        }
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Cleanup"</h3>
+                    {move || match report.get() {
+                        None => view! { <p class="settings-hint">"Checking library..."</p> }.into_any(),
+                        Some(r) => {
+                            const SAMPLES: usize = 5;
+                            let tag_names: Vec<String> = r.unused_tags.iter().take(SAMPLES).map(|t| t.name.clone()).collect();
+                            let untagged: Vec<String> = r.untagged_files.iter().take(SAMPLES).cloned().collect();
+                            let orphaned: Vec<String> = r.orphaned_files.iter().take(SAMPLES).cloned().collect();
+                            let missing: Vec<String> = r.missing_files.iter().take(SAMPLES).cloned().collect();
+                            let missing_count = r.missing_files.len();
+                            view! {
+                                <div>
+                                    {row("Unused tags", "unusedTags", r.unused_tags.len(), tag_names)}
+                                    {row("Files with no tags", "untaggedFiles", r.untagged_files.len(), untagged)}
+                                    {row("Files from removed roots", "orphanedFiles", r.orphaned_files.len(), orphaned)}
+                                    {row("Tagged files missing from disk", "missingFiles", missing_count, missing)}
+                                    {(missing_count > 0).then(|| view! {
+                                        <div class="cleanup-row">
+                                            <div class="cleanup-info cleanup-samples">"Moved outside the app? Find them by content instead of forgetting them."</div>
+                                            <button on:click=move |_| {
+                                                set_show.set(false);
+                                                on_relink();
+                                            }>"Re-link..."</button>
+                                        </div>
+                                    })}
+                                    {row("Dangling tag links", "danglingLinks", r.dangling_links as usize, Vec::new())}
+                                    <div class="cleanup-row">
+                                        <div class="cleanup-info cleanup-samples">"Same file tracked more than once? Union their tags or keep a single copy."</div>
+                                        <button on:click=move |_| {
+                                            set_show.set(false);
+                                            on_duplicates();
+                                        }>"Duplicate files..."</button>
+                                    </div>
+                                    {move || {
+                                        let pairs = duplicates.get();
+                                        (!pairs.is_empty()).then(|| view! {
+                                            <h4>{format!("Possible duplicate tags · {}", pairs.len())}</h4>
+                                            {pairs.into_iter().map(|pair| {
+                                                let label = format!("\"{}\" ({}) → \"{}\" ({})", pair.source_name, pair.source_count, pair.target_name, pair.target_count);
+                                                let similar = format!("{:.0}% similar", pair.similarity * 100.0);
+                                                view! {
+                                                    <div class="cleanup-row">
+                                                        <div class="cleanup-info">
+                                                            <strong>{label}</strong>
+                                                            <div class="cleanup-samples">{similar}</div>
+                                                        </div>
+                                                        <button title="Move the files of the first tag to the second and delete the first"
+                                                            on:click=move |_| merge(pair.clone())>"Merge"</button>
+                                                    </div>
+                                                }
+                                            }).collect_view()}
+                                        })
+                                    }}
+                                </div>
+                            }.into_any()
+                        }
+                    }}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
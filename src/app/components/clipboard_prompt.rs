@@ -0,0 +1,50 @@
+use leptos::prelude::*;
+use std::time::Duration;
+
+const PROMPT_DURATION: Duration = Duration::from_secs(10);
+
+// Small corner prompt shown when file paths are copied to the clipboard (see the
+// clipboard watcher setting). Ignoring it is fine; it goes away on its own.
+#[component]
+pub fn ClipboardPrompt(
+    paths: ReadSignal<Vec<String>>,
+    set_paths: WriteSignal<Vec<String>>,
+    // Selects the paths for tagging
+    on_tag: impl Fn(Vec<String>) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (generation, set_generation) = signal(0u32);
+
+    Effect::new(move |_| {
+        if paths.get().is_empty() {
+            return;
+        }
+        let current = generation.get_untracked() + 1;
+        set_generation.set(current);
+        set_timeout(
+            move || {
+                if generation.get_untracked() == current {
+                    set_paths.set(Vec::new());
+                }
+            },
+            PROMPT_DURATION,
+        );
+    });
+
+    let label = move || {
+        let copied = paths.get();
+        match copied.as_slice() {
+            [one] => format!("Tag \"{}\"?", tagme_paths::file_name(one).unwrap_or(one)),
+            many => format!("Tag {} copied items?", many.len()),
+        }
+    };
+
+    view! {
+        <Show when=move || !paths.get().is_empty()>
+            <div class="clipboard-prompt" title=move || paths.get().join("\n")>
+                <span class="clipboard-prompt-label">{label}</span>
+                <button on:click=move |_| on_tag(paths.get_untracked())>"Tag"</button>
+                <button on:click=move |_| set_paths.set(Vec::new())>"×"</button>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,114 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::app::api::try_invoke;
+use crate::app::types::Metric;
+use crate::app::utils::format_date;
+
+fn label(name: &str) -> &str {
+    match name {
+        "scan" => "Folder scan",
+        "llm.tags" => "LLM tags (file name)",
+        "llm.content" => "LLM tags (content)",
+        "llm.query" => "LLM search query",
+        "llm.taxonomy" => "LLM taxonomy",
+        "query.filter" => "Tag filter query",
+        "query.page" => "File list page",
+        "query.window" => "Large library window",
+        "cache.hash" => "Stored file hashes",
+        "cache.archive" => "Extracted archive entries",
+        "cache.remote" => "Remote file copies",
+        other => other,
+    }
+}
+
+fn ms(value: f64) -> String {
+    if value >= 1000.0 {
+        format!("{:.1} s", value / 1000.0)
+    } else {
+        format!("{:.0} ms", value)
+    }
+}
+
+// Local counters for spotting slow setups: how long scans, LLM calls and list
+// queries take and how often the caches hit. Nothing here leaves the machine.
+#[component]
+pub fn DiagnosticsDialog(show: ReadSignal<bool>, set_show: WriteSignal<bool>) -> impl IntoView {
+    let (metrics, set_metrics) = signal(Vec::<Metric>::new());
+    let (status, set_status) = signal(None::<String>);
+
+    let load = move || {
+        spawn_local(async move {
+            match try_invoke("get_metrics", JsValue::NULL).await {
+                Ok(v) => set_metrics.set(serde_wasm_bindgen::from_value(v).unwrap_or_default()),
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not load metrics".to_string()))),
+            }
+        });
+    };
+
+    Effect::new(move |_| {
+        if show.get() {
+            set_status.set(None);
+            load();
+        }
+    });
+
+    let reset = move |_| {
+        spawn_local(async move {
+            match try_invoke("reset_metrics", JsValue::NULL).await {
+                Ok(_) => set_metrics.set(Vec::new()),
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not reset metrics".to_string()))),
+            }
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal diagnostics-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Diagnostics"</h3>
+                    <p class="settings-hint">"Timings and cache hit rates measured on this computer. They are stored locally and never sent anywhere."</p>
+                    {move || {
+                        let (timings, caches): (Vec<Metric>, Vec<Metric>) = metrics.get().into_iter().partition(|m| m.kind == "timing");
+                        if timings.is_empty() && caches.is_empty() {
+                            return view! { <p class="settings-hint">"Nothing measured yet."</p> }.into_any();
+                        }
+                        view! {
+                            <table class="metrics-table">
+                                <tr><th>"Timing"</th><th>"Runs"</th><th>"Average"</th><th>"Slowest"</th><th>"Last"</th><th>"Updated"</th></tr>
+                                {timings.into_iter().map(|m| view! {
+                                    <tr>
+                                        <td>{label(&m.name).to_string()}</td>
+                                        <td>{m.count}</td>
+                                        <td>{ms(m.total_ms / m.count.max(1) as f64)}</td>
+                                        <td>{ms(m.max_ms)}</td>
+                                        <td>{ms(m.last_ms)}</td>
+                                        <td>{format_date(m.updated_at)}</td>
+                                    </tr>
+                                }).collect_view()}
+                                <tr><th>"Cache"</th><th>"Lookups"</th><th>"Hit rate"</th><th></th><th></th><th>"Updated"</th></tr>
+                                {caches.into_iter().map(|m| view! {
+                                    <tr>
+                                        <td>{label(&m.name).to_string()}</td>
+                                        <td>{m.count}</td>
+                                        <td>{format!("{:.0}%", m.hits as f64 * 100.0 / m.count.max(1) as f64)}</td>
+                                        <td></td>
+                                        <td></td>
+                                        <td>{format_date(m.updated_at)}</td>
+                                    </tr>
+                                }).collect_view()}
+                            </table>
+                        }.into_any()
+                    }}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| load()>"Refresh"</button>
+                        <button on:click=reset>"Reset"</button>
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
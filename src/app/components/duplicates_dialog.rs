@@ -0,0 +1,317 @@
+use std::collections::{HashMap, HashSet};
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::app::api::try_invoke;
+use crate::app::components::verify_dialog::listen_once;
+use crate::app::types::{
+    DuplicateAction, DuplicateGroup, DuplicateProgress, DuplicateReport, ResolveDuplicatesArgs, SafetyPolicy, TagInfo,
+};
+use crate::app::utils::format_file_size;
+
+// Groups listed in the dialog; the rest are still part of the plan
+const MAX_LISTED: usize = 200;
+
+// The copy kept by default: the one with the most tags, then the first path
+fn default_keep(group: &DuplicateGroup) -> u32 {
+    group
+        .files
+        .iter()
+        .rev()
+        .max_by_key(|f| f.tag_ids.len())
+        .map(|f| f.id)
+        .unwrap_or_default()
+}
+
+// Files with identical content across roots. The user picks the copy to keep in
+// each group, reviews the plan and runs it as a background job: tags can be
+// unioned across the copies and the other copies moved to the trash.
+#[component]
+pub fn DuplicatesDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    // Called after a run so the caller can reload files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+    safety_policy: ReadSignal<SafetyPolicy>,
+) -> impl IntoView {
+    let (groups, set_groups) = signal(None::<Vec<DuplicateGroup>>);
+    let (keep, set_keep) = signal(HashMap::<String, u32>::new());
+    let (skipped, set_skipped) = signal(HashSet::<String>::new());
+    let (union_tags, set_union_tags) = signal(true);
+    let (trash_others, set_trash_others) = signal(false);
+    let (reviewing, set_reviewing) = signal(false);
+    let (running, set_running) = signal(false);
+    let (progress, set_progress) = signal(None::<DuplicateProgress>);
+    let (report, set_report) = signal(None::<DuplicateReport>);
+    let (status, set_status) = signal(None::<String>);
+
+    let load = move || {
+        set_groups.set(None);
+        spawn_local(async move {
+            match try_invoke("find_duplicate_files", JsValue::NULL).await {
+                Ok(v) => {
+                    let found: Vec<DuplicateGroup> = serde_wasm_bindgen::from_value(v).unwrap_or_default();
+                    set_keep.set(found.iter().map(|g| (g.content_hash.clone(), default_keep(g))).collect());
+                    set_skipped.set(HashSet::new());
+                    set_groups.set(Some(found));
+                }
+                Err(e) => {
+                    set_groups.set(Some(Vec::new()));
+                    set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not look for duplicates".to_string())));
+                }
+            }
+        });
+    };
+
+    // The job keeps running with the dialog closed; reopening shows where it is
+    Effect::new(move |_| {
+        listen_once("tauri-duplicates-progress", "__TAGME_DUPLICATES_PROGRESS_LISTENER_SET", move |detail| {
+            if let Ok(p) = serde_wasm_bindgen::from_value::<DuplicateProgress>(detail) {
+                set_progress.set(Some(p));
+            }
+        });
+        listen_once("tauri-duplicates-finished", "__TAGME_DUPLICATES_FINISHED_LISTENER_SET", move |detail| {
+            set_running.set(false);
+            set_report.set(serde_wasm_bindgen::from_value::<DuplicateReport>(detail).ok());
+            on_changed();
+            load();
+        });
+        listen_once("tauri-duplicates-failed", "__TAGME_DUPLICATES_FAILED_LISTENER_SET", move |detail| {
+            set_running.set(false);
+            set_status.set(Some(detail.as_string().unwrap_or_else(|| "Duplicate cleanup failed".to_string())));
+        });
+    });
+
+    Effect::new(move |_| {
+        if show.get() && !running.get_untracked() {
+            set_status.set(None);
+            set_report.set(None);
+            set_reviewing.set(false);
+            load();
+        }
+    });
+
+    let plan = move || -> Vec<DuplicateAction> {
+        let keep = keep.get();
+        let skipped = skipped.get();
+        groups
+            .get()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|g| !skipped.contains(&g.content_hash))
+            .map(|g| DuplicateAction {
+                keep_id: keep.get(&g.content_hash).copied().unwrap_or_else(|| default_keep(&g)),
+                content_hash: g.content_hash,
+                union_tags: union_tags.get(),
+                trash_others: trash_others.get(),
+            })
+            .collect()
+    };
+
+    // One line per effect of the plan, shown before it runs
+    let summary = move || -> Vec<String> {
+        let actions = plan();
+        let skipped = skipped.get();
+        let groups: Vec<DuplicateGroup> = groups
+            .get()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|g| !skipped.contains(&g.content_hash))
+            .collect();
+        let mut lines = vec![format!("{} group(s) of duplicates", actions.len())];
+        if union_tags.get() {
+            let differing = groups.iter().filter(|g| g.files.iter().any(|f| f.tag_ids != g.files[0].tag_ids)).count();
+            lines.push(format!("Tags unioned in {} group(s) whose copies are tagged differently", differing));
+        }
+        if trash_others.get() {
+            let copies: usize = groups.iter().map(|g| g.files.len() - 1).sum();
+            let bytes: u64 = groups.iter().map(|g| g.size_bytes * (g.files.len() as u64 - 1)).sum();
+            lines.push(format!("{} copy(ies) moved to the trash, freeing {}", copies, format_file_size(bytes)));
+        }
+        lines
+    };
+
+    let run = move |_| {
+        let actions = plan();
+        if actions.is_empty() {
+            return;
+        }
+        if trash_others.get_untracked() && safety_policy.get_untracked().confirm {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Move the other copies to the trash?").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+        }
+        set_status.set(None);
+        set_report.set(None);
+        set_progress.set(None);
+        set_reviewing.set(false);
+        set_running.set(true);
+        spawn_local(async move {
+            let args = ResolveDuplicatesArgs { actions };
+            if let Err(e) = try_invoke("resolve_duplicates", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_running.set(false);
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Duplicate cleanup failed".to_string())));
+            }
+        });
+    };
+
+    let tag_names = move |ids: &[u32]| -> String {
+        let tags = all_tags.get_untracked();
+        ids.iter()
+            .filter_map(|id| tags.iter().find(|t| t.id == *id).map(|t| t.name.clone()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let group_view = move |group: DuplicateGroup| {
+        let hash = group.content_hash.clone();
+        let skip_hash = hash.clone();
+        let skip_hash_toggle = hash.clone();
+        let title = format!("{} · {} copies · {}", format_file_size(group.size_bytes), group.files.len(), &hash[..hash.len().min(12)]);
+        view! {
+            <div class="duplicate-group">
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || !skipped.get().contains(&skip_hash)
+                        on:change=move |e| {
+                            let include = event_target_checked(&e);
+                            let hash = skip_hash_toggle.clone();
+                            set_skipped.update(|s| {
+                                if include {
+                                    s.remove(&hash);
+                                } else {
+                                    s.insert(hash);
+                                }
+                            });
+                        }
+                    />
+                    <strong>{title}</strong>
+                </label>
+                {group.files.into_iter().map(|file| {
+                    let hash = hash.clone();
+                    let hash_set = hash.clone();
+                    let id = file.id;
+                    let tags = tag_names(&file.tag_ids);
+                    view! {
+                        <label class="cleanup-row">
+                            <input
+                                type="radio"
+                                name=format!("keep-{}", hash)
+                                title="Keep this copy"
+                                prop:checked=move || keep.get().get(&hash) == Some(&id)
+                                on:change=move |_| set_keep.update(|k| { k.insert(hash_set.clone(), id); })
+                            />
+                            <div class="cleanup-info">
+                                <div class="cleanup-samples">{file.path}</div>
+                                <div class="cleanup-samples">
+                                    {file.root.unwrap_or_default()}
+                                    {(!tags.is_empty()).then(|| format!(" · {}", tags))}
+                                </div>
+                            </div>
+                        </label>
+                    }
+                }).collect_view()}
+            </div>
+        }
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Duplicate files"</h3>
+                    <p class="settings-hint">"Tracked files with identical content, across all roots. Pick the copy to keep in each group."</p>
+                    <div style="display:flex; gap:12px; align-items:center;">
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || union_tags.get()
+                                prop:disabled=move || running.get()
+                                on:change=move |e| set_union_tags.set(event_target_checked(&e))
+                            />
+                            "Union tags across copies"
+                        </label>
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || trash_others.get()
+                                prop:disabled=move || running.get()
+                                on:change=move |e| set_trash_others.set(event_target_checked(&e))
+                            />
+                            "Keep only one copy (trash others)"
+                        </label>
+                    </div>
+                    {move || running.get().then(|| {
+                        let text = progress
+                            .get()
+                            .map(|p| format!("Resolved {} of {} group(s)", p.done, p.total))
+                            .unwrap_or_else(|| "Starting...".to_string());
+                        view! { <p class="settings-hint">{text}</p> }
+                    })}
+                    {move || report.get().map(|r| view! {
+                        <div>
+                            <p class="settings-hint">
+                                {format!(
+                                    "Added {} tag link(s); trashed {} copy(ies), freeing {}",
+                                    r.tagged,
+                                    r.trashed,
+                                    format_file_size(r.freed_bytes)
+                                )}
+                            </p>
+                            {r.errors.into_iter().map(|e| view! { <p class="settings-hint">{e}</p> }).collect_view()}
+                        </div>
+                    })}
+                    {move || match groups.get() {
+                        None => view! { <p class="settings-hint">"Looking for duplicates..."</p> }.into_any(),
+                        Some(list) if list.is_empty() => view! { <p class="settings-hint">"No duplicate files found."</p> }.into_any(),
+                        Some(list) => {
+                            let hidden = list.len().saturating_sub(MAX_LISTED);
+                            view! {
+                                <div class="verify-issues">
+                                    {list.into_iter().take(MAX_LISTED).map(group_view).collect_view()}
+                                    {(hidden > 0).then(|| view! { <p class="settings-hint">{format!("and {} more group(s)", hidden)}</p> })}
+                                </div>
+                            }.into_any()
+                        }
+                    }}
+                    {move || reviewing.get().then(|| view! {
+                        <div class="duplicate-plan">
+                            <h4>"Plan"</h4>
+                            {summary().into_iter().map(|line| view! { <p class="settings-hint">{line}</p> }).collect_view()}
+                        </div>
+                    })}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        {move || if reviewing.get() {
+                            view! {
+                                <button prop:disabled=move || running.get() on:click=run>"Run"</button>
+                                <button on:click=move |_| set_reviewing.set(false)>"Back"</button>
+                            }.into_any()
+                        } else {
+                            view! {
+                                <button
+                                    prop:disabled=move || {
+                                        running.get()
+                                            || plan().is_empty()
+                                            || (!union_tags.get() && !trash_others.get())
+                                    }
+                                    on:click=move |_| set_reviewing.set(true)
+                                >
+                                    "Review plan..."
+                                </button>
+                            }.into_any()
+                        }}
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
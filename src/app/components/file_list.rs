@@ -1,8 +1,8 @@
 use leptos::prelude::*;
 use leptos::task::spawn_local;
-use crate::app::types::{DisplayFile, SortColumn, SortDirection, TagInfo, FileInfo, OpenFileArgs, AddFileTagArgs};
-use crate::app::utils::{format_file_size, format_timestamp};
-use leptos_recommender::RecommendItem;
+use crate::app::types::{DisplayFile, SortColumn, SortDirection, TagBadge, TagInfo, FileInfo, OpenFileArgs, AddFileTagArgs, RemoveFileTagArgs};
+use crate::app::utils::{format_file_size, format_timestamp, recommend_label, tag_badges};
+use leptos_recommender::{Diff, RecommendItem, RecommendSources};
 use crate::app::api::invoke;
 use crate::app::files::load_all_files;
 
@@ -14,6 +14,8 @@ pub fn FileList(
     sort_column: ReadSignal<SortColumn>,
     sort_direction: ReadSignal<SortDirection>,
     on_sort: impl Fn(SortColumn) + 'static + Copy + Send,
+    // A row was dragged, with its path
+    on_drag_start: impl Fn(String) + 'static + Copy + Send,
 ) -> impl IntoView {
     let sort_indicator = move |col: SortColumn| {
         if sort_column.get() == col {
@@ -44,6 +46,15 @@ pub fn FileList(
                         <th class="sortable" on:click=move |_| on_sort(SortColumn::Date)>
                             "Modified" {move || sort_indicator(SortColumn::Date)}
                         </th>
+                        <th class="sortable" on:click=move |_| on_sort(SortColumn::LastTagged)>
+                            "Last tagged" {move || sort_indicator(SortColumn::LastTagged)}
+                        </th>
+                        <th class="sortable" on:click=move |_| on_sort(SortColumn::Opens)>
+                            "Opens" {move || sort_indicator(SortColumn::Opens)}
+                        </th>
+                        <th class="sortable" on:click=move |_| on_sort(SortColumn::LastOpened)>
+                            "Last opened" {move || sort_indicator(SortColumn::LastOpened)}
+                        </th>
                         <th>"Tags"</th>
                     </tr>
                 </thead>
@@ -58,13 +69,23 @@ pub fn FileList(
                             let file_path_for_checked = file_path.clone();
                             
                             let file_path_for_dblclick = file_path.clone();
+                            let file_path_for_drag = file_path.clone();
                             
                                     let tags_check = file.tags.clone();
+                                    let inherited = file.inherited_tags.clone();
+                                    let inherited_loop = file.inherited_tags.clone();
                                     let tags_loop = file.tags.clone();
                                     
                                     view! {
                                         <tr
                                             class:selected=move || selected_file_paths.get().contains(&file_path_for_class)
+                                            draggable="true"
+                                            on:dragstart=move |ev: web_sys::DragEvent| {
+                                                if let Some(dt) = ev.data_transfer() {
+                                                    let _ = dt.set_data("text/plain", &file_path_for_drag);
+                                                }
+                                                on_drag_start(file_path_for_drag.clone());
+                                            }
                                             on:dblclick=move |_| {
                                                 let path = file_path_for_dblclick.clone();
                                                 spawn_local(async move {
@@ -89,20 +110,35 @@ pub fn FileList(
                                             </td>
                                             <td>{format_file_size(file.size_bytes)}</td>
                                             <td>{format_timestamp(file.last_modified)}</td>
+                                            <td>{file.last_tagged.map(format_timestamp).unwrap_or_default()}</td>
+                                            <td>{open_count_cell(file.open_count)}</td>
+                                            <td>{file.last_opened_at.map(format_timestamp).unwrap_or_default()}</td>
                                             <td class="file-tags">
                                                 <Show
-                                                    when=move || !tags_check.is_empty()
+                                                    when=move || !tags_check.is_empty() || !inherited.is_empty()
                                                     fallback=|| view! { <span class="not-in-db">"Not tagged"</span> }
                                                 >
                                             {
                                                 let tags_inner = tags_loop.clone();
+                                                let inherited_inner = inherited_loop.clone();
                                                 view! {
                                                     <For
                                                         each=move || tags_inner.clone()
                                                         key=|tag| tag.id
                                                         children=move |tag| {
                                                             view! {
-                                                                <span class="tag-badge" style=move || tag.color.clone().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
+                                                                <span class="tag-badge" style=move || tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
+                                                                    {tag.name.clone()}
+                                                                </span>
+                                                            }
+                                                        }
+                                                    />
+                                                    <For
+                                                        each=move || inherited_inner.clone()
+                                                        key=|tag| tag.id
+                                                        children=move |tag| {
+                                                            view! {
+                                                                <span class="tag-badge inherited" title="Inherited from a tagged folder" style=move || tag.display_color().map(|c| format!("border-color: {}; color: {}", c, c)).unwrap_or_default()>
                                                                     {tag.name.clone()}
                                                                 </span>
                                                             }
@@ -122,11 +158,27 @@ pub fn FileList(
     }
 }
 
+// The "Opens" column; blank for files never opened from TagMe
+fn open_count_cell(n: u32) -> String {
+    if n > 0 { n.to_string() } else { String::new() }
+}
+
+// Diff of a suggestion against the file's current tags; a tag applied since the
+// suggestion was made counts as already there
+fn chip_diff(item: &RecommendItem, tags: &[TagInfo]) -> Diff {
+    match item.diff {
+        Diff::New if tags.iter().any(|t| t.name == item.name) => Diff::Has,
+        diff => diff,
+    }
+}
+
 #[component]
 pub fn GroupedFileList(
     files: impl Fn() -> Vec<DisplayFile> + 'static + Send,
     roots: ReadSignal<Vec<String>>,
-    active_root_filter: ReadSignal<Option<String>>,
+    active_root_filter: ReadSignal<Vec<String>>,
+    flat_view: ReadSignal<bool>,
+    badge_mode: ReadSignal<String>,
     selected_file_paths: ReadSignal<Vec<String>>,
     on_toggle: impl Fn(String) + 'static + Copy + Send + Sync,
     sort_column: ReadSignal<SortColumn>,
@@ -142,13 +194,17 @@ pub fn GroupedFileList(
     set_all_files: WriteSignal<Vec<FileInfo>>,
     set_displayed_files: WriteSignal<Vec<FileInfo>>,
     set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
+    // Double-click on a row, with the file's extension ("" for folders)
+    on_open: impl Fn(String, String) + 'static + Copy + Send + Sync,
+    // Suggestions scoring at least this are applied by the per-file "Apply all" button
+    accept_threshold: ReadSignal<f32>,
+    // Whether suggestions the file already has are hidden or dimmed
+    recommend_sources: ReadSignal<RecommendSources>,
+    on_apply_recommended: impl Fn(String) + 'static + Copy + Send + Sync,
+    // Rows can be dragged (to the other list of the split view)
+    rows_draggable: ReadSignal<bool>,
+    on_drag_start: impl Fn(String) + 'static + Copy + Send + Sync,
 ) -> impl IntoView {
-    fn is_under_root(file_path: &str, root: &str) -> bool {
-        let mut r = root.replace('/', "\\").to_lowercase();
-        if !r.ends_with('\\') { r.push('\\'); }
-        let f = file_path.replace('/', "\\").to_lowercase();
-        f.starts_with(&r) || f == root.replace('/', "\\").to_lowercase()
-    }
     let sort_indicator = move |col: SortColumn| {
         if sort_column.get() == col {
             match sort_direction.get() {
@@ -166,23 +222,36 @@ pub fn GroupedFileList(
                 let all = files();
                 let roots_vec = roots.get();
                 let filter = active_root_filter.get();
-                let groups: Vec<(String, Vec<DisplayFile>)> = roots_vec.into_iter().map(|r| {
-                    if let Some(ref f) = filter {
-                        if &r != f { return (r.clone(), Vec::<DisplayFile>::new()); }
+                // Each file shows under the deepest root containing it, so nested roots don't repeat rows
+                let groups: Vec<(String, Vec<DisplayFile>)> = roots_vec.iter().map(|r| {
+                    if !filter.is_empty() && !filter.contains(r) {
+                        return (r.clone(), Vec::<DisplayFile>::new());
                     }
                     let v = all
                         .iter()
+                        .filter(|f| tagme_paths::longest_root(&f.path, roots_vec.iter().map(|s| s.as_str())) == Some(r.as_str()))
                         .cloned()
-                        .filter(|f| is_under_root(&f.path, &r))
                         .collect::<Vec<_>>();
-                    (r, v)
+                    (r.clone(), v)
                 }).collect();
 
                 let total: usize = groups.iter().map(|(_, v)| v.len()).sum();
+                let flat = flat_view.get() && total > 0;
+                // Flat view keeps the overall sort order and tags each row with its root
+                let flat_rows: Vec<(String, DisplayFile)> = if flat {
+                    all.iter()
+                        .filter_map(|f| {
+                            let shown = groups.iter().filter(|(_, v)| !v.is_empty()).map(|(r, _)| r.as_str());
+                            tagme_paths::longest_root(&f.path, shown).map(|r| (r.to_string(), f.clone()))
+                        })
+                        .collect()
+                } else {
+                    all.iter().map(|f| (String::new(), f.clone())).collect()
+                };
 
                 view! {
                     <Show
-                        when=move || total == 0
+                        when=move || total == 0 || flat
                         fallback=move || {
                             let groups_clone = groups.clone();
                             view! {
@@ -215,6 +284,15 @@ pub fn GroupedFileList(
                                                                 <th class="sortable" on:click=move |_| on_sort(SortColumn::Date)>
                                                                     "Modified" {move || sort_indicator(SortColumn::Date)}
                                                                 </th>
+                                                                <th class="sortable" on:click=move |_| on_sort(SortColumn::LastTagged)>
+                                                                    "Last tagged" {move || sort_indicator(SortColumn::LastTagged)}
+                                                                </th>
+                                                                <th class="sortable" on:click=move |_| on_sort(SortColumn::Opens)>
+                                                                    "Opens" {move || sort_indicator(SortColumn::Opens)}
+                                                                </th>
+                                                                <th class="sortable" on:click=move |_| on_sort(SortColumn::LastOpened)>
+                                                                    "Last opened" {move || sort_indicator(SortColumn::LastOpened)}
+                                                                </th>
                                                                 <th>"Tags"</th>
                                                             </tr>
                                                         </thead>
@@ -229,18 +307,23 @@ pub fn GroupedFileList(
                                                                     let file_path_for_class = file_path.clone();
                                                                     let file_path_for_checked = file_path.clone();
                                                                     let file_path_for_dblclick = file_path.clone();
+                                                                    let file_path_for_drag = file_path.clone();
+                                                                    let ext_for_dblclick = if file.is_directory { String::new() } else { file.extension.clone() };
                                                                     let tags_check = file.tags.clone();
+                                                                    let inherited = file.inherited_tags.clone();
+                                                                    let inherited_loop = file.inherited_tags.clone();
                                                                     let tags_loop = file.tags.clone();
                                                                     view! {
                                                                         <tr
                                                                             class:selected=move || selected_file_paths.get().contains(&file_path_for_class)
-                                                                            on:dblclick=move |_| {
-                                                                                let path = file_path_for_dblclick.clone();
-                                                                                spawn_local(async move {
-                                                                                    let args = OpenFileArgs { path };
-                                                                                    let _ = invoke("open_file", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                                                                });
+                                                                            draggable=move || rows_draggable.get().then_some("true")
+                                                                            on:dragstart=move |ev: web_sys::DragEvent| {
+                                                                                if let Some(dt) = ev.data_transfer() {
+                                                                                    let _ = dt.set_data("text/plain", &file_path_for_drag);
+                                                                                }
+                                                                                on_drag_start(file_path_for_drag.clone());
                                                                             }
+                                                                            on:dblclick=move |_| on_open(file_path_for_dblclick.clone(), ext_for_dblclick.clone())
                                                                         >
                                                                             <td on:dblclick=|e| e.stop_propagation()>
                                                                                     <input
@@ -286,21 +369,36 @@ pub fn GroupedFileList(
                                                                             </td>
                                                                             <td>{format_file_size(file.size_bytes)}</td>
                                                                             <td>{format_timestamp(file.last_modified)}</td>
+                                                                            <td>{file.last_tagged.map(format_timestamp).unwrap_or_default()}</td>
+                                                                            <td>{open_count_cell(file.open_count)}</td>
+                                                                            <td>{file.last_opened_at.map(format_timestamp).unwrap_or_default()}</td>
                                                                             <td class="file-tags">
                                                                                 <Show
-                                                                                    when=move || !tags_check.is_empty()
+                                                                                    when=move || !tags_check.is_empty() || !inherited.is_empty()
                                                                                     fallback=|| view! { <span class="not-in-db">"Not tagged"</span> }
                                                                                 >
                                                                                     {
                                                                                         let tags_inner = tags_loop.clone();
+                                                                                        let inherited_inner = inherited_loop.clone();
                                                                                         view! {
                                                                                             <For
-                                                                                                each=move || tags_inner.clone()
-                                                                                                key=|tag| tag.id
-                                                                                                children=move |tag| {
+                                                                                                each=move || tag_badges(&tags_inner, &all_tags.get(), &badge_mode.get())
+                                                                                                key=|b| b.tag.id
+                                                                                                children=move |b: TagBadge| {
+                                                                                                    view! {
+                                                                                                        <span class="tag-badge" title=b.title.clone() style=move || b.tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
+                                                                                                            {b.label.clone()}
+                                                                                                        </span>
+                                                                                                    }
+                                                                                                }
+                                                                                            />
+                                                                                            <For
+                                                                                                each=move || tag_badges(&inherited_inner, &all_tags.get(), &badge_mode.get())
+                                                                                                key=|b| b.tag.id
+                                                                                                children=move |b: TagBadge| {
                                                                                                     view! {
-                                                                                                        <span class="tag-badge" style=move || tag.color.clone().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
-                                                                                                            {tag.name.clone()}
+                                                                                                        <span class="tag-badge inherited" title=format!("{} (inherited from a tagged folder)", b.title) style=move || b.tag.display_color().map(|c| format!("border-color: {}; color: {}", c, c)).unwrap_or_default()>
+                                                                                                            {b.label.clone()}
                                                                                                         </span>
                                                                                                     }
                                                                                                 }
@@ -312,39 +410,80 @@ pub fn GroupedFileList(
                                                                                 {
                                                                                     let fp_arc_for_recs = file_path_arc.clone();
                                                                                     let file_path_key_for_recs = file_path_for_toggle.clone();
+                                                                                    let tags_for_recs = file.tags.clone();
+                                                                                    let tags_for_each = file.tags.clone();
+                                                                                    let file_id_for_recs = file.db_id;
+                                                                                    let show_key_for_recs = file_path_for_toggle.clone();
+                                                                                    let tags_for_apply = file.tags.clone();
+                                                                                    let apply_key_for_recs = file_path_for_toggle.clone();
                                                                                     view! {
                                                                                         <div style="margin-top:4px; display:flex; gap:4px; flex-wrap:wrap;">
                                                                                             <For
                                                                                                 each=move || {
+                                                                                                    let hide = recommend_sources.get().hide_existing;
                                                                                                     recommended_info_map.get().get(&file_path_key_for_recs).cloned().unwrap_or_default()
+                                                                                                        .into_iter()
+                                                                                                        .filter(|ri| !(hide && chip_diff(ri, &tags_for_each) == Diff::Has))
+                                                                                                        .collect::<Vec<_>>()
                                                                                                 }
-                                                                                                key=|ri| ri.name.clone()
+                                                                                                key=|ri| (ri.name.clone(), ri.diff)
                                                                                                 children=move |ri: RecommendItem| {
                                                                                                     let fp_arc_local = fp_arc_for_recs.clone();
-                                                                                                    let label = if ri.source == "onnx" { format!("{} ·AI", ri.name) } else if ri.source == "llm" { format!("{} ·LLM", ri.name) } else if ri.source == "llm-vision" { format!("{} ·VL", ri.name) } else { ri.name.clone() };
+                                                                                                    let label = recommend_label(&ri);
                                                                                                     let title_attr = format!("score: {:.3}", ri.score);
                                                                                                     let tname = ri.name.clone();
-                                                                                                    view! {
-                                                                                                        <button style="background:#eee; color:#555; border:none; border-radius:10px; padding:2px 6px; cursor:pointer;"
-                                                                                                            title=title_attr
-                                                                                                            on:click=move |_| {
-                                                                                                                let fp = (*fp_arc_local).clone();
-                                                                                                                // lookup tag id by name
-                                                                                                                let mut found: Option<u32> = None;
-                                                                                                                for tg in all_tags.get().iter() { if tg.name == tname { found = Some(tg.id); break; } }
-                                                                                                                if let Some(tid) = found {
-                                                                                                                    let args = AddFileTagArgs { file_path: fp.clone(), tag_id: tid };
+                                                                                                    let diff = chip_diff(&ri, &tags_for_recs);
+                                                                                                    let file_id = file_id_for_recs;
+                                                                                                    let current = tags_for_recs.clone();
+                                                                                                    match diff {
+                                                                                                        Diff::Has => view! {
+                                                                                                            <span class="rec-chip rec-chip-has" title=format!("Already tagged ({})", title_attr)>{label}</span>
+                                                                                                        }.into_any(),
+                                                                                                        Diff::Unlikely => view! {
+                                                                                                            <button class="rec-chip rec-chip-unlikely"
+                                                                                                                title=format!("Tagged, but the model rates it unlikely ({}). Click to remove.", title_attr)
+                                                                                                                on:click=move |_| {
+                                                                                                                    let (Some(file_id), Some(tag)) = (file_id, current.iter().find(|t| t.name == tname)) else { return; };
+                                                                                                                    let args = RemoveFileTagArgs { file_id, tag_id: tag.id };
                                                                                                                     spawn_local(async move {
-                                                                                                                        let _ = invoke("add_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                                                                                                        // Reload to reflect DB enrollment and new tag
+                                                                                                                        let _ = invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
                                                                                                                         load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
                                                                                                                     });
                                                                                                                 }
-                                                                                                            }
-                                                                                                        >{label}</button>
+                                                                                                            >{format!("− {}", label)}</button>
+                                                                                                        }.into_any(),
+                                                                                                        Diff::New => view! {
+                                                                                                            <button style="background:#eee; color:#555; border:none; border-radius:10px; padding:2px 6px; cursor:pointer;"
+                                                                                                                title=title_attr
+                                                                                                                on:click=move |_| {
+                                                                                                                    let fp = (*fp_arc_local).clone();
+                                                                                                                    // lookup tag id by name
+                                                                                                                    let mut found: Option<u32> = None;
+                                                                                                                    for tg in all_tags.get().iter() { if tg.name == tname { found = Some(tg.id); break; } }
+                                                                                                                    if let Some(tid) = found {
+                                                                                                                        let args = AddFileTagArgs { file_path: fp.clone(), tag_id: tid };
+                                                                                                                        spawn_local(async move {
+                                                                                                                            let _ = invoke("add_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                                                                                                            // Reload to reflect DB enrollment and new tag
+                                                                                                                            load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                                                                                                                        });
+                                                                                                                    }
+                                                                                                                }
+                                                                                                            >{label}</button>
+                                                                                                        }.into_any(),
                                                                                                     }
                                                                                                 }
                                                                                             />
+                                                                                            <Show when=move || recommended_info_map.get().get(&show_key_for_recs).is_some_and(|items| items.iter().any(|i| chip_diff(i, &tags_for_apply) == Diff::New && i.score >= accept_threshold.get()))>
+                                                                                                {
+                                                                                                    let path = apply_key_for_recs.clone();
+                                                                                                    view! {
+                                                                                                        <button class="apply-recommended" title="Apply every suggestion at or above the accept threshold"
+                                                                                                            on:click=move |_| on_apply_recommended(path.clone())
+                                                                                                        >{move || format!("Apply all ≥ {:.2}", accept_threshold.get())}</button>
+                                                                                                    }
+                                                                                                }
+                                                                                            </Show>
                                                                                         </div>
                                                                                     }
                                                                                 }
@@ -354,7 +493,7 @@ pub fn GroupedFileList(
                                                                     }
                                                                 }
                                                             />
-                                                            {move || if group_files_for_empty.is_empty() { Some(view! { <tr><td colspan="6"><em>"No files in this root"</em></td></tr> }) } else { None }}
+                                                            {move || if group_files_for_empty.is_empty() { Some(view! { <tr><td colspan="9"><em>"No files in this root"</em></td></tr> }) } else { None }}
                                                         </tbody>
                                                     </table>
                                                 </div>
@@ -366,9 +505,8 @@ pub fn GroupedFileList(
                         }
                     >
                         {
-                            let all_clone = all.clone();
-                            let all_value = all_clone.clone();
-                            let all_paths = std::sync::Arc::new(all_clone.iter().map(|f| f.path.clone()).collect::<Vec<String>>());
+                            let all_value = flat_rows.clone();
+                            let all_paths = std::sync::Arc::new(flat_rows.iter().map(|(_, f)| f.path.clone()).collect::<Vec<String>>());
                             view! {
                                 <div>
                                 <table>
@@ -387,32 +525,47 @@ pub fn GroupedFileList(
                                             <th class="sortable" on:click=move |_| on_sort(SortColumn::Date)>
                                                 "Modified" {move || sort_indicator(SortColumn::Date)}
                                             </th>
+                                            <th class="sortable" on:click=move |_| on_sort(SortColumn::LastTagged)>
+                                                "Last tagged" {move || sort_indicator(SortColumn::LastTagged)}
+                                            </th>
+                                            <th class="sortable" on:click=move |_| on_sort(SortColumn::Opens)>
+                                                "Opens" {move || sort_indicator(SortColumn::Opens)}
+                                            </th>
+                                            <th class="sortable" on:click=move |_| on_sort(SortColumn::LastOpened)>
+                                                "Last opened" {move || sort_indicator(SortColumn::LastOpened)}
+                                            </th>
+                                            {flat.then(|| view! { <th>"Root"</th> })}
                                             <th>"Tags"</th>
                                         </tr>
                                     </thead>
                                     <tbody>
                                         <For
                                             each=move || all_value.clone()
-                                            key=|file| file.path.clone()
-                                            children=move |file| {
+                                            key=|(_, file)| file.path.clone()
+                                            children=move |(root, file)| {
                                                 let file_path = file.path.clone();
                                                 let file_path_for_toggle = file_path.clone();
                                                 let file_path_arc2 = std::sync::Arc::new(file_path_for_toggle.clone());
                                                 let file_path_for_class = file_path.clone();
                                                 let file_path_for_checked = file_path.clone();
                                                 let file_path_for_dblclick = file_path.clone();
+                                                let file_path_for_drag = file_path.clone();
+                                                let ext_for_dblclick = if file.is_directory { String::new() } else { file.extension.clone() };
                                                 let tags_check = file.tags.clone();
+                                                let inherited = file.inherited_tags.clone();
+                                                let inherited_loop = file.inherited_tags.clone();
                                                 let tags_loop = file.tags.clone();
                                                 view! {
                                                     <tr
                                                         class:selected=move || selected_file_paths.get().contains(&file_path_for_class)
-                                                        on:dblclick=move |_| {
-                                                            let path = file_path_for_dblclick.clone();
-                                                            spawn_local(async move {
-                                                                let args = OpenFileArgs { path };
-                                                                let _ = invoke("open_file", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-                                                            });
+                                                        draggable=move || rows_draggable.get().then_some("true")
+                                                        on:dragstart=move |ev: web_sys::DragEvent| {
+                                                            if let Some(dt) = ev.data_transfer() {
+                                                                let _ = dt.set_data("text/plain", &file_path_for_drag);
+                                                            }
+                                                            on_drag_start(file_path_for_drag.clone());
                                                         }
+                                                        on:dblclick=move |_| on_open(file_path_for_dblclick.clone(), ext_for_dblclick.clone())
                                                     >
                                                         <td on:dblclick=|e| e.stop_propagation()>
                                                             <input
@@ -458,21 +611,37 @@ pub fn GroupedFileList(
                                                         </td>
                                                         <td>{format_file_size(file.size_bytes)}</td>
                                                         <td>{format_timestamp(file.last_modified)}</td>
+                                                        <td>{file.last_tagged.map(format_timestamp).unwrap_or_default()}</td>
+                                                        <td>{open_count_cell(file.open_count)}</td>
+                                                        <td>{file.last_opened_at.map(format_timestamp).unwrap_or_default()}</td>
+                                                        {flat.then(|| view! { <td class="file-root" title=root.clone()>{root.clone()}</td> })}
                                                         <td class="file-tags">
                                                             <Show
-                                                                when=move || !tags_check.is_empty()
+                                                                when=move || !tags_check.is_empty() || !inherited.is_empty()
                                                                 fallback=|| view! { <span class="not-in-db">"Not tagged"</span> }
                                                             >
                                                                 {
                                                                     let tags_inner = tags_loop.clone();
+                                                                    let inherited_inner = inherited_loop.clone();
                                                                     view! {
                                                                         <For
-                                                                            each=move || tags_inner.clone()
-                                                                            key=|tag| tag.id
-                                                                            children=move |tag| {
+                                                                            each=move || tag_badges(&tags_inner, &all_tags.get(), &badge_mode.get())
+                                                                            key=|b| b.tag.id
+                                                                            children=move |b: TagBadge| {
                                                                                 view! {
-                                                                                    <span class="tag-badge" style=move || tag.color.clone().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
-                                                                                        {tag.name.clone()}
+                                                                                    <span class="tag-badge" title=b.title.clone() style=move || b.tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()>
+                                                                                        {b.label.clone()}
+                                                                                    </span>
+                                                                                }
+                                                                            }
+                                                                        />
+                                                                        <For
+                                                                            each=move || tag_badges(&inherited_inner, &all_tags.get(), &badge_mode.get())
+                                                                            key=|b| b.tag.id
+                                                                            children=move |b: TagBadge| {
+                                                                                view! {
+                                                                                    <span class="tag-badge inherited" title=format!("{} (inherited from a tagged folder)", b.title) style=move || b.tag.display_color().map(|c| format!("border-color: {}; color: {}", c, c)).unwrap_or_default()>
+                                                                                        {b.label.clone()}
                                                                                     </span>
                                                                                 }
                                                                             }
@@ -484,37 +653,78 @@ pub fn GroupedFileList(
                                                             {
                                                                 let fp_arc_for_recs = file_path_arc2.clone();
                                                                 let file_path_key_for_recs2 = file_path_for_toggle.clone();
+                                                                let tags_for_recs2 = file.tags.clone();
+                                                                let tags_for_each2 = file.tags.clone();
+                                                                let file_id_for_recs2 = file.db_id;
+                                                                let show_key_for_recs2 = file_path_for_toggle.clone();
+                                                                let tags_for_apply2 = file.tags.clone();
+                                                                let apply_key_for_recs2 = file_path_for_toggle.clone();
                                                                 view! {
                                                                     <div style="margin-top:4px; display:flex; gap:4px; flex-wrap:wrap;">
                                                                         <For
                                                                             each=move || {
+                                                                                let hide = recommend_sources.get().hide_existing;
                                                                                 recommended_info_map.get().get(&file_path_key_for_recs2).cloned().unwrap_or_default()
+                                                                                    .into_iter()
+                                                                                    .filter(|ri| !(hide && chip_diff(ri, &tags_for_each2) == Diff::Has))
+                                                                                    .collect::<Vec<_>>()
                                                                             }
-                                                                            key=|ri| ri.name.clone()
+                                                                            key=|ri| (ri.name.clone(), ri.diff)
                                                                             children=move |ri: RecommendItem| {
                                                                                 let fp_arc_local = fp_arc_for_recs.clone();
-                                                                                let label = if ri.source == "onnx" { format!("{} ·AI", ri.name) } else if ri.source == "llm" { format!("{} ·LLM", ri.name) } else if ri.source == "llm-vision" { format!("{} ·VL", ri.name) } else { ri.name.clone() };
+                                                                                let label = recommend_label(&ri);
                                                                                 let title_attr = format!("score: {:.3}", ri.score);
                                                                                 let tname = ri.name.clone();
-                                                                                view! {
-                                                                                    <button style="background:#eee; color:#555; border:none; border-radius:10px; padding:2px 6px; cursor:pointer;"
-                                                                                        title=title_attr
-                                                                                        on:click=move |_| {
-                                                                                            let fp = (*fp_arc_local).clone();
-                                                                                            let mut found: Option<u32> = None;
-                                                                                            for tg in all_tags.get().iter() { if tg.name == tname { found = Some(tg.id); break; } }
-                                                                                            if let Some(tid) = found {
-                                                                                                let args = AddFileTagArgs { file_path: fp.clone(), tag_id: tid };
+                                                                                let diff = chip_diff(&ri, &tags_for_recs2);
+                                                                                let file_id = file_id_for_recs2;
+                                                                                let current = tags_for_recs2.clone();
+                                                                                match diff {
+                                                                                    Diff::Has => view! {
+                                                                                        <span class="rec-chip rec-chip-has" title=format!("Already tagged ({})", title_attr)>{label}</span>
+                                                                                    }.into_any(),
+                                                                                    Diff::Unlikely => view! {
+                                                                                        <button class="rec-chip rec-chip-unlikely"
+                                                                                            title=format!("Tagged, but the model rates it unlikely ({}). Click to remove.", title_attr)
+                                                                                            on:click=move |_| {
+                                                                                                let (Some(file_id), Some(tag)) = (file_id, current.iter().find(|t| t.name == tname)) else { return; };
+                                                                                                let args = RemoveFileTagArgs { file_id, tag_id: tag.id };
                                                                                                 spawn_local(async move {
-                                                                                                    let _ = invoke("add_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                                                                                    let _ = invoke("remove_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
                                                                                                     load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
                                                                                                 });
                                                                                             }
-                                                                                        }
-                                                                                    >{label}</button>
+                                                                                        >{format!("− {}", label)}</button>
+                                                                                    }.into_any(),
+                                                                                    Diff::New => view! {
+                                                                                        <button style="background:#eee; color:#555; border:none; border-radius:10px; padding:2px 6px; cursor:pointer;"
+                                                                                            title=title_attr
+                                                                                            on:click=move |_| {
+                                                                                                let fp = (*fp_arc_local).clone();
+                                                                                                let mut found: Option<u32> = None;
+                                                                                                for tg in all_tags.get().iter() { if tg.name == tname { found = Some(tg.id); break; } }
+                                                                                                if let Some(tid) = found {
+                                                                                                    let args = AddFileTagArgs { file_path: fp.clone(), tag_id: tid };
+                                                                                                    spawn_local(async move {
+                                                                                                        let _ = invoke("add_file_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                                                                                        load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
+                                                                                                    });
+                                                                                                }
+                                                                                            }
+                                                                                        >{label}</button>
+                                                                                    }.into_any(),
                                                                                 }
                                                                             }
                                                                         />
+                                                                        <Show when=move || recommended_info_map.get().get(&show_key_for_recs2).is_some_and(|items| items.iter().any(|i| chip_diff(i, &tags_for_apply2) == Diff::New && i.score >= accept_threshold.get()))>
+                                                                            {
+                                                                                let path = apply_key_for_recs2.clone();
+                                                                                view! {
+                                                                                    <button class="apply-recommended" title="Apply every suggestion at or above the accept threshold"
+                                                                                        on:click=move |_| on_apply_recommended(path.clone())
+                                                                                    >{move || format!("Apply all ≥ {:.2}", accept_threshold.get())}</button>
+                                                                                }
+                                                                            }
+                                                                        </Show>
                                                                     </div>
                                                                 }
                                                             }
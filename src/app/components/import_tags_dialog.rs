@@ -0,0 +1,212 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::app::api::try_invoke;
+use crate::app::types::{DigikamReport, ExportObsidianArgs, ExportTagsArgs, ImportTagsArgs, ImportTagsReport, ObsidianReport};
+
+const PLACEHOLDER: &str = "Projects\n  Client A\n  Client B\nPhotos/Travel\nurgent, Status, #f7768e";
+
+// Seeds the tag tree from a pasted list: one tag path per line (indent to
+// nest), or CSV rows of name, parent path and color. Also exports the tree as
+// a Markdown or OPML outline or as Obsidian notes, and imports a digiKam library.
+#[component]
+pub fn ImportTagsDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    // Called after an import so the caller can reload tags (and files, which a
+    // digiKam import tags)
+    on_imported: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (text, set_text) = signal(String::new());
+    let (importing, set_importing) = signal(false);
+    let (report, set_report) = signal(None::<ImportTagsReport>);
+    let (status, set_status) = signal(None::<String>);
+    let (format, set_format) = signal("markdown".to_string());
+    let (outline, set_outline) = signal(String::new());
+    let (digikam, set_digikam) = signal(None::<DigikamReport>);
+
+    Effect::new(move |_| {
+        if show.get() {
+            set_report.set(None);
+            set_status.set(None);
+            set_outline.set(String::new());
+            set_digikam.set(None);
+        }
+    });
+
+    let import_digikam = move |_| {
+        set_importing.set(true);
+        set_digikam.set(None);
+        set_status.set(None);
+        spawn_local(async move {
+            match try_invoke("import_digikam", JsValue::NULL).await {
+                Ok(v) => {
+                    if let Ok(Some(r)) = serde_wasm_bindgen::from_value::<Option<DigikamReport>>(v) {
+                        set_digikam.set(Some(r));
+                        on_imported();
+                    }
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Import failed".to_string()))),
+            }
+            set_importing.set(false);
+        });
+    };
+
+    let preview = move |_| {
+        set_status.set(None);
+        spawn_local(async move {
+            let args = ExportTagsArgs { format: format.get_untracked() };
+            match try_invoke("export_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => set_outline.set(v.as_string().unwrap_or_default()),
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Export failed".to_string()))),
+            }
+        });
+    };
+
+    let save = move |_| {
+        set_status.set(None);
+        spawn_local(async move {
+            let args = ExportTagsArgs { format: format.get_untracked() };
+            match try_invoke("save_tag_outline", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    if let Some(path) = v.as_string() {
+                        set_status.set(Some(format!("Saved to {}", path)));
+                    }
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Export failed".to_string()))),
+            }
+        });
+    };
+
+    // "notes" (one per tag, into a vault folder) or "index" (a single note)
+    let export_obsidian = move |mode: &'static str| {
+        set_status.set(None);
+        spawn_local(async move {
+            let args = ExportObsidianArgs { mode: mode.to_string() };
+            match try_invoke("export_obsidian", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    if let Ok(Some(r)) = serde_wasm_bindgen::from_value::<Option<ObsidianReport>>(v) {
+                        let removed = if r.removed > 0 { format!(", {} outdated note(s) removed", r.removed) } else { String::new() };
+                        set_status.set(Some(format!("Wrote {} note(s) listing {} file(s) to {}{}", r.notes, r.files, r.path, removed)));
+                    }
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Export failed".to_string()))),
+            }
+        });
+    };
+
+    let import = move |_| {
+        set_importing.set(true);
+        set_report.set(None);
+        set_status.set(None);
+        spawn_local(async move {
+            let args = ImportTagsArgs { text: text.get_untracked() };
+            match try_invoke("import_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    set_report.set(serde_wasm_bindgen::from_value::<ImportTagsReport>(v).ok());
+                    on_imported();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Import failed".to_string()))),
+            }
+            set_importing.set(false);
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Import / export tags"</h3>
+                    <p class="settings-hint">
+                        "One tag per line. Use Parent/Child or indentation to nest, or CSV rows of name, parent path, color. Tags that already exist are kept."
+                    </p>
+                    <textarea
+                        class="import-tags-text"
+                        rows="12"
+                        placeholder=PLACEHOLDER
+                        prop:value=move || text.get()
+                        on:input=move |e| set_text.set(event_target_value(&e))
+                    ></textarea>
+                    {move || report.get().map(|r| view! {
+                        <div>
+                            <p class="settings-hint">
+                                {format!("Created {} tag(s); {} already existed", r.created, r.existing)}
+                            </p>
+                            {r.errors.into_iter().map(|e| view! { <p class="settings-hint">{e}</p> }).collect_view()}
+                        </div>
+                    })}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button
+                            prop:disabled=move || importing.get() || text.get().trim().is_empty()
+                            on:click=import
+                        >
+                            {move || if importing.get() { "Importing..." } else { "Import" }}
+                        </button>
+                    </div>
+                    <h4>"From digiKam"</h4>
+                    <p class="settings-hint">
+                        "Pick digikam4.db (in digiKam's database folder) to bring over its tag tree and tagged images. Close digiKam first."
+                    </p>
+                    <div style="display:flex; gap:8px;">
+                        <button prop:disabled=move || importing.get() on:click=import_digikam>"Import digiKam database..."</button>
+                    </div>
+                    {move || digikam.get().map(|r| view! {
+                        <div>
+                            <p class="settings-hint">
+                                {format!(
+                                    "Created {} tag(s) ({} already existed); tagged {} file(s) with {} new tag(s)",
+                                    r.tags_created, r.tags_existing, r.files_tagged, r.links_added
+                                )}
+                            </p>
+                            {(r.files_missing > 0).then(|| view! {
+                                <p class="settings-hint">{format!("{} image(s) are no longer on disk", r.files_missing)}</p>
+                            })}
+                            {(!r.unresolved_roots.is_empty()).then(|| view! {
+                                <p class="settings-hint">{format!("Collections not found, skipped: {}", r.unresolved_roots.join(", "))}</p>
+                            })}
+                            {r.errors.into_iter().map(|e| view! { <p class="settings-hint">{e}</p> }).collect_view()}
+                        </div>
+                    })}
+                    <h4>"Export"</h4>
+                    <div style="display:flex; gap:8px;">
+                        <select
+                            prop:value=move || format.get()
+                            on:change=move |e| {
+                                set_format.set(event_target_value(&e));
+                                set_outline.set(String::new());
+                            }
+                        >
+                            <option value="markdown">"Markdown"</option>
+                            <option value="opml">"OPML"</option>
+                        </select>
+                        <button on:click=preview>"Preview"</button>
+                        <button on:click=save>"Save..."</button>
+                    </div>
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button
+                            title="One note per tag with its files as links, written to a TagMe folder in your vault"
+                            on:click=move |_| export_obsidian("notes")
+                        >"Obsidian notes..."</button>
+                        <button
+                            title="All tags and their files in a single note"
+                            on:click=move |_| export_obsidian("index")
+                        >"Obsidian index note..."</button>
+                    </div>
+                    {move || (!outline.get().is_empty()).then(|| view! {
+                        <textarea
+                            class="import-tags-text"
+                            rows="12"
+                            readonly=true
+                            prop:value=move || outline.get()
+                        ></textarea>
+                    })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
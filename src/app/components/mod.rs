@@ -1,2 +1,28 @@
+pub mod add_tag_dialog;
+pub mod batch_estimate;
+pub mod cleanup_dialog;
+pub mod clipboard_prompt;
+pub mod diagnostics_dialog;
+pub mod duplicates_dialog;
 pub mod file_list;
+pub mod import_tags_dialog;
+pub mod nl_query;
+pub mod pinned_files;
+pub mod preview_dialog;
+pub mod recommend_review;
+pub mod relink_dialog;
+pub mod remote_root_dialog;
+pub mod root_defaults_dialog;
+pub mod resume_batch;
+pub mod script_console;
+pub mod settings_dialog;
+pub mod split_pane;
+pub mod tag_context_menu;
+pub mod tag_examples;
+pub mod tag_history;
 pub mod tag_tree;
+pub mod taxonomy_dialog;
+pub mod toast;
+pub mod verify_dialog;
+pub mod view_tabs;
+pub mod whats_new;
@@ -0,0 +1,74 @@
+use leptos::prelude::*;
+
+use crate::app::types::{NlFilter, TagInfo};
+use crate::app::utils::format_date;
+
+// Shows the filter the LLM made of a natural-language query so it can be
+// checked before the file list changes.
+#[component]
+pub fn NlQueryDialog(
+    query: ReadSignal<String>,
+    pending: ReadSignal<Option<NlFilter>>,
+    set_pending: WriteSignal<Option<NlFilter>>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    on_apply: impl Fn(NlFilter) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        {move || pending.get().map(|filter| {
+            let tags = all_tags.get_untracked();
+            let names: Vec<String> = filter
+                .tag_ids
+                .iter()
+                .filter_map(|id| tags.iter().find(|t| t.id == *id).map(|t| t.name.clone()))
+                .collect();
+            let joiner = if filter.use_and { " AND " } else { " OR " };
+            let dates = match (filter.modified_after, filter.modified_before) {
+                (None, None) => None,
+                (Some(a), None) => Some(format!("from {}", format_date(a))),
+                // `modified_before` is exclusive; show the last included day
+                (None, Some(b)) => Some(format!("until {}", format_date(b - 1))),
+                (Some(a), Some(b)) => Some(format!("{} to {}", format_date(a), format_date(b - 1))),
+            };
+            let empty = names.is_empty() && !filter.has_refinements();
+            let apply_filter = filter.clone();
+            view! {
+                <div class="modal-overlay" on:click=move |_| set_pending.set(None)>
+                    <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                        <h3>"Search"</h3>
+                        <p class="settings-hint">{format!("\"{}\"", query.get_untracked())}</p>
+                        <div class="settings-row">
+                            <span>"Tags"</span>
+                            <span>{if names.is_empty() { "any".to_string() } else { names.join(joiner) }}</span>
+                        </div>
+                        {(!filter.extensions.is_empty()).then(|| view! {
+                            <div class="settings-row">
+                                <span>"Types"</span>
+                                <span>{filter.extensions.join(", ")}</span>
+                            </div>
+                        })}
+                        {filter.name_contains.clone().map(|n| view! {
+                            <div class="settings-row">
+                                <span>"Name contains"</span>
+                                <span>{n}</span>
+                            </div>
+                        })}
+                        {dates.map(|d| view! {
+                            <div class="settings-row">
+                                <span>"Modified"</span>
+                                <span>{d}</span>
+                            </div>
+                        })}
+                        {empty.then(|| view! { <p class="settings-hint">"The query didn't map to any filter."</p> })}
+                        <div style="display:flex; gap:8px; margin-top:8px;">
+                            <button prop:disabled=empty on:click=move |_| {
+                                set_pending.set(None);
+                                on_apply(apply_filter.clone());
+                            }>"Apply"</button>
+                            <button on:click=move |_| set_pending.set(None)>"Cancel"</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
@@ -0,0 +1,73 @@
+use leptos::prelude::*;
+
+use crate::app::types::PinnedFile;
+
+// "Pinned" strip at the top of the file list: work-in-progress files that stay
+// in reach whatever the filter. Click selects the file for tagging, double-click
+// opens it.
+#[component]
+pub fn PinnedFiles(
+    pinned: ReadSignal<Vec<PinnedFile>>,
+    selected_file_paths: ReadSignal<Vec<String>>,
+    on_select: impl Fn(String) + Copy + Send + Sync + 'static,
+    // Path and extension ("" for folders), like a double-click in the list
+    on_open: impl Fn(String, String) + Copy + Send + Sync + 'static,
+    on_unpin: impl Fn(String) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        <Show when=move || !pinned.get().is_empty()>
+            <div class="pinned-files">
+                <span class="pinned-label">"Pinned"</span>
+                <For
+                    each=move || pinned.get()
+                    key=|p| (p.path.clone(), p.missing, p.tags.iter().map(|t| t.id).collect::<Vec<_>>())
+                    children=move |file: PinnedFile| {
+                        let path = file.path.clone();
+                        let name = tagme_paths::file_name(&path).unwrap_or(&path).to_string();
+                        let extension = if file.is_directory {
+                            String::new()
+                        } else {
+                            std::path::Path::new(&path).extension().unwrap_or_default().to_string_lossy().to_string()
+                        };
+                        let title = if file.missing { format!("{} (not found)", path) } else { path.clone() };
+                        let (select_path, open_path, unpin_path, selected_path) = (path.clone(), path.clone(), path.clone(), path);
+                        view! {
+                            <div
+                                class="pinned-file"
+                                class:selected=move || selected_file_paths.get().contains(&selected_path)
+                                class:missing=file.missing
+                                title=title
+                                on:click=move |_| on_select(select_path.clone())
+                                on:dblclick=move |_| on_open(open_path.clone(), extension.clone())
+                            >
+                                <span class="pinned-name">{if file.is_directory { "📁 " } else { "" }}{name}</span>
+                                {file
+                                    .tags
+                                    .iter()
+                                    .map(|tag| view! {
+                                        <span
+                                            class="tag-badge"
+                                            style=tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()
+                                        >
+                                            {tag.name.clone()}
+                                        </span>
+                                    })
+                                    .collect_view()}
+                                <button
+                                    class="view-tab-close"
+                                    title="Unpin"
+                                    on:click=move |ev| {
+                                        ev.stop_propagation();
+                                        on_unpin(unpin_path.clone());
+                                    }
+                                >
+                                    "×"
+                                </button>
+                            </div>
+                        }
+                    }
+                />
+            </div>
+        </Show>
+    }
+}
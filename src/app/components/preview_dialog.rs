@@ -0,0 +1,92 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::prelude::*;
+
+use crate::app::api::{open_file, try_invoke};
+use crate::app::types::OpenFileArgs;
+
+#[wasm_bindgen]
+extern "C" {
+    // URL the webview can load a local file from (asset protocol)
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = convertFileSrc)]
+    fn convert_file_src(path: &str) -> String;
+}
+
+const IMAGE_EXTS: [&str; 8] = ["jpg", "jpeg", "png", "gif", "webp", "bmp", "svg", "avif"];
+const VIDEO_EXTS: [&str; 5] = ["mp4", "webm", "mov", "m4v", "ogv"];
+const AUDIO_EXTS: [&str; 6] = ["mp3", "wav", "ogg", "flac", "m4a", "aac"];
+// Rendered by the webview itself in a frame
+const FRAME_EXTS: [&str; 6] = ["pdf", "txt", "md", "json", "html", "log"];
+
+// In-app viewer for the "preview" double-click action. Types the webview can't
+// show fall back to a button that opens the file externally.
+#[component]
+pub fn PreviewDialog(
+    path: ReadSignal<Option<String>>,
+    set_path: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (src, set_src) = signal(None::<String>);
+    let (error, set_error) = signal(None::<String>);
+
+    Effect::new(move |_| {
+        set_src.set(None);
+        set_error.set(None);
+        let Some(p) = path.get() else {
+            return;
+        };
+        spawn_local(async move {
+            let args = OpenFileArgs { path: p.clone() };
+            match try_invoke("allow_preview", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                // Remote files come back as their downloaded copy
+                Ok(local) => set_src.set(Some(convert_file_src(&local.as_string().unwrap_or(p)))),
+                Err(e) => set_error.set(Some(e.as_string().unwrap_or_else(|| "Preview failed".to_string()))),
+            }
+        });
+    });
+
+    let body = move || {
+        let p = path.get()?;
+        if let Some(msg) = error.get() {
+            return Some(view! { <p class="settings-hint">{msg}</p> }.into_any());
+        }
+        let url = src.get()?;
+        let ext = std::path::Path::new(&p)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let view = if IMAGE_EXTS.contains(&ext.as_str()) {
+            view! { <img class="preview-media" src=url /> }.into_any()
+        } else if VIDEO_EXTS.contains(&ext.as_str()) {
+            view! { <video class="preview-media" src=url controls=true autoplay=true></video> }.into_any()
+        } else if AUDIO_EXTS.contains(&ext.as_str()) {
+            view! { <audio src=url controls=true autoplay=true></audio> }.into_any()
+        } else if FRAME_EXTS.contains(&ext.as_str()) {
+            view! { <iframe class="preview-frame" src=url></iframe> }.into_any()
+        } else {
+            view! { <p class="settings-hint">"No preview for this file type."</p> }.into_any()
+        };
+        Some(view)
+    };
+
+    view! {
+        {move || path.get().map(|p| {
+            let name = tagme_paths::file_name(&p).unwrap_or(&p).to_string();
+            let external = p.clone();
+            view! {
+                <div class="modal-overlay" on:click=move |_| set_path.set(None)>
+                    <div class="modal preview-dialog" on:click={|e| e.stop_propagation()}>
+                        <h3 title=p.clone()>{name}</h3>
+                        <div class="preview-body">{body}</div>
+                        <div style="display:flex; gap:8px; margin-top:8px;">
+                            <button on:click=move |_| {
+                                let path = external.clone();
+                                spawn_local(open_file(path));
+                            }>"Open externally"</button>
+                            <button on:click=move |_| set_path.set(None)>"Close"</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
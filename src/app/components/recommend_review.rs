@@ -0,0 +1,178 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_recommender::{Diff, RecommendItem};
+use std::collections::{HashMap, HashSet};
+
+use crate::app::api::try_invoke;
+use crate::app::types::{AddTagsToFilesArgs, TagInfo};
+use crate::app::utils::recommend_label;
+
+// (file path, tag name) of every suggestion scoring at least `threshold`
+fn above(map: &HashMap<String, Vec<RecommendItem>>, threshold: f32) -> HashSet<(String, String)> {
+    map.iter()
+        .flat_map(|(path, items)| {
+            items.iter().filter(|i| i.diff == Diff::New && i.score >= threshold).map(move |i| (path.clone(), i.name.clone()))
+        })
+        .collect()
+}
+
+// Bulk review of the suggestions from "Recommend All". Checked suggestions are
+// applied, unchecked ones are dropped.
+#[component]
+pub fn RecommendReviewDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    recommendations: ReadSignal<HashMap<String, Vec<RecommendItem>>>,
+    set_recommendations: WriteSignal<HashMap<String, Vec<RecommendItem>>>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    // Score at or above which a suggestion starts checked; shared with the per-file "Apply" button
+    threshold: ReadSignal<f32>,
+    set_threshold: WriteSignal<f32>,
+    // Called after tags were applied so the caller can reload files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (selected, set_selected) = signal(HashSet::<(String, String)>::new());
+    let (applying, set_applying) = signal(false);
+    let (error, set_error) = signal(None::<String>);
+
+    Effect::new(move |_| {
+        if show.get() {
+            set_error.set(None);
+            set_selected.set(above(&recommendations.get_untracked(), threshold.get_untracked()));
+        }
+    });
+
+    let rows = move || {
+        let mut files: Vec<(String, Vec<RecommendItem>)> = recommendations
+            .get()
+            .into_iter()
+            .map(|(path, items)| (path, items.into_iter().filter(|i| i.diff == Diff::New).collect::<Vec<_>>()))
+            .filter(|(_, items)| !items.is_empty())
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    };
+
+    let accept = move |_| {
+        let map = recommendations.get_untracked();
+        let picked = selected.get_untracked();
+        let tags = all_tags.get_untracked();
+        let jobs: Vec<AddTagsToFilesArgs> = map
+            .iter()
+            .filter_map(|(path, items)| {
+                let tag_ids: Vec<u32> = items
+                    .iter()
+                    .filter(|i| picked.contains(&(path.clone(), i.name.clone())))
+                    .filter_map(|i| tags.iter().find(|t| t.name == i.name).map(|t| t.id))
+                    .collect();
+                (!tag_ids.is_empty()).then(|| AddTagsToFilesArgs { file_paths: vec![path.clone()], tag_ids })
+            })
+            .collect();
+        set_applying.set(true);
+        spawn_local(async move {
+            let mut failed = None;
+            for args in jobs {
+                if let Err(e) = try_invoke("add_tags_to_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                    failed = Some(e.as_string().unwrap_or_else(|| "Could not apply tags".to_string()));
+                    break;
+                }
+            }
+            set_applying.set(false);
+            on_changed();
+            match failed {
+                Some(msg) => set_error.set(Some(msg)),
+                None => {
+                    set_recommendations.set(HashMap::new());
+                    set_show.set(false);
+                }
+            }
+        });
+    };
+
+    let reject_all = move |_| {
+        set_recommendations.set(HashMap::new());
+        set_show.set(false);
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Review recommendations"</h3>
+                    <p class="settings-hint">"Checked suggestions are applied; the rest are dismissed."</p>
+                    <div class="settings-row">
+                        <span>"Threshold"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            step="0.05"
+                            style="width:70px;"
+                            prop:value=move || threshold.get().to_string()
+                            on:change=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<f32>() {
+                                    set_threshold.set(v.max(0.0));
+                                }
+                            }
+                        />
+                        <button on:click=move |_| set_selected.set(above(&recommendations.get_untracked(), threshold.get_untracked()))>
+                            "Select above threshold"
+                        </button>
+                        <button on:click=move |_| set_selected.set(above(&recommendations.get_untracked(), f32::MIN))>"Select all"</button>
+                        <button on:click=move |_| set_selected.set(HashSet::new())>"Select none"</button>
+                    </div>
+                    <div class="review-list">
+                        {move || {
+                            let files = rows();
+                            if files.is_empty() {
+                                return view! { <p class="settings-hint">"No recommendations. Run \"Recommend All\" first."</p> }.into_any();
+                            }
+                            files.into_iter().map(|(path, items)| {
+                                let name = tagme_paths::file_name(&path).unwrap_or(&path).to_string();
+                                view! {
+                                    <div class="review-file">
+                                        <div class="review-file-name" title=path.clone()>{name}</div>
+                                        <div class="review-chips">
+                                            {items.into_iter().map(|item| {
+                                                let key = (path.clone(), item.name.clone());
+                                                let check_key = key.clone();
+                                                let title = format!("score: {:.3}", item.score);
+                                                view! {
+                                                    <label class="review-chip" title=title>
+                                                        <input
+                                                            type="checkbox"
+                                                            prop:checked=move || selected.get().contains(&check_key)
+                                                            on:change=move |e| {
+                                                                let checked = event_target_checked(&e);
+                                                                let key = key.clone();
+                                                                set_selected.update(|s| {
+                                                                    if checked {
+                                                                        s.insert(key);
+                                                                    } else {
+                                                                        s.remove(&key);
+                                                                    }
+                                                                });
+                                                            }
+                                                        />
+                                                        {recommend_label(&item)}
+                                                    </label>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view().into_any()
+                        }}
+                    </div>
+                    {move || error.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button prop:disabled=move || applying.get() on:click=accept>
+                            {move || format!("Accept {} selected", selected.get().len())}
+                        </button>
+                        <button on:click=reject_all>"Reject all"</button>
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,170 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::collections::HashSet;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{ApplyRelinksArgs, FindRelinkCandidatesArgs, MissingFile, RelinkCandidate};
+
+// Re-attaches tagged files that vanished (moved outside the app) by finding the
+// same content under chosen folders. Matches are listed for review before anything
+// is written.
+#[component]
+pub fn RelinkDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    roots: ReadSignal<Vec<String>>,
+    // Called after files were re-linked so the caller can reload files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (missing, set_missing) = signal(Vec::<MissingFile>::new());
+    let (dirs, set_dirs) = signal(Vec::<String>::new());
+    let (candidates, set_candidates) = signal(None::<Vec<RelinkCandidate>>);
+    let (chosen, set_chosen) = signal(HashSet::<u32>::new());
+    let (searching, set_searching) = signal(false);
+    let (status, set_status) = signal(None::<String>);
+
+    let load_missing = move || {
+        spawn_local(async move {
+            let val = invoke("get_missing_files", wasm_bindgen::JsValue::NULL).await;
+            set_missing.set(serde_wasm_bindgen::from_value::<Vec<MissingFile>>(val).unwrap_or_default());
+        });
+    };
+
+    // Start from the configured roots each time the dialog opens
+    Effect::new(move |_| {
+        if show.get() {
+            set_dirs.set(roots.get_untracked());
+            set_candidates.set(None);
+            set_status.set(None);
+            load_missing();
+        }
+    });
+
+    let add_dir = move |_| {
+        spawn_local(async move {
+            let val = invoke("select_root_directory", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(Some(dir)) = serde_wasm_bindgen::from_value::<Option<String>>(val) {
+                set_dirs.update(|d| {
+                    if !d.contains(&dir) {
+                        d.push(dir);
+                    }
+                });
+            }
+        });
+    };
+
+    let search = move |_| {
+        set_searching.set(true);
+        set_status.set(None);
+        spawn_local(async move {
+            let args = FindRelinkCandidatesArgs { dirs: dirs.get_untracked() };
+            match try_invoke("find_relink_candidates", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    let found = serde_wasm_bindgen::from_value::<Vec<RelinkCandidate>>(v).unwrap_or_default();
+                    set_chosen.set(found.iter().map(|c| c.file_id).collect());
+                    set_candidates.set(Some(found));
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Search failed".to_string()))),
+            }
+            set_searching.set(false);
+        });
+    };
+
+    let apply = move |_| {
+        let picked = chosen.get_untracked();
+        let links: Vec<RelinkCandidate> = candidates
+            .get_untracked()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| picked.contains(&c.file_id))
+            .collect();
+        spawn_local(async move {
+            let args = ApplyRelinksArgs { links };
+            match try_invoke("apply_relinks", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    let n = v.as_f64().unwrap_or(0.0) as usize;
+                    set_status.set(Some(format!("Re-linked {} file(s)", n)));
+                    set_candidates.set(None);
+                    on_changed();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Re-link failed".to_string()))),
+            }
+            load_missing();
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Re-link missing files"</h3>
+                    <p class="settings-hint">
+                        {move || format!("{} tagged file(s) are missing from disk. Matches are found by content, so renamed files are found too.", missing.get().len())}
+                    </p>
+                    <div class="relink-dirs">
+                        <For
+                            each=move || dirs.get()
+                            key=|d| d.clone()
+                            children=move |d| {
+                                let dir = d.clone();
+                                view! {
+                                    <div class="cleanup-row">
+                                        <div class="cleanup-info cleanup-samples">{d.clone()}</div>
+                                        <button on:click=move |_| set_dirs.update(|v| v.retain(|x| x != &dir))>"Remove"</button>
+                                    </div>
+                                }
+                            }
+                        />
+                    </div>
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=add_dir>"Add folder..."</button>
+                        <button
+                            prop:disabled=move || searching.get() || dirs.get().is_empty() || missing.get().is_empty()
+                            on:click=search
+                        >
+                            {move || if searching.get() { "Searching..." } else { "Find matches" }}
+                        </button>
+                    </div>
+                    {move || candidates.get().map(|found| {
+                        if found.is_empty() {
+                            return view! { <p class="settings-hint">"No matching files found."</p> }.into_any();
+                        }
+                        view! {
+                            <div>
+                                <div class="verify-issues">
+                                    {found.into_iter().map(|c| {
+                                        let id = c.file_id;
+                                        view! {
+                                            <label class="cleanup-row">
+                                                <input
+                                                    type="checkbox"
+                                                    prop:checked=move || chosen.get().contains(&id)
+                                                    on:change=move |_| set_chosen.update(|s| {
+                                                        if !s.remove(&id) {
+                                                            s.insert(id);
+                                                        }
+                                                    })
+                                                />
+                                                <div class="cleanup-info">
+                                                    <div class="cleanup-samples" title=c.old_path.clone()>{c.old_path.clone()}</div>
+                                                    <div class="cleanup-samples" title=c.new_path.clone()>{format!("→ {}", c.new_path)}</div>
+                                                </div>
+                                            </label>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                                <button prop:disabled=move || chosen.get().is_empty() on:click=apply>
+                                    {move || format!("Re-link {} file(s)", chosen.get().len())}
+                                </button>
+                            </div>
+                        }.into_any()
+                    })}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
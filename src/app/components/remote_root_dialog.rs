@@ -0,0 +1,152 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::try_invoke;
+use crate::app::types::{AddRemoteRootArgs, RemoteSettings};
+
+// Adds a WebDAV share or S3-compatible bucket as a root. The backend lists it
+// once before saving, so wrong addresses or credentials are reported here.
+#[component]
+pub fn RemoteRootDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    // Called with the stored root after it was added
+    on_added: impl Fn(String) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (url, set_url) = signal(String::new());
+    let (settings, set_settings) = signal(RemoteSettings::default());
+    let (adding, set_adding) = signal(false);
+    let (status, set_status) = signal(None::<String>);
+
+    Effect::new(move |_| {
+        if show.get() {
+            set_url.set(String::new());
+            set_settings.set(RemoteSettings::default());
+            set_status.set(None);
+        }
+    });
+
+    let is_s3 = move || url.get().trim().to_ascii_lowercase().starts_with("s3://");
+
+    let add = move |_| {
+        set_adding.set(true);
+        set_status.set(None);
+        spawn_local(async move {
+            let args = AddRemoteRootArgs { url: url.get_untracked(), settings: settings.get_untracked() };
+            match try_invoke("add_remote_root", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(root) => {
+                    set_show.set(false);
+                    on_added(root.as_string().unwrap_or_default());
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Couldn't add location".to_string()))),
+            }
+            set_adding.set(false);
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Add remote location"</h3>
+                    <p class="settings-hint">
+                        "davs://host/folder for WebDAV over HTTPS (dav:// without TLS), s3://bucket/prefix for S3-compatible storage. Files are listed every minute and downloaded when previewed or tagged."
+                    </p>
+                    <div class="settings-row">
+                        <input
+                            type="text"
+                            placeholder="davs://nas.local/photos"
+                            prop:value=move || url.get()
+                            on:input=move |e| set_url.set(event_target_value(&e))
+                        />
+                    </div>
+                    {move || if is_s3() {
+                        view! {
+                            <div>
+                                <div class="settings-row">
+                                    <input
+                                        type="text"
+                                        placeholder="Endpoint (empty for AWS)"
+                                        prop:value=move || settings.get().endpoint
+                                        on:input=move |e| {
+                                            let v = event_target_value(&e);
+                                            set_settings.update(|s| s.endpoint = v);
+                                        }
+                                    />
+                                    <input
+                                        type="text"
+                                        placeholder="Region (us-east-1)"
+                                        prop:value=move || settings.get().region
+                                        on:input=move |e| {
+                                            let v = event_target_value(&e);
+                                            set_settings.update(|s| s.region = v);
+                                        }
+                                    />
+                                </div>
+                                <div class="settings-row">
+                                    <input
+                                        type="text"
+                                        placeholder="Access key"
+                                        prop:value=move || settings.get().access_key
+                                        on:input=move |e| {
+                                            let v = event_target_value(&e);
+                                            set_settings.update(|s| s.access_key = v);
+                                        }
+                                    />
+                                    <input
+                                        type="password"
+                                        placeholder="Secret key"
+                                        prop:value=move || settings.get().secret_key
+                                        on:input=move |e| {
+                                            let v = event_target_value(&e);
+                                            set_settings.update(|s| s.secret_key = v);
+                                        }
+                                    />
+                                </div>
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <div class="settings-row">
+                                <input
+                                    type="text"
+                                    placeholder="Username (optional)"
+                                    prop:value=move || settings.get().username
+                                    on:input=move |e| {
+                                        let v = event_target_value(&e);
+                                        set_settings.update(|s| s.username = v);
+                                    }
+                                />
+                                <input
+                                    type="password"
+                                    placeholder="Password (optional)"
+                                    prop:value=move || settings.get().password
+                                    on:input=move |e| {
+                                        let v = event_target_value(&e);
+                                        set_settings.update(|s| s.password = v);
+                                    }
+                                />
+                            </div>
+                        }.into_any()
+                    }}
+                    {move || {
+                        let s = settings.get();
+                        let has_secret = !s.password.is_empty() || !s.secret_key.is_empty();
+                        has_secret.then(|| view! {
+                            <p class="settings-hint">
+                                "The password or secret key is saved unencrypted in TagMe's database, like other settings. Prefer a read-only account or access key."
+                            </p>
+                        })
+                    }}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button prop:disabled=move || adding.get() || url.get().trim().is_empty() on:click=add>
+                            {move || if adding.get() { "Connecting..." } else { "Add" }}
+                        </button>
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
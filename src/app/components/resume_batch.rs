@@ -0,0 +1,94 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{BatchArgs, PendingBatch};
+use crate::app::utils::format_date;
+
+// Shown at startup when a batch job (mass tagging, tag import, manifest export)
+// was killed mid-run: resume it from where it stopped, or discard it.
+#[component]
+pub fn ResumeBatchPrompt(
+    // Called with a summary after a run was resumed, so the lists can reload
+    on_resumed: impl Fn(String) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (batches, set_batches) = signal(Vec::<PendingBatch>::new());
+    let (running, set_running) = signal(None::<i64>);
+    let (error, set_error) = signal(None::<String>);
+
+    spawn_local(async move {
+        if let Ok(pending) = serde_wasm_bindgen::from_value::<Vec<PendingBatch>>(invoke("get_pending_batches", JsValue::NULL).await) {
+            set_batches.set(pending);
+        }
+    });
+
+    let forget = move |id: i64| set_batches.update(|list| list.retain(|b| b.id != id));
+
+    let resume = move |id: i64| {
+        set_running.set(Some(id));
+        set_error.set(None);
+        spawn_local(async move {
+            let args = BatchArgs { id };
+            match try_invoke("resume_batch", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    forget(id);
+                    on_resumed(v.as_string().unwrap_or_default());
+                }
+                Err(e) => {
+                    // A failed run is closed on the backend; it can't be resumed again
+                    forget(id);
+                    set_error.set(Some(e.as_string().unwrap_or_else(|| "Resuming failed".to_string())));
+                }
+            }
+            set_running.set(None);
+        });
+    };
+
+    let discard = move |id: i64| {
+        spawn_local(async move {
+            let args = BatchArgs { id };
+            let _ = invoke("discard_batch", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            forget(id);
+        });
+    };
+
+    view! {
+        {move || (!batches.get().is_empty() || error.get().is_some()).then(|| view! {
+            <div class="modal-overlay">
+                <div class="modal resume-batch">
+                    <h3>"Resume previous batch operation?"</h3>
+                    <p class="settings-hint">"TagMe was closed before these finished. Resuming picks up where they stopped."</p>
+                    <For
+                        each=move || batches.get()
+                        key=|b| b.id
+                        children=move |b| {
+                            let id = b.id;
+                            let progress = if b.total > 1 {
+                                format!("{} of {} done, started {}", b.done, b.total, format_date(b.started_at))
+                            } else {
+                                format!("Started {}", format_date(b.started_at))
+                            };
+                            view! {
+                                <div class="resume-batch-row">
+                                    <div>
+                                        <div>{b.description.clone()}</div>
+                                        <div class="settings-hint">{progress}</div>
+                                    </div>
+                                    <button disabled=move || running.get().is_some() on:click=move |_| resume(id)>
+                                        {move || if running.get() == Some(id) { "Resuming…" } else { "Resume" }}
+                                    </button>
+                                    <button disabled=move || running.get().is_some() on:click=move |_| discard(id)>"Discard"</button>
+                                </div>
+                            }
+                        }
+                    />
+                    {move || error.get().map(|msg| view! {
+                        <p style="color:#c00;">{msg}</p>
+                        <button on:click=move |_| set_error.set(None)>"Close"</button>
+                    })}
+                </div>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::try_invoke;
+use crate::app::types::{SetRootDefaultTagsArgs, TagInfo};
+use crate::app::utils::{active_tags, tag_path};
+
+// Default tags of one root: every file that shows up in it from now on gets
+// them when a scan or the watcher finds it. Files already there are left alone.
+#[component]
+pub fn RootDefaultsDialog(
+    // The root being edited; None hides the dialog
+    root: ReadSignal<Option<String>>,
+    set_root: WriteSignal<Option<String>>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    defaults: ReadSignal<HashMap<String, Vec<u32>>>,
+    // Called with the root and its saved tags
+    on_saved: impl Fn(String, Vec<u32>) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (chosen, set_chosen) = signal(Vec::<u32>::new());
+    let (saving, set_saving) = signal(false);
+    let (status, set_status) = signal(None::<String>);
+
+    Effect::new(move |_| {
+        if let Some(path) = root.get() {
+            set_chosen.set(defaults.get_untracked().get(&path).cloned().unwrap_or_default());
+            set_status.set(None);
+        }
+    });
+
+    let save = move |_| {
+        let Some(path) = root.get_untracked() else { return };
+        let tag_ids = chosen.get_untracked();
+        set_saving.set(true);
+        set_status.set(None);
+        spawn_local(async move {
+            let args = SetRootDefaultTagsArgs { path: path.clone(), tag_ids: tag_ids.clone() };
+            match try_invoke("set_root_default_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => {
+                    set_root.set(None);
+                    on_saved(path, tag_ids);
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Couldn't save the default tags".to_string()))),
+            }
+            set_saving.set(false);
+        });
+    };
+
+    view! {
+        {move || root.get().map(|path| view! {
+            <div class="modal-overlay" on:click=move |_| set_root.set(None)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Default tags"</h3>
+                    <p class="settings-hint">
+                        {format!("New files that appear in {} get these tags when a scan or the watcher finds them. Files already there keep their tags.", path)}
+                    </p>
+                    <div class="settings-row">
+                        <select
+                            prop:value=""
+                            on:change=move |ev| {
+                                if let Ok(id) = event_target_value(&ev).parse::<u32>() {
+                                    set_chosen.update(|ids| {
+                                        if !ids.contains(&id) {
+                                            ids.push(id);
+                                        }
+                                    });
+                                }
+                            }
+                        >
+                            <option value="">"Add a tag…"</option>
+                            {move || {
+                                let all = all_tags.get();
+                                let picked = chosen.get();
+                                let mut options: Vec<(u32, String)> = active_tags(&all)
+                                    .iter()
+                                    .filter(|t| !picked.contains(&t.id))
+                                    .map(|t| (t.id, tag_path(t, &all)))
+                                    .collect();
+                                options.sort_by_key(|(_, path)| path.to_lowercase());
+                                options
+                                    .into_iter()
+                                    .map(|(id, path)| view! { <option value=id.to_string()>{path}</option> })
+                                    .collect_view()
+                            }}
+                        </select>
+                    </div>
+                    <div class="settings-row">
+                        {move || {
+                            let all = all_tags.get();
+                            chosen
+                                .get()
+                                .into_iter()
+                                .filter_map(|id| all.iter().find(|t| t.id == id).cloned())
+                                .map(|tag| {
+                                    let id = tag.id;
+                                    view! {
+                                        <span
+                                            class="tag-badge"
+                                            style=tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()
+                                        >
+                                            {tag.name.clone()}
+                                            <button
+                                                class="view-tab-close"
+                                                title="Don't give new files this tag"
+                                                on:click=move |_| set_chosen.update(|ids| ids.retain(|t| *t != id))
+                                            >
+                                                "×"
+                                            </button>
+                                        </span>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                        {move || chosen.get().is_empty().then(|| view! { <span class="settings-hint">"No default tags"</span> })}
+                    </div>
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button prop:disabled=move || saving.get() on:click=save>
+                            {move || if saving.get() { "Saving..." } else { "Save" }}
+                        </button>
+                        <button on:click=move |_| set_root.set(None)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
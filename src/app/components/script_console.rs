@@ -0,0 +1,98 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::try_invoke;
+use crate::app::types::{RunScriptArgs, ScriptResult};
+
+const EXAMPLE: &str = r#"// Tag every PDF that has no tags yet
+for f in files() {
+    if f.ext == "pdf" && f.tags.is_empty() {
+        add_tag(f.path, "Documents");
+    }
+}
+"#;
+
+// Rhai console for one-off batch edits. Scripts can list files and tags, add and
+// remove tags, create tags and rename files; a dry run (the default) only reports
+// what would change.
+#[component]
+pub fn ScriptConsole(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    // Called after a real run made changes so the caller can reload tags and files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (source, set_source) = signal(EXAMPLE.to_string());
+    let (dry_run, set_dry_run) = signal(true);
+    let (running, set_running) = signal(false);
+    let (result, set_result) = signal(None::<ScriptResult>);
+    let (error, set_error) = signal(None::<String>);
+
+    let run = move |_| {
+        set_running.set(true);
+        set_error.set(None);
+        spawn_local(async move {
+            let args = RunScriptArgs { source: source.get_untracked(), dry_run: dry_run.get_untracked() };
+            match try_invoke("run_script", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    let r = serde_wasm_bindgen::from_value::<ScriptResult>(v).ok();
+                    if r.as_ref().is_some_and(|r| !r.dry_run && r.changes > 0) {
+                        on_changed();
+                    }
+                    set_result.set(r);
+                }
+                Err(e) => set_error.set(Some(e.as_string().unwrap_or_else(|| "Script failed".to_string()))),
+            }
+            set_running.set(false);
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal script-console" on:click={|e| e.stop_propagation()}>
+                    <h3>"Script console"</h3>
+                    <p class="settings-hint">
+                        "Rhai scripts with files(), files_with_tag(tag), tags(), add_tag(path, tag), remove_tag(path, tag), create_tag(name[, parent]), rename(path, new_name) and print(..). Files are maps with path, name, ext, size, modified, is_dir and tags."
+                    </p>
+                    <textarea
+                        class="script-editor"
+                        spellcheck="false"
+                        prop:value=source
+                        on:input=move |e| set_source.set(event_target_value(&e))
+                    ></textarea>
+                    <div class="settings-row">
+                        <label class="settings-row">
+                            <input
+                                type="checkbox"
+                                prop:checked=dry_run
+                                on:change=move |e| set_dry_run.set(event_target_checked(&e))
+                            />
+                            "Dry run (only report changes)"
+                        </label>
+                        <button prop:disabled=move || running.get() on:click=run>
+                            {move || if running.get() { "Running..." } else { "Run" }}
+                        </button>
+                    </div>
+                    {move || error.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    {move || result.get().map(|r| {
+                        let summary = if r.dry_run {
+                            format!("Dry run: {} change(s) would be made", r.changes)
+                        } else {
+                            format!("{} change(s) made", r.changes)
+                        };
+                        view! {
+                            <div>
+                                <p class="settings-hint">{summary}</p>
+                                <pre class="script-output">{r.output.join("\n")}</pre>
+                            </div>
+                        }
+                    })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
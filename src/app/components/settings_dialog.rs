@@ -0,0 +1,831 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::collections::BTreeMap;
+use leptos_recommender::RecommendSources;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::utils::format_file_size;
+use crate::app::types::{DataLocation, EmbeddingSettings, LlmPricing, MoveDataDirArgs, MoveReport, ProxySettings, SafetyPolicy, SetAutoColorTagsArgs, SetAutostartArgs, SetInheritTagColorsArgs, SetClipboardWatchArgs, SetEmbeddingSettingsArgs, SetExpandArchivesArgs, SetLargeLibraryArgs, SetLlmPricingArgs, SetManualRefreshArgs, SetOfflineModeArgs, SetOpenActionsArgs, SetStripImageMetadataArgs, SetProxySettingsArgs, SetRecommendSourcesArgs, SetSafetyPolicyArgs, SetTaggerPluginsArgs, TaggerPlugin};
+
+// Recommendation sources as (source key, label) for the settings rows
+const SOURCES: [(&str, &str); 5] = [
+    ("llm", "LLM (file name)"),
+    ("llm-vision", "Vision (images)"),
+    ("llm-content", "Content (text & code)"),
+    ("rule", "File-name rules"),
+    ("plugin:", "Tagger plugins"),
+];
+
+#[component]
+pub fn SettingsDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    // App-wide copy of the safety policy, updated on save
+    set_safety_policy: WriteSignal<SafetyPolicy>,
+    // App-wide copy of the per-extension double-click actions, updated on save
+    set_open_actions: WriteSignal<BTreeMap<String, String>>,
+    // App-wide copy of the recommendation source settings, updated on save
+    set_recommend_sources: WriteSignal<RecommendSources>,
+    // App-wide manual refresh flag, updated on save
+    set_manual_refresh: WriteSignal<bool>,
+    // App-wide large library flag, updated on save
+    set_large_library: WriteSignal<bool>,
+    // Called after saving so the caller can reload tags drawn with inherited colors
+    on_saved: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (proxy, set_proxy) = signal(ProxySettings::default());
+    let (auto_color, set_auto_color) = signal(true);
+    let (inherit_colors, set_inherit_colors) = signal(false);
+    let (autostart, set_autostart) = signal(false);
+    let (clipboard_watch, set_clipboard_watch) = signal(false);
+    let (offline_mode, set_offline_mode) = signal(false);
+    let (strip_metadata, set_strip_metadata) = signal(true);
+    let (expand_archives, set_expand_archives) = signal(false);
+    let (manual, set_manual) = signal(false);
+    let (large, set_large) = signal(false);
+    let (actions, set_actions) = signal(BTreeMap::<String, String>::new());
+    let (new_ext, set_new_ext) = signal(String::new());
+    let (new_action, set_new_action) = signal(String::from("preview"));
+    let (plugins, set_plugins) = signal(Vec::<TaggerPlugin>::new());
+    let (new_plugin, set_new_plugin) = signal(TaggerPlugin { enabled: true, ..Default::default() });
+    let (new_plugin_exts, set_new_plugin_exts) = signal(String::new());
+    let (sources, set_sources) = signal(RecommendSources::default());
+    let (pricing, set_pricing) = signal(LlmPricing::default());
+    let (embedding, set_embedding) = signal(EmbeddingSettings::default());
+    let (policy, set_policy) = signal(SafetyPolicy::default());
+    let (status, set_status) = signal(None::<String>);
+    let (backup_status, set_backup_status) = signal(None::<String>);
+    let (data_location, set_data_location) = signal(DataLocation::default());
+    let (moving, set_moving) = signal(false);
+    let (move_status, set_move_status) = signal(None::<String>);
+
+    // Reload persisted values every time the dialog opens
+    Effect::new(move |_| {
+        if show.get() {
+            set_status.set(None);
+            set_backup_status.set(None);
+            set_move_status.set(None);
+            spawn_local(async move {
+                let val = invoke("get_data_location", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(location) = serde_wasm_bindgen::from_value::<DataLocation>(val) {
+                    set_data_location.set(location);
+                }
+                let val = invoke("get_proxy_settings", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(p) = serde_wasm_bindgen::from_value::<ProxySettings>(val) {
+                    set_proxy.set(p);
+                }
+                let val = invoke("get_auto_color_tags", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_auto_color.set(enabled);
+                }
+                let val = invoke("get_inherit_tag_colors", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_inherit_colors.set(enabled);
+                }
+                let val = invoke("get_autostart", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_autostart.set(enabled);
+                }
+                let val = invoke("get_clipboard_watch", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_clipboard_watch.set(enabled);
+                }
+                let val = invoke("get_offline_mode", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_offline_mode.set(enabled);
+                }
+                let val = invoke("get_strip_image_metadata", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_strip_metadata.set(enabled);
+                }
+                let val = invoke("get_expand_archives", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_expand_archives.set(enabled);
+                }
+                let val = invoke("get_manual_refresh", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_manual.set(enabled);
+                }
+                let val = invoke("get_large_library", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(val) {
+                    set_large.set(enabled);
+                }
+                let val = invoke("get_open_actions", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(a) = serde_wasm_bindgen::from_value::<BTreeMap<String, String>>(val) {
+                    set_actions.set(a);
+                }
+                let val = invoke("get_tagger_plugins", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(p) = serde_wasm_bindgen::from_value::<Vec<TaggerPlugin>>(val) {
+                    set_plugins.set(p);
+                }
+                let val = invoke("get_recommend_sources", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(s) = serde_wasm_bindgen::from_value::<Option<RecommendSources>>(val) {
+                    set_sources.set(s.unwrap_or_default());
+                }
+                let val = invoke("get_llm_pricing", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(p) = serde_wasm_bindgen::from_value::<LlmPricing>(val) {
+                    set_pricing.set(p);
+                }
+                let val = invoke("get_embedding_settings", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(e) = serde_wasm_bindgen::from_value::<EmbeddingSettings>(val) {
+                    set_embedding.set(e);
+                }
+                let val = invoke("get_safety_policy", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(p) = serde_wasm_bindgen::from_value::<SafetyPolicy>(val) {
+                    set_policy.set(p);
+                }
+            });
+        }
+    });
+
+    let save = move |_| {
+        let settings = proxy.get_untracked();
+        let enabled = auto_color.get_untracked();
+        let inherit = inherit_colors.get_untracked();
+        let safety = policy.get_untracked();
+        let start_at_login = autostart.get_untracked();
+        let watch_clipboard = clipboard_watch.get_untracked();
+        let offline = offline_mode.get_untracked();
+        let strip = strip_metadata.get_untracked();
+        let expand = expand_archives.get_untracked();
+        let manual_only = manual.get_untracked();
+        let large_library = large.get_untracked();
+        let open_actions = actions.get_untracked();
+        let tagger_plugins = plugins.get_untracked();
+        let recommend_sources = sources.get_untracked();
+        let llm_pricing = pricing.get_untracked();
+        let embedding_settings = embedding.get_untracked();
+        spawn_local(async move {
+            let args = SetAutostartArgs { enabled: start_at_login };
+            if let Err(e) = try_invoke("set_autostart", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not change login autostart".to_string())));
+                return;
+            }
+            let args = SetClipboardWatchArgs { enabled: watch_clipboard };
+            let _ = invoke("set_clipboard_watch", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            let args = SetOfflineModeArgs { enabled: offline };
+            if let Err(e) = try_invoke("set_offline_mode", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not change offline mode".to_string())));
+                return;
+            }
+            let args = SetStripImageMetadataArgs { enabled: strip };
+            let _ = invoke("set_strip_image_metadata", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            let args = SetExpandArchivesArgs { enabled: expand };
+            let _ = invoke("set_expand_archives", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            let args = SetManualRefreshArgs { enabled: manual_only };
+            if try_invoke("set_manual_refresh", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok() {
+                set_manual_refresh.set(manual_only);
+            }
+            let args = SetLargeLibraryArgs { enabled: large_library };
+            if try_invoke("set_large_library", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok() {
+                set_large_library.set(large_library);
+            }
+            let args = SetOpenActionsArgs { actions: open_actions.clone() };
+            match try_invoke("set_open_actions", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => set_open_actions.set(open_actions),
+                Err(e) => {
+                    set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not save double-click actions".to_string())));
+                    return;
+                }
+            }
+            let args = SetTaggerPluginsArgs { plugins: tagger_plugins };
+            if let Err(e) = try_invoke("set_tagger_plugins", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not save tagger plugins".to_string())));
+                return;
+            }
+            let args = SetRecommendSourcesArgs { sources: recommend_sources.clone() };
+            match try_invoke("set_recommend_sources", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => set_recommend_sources.set(recommend_sources),
+                Err(e) => {
+                    set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not save recommendation sources".to_string())));
+                    return;
+                }
+            }
+            let args = SetLlmPricingArgs { pricing: llm_pricing };
+            if let Err(e) = try_invoke("set_llm_pricing", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not save pricing".to_string())));
+                return;
+            }
+            let args = SetEmbeddingSettingsArgs { settings: embedding_settings };
+            if let Err(e) = try_invoke("set_embedding_settings", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not save embedding settings".to_string())));
+                return;
+            }
+            let args = SetAutoColorTagsArgs { enabled };
+            let _ = invoke("set_auto_color_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            let args = SetInheritTagColorsArgs { enabled: inherit };
+            let _ = invoke("set_inherit_tag_colors", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            let args = SetSafetyPolicyArgs { policy: safety.clone() };
+            if try_invoke("set_safety_policy", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok() {
+                set_safety_policy.set(safety);
+            }
+            let args = SetProxySettingsArgs { settings };
+            match try_invoke("set_proxy_settings", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => {
+                    set_status.set(None);
+                    set_show.set(false);
+                    on_saved();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Failed to save settings".to_string()))),
+            }
+        });
+    };
+
+    let add_action = move |_| {
+        let ext = new_ext.get_untracked().trim().trim_start_matches('.').to_lowercase();
+        if ext.is_empty() {
+            return;
+        }
+        set_actions.update(|a| {
+            a.insert(ext, new_action.get_untracked());
+        });
+        set_new_ext.set(String::new());
+    };
+
+    let add_plugin = move |_| {
+        let mut plugin = new_plugin.get_untracked();
+        if plugin.name.trim().is_empty() || plugin.command.trim().is_empty() {
+            return;
+        }
+        plugin.extensions = new_plugin_exts
+            .get_untracked()
+            .split(',')
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+        set_plugins.update(|p| p.push(plugin));
+        set_new_plugin.set(TaggerPlugin { enabled: true, ..Default::default() });
+        set_new_plugin_exts.set(String::new());
+    };
+
+    let backup_now = move |_| {
+        spawn_local(async move {
+            match try_invoke("backup_database", wasm_bindgen::JsValue::NULL).await {
+                Ok(v) => set_backup_status.set(Some(format!("Saved {}", v.as_string().unwrap_or_default()))),
+                Err(e) => set_backup_status.set(Some(e.as_string().unwrap_or_else(|| "Backup failed".to_string()))),
+            }
+        });
+    };
+
+    // Copies the library, verifies the copy and restarts into it; the old copy
+    // is removed on that start
+    let move_data = move |to_default: bool| {
+        let message = if to_default {
+            "Move the database and backups back to the default location? TagMe checks the copy, then restarts."
+        } else {
+            "Move the database and backups to a folder you pick? TagMe checks the copy, then restarts."
+        };
+        let confirmed = web_sys::window()
+            .and_then(|w| w.confirm_with_message(message).ok())
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        set_moving.set(true);
+        set_move_status.set(Some("Copying and verifying…".to_string()));
+        spawn_local(async move {
+            let args = MoveDataDirArgs { to_default };
+            match try_invoke("move_data_dir", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => match serde_wasm_bindgen::from_value::<Option<MoveReport>>(v) {
+                    Ok(Some(report)) => {
+                        set_move_status.set(Some(format!(
+                            "Copied {} file(s) ({}) to {}. Restarting…",
+                            report.files,
+                            format_file_size(report.bytes),
+                            report.target
+                        )));
+                        set_timeout(
+                            || spawn_local(async move { let _ = invoke("restart_app", wasm_bindgen::JsValue::NULL).await; }),
+                            std::time::Duration::from_millis(1500),
+                        );
+                        return;
+                    }
+                    _ => set_move_status.set(None),
+                },
+                Err(e) => set_move_status.set(Some(e.as_string().unwrap_or_else(|| "Moving failed".to_string()))),
+            }
+            set_moving.set(false);
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| if !moving.get_untracked() { set_show.set(false) }>
+                <div class="modal settings-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Settings"</h3>
+                    <h4>"Startup"</h4>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=autostart
+                            on:change=move |e| set_autostart.set(event_target_checked(&e))
+                        />
+                        "Start TagMe at login, hidden in the tray with folder watching on"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=clipboard_watch
+                            on:change=move |e| set_clipboard_watch.set(event_target_checked(&e))
+                        />
+                        "Offer to tag files when their paths are copied to the clipboard"
+                    </label>
+                    <h4>"Scanning"</h4>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=expand_archives
+                            on:change=move |e| set_expand_archives.set(event_target_checked(&e))
+                        />
+                        "List the files inside zip and 7z archives so they can be tagged without unpacking"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=manual
+                            on:change=move |e| set_manual.set(event_target_checked(&e))
+                        />
+                        "Manual refresh only: don't watch folders or rescan on startup, only when Scan is clicked"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=large
+                            on:change=move |e| set_large.set(event_target_checked(&e))
+                        />
+                        "Large library: keep one page of files in memory and page through the rest"
+                    </label>
+                    <h4>"Tags"</h4>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=auto_color
+                            on:change=move |e| set_auto_color.set(event_target_checked(&e))
+                        />
+                        "Give quick-created tags a color from the palette"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=inherit_colors
+                            on:change=move |e| set_inherit_colors.set(event_target_checked(&e))
+                        />
+                        "Show child tags without a color in a shade of their parent's color"
+                    </label>
+                    <h4>"Double-click"</h4>
+                    <p class="settings-hint">"What double-clicking a file does, by extension. Other files open in their default app."</p>
+                    <For
+                        each=move || Vec::from_iter(actions.get())
+                        key=|entry| format!("{}={}", entry.0, entry.1)
+                        children=move |entry| {
+                            let (ext, action) = entry;
+                            let label = match action.as_str() {
+                                "preview" => "Preview in TagMe",
+                                "reveal" => "Show in folder",
+                                "open-with" => "Open with...",
+                                _ => "Open",
+                            };
+                            let key = ext.clone();
+                            view! {
+                                <div class="settings-row">
+                                    <span>{format!(".{} → {}", ext, label)}</span>
+                                    <button on:click=move |_| set_actions.update(|a| {
+                                        a.remove(&key);
+                                    })>"Remove"</button>
+                                </div>
+                            }
+                        }
+                    />
+                    <div class="settings-row">
+                        <input
+                            type="text"
+                            placeholder="Extension, e.g. mp4"
+                            prop:value=new_ext
+                            on:input=move |e| set_new_ext.set(event_target_value(&e))
+                        />
+                        <select prop:value=new_action on:change=move |e| set_new_action.set(event_target_value(&e))>
+                            <option value="open">"Open"</option>
+                            <option value="preview">"Preview in TagMe"</option>
+                            <option value="reveal">"Show in folder"</option>
+                            <option value="open-with">"Open with..."</option>
+                        </select>
+                        <button on:click=add_action>"Add"</button>
+                    </div>
+                    <h4>"Tagger plugins"</h4>
+                    <p class="settings-hint">"Programs that suggest tags alongside the AI. Each gets the file path as its last argument and {path, labels} as JSON on stdin, and prints [{name, score}]."</p>
+                    {move || plugins.get().into_iter().enumerate().map(|(i, plugin)| {
+                        let exts = if plugin.extensions.is_empty() { "all files".to_string() } else { plugin.extensions.join(", ") };
+                        view! {
+                            <div class="settings-row">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=plugin.enabled
+                                    on:change=move |e| {
+                                        let checked = event_target_checked(&e);
+                                        set_plugins.update(|p| p[i].enabled = checked);
+                                    }
+                                />
+                                <span title=plugin.command.clone()>{format!("{} ({})", plugin.name, exts)}</span>
+                                <button on:click=move |_| set_plugins.update(|p| {
+                                    p.remove(i);
+                                })>"Remove"</button>
+                            </div>
+                        }
+                    }).collect_view()}
+                    <div class="settings-row">
+                        <input
+                            type="text"
+                            placeholder="Name"
+                            prop:value=move || new_plugin.get().name
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_new_plugin.update(|p| p.name = v);
+                            }
+                        />
+                        <input
+                            type="text"
+                            placeholder="Command"
+                            prop:value=move || new_plugin.get().command
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_new_plugin.update(|p| p.command = v);
+                            }
+                        />
+                        <input
+                            type="text"
+                            placeholder="Extensions, e.g. jpg, png"
+                            prop:value=new_plugin_exts
+                            on:input=move |e| set_new_plugin_exts.set(event_target_value(&e))
+                        />
+                        <button on:click=add_plugin>"Add"</button>
+                    </div>
+                    <h4>"Recommendation sources"</h4>
+                    <p class="settings-hint">"Suggestions scoring below a source's threshold are dropped; the rest are multiplied by its weight before sources are merged."</p>
+                    {SOURCES.into_iter().map(|(key, label)| view! {
+                        <div class="settings-row">
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || sources.get().for_source(key).enabled
+                                    on:change=move |e| {
+                                        let checked = event_target_checked(&e);
+                                        set_sources.update(|s| s.for_source_mut(key).enabled = checked);
+                                    }
+                                />
+                                {label}
+                            </label>
+                            <span>"Weight"</span>
+                            <input
+                                type="number"
+                                min="0"
+                                step="0.1"
+                                style="width:70px;"
+                                prop:value=move || sources.get().for_source(key).weight.to_string()
+                                on:change=move |e| {
+                                    if let Ok(v) = event_target_value(&e).parse::<f32>() {
+                                        set_sources.update(|s| s.for_source_mut(key).weight = v.max(0.0));
+                                    }
+                                }
+                            />
+                            <span>"Threshold"</span>
+                            <input
+                                type="number"
+                                min="0"
+                                max="1"
+                                step="0.05"
+                                style="width:70px;"
+                                prop:value=move || sources.get().for_source(key).threshold.to_string()
+                                on:change=move |e| {
+                                    if let Ok(v) = event_target_value(&e).parse::<f32>() {
+                                        set_sources.update(|s| s.for_source_mut(key).threshold = v.clamp(0.0, 1.0));
+                                    }
+                                }
+                            />
+                        </div>
+                    }).collect_view()}
+                    <div class="settings-row">
+                        <span>"Recommend for"</span>
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || sources.get().types.images
+                                on:change=move |e| {
+                                    let checked = event_target_checked(&e);
+                                    set_sources.update(|s| s.types.images = checked);
+                                }
+                            />
+                            "Images"
+                        </label>
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || sources.get().types.text
+                                on:change=move |e| {
+                                    let checked = event_target_checked(&e);
+                                    set_sources.update(|s| s.types.text = checked);
+                                }
+                            />
+                            "Text & code"
+                        </label>
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || sources.get().types.other
+                                on:change=move |e| {
+                                    let checked = event_target_checked(&e);
+                                    set_sources.update(|s| s.types.other = checked);
+                                }
+                            />
+                            "Other files"
+                        </label>
+                    </div>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || sources.get().hide_existing
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_sources.update(|s| s.hide_existing = checked);
+                            }
+                        />
+                        "Hide suggestions the file already has (instead of dimming them)"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || sources.get().flag_unlikely
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_sources.update(|s| s.flag_unlikely = checked);
+                            }
+                        />
+                        "Flag current tags the model considers unlikely"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || sources.get().background_indexing
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_sources.update(|s| s.background_indexing = checked);
+                            }
+                        />
+                        "Prepare suggestions for new untagged files while TagMe is idle"
+                    </label>
+                    <div class="settings-row">
+                        <span>"Parallel image requests"</span>
+                        <input
+                            type="number"
+                            min="1"
+                            max="8"
+                            style="width:70px;"
+                            prop:value=move || sources.get().image_concurrency.to_string()
+                            on:change=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<usize>() {
+                                    set_sources.update(|s| s.image_concurrency = v.clamp(1, 8));
+                                }
+                            }
+                        />
+                    </div>
+                    <p class="settings-hint">"Prices of the LLM endpoint. When set, \"Recommend All\" shows the estimated cost and asks before running."</p>
+                    <div class="settings-row">
+                        <span>"Input per 1M tokens"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            step="0.01"
+                            style="width:80px;"
+                            prop:value=move || pricing.get().input_per_million.to_string()
+                            on:change=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<f64>() {
+                                    set_pricing.update(|p| p.input_per_million = v.max(0.0));
+                                }
+                            }
+                        />
+                        <span>"Output per 1M tokens"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            step="0.01"
+                            style="width:80px;"
+                            prop:value=move || pricing.get().output_per_million.to_string()
+                            on:change=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<f64>() {
+                                    set_pricing.update(|p| p.output_per_million = v.max(0.0));
+                                }
+                            }
+                        />
+                        <input
+                            type="text"
+                            placeholder="Currency"
+                            style="width:60px;"
+                            prop:value=move || pricing.get().currency
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_pricing.update(|p| p.currency = v);
+                            }
+                        />
+                    </div>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || embedding.get().enabled
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_embedding.update(|s| s.enabled = checked);
+                            }
+                        />
+                        "Match file names to tags with a local embedding model (catches synonyms and other languages)"
+                    </label>
+                    <div class="settings-row">
+                        <input
+                            type="text"
+                            placeholder="Endpoint"
+                            prop:value=move || embedding.get().base_url
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_embedding.update(|s| s.base_url = v);
+                            }
+                        />
+                        <input
+                            type="text"
+                            placeholder="Model"
+                            style="width:140px;"
+                            prop:value=move || embedding.get().model
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_embedding.update(|s| s.model = v);
+                            }
+                        />
+                        <span>"Min similarity"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            max="1"
+                            step="0.05"
+                            style="width:70px;"
+                            prop:value=move || embedding.get().min_score.to_string()
+                            on:change=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<f32>() {
+                                    set_embedding.update(|s| s.min_score = v.clamp(0.0, 1.0));
+                                }
+                            }
+                        />
+                    </div>
+                    <h4>"Privacy"</h4>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=offline_mode
+                            on:change=move |e| set_offline_mode.set(event_target_checked(&e))
+                        />
+                        "Offline mode: only use models on this computer (localhost endpoints)"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=strip_metadata
+                            on:change=move |e| set_strip_metadata.set(event_target_checked(&e))
+                        />
+                        "Remove EXIF and GPS data from images sent to cloud vision models"
+                    </label>
+                    <p class="settings-hint">"Folders marked 🔒 on their root chip are never sent to cloud models, whatever this says."</p>
+                    <h4>"Safety"</h4>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || policy.get().confirm
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_policy.update(|p| p.confirm = checked);
+                            }
+                        />
+                        "Ask before deleting tags, purging files or applying cleanup fixes"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || policy.get().type_to_confirm
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_policy.update(|p| p.type_to_confirm = checked);
+                            }
+                        />
+                        "Require typing the folder name (or \"all\") to purge files"
+                    </label>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || policy.get().backup
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_policy.update(|p| p.backup = checked);
+                            }
+                        />
+                        "Back up the database before anything is deleted"
+                    </label>
+                    <div class="settings-row">
+                        <button on:click=backup_now>"Back up now"</button>
+                        {move || backup_status.get().map(|msg| view! { <span class="settings-hint">{msg}</span> })}
+                    </div>
+                    <h4>"Data location"</h4>
+                    <p class="settings-hint">"The database, its backups and the caches. Move them off a full system drive here."</p>
+                    <div class="settings-row">
+                        <code class="data-location">{move || data_location.get().current}</code>
+                    </div>
+                    {move || data_location.get().pending.map(|p| view! {
+                        <p class="settings-hint">{format!("Moved to {} — restart TagMe to finish.", p)}</p>
+                    })}
+                    <div class="settings-row">
+                        <button
+                            prop:disabled=move || moving.get() || data_location.get().pending.is_some()
+                            on:click=move |_| move_data(false)
+                        >"Move…"</button>
+                        <Show when=move || data_location.get().relocated>
+                            <button
+                                prop:disabled=move || moving.get() || data_location.get().pending.is_some()
+                                on:click=move |_| move_data(true)
+                            >"Use default location"</button>
+                        </Show>
+                        {move || move_status.get().map(|msg| view! { <span class="settings-hint">{msg}</span> })}
+                    </div>
+                    <h4>"Network proxy"</h4>
+                    <p class="settings-hint">"Used for update checks and AI tag recommendations."</p>
+                    <label class="settings-row">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || proxy.get().enabled
+                            on:change=move |e| {
+                                let checked = event_target_checked(&e);
+                                set_proxy.update(|p| p.enabled = checked);
+                            }
+                        />
+                        "Use a proxy"
+                    </label>
+                    <div class="settings-row">
+                        <select
+                            prop:value=move || proxy.get().scheme
+                            prop:disabled=move || !proxy.get().enabled
+                            on:change=move |e| {
+                                let v = event_target_value(&e);
+                                set_proxy.update(|p| p.scheme = v);
+                            }
+                        >
+                            <option value="http">"HTTP"</option>
+                            <option value="https">"HTTPS"</option>
+                            <option value="socks5">"SOCKS5"</option>
+                        </select>
+                        <input
+                            type="text"
+                            placeholder="Host"
+                            prop:value=move || proxy.get().host
+                            prop:disabled=move || !proxy.get().enabled
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_proxy.update(|p| p.host = v);
+                            }
+                        />
+                        <input
+                            type="number"
+                            placeholder="Port"
+                            class="settings-port"
+                            prop:value=move || { let port = proxy.get().port; if port == 0 { String::new() } else { port.to_string() } }
+                            prop:disabled=move || !proxy.get().enabled
+                            on:input=move |e| {
+                                let v = event_target_value(&e).parse::<u16>().unwrap_or(0);
+                                set_proxy.update(|p| p.port = v);
+                            }
+                        />
+                    </div>
+                    <div class="settings-row">
+                        <input
+                            type="text"
+                            placeholder="Username (optional)"
+                            prop:value=move || proxy.get().username
+                            prop:disabled=move || !proxy.get().enabled
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_proxy.update(|p| p.username = v);
+                            }
+                        />
+                        <input
+                            type="password"
+                            placeholder="Password (optional)"
+                            prop:value=move || proxy.get().password
+                            prop:disabled=move || !proxy.get().enabled
+                            on:input=move |e| {
+                                let v = event_target_value(&e);
+                                set_proxy.update(|p| p.password = v);
+                            }
+                        />
+                    </div>
+                    {move || status.get().map(|msg| view! { <p style="color:#c00;">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=save>"Save"</button>
+                        <button on:click=move |_| set_show.set(false)>"Cancel"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,180 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::components::file_list::FileList;
+use crate::app::files::query_files_with_tags;
+use crate::app::types::{DisplayFile, FileInfo, FileWithTags, SortColumn, SortDirection, TagFilter, TagInfo};
+use crate::app::utils::{active_tags, tag_path};
+
+// Right half of the split view: a second file list with its own tag filter, sort
+// and selection, for re-sorting files between two tag sets. Files dragged from
+// or onto it are handled by the app (tags follow the list they're dropped on).
+#[component]
+pub fn SplitPane(
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    // The library's files; the list is queried again whenever they change
+    all_files: ReadSignal<Vec<FileInfo>>,
+    tag_ids: ReadSignal<Vec<u32>>,
+    set_tag_ids: WriteSignal<Vec<u32>>,
+    use_and: ReadSignal<bool>,
+    set_use_and: WriteSignal<bool>,
+    // Paths dragged out of this list: the selection, or the grabbed row alone
+    on_drag_start: impl Fn(Vec<String>) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (rows, set_rows) = signal(Vec::<FileWithTags>::new());
+    let (selected, set_selected) = signal(Vec::<String>::new());
+    let (sort_column, set_sort_column) = signal(SortColumn::Name);
+    let (sort_direction, set_sort_direction) = signal(SortDirection::Asc);
+
+    Effect::new(move |_| {
+        let ids = tag_ids.get();
+        let and = use_and.get();
+        all_files.track();
+        if ids.is_empty() {
+            set_rows.set(Vec::new());
+            return;
+        }
+        spawn_local(async move {
+            if let Some(found) = query_files_with_tags(Some(TagFilter { tag_ids: ids, use_and: and }), Vec::new()).await {
+                set_rows.set(found);
+            }
+        });
+    });
+
+    let files = move || {
+        let mut files: Vec<DisplayFile> = rows.get().into_iter().map(display_file).collect();
+        let col = sort_column.get();
+        files.sort_by(|a, b| {
+            let cmp = col.compare(a, b);
+            match sort_direction.get() {
+                SortDirection::Asc => cmp,
+                SortDirection::Desc => cmp.reverse(),
+            }
+        });
+        files
+    };
+
+    let on_sort = move |col: SortColumn| {
+        if sort_column.get_untracked() == col {
+            set_sort_direction.update(|d| *d = if *d == SortDirection::Asc { SortDirection::Desc } else { SortDirection::Asc });
+        } else {
+            set_sort_column.set(col);
+            set_sort_direction.set(SortDirection::Asc);
+        }
+    };
+    let on_toggle = move |path: String| {
+        set_selected.update(|sel| {
+            if let Some(pos) = sel.iter().position(|p| p == &path) {
+                sel.remove(pos);
+            } else {
+                sel.push(path);
+            }
+        });
+    };
+    let drag_start = move |path: String| {
+        let sel = selected.get_untracked();
+        on_drag_start(if sel.contains(&path) { sel } else { vec![path] });
+    };
+
+    view! {
+        <div class="split-pane">
+            <div class="split-pane-filter">
+                <select
+                    prop:value=""
+                    on:change=move |ev| {
+                        if let Ok(id) = event_target_value(&ev).parse::<u32>() {
+                            set_tag_ids.update(|ids| {
+                                if !ids.contains(&id) {
+                                    ids.push(id);
+                                }
+                            });
+                        }
+                    }
+                >
+                    <option value="">"Filter by tag…"</option>
+                    {move || {
+                        let all = all_tags.get();
+                        let chosen = tag_ids.get();
+                        let mut options: Vec<(u32, String)> = active_tags(&all)
+                            .iter()
+                            .filter(|t| !chosen.contains(&t.id))
+                            .map(|t| (t.id, tag_path(t, &all)))
+                            .collect();
+                        options.sort_by_key(|(_, path)| path.to_lowercase());
+                        options
+                            .into_iter()
+                            .map(|(id, path)| view! { <option value=id.to_string()>{path}</option> })
+                            .collect_view()
+                    }}
+                </select>
+                {move || {
+                    let all = all_tags.get();
+                    tag_ids
+                        .get()
+                        .into_iter()
+                        .filter_map(|id| all.iter().find(|t| t.id == id).cloned())
+                        .map(|tag| {
+                            let id = tag.id;
+                            view! {
+                                <span
+                                    class="tag-badge"
+                                    style=tag.display_color().map(|c| format!("background-color: {}", c)).unwrap_or_default()
+                                >
+                                    {tag.name.clone()}
+                                    <button
+                                        class="view-tab-close"
+                                        title="Remove from this list's filter"
+                                        on:click=move |_| set_tag_ids.update(|ids| ids.retain(|t| *t != id))
+                                    >
+                                        "×"
+                                    </button>
+                                </span>
+                            }
+                        })
+                        .collect_view()
+                }}
+                <button on:click=move |_| set_use_and.update(|v| *v = !*v)>
+                    {move || if use_and.get() { "AND" } else { "OR" }}
+                </button>
+                <span class="file-count">{move || format!("{} files", rows.get().len())}</span>
+            </div>
+            <Show
+                when=move || !tag_ids.get().is_empty()
+                fallback=|| view! {
+                    <p class="settings-hint split-pane-hint">
+                        "Pick tags to fill this list, then drag files between the lists to move them between the tag sets. Hold Ctrl while dropping to add tags without removing any."
+                    </p>
+                }
+            >
+                <FileList
+                    files=files
+                    selected_file_paths=selected
+                    on_toggle=on_toggle
+                    sort_column=sort_column
+                    sort_direction=sort_direction
+                    on_sort=on_sort
+                    on_drag_start=drag_start
+                />
+            </Show>
+        </div>
+    }
+}
+
+fn display_file(row: FileWithTags) -> DisplayFile {
+    let file = row.file;
+    let path = std::path::Path::new(&file.path);
+    DisplayFile {
+        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        extension: path.extension().unwrap_or_default().to_string_lossy().to_string(),
+        size_bytes: file.size_bytes,
+        last_modified: file.last_modified,
+        db_id: Some(file.id),
+        tags: row.tags,
+        is_directory: file.is_directory,
+        last_tagged: file.last_tagged,
+        open_count: file.open_count,
+        last_opened_at: file.last_opened_at,
+        inherited_tags: Vec::new(),
+        path: file.path,
+    }
+}
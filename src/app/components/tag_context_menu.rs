@@ -0,0 +1,269 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{MergeTagsArgs, SetTagArchivedArgs, SetTagHookArgs, SetTagLockedArgs, TagHook, TagInfo, UpdateTagArgs};
+use crate::app::utils::TAG_PALETTE;
+
+// Tag and click position of an open tag context menu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TagMenuTarget {
+    pub tag_id: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TagMenuDialog {
+    Rename,
+    Color,
+    Merge,
+    Hook,
+}
+
+// Tags a tag can be merged into: everything except itself and its descendants
+fn merge_candidates(tags: &[TagInfo], source: u32) -> Vec<TagInfo> {
+    let is_in_subtree = |mut id: Option<u32>| {
+        while let Some(cur) = id {
+            if cur == source {
+                return true;
+            }
+            id = tags.iter().find(|t| t.id == cur).and_then(|t| t.parent_id);
+        }
+        false
+    };
+    let mut list: Vec<TagInfo> = tags.iter().filter(|t| !is_in_subtree(Some(t.id))).cloned().collect();
+    list.sort_by_key(|t| t.name.to_lowercase());
+    list
+}
+
+#[component]
+pub fn TagContextMenu(
+    target: ReadSignal<Option<TagMenuTarget>>,
+    set_target: WriteSignal<Option<TagMenuTarget>>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    on_new_child: impl Fn(u32) + Copy + Send + Sync + 'static,
+    on_delete: impl Fn(u32) + Copy + Send + Sync + 'static,
+    // Opens the example files panel for the tag
+    on_examples: impl Fn(u32) + Copy + Send + Sync + 'static,
+    // Called after rename, recolor, merge, archiving or locking so the caller can reload tags and files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (dialog, set_dialog) = signal(None::<(TagMenuDialog, u32)>);
+    let (name_input, set_name_input) = signal(String::new());
+    let (merge_target, set_merge_target) = signal(None::<u32>);
+    let (hook_input, set_hook_input) = signal(String::new());
+    let (error, set_error) = signal(None::<String>);
+
+    let tag_by_id = move |id: u32| all_tags.get_untracked().into_iter().find(|t| t.id == id);
+
+    let open_dialog = move |kind: TagMenuDialog, id: u32| {
+        set_target.set(None);
+        set_error.set(None);
+        set_merge_target.set(None);
+        set_name_input.set(tag_by_id(id).map(|t| t.name).unwrap_or_default());
+        set_hook_input.set(String::new());
+        if kind == TagMenuDialog::Hook {
+            spawn_local(async move {
+                let val = invoke("get_tag_hooks", wasm_bindgen::JsValue::NULL).await;
+                let hooks = serde_wasm_bindgen::from_value::<Vec<TagHook>>(val).unwrap_or_default();
+                if let Some(hook) = hooks.into_iter().find(|h| h.tag_id == id) {
+                    set_hook_input.set(hook.command);
+                }
+            });
+        }
+        set_dialog.set(Some((kind, id)));
+    };
+
+    let update = move |id: u32, name: String, color: Option<String>| {
+        spawn_local(async move {
+            let args = UpdateTagArgs { id, name, color };
+            match try_invoke("update_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => {
+                    set_dialog.set(None);
+                    on_changed();
+                }
+                Err(e) => set_error.set(Some(e.as_string().unwrap_or_else(|| "Failed to update tag".to_string()))),
+            }
+        });
+    };
+
+    let rename = move || {
+        let Some((_, id)) = dialog.get_untracked() else { return };
+        let Some(tag) = tag_by_id(id) else { return };
+        let name = name_input.get_untracked().trim().to_string();
+        if !name.is_empty() {
+            update(id, name, tag.color);
+        }
+    };
+
+    let recolor = move |color: Option<String>| {
+        let Some((_, id)) = dialog.get_untracked() else { return };
+        let Some(tag) = tag_by_id(id) else { return };
+        update(id, tag.name, color);
+    };
+
+    let merge = move || {
+        let (Some((_, source_id)), Some(target_id)) = (dialog.get_untracked(), merge_target.get_untracked()) else { return };
+        spawn_local(async move {
+            let args = MergeTagsArgs { source_id, target_id };
+            match try_invoke("merge_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => {
+                    set_dialog.set(None);
+                    on_changed();
+                }
+                Err(e) => set_error.set(Some(e.as_string().unwrap_or_else(|| "Failed to merge tags".to_string()))),
+            }
+        });
+    };
+
+    let set_archived = move |id: u32, archived: bool| {
+        set_target.set(None);
+        spawn_local(async move {
+            let args = SetTagArchivedArgs { id, archived };
+            if try_invoke("set_tag_archived", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok() {
+                on_changed();
+            }
+        });
+    };
+
+    let set_locked = move |id: u32, locked: bool| {
+        set_target.set(None);
+        spawn_local(async move {
+            let args = SetTagLockedArgs { id, locked };
+            if try_invoke("set_tag_locked", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok() {
+                on_changed();
+            }
+        });
+    };
+
+    let save_hook = move || {
+        let Some((_, tag_id)) = dialog.get_untracked() else { return };
+        let command = hook_input.get_untracked();
+        spawn_local(async move {
+            let args = SetTagHookArgs { tag_id, command };
+            match try_invoke("set_tag_hook", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(_) => set_dialog.set(None),
+                Err(e) => set_error.set(Some(e.as_string().unwrap_or_else(|| "Failed to save hook".to_string()))),
+            }
+        });
+    };
+
+    view! {
+        {move || target.get().map(|t| {
+            let id = t.tag_id;
+            let archived = tag_by_id(id).is_some_and(|t| t.archived);
+            let locked = tag_by_id(id).is_some_and(|t| t.locked);
+            view! {
+                <div
+                    class="context-menu-backdrop"
+                    on:click=move |_| set_target.set(None)
+                    on:contextmenu=move |ev: web_sys::MouseEvent| {
+                        ev.prevent_default();
+                        set_target.set(None);
+                    }
+                >
+                    <ul class="context-menu" style=format!("left: {}px; top: {}px", t.x, t.y) on:click=|e| e.stop_propagation()>
+                        <li on:click=move |_| { set_target.set(None); on_new_child(id); }>"New child tag"</li>
+                        <li on:click=move |_| open_dialog(TagMenuDialog::Rename, id)>"Rename"</li>
+                        <li on:click=move |_| open_dialog(TagMenuDialog::Color, id)>"Change color"</li>
+                        <li on:click=move |_| open_dialog(TagMenuDialog::Merge, id)>"Merge into…"</li>
+                        <li on:click=move |_| open_dialog(TagMenuDialog::Hook, id)>"Automation hook…"</li>
+                        <li on:click=move |_| { set_target.set(None); on_examples(id); }>"Examples…"</li>
+                        <li
+                            title="Archived tags are hidden from the tree and suggestions but stay on their files"
+                            on:click=move |_| set_archived(id, !archived)
+                        >{if archived { "Unarchive" } else { "Archive" }}</li>
+                        <li
+                            title="Locked tags and their children can't be renamed, moved or deleted"
+                            on:click=move |_| set_locked(id, !locked)
+                        >{if locked { "Unlock" } else { "Lock" }}</li>
+                        <li class="danger" on:click=move |_| { set_target.set(None); on_delete(id); }>"Delete"</li>
+                    </ul>
+                </div>
+            }
+        })}
+        {move || dialog.get().map(|(kind, id)| {
+            let title = match kind {
+                TagMenuDialog::Rename => "Rename Tag",
+                TagMenuDialog::Color => "Change Color",
+                TagMenuDialog::Merge => "Merge Into…",
+                TagMenuDialog::Hook => "Automation Hook",
+            };
+            let tag_name = tag_by_id(id).map(|t| t.name).unwrap_or_default();
+            let body = match kind {
+                TagMenuDialog::Rename => view! {
+                    <input
+                        type="text"
+                        prop:value=name_input
+                        on:input=move |e| set_name_input.set(event_target_value(&e))
+                        on:keydown=move |e| if e.key() == "Enter" { rename() }
+                    />
+                    <div style="display:flex; gap:8px;">
+                        <button on:click=move |_| rename()>"Rename"</button>
+                        <button on:click=move |_| set_dialog.set(None)>"Cancel"</button>
+                    </div>
+                }.into_any(),
+                TagMenuDialog::Color => view! {
+                    <div class="swatches">
+                        <button class="swatch swatch-none" title="No color" on:click=move |_| recolor(None)>"∅"</button>
+                        {TAG_PALETTE.iter().map(|c| {
+                            let c = c.to_string();
+                            let c_set = c.clone();
+                            view! {
+                                <button
+                                    class="swatch"
+                                    style=format!("background: {}", c)
+                                    title=c
+                                    on:click=move |_| recolor(Some(c_set.clone()))
+                                ></button>
+                            }
+                        }).collect_view()}
+                    </div>
+                    <div style="display:flex; gap:8px; margin-top:12px;">
+                        <button on:click=move |_| set_dialog.set(None)>"Cancel"</button>
+                    </div>
+                }.into_any(),
+                TagMenuDialog::Merge => view! {
+                    <p class="settings-hint">{format!("Files tagged \"{}\" get the chosen tag instead; its children move along.", tag_name)}</p>
+                    <select
+                        class="merge-target-select"
+                        on:change=move |e| set_merge_target.set(event_target_value(&e).parse::<u32>().ok())
+                    >
+                        <option value="">"Choose a tag…"</option>
+                        {merge_candidates(&all_tags.get_untracked(), id).into_iter().map(|t| view! {
+                            <option value=t.id.to_string()>{t.name}</option>
+                        }).collect_view()}
+                    </select>
+                    <div style="display:flex; gap:8px;">
+                        <button prop:disabled=move || merge_target.get().is_none() on:click=move |_| merge()>"Merge"</button>
+                        <button on:click=move |_| set_dialog.set(None)>"Cancel"</button>
+                    </div>
+                }.into_any(),
+                TagMenuDialog::Hook => view! {
+                    <p class="settings-hint">{format!("Runs whenever \"{}\" is applied to a file, with the file's path as the last argument. Leave empty to remove.", tag_name)}</p>
+                    <input
+                        type="text"
+                        placeholder="Command, e.g. python convert.py"
+                        prop:value=hook_input
+                        on:input=move |e| set_hook_input.set(event_target_value(&e))
+                        on:keydown=move |e| if e.key() == "Enter" { save_hook() }
+                    />
+                    <div style="display:flex; gap:8px;">
+                        <button on:click=move |_| save_hook()>"Save"</button>
+                        <button on:click=move |_| set_dialog.set(None)>"Cancel"</button>
+                    </div>
+                }.into_any(),
+            };
+            view! {
+                <div class="modal-overlay" on:click=move |_| set_dialog.set(None)>
+                    <div class="modal" on:click={|e| e.stop_propagation()}>
+                        <h3>{title}</h3>
+                        {body}
+                        {move || error.get().map(|msg| view! { <p style="color:#c00;">{msg}</p> })}
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
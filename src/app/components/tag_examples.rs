@@ -0,0 +1,113 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{AddTagExamplesArgs, GetTagExamplesArgs, RemoveTagExampleArgs, TagExample, TagInfo};
+
+// Tag detail panel for example files: what the tag looks like and what it
+// doesn't. The text recommenders show them to the model alongside the labels.
+#[component]
+pub fn TagExamplesDialog(
+    tag_id: ReadSignal<Option<u32>>,
+    set_tag_id: WriteSignal<Option<u32>>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    selected_paths: ReadSignal<Vec<String>>,
+) -> impl IntoView {
+    let (examples, set_examples) = signal(Vec::<TagExample>::new());
+    let (error, set_error) = signal(None::<String>);
+
+    let load = move |id: u32| {
+        spawn_local(async move {
+            let args = GetTagExamplesArgs { tag_id: id };
+            let val = invoke("get_tag_examples", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            set_examples.set(serde_wasm_bindgen::from_value(val).unwrap_or_default());
+        });
+    };
+
+    Effect::new(move |_| {
+        if let Some(id) = tag_id.get() {
+            set_error.set(None);
+            set_examples.set(Vec::new());
+            load(id);
+        }
+    });
+
+    let add = move |positive: bool| {
+        let Some(id) = tag_id.get_untracked() else { return };
+        let file_paths = selected_paths.get_untracked();
+        if file_paths.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let args = AddTagExamplesArgs { tag_id: id, file_paths, positive };
+            if let Err(e) = try_invoke("add_tag_examples", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_error.set(Some(e.as_string().unwrap_or_else(|| "Failed to add examples".to_string())));
+            }
+            load(id);
+        });
+    };
+
+    let remove = move |file_path: String| {
+        let Some(id) = tag_id.get_untracked() else { return };
+        spawn_local(async move {
+            let args = RemoveTagExampleArgs { tag_id: id, file_path };
+            if let Err(e) = try_invoke("remove_tag_example", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_error.set(Some(e.as_string().unwrap_or_else(|| "Failed to remove example".to_string())));
+            }
+            load(id);
+        });
+    };
+
+    let list = move |positive: bool| {
+        let items: Vec<TagExample> = examples.get().into_iter().filter(|e| e.positive == positive).collect();
+        if items.is_empty() {
+            return view! { <p class="settings-hint">"None yet."</p> }.into_any();
+        }
+        items
+            .into_iter()
+            .map(|e| {
+                let name = std::path::Path::new(&e.file_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| e.file_path.clone());
+                let path = e.file_path.clone();
+                view! {
+                    <div class="cleanup-row">
+                        <div class="cleanup-info" title=e.file_path>{name}</div>
+                        <button title="Remove example" on:click=move |_| remove(path.clone())>"×"</button>
+                    </div>
+                }
+            })
+            .collect_view()
+            .into_any()
+    };
+
+    view! {
+        {move || tag_id.get().map(|id| {
+            let tag_name = all_tags.get_untracked().into_iter().find(|t| t.id == id).map(|t| t.name).unwrap_or_default();
+            let selected = move || selected_paths.get().len();
+            view! {
+                <div class="modal-overlay" on:click=move |_| set_tag_id.set(None)>
+                    <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                        <h3>{format!("Examples for \"{}\"", tag_name)}</h3>
+                        <p class="settings-hint">"Files that show what this tag means help the recommender pick it, and counter-examples help it hold back."</p>
+                        <h4>"Examples"</h4>
+                        {move || list(true)}
+                        <h4>"Counter-examples"</h4>
+                        {move || list(false)}
+                        {move || error.get().map(|msg| view! { <p style="color:#c00;">{msg}</p> })}
+                        <div style="display:flex; gap:8px; margin-top:8px;">
+                            <button prop:disabled=move || selected() == 0 on:click=move |_| add(true)>
+                                {move || format!("Add {} selected as examples", selected())}
+                            </button>
+                            <button prop:disabled=move || selected() == 0 on:click=move |_| add(false)>
+                                {move || format!("Add {} selected as counter-examples", selected())}
+                            </button>
+                            <button on:click=move |_| set_tag_id.set(None)>"Close"</button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
@@ -0,0 +1,59 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::collections::HashMap;
+
+use crate::app::api::invoke;
+use crate::app::types::{GetTagHistoryArgs, TagHistoryEntry, TagInfo};
+use crate::app::utils::format_timestamp;
+
+// Tag add/remove history for a single selected file
+#[component]
+pub fn TagHistory(
+    selected_file_paths: ReadSignal<Vec<String>>,
+    file_tags_map: ReadSignal<HashMap<u32, Vec<TagInfo>>>,
+) -> impl IntoView {
+    let (entries, set_entries) = signal(Vec::<TagHistoryEntry>::new());
+
+    // Reload on selection change and after any tag change (which reloads file_tags_map)
+    Effect::new(move |_| {
+        let files = selected_file_paths.get();
+        file_tags_map.track();
+        if files.len() != 1 {
+            set_entries.set(Vec::new());
+            return;
+        }
+        let file_path = files[0].clone();
+        spawn_local(async move {
+            let args = GetTagHistoryArgs { file_path };
+            let val = invoke("get_tag_history", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<TagHistoryEntry>>(val) {
+                set_entries.set(list);
+            }
+        });
+    });
+
+    view! {
+        <Show when=move || selected_file_paths.get().len() == 1>
+            <div class="tag-history">
+                <h4>"History"</h4>
+                <Show
+                    when=move || !entries.get().is_empty()
+                    fallback=|| view! { <div class="tag-history-empty">"No tag changes recorded"</div> }
+                >
+                    <ul>
+                        {move || entries.get().into_iter().map(|e| {
+                            let added = e.action == "added";
+                            view! {
+                                <li class:removed=!added>
+                                    <span class="tag-history-action">{if added { "+" } else { "−" }}</span>
+                                    <span class="tag-history-name">{e.tag_name}</span>
+                                    <span class="tag-history-at">{format_timestamp(e.at)}</span>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                </Show>
+            </div>
+        </Show>
+    }
+}
@@ -1,6 +1,7 @@
 use leptos::prelude::*;
 use wasm_bindgen::JsCast;
 
+use crate::app::components::tag_context_menu::TagMenuTarget;
 use crate::app::types::{TagInfo, FileInfo, DeleteTagArgs};
 use crate::app::files::filter_files;
 use crate::app::api::invoke;
@@ -8,6 +9,8 @@ use crate::app::api::invoke;
 #[component]
 pub fn TagTree(
     tags: ReadSignal<Vec<TagInfo>>,
+    // Archived tags (and their children) are left out unless this is on
+    show_archived: ReadSignal<bool>,
     selected_tag_ids: ReadSignal<Vec<u32>>,
     set_selected_tag_ids: WriteSignal<Vec<u32>>,
     use_and_logic: ReadSignal<bool>,
@@ -31,12 +34,25 @@ pub fn TagTree(
     let root_tags = move || {
         tags.get()
             .into_iter()
-            .filter(|t| t.parent_id.is_none())
+            .filter(|t| t.parent_id.is_none() && (show_archived.get() || !t.archived))
             .collect::<Vec<_>>()
     };
 
+    let (collapsed_tag_ids, set_collapsed_tag_ids) = signal(std::collections::HashSet::<u32>::new());
+
+    let tree_ref = NodeRef::<leptos::html::Div>::new();
+    leptos_dragdrop::bind_auto_scroll(dnd.clone(), tree_ref, leptos_dragdrop::AutoScrollConfig::default());
+    leptos_dragdrop::bind_hover_expand(
+        dnd,
+        move || tags.get_untracked().iter().map(|t| leptos_dragdrop::Node { id: t.id, parent_id: t.parent_id, position: t.position }).collect(),
+        move |id| collapsed_tag_ids.get_untracked().contains(&id),
+        move |id| set_collapsed_tag_ids.update(|c| { c.remove(&id); }),
+        move |id| set_collapsed_tag_ids.update(|c| { c.insert(id); }),
+        700,
+    );
+
     view! {
-        <div class="tag-tree">
+        <div class="tag-tree" node_ref=tree_ref>
             <For
                 each=root_tags
                 key=|tag| tag.id
@@ -63,6 +79,9 @@ pub fn TagTree(
                         set_reload_tags_trigger=set_reload_tags_trigger
                         drag_just_ended=drag_just_ended
                         set_drag_just_ended=set_drag_just_ended
+                        collapsed_tag_ids=collapsed_tag_ids
+                        set_collapsed_tag_ids=set_collapsed_tag_ids
+                        show_archived=show_archived
                         />
                     }
                 }
@@ -75,6 +94,7 @@ pub fn TagTree(
 pub fn TagNode(
     tag: TagInfo,
     all_tags: ReadSignal<Vec<TagInfo>>,
+    show_archived: ReadSignal<bool>,
     selected_tag_ids: ReadSignal<Vec<u32>>,
     set_selected_tag_ids: WriteSignal<Vec<u32>>,
     use_and_logic: ReadSignal<bool>,
@@ -93,18 +113,23 @@ pub fn TagNode(
     set_reload_tags_trigger: WriteSignal<u32>,
     drag_just_ended: ReadSignal<bool>,
     set_drag_just_ended: WriteSignal<bool>,
+    collapsed_tag_ids: ReadSignal<std::collections::HashSet<u32>>,
+    set_collapsed_tag_ids: WriteSignal<std::collections::HashSet<u32>>,
 ) -> AnyView {
     let dnd = expect_context::<leptos_dragdrop::DndSignals>();
+    let set_tag_menu = expect_context::<WriteSignal<Option<TagMenuTarget>>>();
     let tag_id = tag.id;
+    let archived = tag.archived;
     let children = move || {
         all_tags.get()
             .into_iter()
-            .filter(move |t| t.parent_id == Some(tag_id))
+            .filter(move |t| t.parent_id == Some(tag_id) && (show_archived.get() || !t.archived))
             .collect::<Vec<_>>()
     };
 
     let is_selected = move || selected_tag_ids.get().contains(&tag_id);
     let has_children = move || !children().is_empty();
+    let is_collapsed = move || collapsed_tag_ids.get().contains(&tag_id);
     
     let _is_dragging = move || dragging_tag_id.get() == Some(tag_id);
     let _is_drop_target = move || drop_target_tag_id.get() == Some(tag_id);
@@ -148,6 +173,7 @@ pub fn TagNode(
     let on_mouseenter = leptos_dragdrop::make_on_mousemove(dnd.clone(), current_node, get_nodes);
     let on_mousemove = leptos_dragdrop::make_on_mousemove(dnd.clone(), current_node, get_nodes);
 
+    let dnd_for_class = dnd.clone();
     // Visual feedback based on drag state
     let node_class = move || {
         let mut classes = vec![];
@@ -155,16 +181,14 @@ pub fn TagNode(
         if dragging_tag_id.get() == Some(tag_id) {
             classes.push("dragging");
         }
+
+        if dnd_for_class.selected_ids_read.get().contains(&tag_id) {
+            classes.push("multi-selected");
+        }
         
         if drop_target_tag_id.get() == Some(tag_id) {
             let pos = drop_position.get();
-            if pos < 0.25 {
-                classes.push("drop-before");
-            } else if pos > 0.75 {
-                classes.push("drop-after");
-            } else {
-                classes.push("drop-child");
-            }
+            classes.push(leptos_dragdrop::DropConfig::default().zone(pos).class());
         }
         
         classes.join(" ")
@@ -173,6 +197,7 @@ pub fn TagNode(
     view! {
         <div 
             class=move || format!("tag-node {}", node_class())
+            class:archived=archived
             style=format!("margin-left: {}px", level * 20)
         >
             <label 
@@ -180,16 +205,41 @@ pub fn TagNode(
                 on:mousedown=on_mousedown
                 on:mouseenter=on_mouseenter
                 on:mousemove=on_mousemove
+                on:mouseleave=leptos_dragdrop::make_on_mouseleave(dnd.clone())
                 on:click=leptos_dragdrop::make_label_click_guard(dnd.clone())
+                on:contextmenu=move |ev: web_sys::MouseEvent| {
+                    ev.prevent_default();
+                    ev.stop_propagation();
+                    set_tag_menu.set(Some(TagMenuTarget { tag_id, x: ev.client_x(), y: ev.client_y() }));
+                }
             >
+                <span
+                    class="tag-toggle"
+                    style=move || format!("display:inline-block; width:14px; cursor:pointer; visibility:{};", if has_children() { "visible" } else { "hidden" })
+                    on:mousedown=move |ev: web_sys::MouseEvent| {
+                        ev.stop_propagation();
+                        ev.prevent_default();
+                    }
+                    on:click=move |ev: web_sys::MouseEvent| {
+                        ev.stop_propagation();
+                        ev.prevent_default();
+                        set_collapsed_tag_ids.update(|c| {
+                            if !c.remove(&tag_id) {
+                                c.insert(tag_id);
+                            }
+                        });
+                    }
+                >{move || if is_collapsed() { "▸" } else { "▾" }}</span>
                 <input
                     type="checkbox"
                     prop:checked=is_selected
                     on:change=leptos_dragdrop::make_checkbox_change_guard(dnd.clone(), on_toggle, tag_id)
                     on:click=leptos_dragdrop::make_checkbox_click_guard(dnd.clone())
                 />
-                <span class="tag-name" style=move || tag.color.clone().map(|c| format!("color: {}", c)).unwrap_or_default()>
+                <span class="tag-name" style=move || tag.display_color().map(|c| format!("color: {}", c)).unwrap_or_default()>
+                    {tag.icon.clone().map(|i| format!("{} ", i))}
                     {tag.name.clone()}
+                    {tag.locked.then_some(" 🔒")}
                 </span>
                 <button
                     class="tag-delete"
@@ -207,7 +257,7 @@ pub fn TagNode(
                     }
                 >"×"</button>
             </label>
-            {move || has_children().then(|| view! {
+            {move || (has_children() && !is_collapsed()).then(|| view! {
                 <div class="tag-children">
                     <For
                         each=children
@@ -235,6 +285,9 @@ pub fn TagNode(
                                 set_reload_tags_trigger=set_reload_tags_trigger
                                 drag_just_ended=drag_just_ended
                                 set_drag_just_ended=set_drag_just_ended
+                                collapsed_tag_ids=collapsed_tag_ids
+                                set_collapsed_tag_ids=set_collapsed_tag_ids
+                                show_archived=show_archived
                                 />
                             }
                         }
@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::app::api::try_invoke;
+use crate::app::types::{CreateTagArgs, MergeTagsArgs, MoveTagArgs, SuggestTaxonomyArgs, TagInfo, TaxonomyChange, UpdateTagArgs};
+
+// Merges, renames and new parent groupings proposed by the LLM for the whole
+// tag list. Nothing changes until the checked proposals are applied.
+#[component]
+pub fn TaxonomyDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    // Called after applying so the caller can reload tags and files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (changes, set_changes) = signal(None::<Vec<TaxonomyChange>>);
+    // Indexes into `changes` left out of the apply
+    let (skipped, set_skipped) = signal(HashSet::<usize>::new());
+    let (status, set_status) = signal(None::<String>);
+    let (busy, set_busy) = signal(false);
+
+    // Ask again every time the dialog opens
+    Effect::new(move |_| {
+        if show.get() {
+            set_changes.set(None);
+            set_skipped.set(HashSet::new());
+            set_status.set(None);
+            spawn_local(async move {
+                let args = SuggestTaxonomyArgs { base_url: None, model: None };
+                match try_invoke("suggest_taxonomy", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                    Ok(v) => set_changes.set(Some(serde_wasm_bindgen::from_value(v).unwrap_or_default())),
+                    Err(e) => {
+                        set_changes.set(Some(Vec::new()));
+                        set_status.set(Some(e.as_string().unwrap_or_else(|| "Suggestion failed".to_string())));
+                    }
+                }
+            });
+        }
+    });
+
+    let tag_name = move |id: u32| {
+        all_tags
+            .get_untracked()
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.name.clone())
+            .unwrap_or_else(|| format!("#{}", id))
+    };
+    let describe = move |change: &TaxonomyChange| match change {
+        TaxonomyChange::Merge { source_id, target_id, .. } => {
+            format!("Merge \"{}\" into \"{}\"", tag_name(*source_id), tag_name(*target_id))
+        }
+        TaxonomyChange::Rename { id, name, .. } => format!("Rename \"{}\" to \"{}\"", tag_name(*id), name),
+        TaxonomyChange::Group { parent, ids, .. } => format!(
+            "Group {} under \"{}\"",
+            ids.iter().map(|id| format!("\"{}\"", tag_name(*id))).collect::<Vec<_>>().join(", "),
+            parent
+        ),
+    };
+
+    let apply = move |_| {
+        let Some(list) = changes.get_untracked() else { return };
+        let skip = skipped.get_untracked();
+        let selected: Vec<TaxonomyChange> =
+            list.into_iter().enumerate().filter(|(i, _)| !skip.contains(i)).map(|(_, c)| c).collect();
+        if selected.is_empty() {
+            return;
+        }
+        set_busy.set(true);
+        spawn_local(async move {
+            let (mut applied, mut failed) = (0, 0);
+            // Parents created by earlier proposals in this run
+            let mut created: HashMap<String, u32> = HashMap::new();
+            for change in selected {
+                let tags = all_tags.get_untracked();
+                let ok = match change {
+                    TaxonomyChange::Merge { source_id, target_id, .. } => {
+                        let args = MergeTagsArgs { source_id, target_id };
+                        try_invoke("merge_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok()
+                    }
+                    TaxonomyChange::Rename { id, name, .. } => {
+                        let color = tags.iter().find(|t| t.id == id).and_then(|t| t.color.clone());
+                        let args = UpdateTagArgs { id, name, color };
+                        try_invoke("update_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok()
+                    }
+                    TaxonomyChange::Group { parent, ids, .. } => {
+                        // Reuse a top-level tag of that name, else create one
+                        let existing = tags
+                            .iter()
+                            .find(|t| t.parent_id.is_none() && t.name == parent)
+                            .map(|t| t.id)
+                            .or_else(|| created.get(&parent).copied());
+                        let parent_id = match existing {
+                            Some(id) => Some(id),
+                            None => {
+                                let args = CreateTagArgs { name: parent.clone(), parent_id: None, color: None, icon: None };
+                                let id = try_invoke("create_tag", serde_wasm_bindgen::to_value(&args).unwrap())
+                                    .await
+                                    .ok()
+                                    .and_then(|v| v.as_f64())
+                                    .map(|id| id as u32);
+                                if let Some(id) = id {
+                                    created.insert(parent, id);
+                                }
+                                id
+                            }
+                        };
+                        match parent_id {
+                            Some(parent_id) => {
+                                let mut all_moved = true;
+                                for (position, id) in ids.into_iter().filter(|id| *id != parent_id).enumerate() {
+                                    let args = MoveTagArgs { id, new_parent_id: Some(parent_id), target_position: position as i32 };
+                                    all_moved &= try_invoke("move_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await.is_ok();
+                                }
+                                all_moved
+                            }
+                            None => false,
+                        }
+                    }
+                };
+                if ok {
+                    applied += 1;
+                } else {
+                    failed += 1;
+                }
+            }
+            set_busy.set(false);
+            set_status.set(Some(if failed > 0 {
+                format!("Applied {} change(s), {} failed", applied, failed)
+            } else {
+                format!("Applied {} change(s)", applied)
+            }));
+            set_changes.set(Some(Vec::new()));
+            on_changed();
+        });
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Suggest taxonomy improvements"</h3>
+                    {move || match changes.get() {
+                        None => view! { <p class="settings-hint">"Asking the model about your tags..."</p> }.into_any(),
+                        Some(list) if list.is_empty() => view! { <p class="settings-hint">"Nothing to review."</p> }.into_any(),
+                        Some(list) => list.into_iter().enumerate().map(|(i, change)| {
+                            let reason = match &change {
+                                TaxonomyChange::Merge { reason, .. }
+                                | TaxonomyChange::Rename { reason, .. }
+                                | TaxonomyChange::Group { reason, .. } => reason.clone(),
+                            };
+                            view! {
+                                <label class="cleanup-row">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=move || !skipped.get().contains(&i)
+                                        on:change=move |e| {
+                                            let checked = event_target_checked(&e);
+                                            set_skipped.update(|s| {
+                                                if checked { s.remove(&i); } else { s.insert(i); }
+                                            });
+                                        }
+                                    />
+                                    <div class="cleanup-info">
+                                        <strong>{describe(&change)}</strong>
+                                        <div class="cleanup-samples">{reason}</div>
+                                    </div>
+                                </label>
+                            }
+                        }).collect_view().into_any(),
+                    }}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button
+                            prop:disabled=move || busy.get() || changes.get().is_none_or(|l| l.len() <= skipped.get().len())
+                            on:click=apply
+                        >"Apply selected"</button>
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
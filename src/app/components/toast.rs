@@ -0,0 +1,38 @@
+use leptos::prelude::*;
+use std::time::Duration;
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+// Transient message in the bottom corner. Each new message restarts the timer,
+// so an older timeout never hides a newer toast.
+#[component]
+pub fn Toast(
+    message: ReadSignal<Option<String>>,
+    set_message: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (generation, set_generation) = signal(0u32);
+
+    Effect::new(move |_| {
+        if message.get().is_none() {
+            return;
+        }
+        let current = generation.get_untracked() + 1;
+        set_generation.set(current);
+        set_timeout(
+            move || {
+                if generation.get_untracked() == current {
+                    set_message.set(None);
+                }
+            },
+            TOAST_DURATION,
+        );
+    });
+
+    view! {
+        <Show when=move || message.get().is_some()>
+            <div class="toast" on:click=move |_| set_message.set(None)>
+                {move || message.get().unwrap_or_default()}
+            </div>
+        </Show>
+    }
+}
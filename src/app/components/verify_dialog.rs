@@ -0,0 +1,266 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{
+    AcceptFileHashesArgs, ExportManifestArgs, ManifestReport, OpenFileArgs, VerifyFilesArgs, VerifyIssue, VerifyProgress,
+    VerifyReport,
+};
+
+// Issues listed in the dialog; the rest are summarized as a count
+const MAX_LISTED: usize = 200;
+
+// Registers a window listener for one of the bridged `tauri-*` events, once
+pub fn listen_once(event: &str, flag: &str, handler: impl Fn(JsValue) + 'static) {
+    let window = web_sys::window().expect("no window");
+    let set = js_sys::Reflect::get(&window, &JsValue::from_str(flag))
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if set {
+        return;
+    }
+    let closure = Closure::wrap(Box::new(move |ev: web_sys::Event| {
+        if let Some(ce) = ev.dyn_ref::<web_sys::CustomEvent>() {
+            handler(ce.detail());
+        }
+    }) as Box<dyn FnMut(_)>);
+    let _ = window.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+    let _ = js_sys::Reflect::set(&window, &JsValue::from_str(flag), &JsValue::from_bool(true));
+    closure.forget();
+}
+
+#[component]
+pub fn VerifyDialog(
+    show: ReadSignal<bool>,
+    set_show: WriteSignal<bool>,
+    roots: ReadSignal<Vec<String>>,
+    // The tag filter, exported when no root is picked
+    tag_ids: ReadSignal<Vec<u32>>,
+    use_and: ReadSignal<bool>,
+    // Called after hashes were accepted so the caller can reload files
+    on_changed: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let (root, set_root) = signal(String::new());
+    let (running, set_running) = signal(false);
+    let (progress, set_progress) = signal(None::<VerifyProgress>);
+    let (report, set_report) = signal(None::<VerifyReport>);
+    let (status, set_status) = signal(None::<String>);
+    let (manifest_report, set_manifest_report) = signal(None::<ManifestReport>);
+
+    // The check keeps running with the dialog closed; reopening shows where it is
+    Effect::new(move |_| {
+        listen_once("tauri-verify-progress", "__TAGME_VERIFY_PROGRESS_LISTENER_SET", move |detail| {
+            if let Ok(p) = serde_wasm_bindgen::from_value::<VerifyProgress>(detail) {
+                set_progress.set(Some(p));
+            }
+        });
+        listen_once("tauri-verify-finished", "__TAGME_VERIFY_FINISHED_LISTENER_SET", move |detail| {
+            set_running.set(false);
+            set_report.set(serde_wasm_bindgen::from_value::<VerifyReport>(detail).ok());
+        });
+        listen_once("tauri-verify-failed", "__TAGME_VERIFY_FAILED_LISTENER_SET", move |detail| {
+            set_running.set(false);
+            set_status.set(Some(detail.as_string().unwrap_or_else(|| "Integrity check failed".to_string())));
+        });
+    });
+
+    let start = move |_| {
+        let root = Some(root.get_untracked()).filter(|r| !r.is_empty());
+        set_status.set(None);
+        set_report.set(None);
+        set_progress.set(None);
+        set_running.set(true);
+        spawn_local(async move {
+            let args = VerifyFilesArgs { root };
+            if let Err(e) = try_invoke("verify_files", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_running.set(false);
+                set_status.set(Some(e.as_string().unwrap_or_else(|| "Integrity check failed".to_string())));
+            }
+        });
+    };
+
+    // Stores the current hash for the given files and drops them from the report
+    let accept = move |file_ids: Vec<u32>| {
+        spawn_local(async move {
+            let args = AcceptFileHashesArgs { file_ids: file_ids.clone() };
+            match try_invoke("accept_file_hashes", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => {
+                    let n = v.as_f64().unwrap_or(0.0) as usize;
+                    set_status.set(Some(format!("Updated {} hash(es)", n)));
+                    set_report.update(|r| {
+                        if let Some(r) = r {
+                            r.issues.retain(|i| !file_ids.contains(&i.file_id));
+                        }
+                    });
+                    on_changed();
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not update hashes".to_string()))),
+            }
+        });
+    };
+
+    let export_manifest = move |_| {
+        let args = ExportManifestArgs {
+            root: Some(root.get_untracked()).filter(|r| !r.is_empty()),
+            tag_ids: tag_ids.get_untracked(),
+            use_and: use_and.get_untracked(),
+        };
+        set_status.set(Some("Writing manifest...".to_string()));
+        spawn_local(async move {
+            match try_invoke("export_manifest", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                Ok(v) => set_status.set(v.as_f64().map(|n| format!("Manifest lists {} file(s)", n as usize))),
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not write manifest".to_string()))),
+            }
+        });
+    };
+
+    let verify_manifest = move |_| {
+        set_manifest_report.set(None);
+        set_status.set(Some("Checking manifest...".to_string()));
+        spawn_local(async move {
+            match try_invoke("verify_manifest", JsValue::NULL).await {
+                Ok(v) => {
+                    set_status.set(None);
+                    set_manifest_report.set(serde_wasm_bindgen::from_value::<Option<ManifestReport>>(v).ok().flatten());
+                }
+                Err(e) => set_status.set(Some(e.as_string().unwrap_or_else(|| "Could not check manifest".to_string()))),
+            }
+        });
+    };
+
+    let open = move |path: String| {
+        spawn_local(async move {
+            let args = OpenFileArgs { path };
+            let _ = invoke("open_file", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+        });
+    };
+
+    let issue_row = move |issue: VerifyIssue| {
+        let label = match issue.kind.as_str() {
+            "modified" => "Modified",
+            "missing" => "Missing",
+            _ => "Unreadable",
+        };
+        let title = match &issue.current_hash {
+            Some(current) => format!("stored: {}\ncurrent: {}", issue.stored_hash, current),
+            None => format!("stored: {}", issue.stored_hash),
+        };
+        let modified = issue.kind == "modified";
+        let missing = issue.kind == "missing";
+        let id = issue.file_id;
+        let path = issue.path.clone();
+        view! {
+            <div class="cleanup-row" title=title>
+                <span class=format!("verify-kind {}", issue.kind)>{label}</span>
+                <div class="cleanup-info cleanup-samples">{issue.path.clone()}</div>
+                {(!missing).then(|| view! { <button on:click=move |_| open(path.clone())>"Open"</button> })}
+                {modified.then(|| view! { <button on:click=move |_| accept(vec![id])>"Accept"</button> })}
+            </div>
+        }
+    };
+
+    view! {
+        {move || show.get().then(|| view! {
+            <div class="modal-overlay" on:click=move |_| set_show.set(false)>
+                <div class="modal cleanup-dialog" on:click={|e| e.stop_propagation()}>
+                    <h3>"Verify integrity"</h3>
+                    <p class="settings-hint">"Re-hashes tracked files and reports content that changed or disappeared since it was tagged."</p>
+                    <div style="display:flex; gap:8px; align-items:center;">
+                        <select
+                            prop:value=move || root.get()
+                            prop:disabled=move || running.get()
+                            on:change=move |e| set_root.set(event_target_value(&e))
+                        >
+                            <option value="">"All roots"</option>
+                            <For
+                                each=move || roots.get()
+                                key=|r| r.clone()
+                                children=move |r| view! { <option value=r.clone()>{r.clone()}</option> }
+                            />
+                        </select>
+                        <button prop:disabled=move || running.get() on:click=start>
+                            {move || if running.get() { "Checking..." } else { "Start check" }}
+                        </button>
+                    </div>
+                    {move || running.get().then(|| {
+                        let text = progress
+                            .get()
+                            .map(|p| format!("Checked {} of {} file(s)", p.checked, p.total))
+                            .unwrap_or_else(|| "Starting...".to_string());
+                        view! { <p class="settings-hint">{text}</p> }
+                    })}
+                    {move || report.get().map(|r| {
+                        let modified: Vec<u32> = r.issues.iter().filter(|i| i.kind == "modified").map(|i| i.file_id).collect();
+                        let hidden = r.issues.len().saturating_sub(MAX_LISTED);
+                        let listed: Vec<VerifyIssue> = r.issues.iter().take(MAX_LISTED).cloned().collect();
+                        view! {
+                            <div>
+                                <p class="settings-hint">
+                                    {format!("Checked {} file(s): {} issue(s)", r.checked, r.issues.len())}
+                                </p>
+                                <div class="verify-issues">
+                                    {listed.into_iter().map(issue_row).collect_view()}
+                                    {(hidden > 0).then(|| view! { <p class="settings-hint">{format!("and {} more", hidden)}</p> })}
+                                </div>
+                                {(!modified.is_empty()).then(|| {
+                                    let count = modified.len();
+                                    view! {
+                                        <button on:click=move |_| accept(modified.clone())>
+                                            {format!("Accept {} modified file(s)", count)}
+                                        </button>
+                                    }
+                                })}
+                            </div>
+                        }
+                    })}
+                    <h4>"Checksum manifest"</h4>
+                    <p class="settings-hint">
+                        "Lists the SHA-256 and size of every file in the picked root (or with the filtered tags) for hand-offs and backups."
+                    </p>
+                    <div style="display:flex; gap:8px; align-items:center;">
+                        <button
+                            prop:disabled=move || running.get() || (root.get().is_empty() && tag_ids.get().is_empty())
+                            on:click=export_manifest
+                        >
+                            "Export manifest..."
+                        </button>
+                        <button prop:disabled=move || running.get() on:click=verify_manifest>"Verify manifest..."</button>
+                    </div>
+                    {move || manifest_report.get().map(|r| {
+                        let hidden = r.issues.len().saturating_sub(MAX_LISTED);
+                        view! {
+                            <div>
+                                <p class="settings-hint" title=r.base.clone().unwrap_or_default()>
+                                    {format!("Checked {} file(s): {} issue(s)", r.checked, r.issues.len())}
+                                </p>
+                                <div class="verify-issues">
+                                    {r.issues.iter().take(MAX_LISTED).map(|issue| {
+                                        let label = match issue.kind.as_str() {
+                                            "modified" => "Modified",
+                                            "missing" => "Missing",
+                                            _ => "Unreadable",
+                                        };
+                                        view! {
+                                            <div class="cleanup-row">
+                                                <span class=format!("verify-kind {}", issue.kind)>{label}</span>
+                                                <div class="cleanup-info cleanup-samples">{issue.path.clone()}</div>
+                                            </div>
+                                        }
+                                    }).collect_view()}
+                                    {(hidden > 0).then(|| view! { <p class="settings-hint">{format!("and {} more", hidden)}</p> })}
+                                </div>
+                            </div>
+                        }
+                    })}
+                    {move || status.get().map(|msg| view! { <p class="settings-hint">{msg}</p> })}
+                    <div style="display:flex; gap:8px; margin-top:8px;">
+                        <button on:click=move |_| set_show.set(false)>"Close"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
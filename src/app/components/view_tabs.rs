@@ -0,0 +1,82 @@
+use leptos::prelude::*;
+
+use crate::app::types::{TagInfo, ViewTab};
+
+// Tab bar above the file list. Each tab is its own filter, sort and selection,
+// e.g. a triage view kept open while looking through another tag set.
+#[component]
+pub fn ViewTabs(
+    // Every tab, the active one with its live state
+    tabs: Signal<Vec<ViewTab>>,
+    active: ReadSignal<u32>,
+    all_tags: ReadSignal<Vec<TagInfo>>,
+    on_select: impl Fn(u32) + Copy + Send + Sync + 'static,
+    on_new: impl Fn() + Copy + Send + Sync + 'static,
+    on_close: impl Fn(u32) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        <div class="view-tabs">
+            <For
+                each=move || tabs.get()
+                key=|tab| tab.id
+                children=move |tab| {
+                    let id = tab.id;
+                    let label = move || {
+                        tabs.get()
+                            .iter()
+                            .find(|t| t.id == id)
+                            .map(|t| tab_label(t, &all_tags.get()))
+                            .unwrap_or_default()
+                    };
+                    view! {
+                        <div
+                            class=move || if active.get() == id { "view-tab active" } else { "view-tab" }
+                            title=label
+                            on:click=move |_| on_select(id)
+                        >
+                            <span class="view-tab-label">{label}</span>
+                            {move || (tabs.get().len() > 1).then(|| view! {
+                                <button
+                                    class="view-tab-close"
+                                    title="Close tab"
+                                    on:click=move |ev| {
+                                        ev.stop_propagation();
+                                        on_close(id);
+                                    }
+                                >
+                                    "×"
+                                </button>
+                            })}
+                        </div>
+                    }
+                }
+            />
+            <button class="view-tab-new" title="New tab" on:click=move |_| on_new()>"+"</button>
+        </div>
+    }
+}
+
+// "Invoices + 2024", "Inbox | Later" for OR, the folder name for a root-only
+// view, "All files" otherwise; "…" marks search limits
+fn tab_label(tab: &ViewTab, all: &[TagInfo]) -> String {
+    let names: Vec<&str> = tab
+        .tag_ids
+        .iter()
+        .filter_map(|id| all.iter().find(|t| t.id == *id))
+        .map(|t| t.name.as_str())
+        .collect();
+    let label = if !names.is_empty() {
+        names.join(if tab.use_and { " + " } else { " | " })
+    } else if let [root] = tab.roots.as_slice() {
+        tagme_paths::file_name(root).unwrap_or(root).to_string()
+    } else if !tab.roots.is_empty() {
+        format!("{} folders", tab.roots.len())
+    } else {
+        "All files".to_string()
+    };
+    if tab.refine.is_some() {
+        format!("{} …", label)
+    } else {
+        label
+    }
+}
@@ -0,0 +1,79 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{PostUpdateInfo, RunMigrationArgs};
+
+// Shown once after the app relaunches on a new version: applies pending data
+// migrations with progress, then the release notes of the installed build.
+#[component]
+pub fn WhatsNew() -> impl IntoView {
+    let (info, set_info) = signal(None::<PostUpdateInfo>);
+    let (migrating, set_migrating) = signal(None::<(usize, usize, String)>);
+    let (error, set_error) = signal(None::<String>);
+    let (visible, set_visible) = signal(false);
+
+    spawn_local(async move {
+        let Ok(post) = serde_wasm_bindgen::from_value::<PostUpdateInfo>(invoke("get_post_update_info", JsValue::NULL).await) else {
+            return;
+        };
+        let updated = post.updated;
+        let pending = post.pending_migrations.clone();
+        set_info.set(Some(post));
+        if updated || !pending.is_empty() {
+            set_visible.set(true);
+        }
+
+        let total = pending.len();
+        for (i, migration) in pending.into_iter().enumerate() {
+            set_migrating.set(Some((i, total, migration.description.clone())));
+            let args = RunMigrationArgs { version: migration.version };
+            if let Err(e) = try_invoke("run_migration", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_error.set(Some(format!("{}: {}", migration.description, e.as_string().unwrap_or_default())));
+                set_migrating.set(None);
+                return;
+            }
+        }
+        set_migrating.set(None);
+
+        // Fresh installs only needed the migrations; record the version and stay quiet
+        let _ = invoke("acknowledge_update", JsValue::NULL).await;
+        if !updated {
+            set_visible.set(false);
+        }
+    });
+
+    view! {
+        {move || visible.get().then(|| view! {
+            <div class="modal-overlay">
+                <div class="modal whats-new">
+                    {move || match migrating.get() {
+                        Some((done, total, description)) => view! {
+                            <h3>"Updating your library…"</h3>
+                            <p>{format!("Step {} of {}: {}", done + 1, total, description)}</p>
+                            <progress max=total.to_string() value=done.to_string()></progress>
+                        }.into_any(),
+                        None => {
+                            let post = info.get();
+                            let version = post.as_ref().map(|p| p.current_version.clone()).unwrap_or_default();
+                            let previous = post.as_ref().and_then(|p| p.previous_version.clone());
+                            let notes = post.and_then(|p| p.notes).unwrap_or_default();
+                            view! {
+                                <h3>{format!("What's new in {}", version)}</h3>
+                                {previous.map(|v| view! { <p class="settings-hint">{format!("Updated from {}", v)}</p> })}
+                                {move || error.get().map(|msg| view! { <p style="color:#c00;">{format!("Migration failed — {}", msg)}</p> })}
+                                {if notes.trim().is_empty() {
+                                    view! { <p>"Release notes are available on the project's releases page."</p> }.into_any()
+                                } else {
+                                    view! { <div class="release-notes" inner_html=leptos_updater::render_release_notes(&notes)></div> }.into_any()
+                                }}
+                                <button on:click=move |_| set_visible.set(false)>"Got it"</button>
+                            }.into_any()
+                        }
+                    }}
+                </div>
+            </div>
+        })}
+    }
+}
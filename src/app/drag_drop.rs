@@ -3,7 +3,7 @@ use leptos::task::spawn_local;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use crate::app::types::*;
-use crate::app::api::invoke;
+use crate::app::api::try_invoke;
 
 pub fn setup_drag_drop(
     dragging_tag_id: ReadSignal<Option<u32>>,
@@ -13,8 +13,10 @@ pub fn setup_drag_drop(
     drop_position: ReadSignal<f64>,
     set_drop_position: WriteSignal<f64>,
     set_drag_just_ended: WriteSignal<bool>,
+    selected_tag_nodes: ReadSignal<Vec<u32>>,
     all_tags: ReadSignal<Vec<TagInfo>>,
     set_reload_tags_trigger: WriteSignal<u32>,
+    on_cancel: impl Fn() + Copy + 'static,
 ) {
     Effect::new(move |_| {
         let window = web_sys::window().unwrap();
@@ -47,20 +49,32 @@ pub fn setup_drag_drop(
                             .iter()
                             .map(|t| leptos_dragdrop::Node { id: t.id, parent_id: t.parent_id, position: t.position })
                             .collect();
-                        if let Some((new_parent_id, target_position, action)) = leptos_dragdrop::compute_drop_action(dragged_id, target_id, pos, &nodes) {
-                            web_sys::console::log_1(&format!("🎯 Action: {}, Parent: {:?}, Position: {}", action, new_parent_id, target_position).into());
+                        // Ctrl-selected nodes travel together when the grabbed node is one of them
+                        let selected = selected_tag_nodes.get_untracked();
+                        let dragged_ids = if selected.contains(&dragged_id) { selected } else { vec![dragged_id] };
+                        let moves = leptos_dragdrop::compute_multi_drop_actions(&dragged_ids, target_id, pos, &nodes);
+                        if !moves.is_empty() {
+                            web_sys::console::log_1(&format!("🎯 Moves: {:?}", moves).into());
                             spawn_local(async move {
-                                let args = MoveTagArgs { id: dragged_id, new_parent_id, target_position };
-                                let _ = invoke("move_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                                for (id, new_parent_id, target_position) in moves {
+                                    let args = MoveTagArgs { id, new_parent_id, target_position };
+                                    // A same-named sibling at the target rejects the move
+                                    if let Err(e) = try_invoke("move_tag", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                                        web_sys::console::warn_1(&e);
+                                    }
+                                }
                                 set_reload_tags_trigger.update(|v| *v += 1);
                             });
                         } else {
                             web_sys::console::log_1(&"⚠️ Cannot drop - invalid target".into());
                         }
                     }
+                    leptos_dragdrop::end_drag(set_dragging_tag_id, set_drop_target_tag_id, set_drag_just_ended);
+                } else {
+                    web_sys::console::log_1(&"↩️ Released outside the tree - drag cancelled".into());
+                    leptos_dragdrop::end_drag(set_dragging_tag_id, set_drop_target_tag_id, set_drag_just_ended);
+                    on_cancel();
                 }
-                
-                leptos_dragdrop::end_drag(set_dragging_tag_id, set_drop_target_tag_id, set_drag_just_ended);
             }
         });
         
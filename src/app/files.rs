@@ -2,8 +2,9 @@ use leptos::prelude::*;
 use leptos::task::spawn_local;
 use wasm_bindgen::prelude::*;
 use serde::Serialize;
+use std::cell::Cell;
 use crate::app::types::*;
-use crate::app::api::invoke;
+use crate::app::api::{invoke, try_invoke};
 
 pub async fn load_tags(set_all_tags: WriteSignal<Vec<TagInfo>>) {
     web_sys::console::log_1(&"Loading tags...".into());
@@ -29,30 +30,247 @@ pub async fn load_all_files(
     set_displayed_files: WriteSignal<Vec<FileInfo>>,
     set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
 ) {
-    let files_val = invoke("get_all_files", JsValue::NULL).await;
-    let files = match serde_wasm_bindgen::from_value::<Vec<FileInfo>>(files_val) {
-        Ok(f) => f,
-        Err(e) => {
-            web_sys::console::error_1(&format!("Error loading files: {:?}", e).into());
+    // In large library mode a reload refetches the current window only
+    if let Some(signals) = WINDOW_SIGNALS.get() {
+        if let Some(state) = signals.window.get_untracked() {
+            fetch_window(signals, state).await;
             return;
         }
+    }
+    // Files and their tags come back already joined in a single round-trip
+    let Some(rows) = query_files_with_tags(None, Vec::new()).await else {
+        return;
     };
+    let mut files = Vec::with_capacity(rows.len());
+    let mut tags_map = std::collections::HashMap::with_capacity(rows.len());
+    for FileWithTags { file, tags } in rows {
+        tags_map.insert(file.id, tags);
+        files.push(file);
+    }
     
-    // Load tags for each file
-    let mut tags_map = std::collections::HashMap::new();
-    for file in &files {
-        let file_id = file.id;
-        let args = GetFileTagsArgs { file_id };
-        let tags_val = invoke("get_file_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-        
-        if let Ok(tags) = serde_wasm_bindgen::from_value::<Vec<TagInfo>>(tags_val) {
-            tags_map.insert(file_id, tags);
+    // Skip notifying when nothing changed so watcher pings don't re-render the list
+    set_file_tags_map.maybe_update(|current| replace_if_changed(current, tags_map));
+    set_all_files.maybe_update(|current| replace_if_changed(current, files.clone()));
+    set_displayed_files.maybe_update(|current| replace_if_changed(current, files));
+}
+
+// Rows shown before the rest of the library is loaded
+pub const FIRST_PAGE: usize = 500;
+// Rows per request while the rest is loaded in the background
+const HYDRATE_PAGE: usize = 5000;
+
+async fn load_files_page(offset: usize, limit: usize) -> Option<FilesPage> {
+    let args = GetFilesPageArgs { offset, limit };
+    match try_invoke("get_files_page", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+        Ok(v) => serde_wasm_bindgen::from_value::<FilesPage>(v).ok(),
+        Err(e) => {
+            web_sys::console::error_1(&format!("Error loading files: {:?}", e).into());
+            None
         }
     }
-    
+}
+
+// Startup load: shows the first FIRST_PAGE files right away and returns the
+// library size, so `hydrate_files` can fetch the rest
+pub async fn load_first_page(
+    set_all_files: WriteSignal<Vec<FileInfo>>,
+    set_displayed_files: WriteSignal<Vec<FileInfo>>,
+    set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
+) -> usize {
+    let Some(page) = load_files_page(0, FIRST_PAGE).await else {
+        return 0;
+    };
+    let mut files = Vec::with_capacity(page.rows.len());
+    let mut tags_map = std::collections::HashMap::with_capacity(page.rows.len());
+    for FileWithTags { file, tags } in page.rows {
+        tags_map.insert(file.id, tags);
+        files.push(file);
+    }
     set_file_tags_map.set(tags_map);
     set_all_files.set(files.clone());
     set_displayed_files.set(files);
+    page.total
+}
+
+// Appends the rest of the library page by page, reporting (loaded, total).
+// The unfiltered list grows along; a filtered one is left alone. Stops early if
+// something else reloaded the list in the meantime.
+pub async fn hydrate_files(
+    total: usize,
+    all_files: ReadSignal<Vec<FileInfo>>,
+    set_all_files: WriteSignal<Vec<FileInfo>>,
+    set_displayed_files: WriteSignal<Vec<FileInfo>>,
+    set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
+    set_progress: WriteSignal<Option<(usize, usize)>>,
+) {
+    let mut offset = all_files.get_untracked().len();
+    while offset < total {
+        set_progress.set(Some((offset, total)));
+        let Some(page) = load_files_page(offset, HYDRATE_PAGE).await else { break };
+        if page.rows.is_empty() || all_files.get_untracked().len() != offset {
+            break;
+        }
+        let mut files = Vec::with_capacity(page.rows.len());
+        set_file_tags_map.update(|map| {
+            for FileWithTags { file, tags } in page.rows {
+                map.insert(file.id, tags);
+                files.push(file);
+            }
+        });
+        let last_id = all_files.with_untracked(|all| all.last().map(|f| f.id));
+        set_displayed_files.maybe_update(|shown| {
+            let mirrors_all = shown.len() == offset && shown.last().map(|f| f.id) == last_id;
+            if mirrors_all {
+                shown.extend(files.iter().cloned());
+            }
+            mirrors_all
+        });
+        offset += files.len();
+        set_all_files.update(|all| all.extend(files));
+    }
+    set_progress.set(None);
+}
+
+// Rows per window in large library mode
+pub const WINDOW_SIZE: usize = 1000;
+
+// Large library mode: the list holds one window of files in path order and
+// pages through the rest with a path cursor instead of loading everything
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileWindow {
+    pub filter: Option<TagFilter>,
+    pub roots: Vec<String>,
+    // Cursor the current window starts after (None for the first), and the
+    // starts of the windows before it
+    pub start: Option<String>,
+    pub previous: Vec<Option<String>>,
+    pub next: Option<String>,
+    // Rows in the current window, and matching files in all of them
+    pub rows: usize,
+    pub total: usize,
+}
+
+// The list signals a window reload writes to; `window` is None while large
+// library mode is off
+#[derive(Clone, Copy)]
+pub struct WindowSignals {
+    pub window: RwSignal<Option<FileWindow>>,
+    pub set_all_files: WriteSignal<Vec<FileInfo>>,
+    pub set_displayed_files: WriteSignal<Vec<FileInfo>>,
+    pub set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
+}
+
+thread_local! {
+    static WINDOW_SIGNALS: Cell<Option<WindowSignals>> = const { Cell::new(None) };
+}
+
+// Lets load_all_files and filter_files page instead of loading everything
+pub fn register_window(signals: WindowSignals) {
+    WINDOW_SIGNALS.set(Some(signals));
+}
+
+async fn fetch_window(signals: WindowSignals, mut state: FileWindow) {
+    let args = GetFilesWindowArgs {
+        after: state.start.clone(),
+        limit: WINDOW_SIZE,
+        filter: state.filter.clone(),
+        roots: state.roots.clone(),
+    };
+    let page = match try_invoke("get_files_window", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+        Ok(v) => match serde_wasm_bindgen::from_value::<FilesWindow>(v) {
+            Ok(page) => page,
+            Err(e) => {
+                web_sys::console::error_1(&format!("Error loading files: {:?}", e).into());
+                return;
+            }
+        },
+        Err(e) => {
+            web_sys::console::error_1(&format!("Error loading files: {:?}", e).into());
+            return;
+        }
+    };
+    let mut files = Vec::with_capacity(page.rows.len());
+    let mut tags_map = std::collections::HashMap::with_capacity(page.rows.len());
+    for FileWithTags { file, tags } in page.rows {
+        tags_map.insert(file.id, tags);
+        files.push(file);
+    }
+    state.next = page.next_cursor;
+    state.rows = files.len();
+    state.total = page.total;
+    signals.set_file_tags_map.maybe_update(|current| replace_if_changed(current, tags_map));
+    signals.set_all_files.maybe_update(|current| replace_if_changed(current, files.clone()));
+    signals.set_displayed_files.maybe_update(|current| replace_if_changed(current, files));
+    signals.window.set(Some(state));
+}
+
+// Moves the large library window one page forward or back
+pub fn step_window(forward: bool) {
+    let Some(signals) = WINDOW_SIGNALS.get() else { return };
+    let Some(mut state) = signals.window.get_untracked() else { return };
+    if forward {
+        let Some(next) = state.next.take() else { return };
+        state.previous.push(state.start.replace(next));
+    } else {
+        let Some(start) = state.previous.pop() else { return };
+        state.start = start;
+    }
+    spawn_local(fetch_window(signals, state));
+}
+
+pub async fn query_files_with_tags(filter: Option<TagFilter>, roots: Vec<String>) -> Option<Vec<FileWithTags>> {
+    let args = QueryFilesWithTagsArgs { filter, roots };
+    let val = invoke("query_files_with_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+    match serde_wasm_bindgen::from_value::<Vec<FileWithTags>>(val) {
+        Ok(rows) => Some(rows),
+        Err(e) => {
+            web_sys::console::error_1(&format!("Error loading files: {:?}", e).into());
+            None
+        }
+    }
+}
+
+fn replace_if_changed<T: PartialEq>(current: &mut T, new: T) -> bool {
+    if *current == new {
+        return false;
+    }
+    *current = new;
+    true
+}
+
+// Applies a fresh scan onto the current list instead of replacing it: rows keep
+// their position, vanished paths are dropped, changed rows updated in place and
+// new paths appended in scan order. An identical scan doesn't notify at all.
+pub fn apply_scan(set_scanned_files: WriteSignal<Vec<FileListItem>>, files: Vec<FileListItem>) {
+    set_scanned_files.maybe_update(|current| diff_scan(current, files));
+}
+
+fn diff_scan(current: &mut Vec<FileListItem>, files: Vec<FileListItem>) -> bool {
+    let mut incoming: std::collections::HashMap<String, FileListItem> =
+        files.iter().map(|f| (f.path.clone(), f.clone())).collect();
+    let before = current.len();
+    let mut changed = false;
+
+    current.retain_mut(|f| match incoming.remove(&f.path) {
+        Some(new) => {
+            if *f != new {
+                *f = new;
+                changed = true;
+            }
+            true
+        }
+        None => false,
+    });
+    changed |= current.len() != before;
+
+    // Whatever is left in `incoming` is new; append in scan order
+    for f in files {
+        if incoming.contains_key(&f.path) {
+            current.push(f);
+            changed = true;
+        }
+    }
+    changed
 }
 
 pub fn filter_files(
@@ -60,7 +278,16 @@ pub fn filter_files(
     use_and: bool,
     set_displayed_files: WriteSignal<Vec<FileInfo>>,
     all_files: Vec<FileInfo>,
+    roots: Vec<String>,
 ) {
+    // Large library mode: the filter goes to the backend and paging starts over
+    if let Some(signals) = WINDOW_SIGNALS.get() {
+        if signals.window.get_untracked().is_some() {
+            let filter = (!tag_ids.is_empty()).then_some(TagFilter { tag_ids, use_and });
+            spawn_local(fetch_window(signals, FileWindow { filter, roots, ..Default::default() }));
+            return;
+        }
+    }
     if tag_ids.is_empty() {
         set_displayed_files.set(all_files);
         return;
@@ -68,15 +295,10 @@ pub fn filter_files(
 
     spawn_local(async move {
         web_sys::console::log_1(&format!("filter_files start, tag_ids={:?}, use_and={}", tag_ids, use_and).into());
-        let args = FilterFilesByTagsArgs {
-            tag_ids,
-            use_and_logic: use_and,
-        };
-        let result_val = invoke("filter_files_by_tags", serde_wasm_bindgen::to_value(&args).unwrap()).await;
-        
-        if let Ok(files) = serde_wasm_bindgen::from_value::<Vec<FileInfo>>(result_val) {
-            web_sys::console::log_1(&format!("filter_files result count={}", files.len()).into());
-            set_displayed_files.set(files);
+        let filter = TagFilter { tag_ids, use_and };
+        if let Some(rows) = query_files_with_tags(Some(filter), roots).await {
+            web_sys::console::log_1(&format!("filter_files result count={}", rows.len()).into());
+            set_displayed_files.set(rows.into_iter().map(|r| r.file).collect());
         }
     });
 }
@@ -113,7 +335,7 @@ pub fn handle_scan_directory(
 
             set_scanning.set(false);
             if let Some(files) = result {
-                set_scanned_files.set(files);
+                apply_scan(set_scanned_files, files);
                 // Refresh DB files as well
                 load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
             }
@@ -129,20 +351,44 @@ pub fn handle_select_directory(
     set_all_files: WriteSignal<Vec<FileInfo>>,
     set_displayed_files: WriteSignal<Vec<FileInfo>>,
     set_file_tags_map: WriteSignal<std::collections::HashMap<u32, Vec<TagInfo>>>,
-    active_root_filter: ReadSignal<Option<String>>,
-    set_active_root_filter: WriteSignal<Option<String>>,
+    active_root_filter: ReadSignal<Vec<String>>,
+    set_active_root_filter: WriteSignal<Vec<String>>,
+    set_toast: WriteSignal<Option<String>>,
 ) {
     spawn_local(async move {
         let path_val = invoke("select_root_directory", JsValue::NULL).await;
         if let Ok(opt_path) = serde_wasm_bindgen::from_value::<Option<String>>(path_val) {
-            if opt_path.is_none() {
+            let Some(path) = opt_path else {
                 web_sys::console::log_1(&"[Root] selection canceled".into());
                 return;
+            };
+            // Nesting is allowed but has to be intended: the inner root takes its files over
+            let overlap = root_directories.get_untracked().into_iter().find(|r| {
+                !tagme_paths::same_path(r, &path) && (tagme_paths::is_under(&path, r) || tagme_paths::is_under(r, &path))
+            });
+            let allow_nested = match overlap {
+                Some(r) => {
+                    let message = format!(
+                        "{}\noverlaps the root\n{}\n\nAdd it as a nested root? Files inside the inner folder will belong to the inner root.",
+                        path, r
+                    );
+                    let confirmed = web_sys::window().and_then(|w| w.confirm_with_message(&message).ok()).unwrap_or(false);
+                    if !confirmed {
+                        return;
+                    }
+                    true
+                }
+                None => false,
+            };
+            let args = AddRootDirectoryArgs { path, allow_nested };
+            if let Err(e) = try_invoke("add_root_directory", serde_wasm_bindgen::to_value(&args).unwrap()).await {
+                set_toast.set(Some(e.as_string().unwrap_or_else(|| "Couldn't add folder".to_string())));
+                return;
+            }
+            // The backend stores roots canonicalized, so take its list rather than the picked path
+            if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<String>>(invoke("get_root_directories", JsValue::NULL).await) {
+                set_root_directories.set(list);
             }
-            let path = opt_path.unwrap();
-            let mut list = root_directories.get_untracked();
-            if !list.iter().any(|p| p == &path) { list.push(path.clone()); }
-            set_root_directories.set(list.clone());
             
             // Automatically trigger scan after selecting directory
             set_scanning.set(true);
@@ -166,7 +412,7 @@ pub fn handle_select_directory(
 
             set_scanning.set(false);
             if let Some(files) = scan_result {
-                set_scanned_files.set(files);
+                apply_scan(set_scanned_files, files);
                 // Refresh DB files as well
                 load_all_files(set_all_files, set_displayed_files, set_file_tags_map).await;
             }
@@ -175,9 +421,9 @@ pub fn handle_select_directory(
             spawn_local(async move {
                 #[derive(Serialize)]
                 #[serde(rename_all = "camelCase")]
-                struct StartWatchingMultiArgs { root_paths: Vec<String> }
-                let args = StartWatchingMultiArgs { root_paths: root_directories.get_untracked() };
-                let _ = invoke("start_watching_multi", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+                struct ReconcileWatchersArgs { roots: Vec<String> }
+                let args = ReconcileWatchersArgs { roots: root_directories.get_untracked() };
+                let _ = invoke("reconcile_watchers", serde_wasm_bindgen::to_value(&args).unwrap()).await;
             });
         }
     });
@@ -0,0 +1,147 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_recommender::{RecommendItem, RecommendSources};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::app::api::{invoke, try_invoke};
+use crate::app::types::{FileInfo, FileListItem, StoreRecommendationsArgs, TagInfo};
+use crate::app::utils::active_tags;
+
+// How often the indexer looks for a file to work on
+const TICK_MS: i32 = 15_000;
+// No keyboard or mouse input for this long counts as idle
+const IDLE_MS: f64 = 60_000.0;
+
+#[derive(Clone, Copy)]
+pub struct IndexerArgs {
+    pub recommend_sources: ReadSignal<RecommendSources>,
+    pub scanned_files: ReadSignal<Vec<FileListItem>>,
+    pub all_files: ReadSignal<Vec<FileInfo>>,
+    pub all_tags: ReadSignal<Vec<TagInfo>>,
+    pub file_tags_map: ReadSignal<HashMap<u32, Vec<TagInfo>>>,
+    pub batch_running: ReadSignal<bool>,
+    pub recommended_info_map: ReadSignal<HashMap<String, Vec<RecommendItem>>>,
+    pub set_recommended_info_map: WriteSignal<HashMap<String, Vec<RecommendItem>>>,
+    pub set_recommended_tags_map: WriteSignal<HashMap<u32, Vec<TagInfo>>>,
+    pub set_show_recommended: WriteSignal<bool>,
+}
+
+// Shows the suggestions stored by earlier sessions; call once the files are loaded
+pub async fn load_stored_recommendations(args: &IndexerArgs) {
+    let val = invoke("get_stored_recommendations", JsValue::NULL).await;
+    let stored: HashMap<String, Vec<RecommendItem>> = serde_wasm_bindgen::from_value(val).unwrap_or_default();
+    if !stored.is_empty() {
+        merge(args, stored);
+    }
+}
+
+// When background indexing is on, recommends one new untagged file at a time
+// while the app is idle and stores the result so it is ready next time too
+pub fn init_background_indexer(args: IndexerArgs) {
+    Effect::new(move |_| {
+        let window = web_sys::window().expect("no window");
+        let flag = js_sys::Reflect::get(&window, &JsValue::from_str("__TAGME_INDEXER_INTERVAL_SET"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if flag {
+            return;
+        }
+        let last_input = Rc::new(Cell::new(js_sys::Date::now()));
+        let li = last_input.clone();
+        let on_input = Closure::wrap(Box::new(move |_: web_sys::Event| li.set(js_sys::Date::now())) as Box<dyn FnMut(_)>);
+        for event in ["keydown", "mousedown", "mousemove", "wheel"] {
+            let _ = window.add_event_listener_with_callback(event, on_input.as_ref().unchecked_ref());
+        }
+        on_input.forget();
+
+        let in_flight = Rc::new(Cell::new(false));
+        // Files tried this session, including those that got no suggestions
+        let attempted = Rc::new(RefCell::new(HashSet::<String>::new()));
+        let tick = Closure::wrap(Box::new(move || {
+            let sources = args.recommend_sources.get_untracked();
+            if !sources.background_indexing
+                || in_flight.get()
+                || args.batch_running.get_untracked()
+                || js_sys::Date::now() - last_input.get() < IDLE_MS
+            {
+                return;
+            }
+            let Some(path) = next_file(&args, &sources, &attempted.borrow()) else { return };
+            attempted.borrow_mut().insert(path.clone());
+            in_flight.set(true);
+            let in_flight = in_flight.clone();
+            spawn_local(async move {
+                let tags = args.all_tags.get_untracked();
+                let labels: Vec<String> = active_tags(&tags).into_iter().map(|t| t.name).collect();
+                let top_k = core::cmp::min(labels.len(), 8);
+                let list = leptos_recommender::generate_for_file(
+                    path.clone(),
+                    labels,
+                    Vec::new(),
+                    top_k,
+                    &sources,
+                    Some(String::from("https://api.siliconflow.cn/v1")),
+                    None,
+                )
+                .await;
+                if !list.is_empty() {
+                    let store = StoreRecommendationsArgs { file_path: path.clone(), items: list.clone() };
+                    if let Err(e) = try_invoke("store_recommendations", serde_wasm_bindgen::to_value(&store).unwrap()).await {
+                        web_sys::console::error_1(&format!("[INDEXER] store failed for '{}': {:?}", path, e).into());
+                    }
+                    merge(&args, HashMap::from([(path, list)]));
+                }
+                in_flight.set(false);
+            });
+        }) as Box<dyn FnMut()>);
+        let _ = window.set_interval_with_callback_and_timeout_and_arguments_0(tick.as_ref().unchecked_ref(), TICK_MS);
+        let _ = js_sys::Reflect::set(&window, &JsValue::from_str("__TAGME_INDEXER_INTERVAL_SET"), &JsValue::from_bool(true));
+        tick.forget();
+    });
+}
+
+// The next untagged file without suggestions: fresh scan results first, then
+// files already in the database
+fn next_file(args: &IndexerArgs, sources: &RecommendSources, attempted: &HashSet<String>) -> Option<String> {
+    let known = args.recommended_info_map.get_untracked();
+    let files = args.all_files.get_untracked();
+    let file_tags = args.file_tags_map.get_untracked();
+    let tagged = |path: &str| {
+        files
+            .iter()
+            .find(|f| f.path == path)
+            .is_some_and(|f| file_tags.get(&f.id).is_some_and(|t| !t.is_empty()))
+    };
+    let scanned = args.scanned_files.get_untracked();
+    scanned
+        .iter()
+        .filter(|f| !f.is_directory)
+        .map(|f| &f.path)
+        .chain(files.iter().filter(|f| !f.is_directory).map(|f| &f.path))
+        .find(|path| {
+            sources.includes(path) && !attempted.contains(*path) && !known.contains_key(*path) && !tagged(path)
+        })
+        .cloned()
+}
+
+// Adds suggestions to what the file list shows without dropping a batch run's
+fn merge(args: &IndexerArgs, found: HashMap<String, Vec<RecommendItem>>) {
+    let files = args.all_files.get_untracked();
+    let tags = args.all_tags.get_untracked();
+    args.set_recommended_tags_map.update(|m| {
+        for (path, list) in &found {
+            if let Some(f) = files.iter().find(|f| &f.path == path) {
+                let infos = list.iter().filter_map(|item| tags.iter().find(|t| t.name == item.name).cloned()).collect();
+                m.insert(f.id, infos);
+            }
+        }
+    });
+    args.set_recommended_info_map.update(|m| m.extend(found));
+    args.set_show_recommended.set(true);
+}
@@ -23,6 +23,10 @@ pub fn build_display_files(
             db_id: Some(file.id),
             tags: tags_map.get(&file.id).cloned().unwrap_or_default(),
             is_directory: file.is_directory,
+            last_tagged: file.last_tagged,
+            open_count: file.open_count,
+            last_opened_at: file.last_opened_at,
+            inherited_tags: Vec::new(),
         });
     }
     let has_tag_filter = !selected_tag_ids.is_empty();
@@ -41,6 +45,10 @@ pub fn build_display_files(
                     db_id: None,
                     tags: Vec::new(),
                     is_directory: file.is_directory,
+                    last_tagged: None,
+                    open_count: 0,
+                    last_opened_at: None,
+                    inherited_tags: Vec::new(),
                 });
             }
         }
@@ -50,12 +58,7 @@ pub fn build_display_files(
 
 pub fn sort_display_files(mut display_files: Vec<DisplayFile>, col: SortColumn, dir: SortDirection) -> Vec<DisplayFile> {
     display_files.sort_by(|a, b| {
-        let cmp = match col {
-            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            SortColumn::Size => a.size_bytes.cmp(&b.size_bytes),
-            SortColumn::Date => a.last_modified.cmp(&b.last_modified),
-            SortColumn::Type => a.extension.to_lowercase().cmp(&b.extension.to_lowercase()),
-        };
+        let cmp = col.compare(a, b);
         match dir { SortDirection::Asc => cmp, SortDirection::Desc => cmp.reverse() }
     });
     display_files
@@ -16,6 +16,12 @@ pub struct FileInfo {
     pub size_bytes: u64,
     pub last_modified: i64,
     pub is_directory: bool,
+    #[serde(default)]
+    pub last_tagged: Option<i64>,
+    #[serde(default)]
+    pub open_count: u32,
+    #[serde(default)]
+    pub last_opened_at: Option<i64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -25,6 +31,22 @@ pub struct TagInfo {
     pub parent_id: Option<u32>,
     pub color: Option<String>,
     pub position: i32,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub locked: bool,
+    // Shade of a colored ancestor, for tags without their own color
+    #[serde(default)]
+    pub shade: Option<String>,
+}
+
+impl TagInfo {
+    // The color badges and the tree show: the tag's own, else its shade
+    pub fn display_color(&self) -> Option<String> {
+        self.color.clone().or_else(|| self.shade.clone())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -39,6 +61,41 @@ pub enum SortColumn {
     Size,
     Date,
     Type,
+    LastTagged,
+    Opens,
+    LastOpened,
+}
+
+// "Recent/Frequent" quick filter: only files opened through tagme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenedFilter {
+    All,
+    Recent,
+    Frequent,
+}
+
+impl SortColumn {
+    // Ascending order of two rows by this column
+    pub fn compare(self, a: &DisplayFile, b: &DisplayFile) -> std::cmp::Ordering {
+        match self {
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::Size => a.size_bytes.cmp(&b.size_bytes),
+            SortColumn::Date => a.last_modified.cmp(&b.last_modified),
+            SortColumn::Type => a.extension.to_lowercase().cmp(&b.extension.to_lowercase()),
+            SortColumn::LastTagged => a.last_tagged.cmp(&b.last_tagged),
+            SortColumn::Opens => a.open_count.cmp(&b.open_count),
+            SortColumn::LastOpened => a.last_opened_at.cmp(&b.last_opened_at),
+        }
+    }
+}
+
+// What clicking a tag in the tree does
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum TagClickMode {
+    // Filter the file list by the clicked tags
+    Filter,
+    // Add/remove the tag on the selected files
+    Assign,
 }
 
 #[derive(Clone, Debug, PartialEq, Copy)]
@@ -47,6 +104,43 @@ pub enum SortDirection {
     Desc,
 }
 
+// A file list view kept in a tab. The active tab lives in the app's filter, sort
+// and selection signals; the others keep their state here until switched to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewTab {
+    pub id: u32,
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+    pub roots: Vec<String>,
+    pub refine: Option<NlFilter>,
+    pub sort_column: SortColumn,
+    pub sort_direction: SortDirection,
+    pub selected_file_paths: Vec<String>,
+}
+
+impl ViewTab {
+    // Unfiltered, sorted by name
+    pub fn new(id: u32) -> Self {
+        ViewTab {
+            id,
+            tag_ids: Vec::new(),
+            use_and: true,
+            roots: Vec::new(),
+            refine: None,
+            sort_column: SortColumn::Name,
+            sort_direction: SortDirection::Asc,
+            selected_file_paths: Vec::new(),
+        }
+    }
+}
+
+// Files being dragged between the lists of the split view
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileDrag {
+    pub from_split: bool,
+    pub paths: Vec<String>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct DisplayFile {
     pub path: String,
@@ -57,6 +151,11 @@ pub struct DisplayFile {
     pub db_id: Option<u32>,
     pub tags: Vec<TagInfo>,
     pub is_directory: bool,
+    pub last_tagged: Option<i64>,
+    pub open_count: u32,
+    pub last_opened_at: Option<i64>,
+    // Tags of tagged folders above this path, when folder inheritance is on
+    pub inherited_tags: Vec<TagInfo>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -65,6 +164,7 @@ pub struct CreateTagArgs {
     pub name: String,
     pub parent_id: Option<u32>,
     pub color: Option<String>,
+    pub icon: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -95,6 +195,13 @@ pub struct AddFileTagArgs {
     pub tag_id: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTagsToFilesArgs {
+    pub file_paths: Vec<String>,
+    pub tag_ids: Vec<u32>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveFileTagArgs {
@@ -104,25 +211,865 @@ pub struct RemoveFileTagArgs {
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetFileTagsArgs {
-    pub file_id: u32,
+pub struct ScanFilesArgs {
+    pub root_path: String,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FilterFilesByTagsArgs {
-    pub tag_ids: Vec<u32>,
-    pub use_and_logic: bool,
+pub struct OpenFileArgs {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    pub enabled: bool,
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ScanFilesArgs {
-    pub root_path: String,
+pub struct SetProxySettingsArgs {
+    pub settings: ProxySettings,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LlmPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    pub currency: String,
+}
+
+// Local embedding backend used by the title recommender
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmbeddingSettings {
+    pub enabled: bool,
+    pub base_url: String,
+    pub model: String,
+    pub min_score: f32,
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "http://localhost:11434/v1".to_string(),
+            model: "nomic-embed-text".to_string(),
+            min_score: 0.5,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetEmbeddingSettingsArgs {
+    pub settings: EmbeddingSettings,
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct OpenFileArgs {
+pub struct SetLlmPricingArgs {
+    pub pricing: LlmPricing,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateRecommendationArgs {
+    // Files by the model they go to
+    pub images: Vec<String>,
+    pub contents: Vec<String>,
+    pub titles: Vec<String>,
+    pub labels: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEstimate {
+    pub files: usize,
+    pub image_files: usize,
+    pub image_bytes: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost: f64,
+    pub currency: String,
+    pub priced: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafetyPolicy {
+    pub confirm: bool,
+    pub type_to_confirm: bool,
+    pub backup: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self { confirm: true, type_to_confirm: false, backup: false }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAutostartArgs {
+    pub enabled: bool,
+}
+
+// External tagger registered in settings (see src-tauri/src/plugins.rs)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaggerPlugin {
+    pub name: String,
+    pub command: String,
+    pub extensions: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTaggerPluginsArgs {
+    pub plugins: Vec<TaggerPlugin>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRecommendSourcesArgs {
+    pub sources: leptos_recommender::RecommendSources,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetOpenActionsArgs {
+    pub actions: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetClipboardWatchArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetOfflineModeArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetStripImageMetadataArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExpandArchivesArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetManualRefreshArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSafetyPolicyArgs {
+    pub policy: SafetyPolicy,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeAllFilesArgs {
+    pub confirmation: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostUpdateInfo {
+    pub previous_version: Option<String>,
+    pub current_version: String,
+    pub updated: bool,
+    pub notes: Option<String>,
+    pub pending_migrations: Vec<PendingMigration>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMigrationArgs {
+    pub version: i64,
+}
+
+// Where the database, backups and caches live; `pending` is set after a move
+// until the app restarts
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub current: String,
+    pub default: String,
+    pub relocated: bool,
+    pub pending: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveReport {
+    pub target: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveDataDirArgs {
+    pub to_default: bool,
+}
+
+// A batch job (mass tagging, tag import, manifest export) that was killed mid-run
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBatch {
+    pub id: i64,
+    pub kind: String,
+    pub description: String,
+    pub total: usize,
+    pub done: usize,
+    pub started_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchArgs {
+    pub id: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchStatus {
+    pub root: String,
+    pub watching: bool,
+    pub paused: bool,
+    #[serde(default)]
+    pub offline: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub manual: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagHistoryEntry {
+    pub tag_id: u32,
+    pub tag_name: String,
+    pub action: String,
+    pub at: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTagHistoryArgs {
+    pub file_path: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub unused_tags: Vec<TagInfo>,
+    pub untagged_files: Vec<String>,
+    pub orphaned_files: Vec<String>,
+    pub missing_files: Vec<String>,
+    pub dangling_links: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateTags {
+    pub source_id: u32,
+    pub source_name: String,
+    pub source_count: u32,
+    pub target_id: u32,
+    pub target_name: String,
+    pub target_count: u32,
+    pub similarity: f32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyCleanupArgs {
+    pub category: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFile {
+    pub id: u32,
+    pub path: String,
+    pub size_bytes: u64,
+    pub missing_since: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkCandidate {
+    pub file_id: u32,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindRelinkCandidatesArgs {
+    pub dirs: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyRelinksArgs {
+    pub links: Vec<RelinkCandidate>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyIssue {
+    pub file_id: u32,
+    pub path: String,
+    pub kind: String,
+    pub stored_hash: String,
+    pub current_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub root: Option<String>,
+    pub checked: usize,
+    pub issues: Vec<VerifyIssue>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyProgress {
+    pub checked: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyFilesArgs {
+    pub root: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptFileHashesArgs {
+    pub file_ids: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFile {
+    pub id: u32,
     pub path: String,
+    pub root: Option<String>,
+    pub tag_ids: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub content_hash: String,
+    pub size_bytes: u64,
+    pub files: Vec<DuplicateFile>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateAction {
+    pub content_hash: String,
+    pub keep_id: u32,
+    pub union_tags: bool,
+    pub trash_others: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveDuplicatesArgs {
+    pub actions: Vec<DuplicateAction>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+// A local diagnostics counter: a timing ("scan", "llm.tags"...) or a cache
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metric {
+    pub name: String,
+    pub kind: String,
+    pub count: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    pub hits: u64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    pub tagged: usize,
+    pub trashed: usize,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestIssue {
+    pub path: String,
+    pub kind: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestReport {
+    pub base: Option<String>,
+    pub checked: usize,
+    pub issues: Vec<ManifestIssue>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifestArgs {
+    pub root: Option<String>,
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagSortModeArgs {
+    pub mode: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAutoColorTagsArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTagsArgs {
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTagsReport {
+    pub created: usize,
+    pub existing: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigikamReport {
+    pub tags_created: usize,
+    pub tags_existing: usize,
+    pub files_tagged: usize,
+    pub links_added: usize,
+    pub files_missing: usize,
+    pub unresolved_roots: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianReport {
+    pub notes: usize,
+    pub files: usize,
+    pub removed: usize,
+    pub path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportObsidianArgs {
+    pub mode: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTagsArgs {
+    pub format: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagArchivedArgs {
+    pub id: u32,
+    pub archived: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagLockedArgs {
+    pub id: u32,
+    pub locked: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetInheritTagColorsArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFlatViewArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagBadgeModeArgs {
+    pub mode: String,
+}
+
+// One badge in a file row: the tag plus its rendered label and tooltip
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagBadge {
+    pub tag: TagInfo,
+    pub label: String,
+    pub title: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetInheritFolderTagsArgs {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeTagsArgs {
+    pub source_id: u32,
+    pub target_id: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunScriptArgs {
+    pub source: String,
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptResult {
+    pub output: Vec<String>,
+    pub changes: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagHook {
+    pub tag_id: u32,
+    pub command: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagHookArgs {
+    pub tag_id: u32,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookFailure {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagFilter {
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilesArgs {
+    pub query: String,
+    pub filter: Option<TagFilter>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTagFilterArgs {
+    pub root: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTagFilterArgs {
+    pub root: Option<String>,
+    pub filter: TagFilter,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedFile {
+    pub path: String,
+    pub is_directory: bool,
+    pub missing: bool,
+    pub tags: Vec<TagInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinFilesArgs {
+    pub paths: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnpinFileArgs {
+    pub path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyTextArgs {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryFilesWithTagsArgs {
+    pub filter: Option<TagFilter>,
+    // Roots to scope to; empty means all
+    pub roots: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFilesPageArgs {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFilesWindowArgs {
+    pub after: Option<String>,
+    pub limit: usize,
+    pub filter: Option<TagFilter>,
+    pub roots: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesWindow {
+    pub rows: Vec<FileWithTags>,
+    pub total: usize,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLargeLibraryArgs {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesPage {
+    pub rows: Vec<FileWithTags>,
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddRootDirectoryArgs {
+    pub path: String,
+    pub allow_nested: bool,
+}
+
+// How to reach a WebDAV or S3 root; mirrors the backend's remote::RemoteSettings
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteSettings {
+    pub username: String,
+    pub password: String,
+    pub endpoint: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddRemoteRootArgs {
+    pub url: String,
+    pub settings: RemoteSettings,
+}
+
+// Entries a scan held back for one root
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanOverflow {
+    pub root: String,
+    pub shown: usize,
+    pub hidden: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadMoreScanArgs {
+    pub root: String,
+}
+
+// Payload of "image-tag-status"
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageTagStatus {
+    pub status: String,
+}
+
+// A change proposed by suggest_taxonomy
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TaxonomyChange {
+    #[serde(rename_all = "camelCase")]
+    Merge { source_id: u32, target_id: u32, reason: String },
+    #[serde(rename_all = "camelCase")]
+    Rename { id: u32, name: String, reason: String },
+    #[serde(rename_all = "camelCase")]
+    Group { parent: String, ids: Vec<u32>, reason: String },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestTaxonomyArgs {
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+}
+
+// Filter returned by translate_query; dates are Unix seconds
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NlFilter {
+    pub tag_ids: Vec<u32>,
+    pub use_and: bool,
+    pub extensions: Vec<String>,
+    pub name_contains: Option<String>,
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+}
+
+impl NlFilter {
+    // Limits beyond the tag filter, applied to the file list directly
+    pub fn has_refinements(&self) -> bool {
+        !self.extensions.is_empty()
+            || self.name_contains.is_some()
+            || self.modified_after.is_some()
+            || self.modified_before.is_some()
+    }
+
+    pub fn matches(&self, name: &str, extension: &str, last_modified: i64) -> bool {
+        (self.extensions.is_empty() || self.extensions.iter().any(|e| e.eq_ignore_ascii_case(extension)))
+            && self.name_contains.as_ref().is_none_or(|n| name.to_lowercase().contains(&n.to_lowercase()))
+            && self.modified_after.is_none_or(|t| last_modified >= t)
+            && self.modified_before.is_none_or(|t| last_modified < t)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateQueryArgs {
+    pub query: String,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagExample {
+    pub tag_id: u32,
+    pub file_path: String,
+    pub positive: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTagExamplesArgs {
+    pub tag_id: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTagExamplesArgs {
+    pub tag_id: u32,
+    pub file_paths: Vec<String>,
+    pub positive: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveTagExampleArgs {
+    pub tag_id: u32,
+    pub file_path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreRecommendationsArgs {
+    pub file_path: String,
+    pub items: Vec<leptos_recommender::RecommendItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRootLocalOnlyArgs {
+    pub path: String,
+    pub local_only: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRootDefaultTagsArgs {
+    pub path: String,
+    pub tag_ids: Vec<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoOccurringTagsArgs {
+    pub tag_id: u32,
+    pub limit: usize,
 }
@@ -1,3 +1,6 @@
+use crate::app::types::{FileInfo, TagBadge, TagInfo};
+use std::collections::HashMap;
+
 pub fn format_file_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
@@ -13,6 +16,19 @@ pub fn format_file_size(bytes: u64) -> String {
     }
 }
 
+// 12431 -> "12,431"
+pub fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
 pub fn format_timestamp(ts: i64) -> String {
     if ts <= 0 { return "Unknown".to_string(); }
     const SECONDS_PER_MINUTE: i64 = 60;
@@ -26,3 +42,190 @@ pub fn format_timestamp(ts: i64) -> String {
     let seconds = remaining_after_hours % SECONDS_PER_MINUTE;
     format!("{} days, {:02}:{:02}:{:02}", total_days, hours, minutes, seconds)
 }
+
+// Unix seconds as a UTC "YYYY-MM-DD"
+pub fn format_date(ts: i64) -> String {
+    let z = ts.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Curated tag colors, tuned for the dark theme
+pub const TAG_PALETTE: &[&str] = &[
+    "#f7768e", "#ff9e64", "#e0af68", "#9ece6a", "#73daca", "#7dcfff", "#7aa2f7", "#bb9af7",
+];
+
+// Tags neither archived nor under an archived tag: what the tree shows by
+// default and what recommenders get as labels
+pub fn active_tags(tags: &[TagInfo]) -> Vec<TagInfo> {
+    let by_id: HashMap<u32, &TagInfo> = tags.iter().map(|t| (t.id, t)).collect();
+    let is_archived = |tag: &TagInfo| {
+        let mut current = Some(tag);
+        // Bounded in case of a parent cycle
+        for _ in 0..=tags.len() {
+            match current {
+                Some(t) if t.archived => return true,
+                Some(t) => current = t.parent_id.and_then(|p| by_id.get(&p).copied()),
+                None => break,
+            }
+        }
+        false
+    };
+    tags.iter().filter(|t| !is_archived(t)).cloned().collect()
+}
+
+// Least-used palette color among existing tags; ties go to palette order
+pub fn next_tag_color(tags: &[TagInfo]) -> String {
+    TAG_PALETTE
+        .iter()
+        .min_by_key(|c| {
+            tags.iter()
+                .filter(|t| t.color.as_deref().is_some_and(|tc| tc.eq_ignore_ascii_case(c)))
+                .count()
+        })
+        .unwrap()
+        .to_string()
+}
+
+// True when the root owning `path` is one of `scope`
+pub fn in_root_scope(path: &str, roots: &[String], scope: &[String]) -> bool {
+    tagme_paths::longest_root(path, roots.iter().map(|r| r.as_str()))
+        .is_some_and(|owner| scope.iter().any(|r| r == owner))
+}
+
+// Settings key for the tag filter remembered per root selection: None for all
+// roots, the root itself for one, and the sorted paths joined for several
+pub fn tag_filter_key(scope: &[String]) -> Option<String> {
+    match scope {
+        [] => None,
+        [root] => Some(root.clone()),
+        _ => {
+            let mut roots = scope.to_vec();
+            roots.sort();
+            Some(roots.join("\n"))
+        }
+    }
+}
+
+// Directory rows that carry tags, for virtual inheritance
+pub fn tagged_folders(files: &[FileInfo], tags_map: &HashMap<u32, Vec<TagInfo>>) -> Vec<(String, Vec<TagInfo>)> {
+    files
+        .iter()
+        .filter(|f| f.is_directory)
+        .filter_map(|f| tags_map.get(&f.id).filter(|t| !t.is_empty()).map(|t| (f.path.clone(), t.clone())))
+        .collect()
+}
+
+// Tags `path` picks up from the tagged folders above it, minus the ones it has itself
+pub fn inherited_tags(path: &str, own: &[TagInfo], folders: &[(String, Vec<TagInfo>)]) -> Vec<TagInfo> {
+    let mut out: Vec<TagInfo> = Vec::new();
+    for (dir, tags) in folders {
+        if tagme_paths::same_path(path, dir) || !tagme_paths::is_under(path, dir) {
+            continue;
+        }
+        for t in tags {
+            if !own.iter().chain(&out).any(|o| o.id == t.id) {
+                out.push(t.clone());
+            }
+        }
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+// Ancestors of `tag`, root first. Stops at a missing parent or a cycle.
+fn tag_ancestors<'a>(tag: &TagInfo, all: &'a [TagInfo]) -> Vec<&'a TagInfo> {
+    let mut chain = Vec::new();
+    let mut parent = tag.parent_id;
+    while let Some(pid) = parent {
+        match all.iter().find(|t| t.id == pid) {
+            Some(p) if p.id != tag.id && !chain.iter().any(|c: &&TagInfo| c.id == p.id) => {
+                chain.push(p);
+                parent = p.parent_id;
+            }
+            _ => break,
+        }
+    }
+    chain.reverse();
+    chain
+}
+
+// "Work ▸ ProjectA"
+pub fn tag_path(tag: &TagInfo, all: &[TagInfo]) -> String {
+    tag_ancestors(tag, all)
+        .iter()
+        .map(|t| t.name.as_str())
+        .chain(std::iter::once(tag.name.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ▸ ")
+}
+
+// A tag as the names from the top of the tree down, as filter links name it
+pub fn tag_name_path(tag: &TagInfo, all: &[TagInfo]) -> Vec<String> {
+    tag_ancestors(tag, all)
+        .iter()
+        .map(|t| t.name.clone())
+        .chain(std::iter::once(tag.name.clone()))
+        .collect()
+}
+
+// The tag a name path leads to, matching names exactly and then ignoring case
+pub fn find_tag_by_path(path: &[String], all: &[TagInfo]) -> Option<u32> {
+    let mut parent = None;
+    for name in path {
+        let children: Vec<&TagInfo> = all.iter().filter(|t| t.parent_id == parent).collect();
+        let tag = children
+            .iter()
+            .find(|t| t.name == *name)
+            .or_else(|| children.iter().find(|t| t.name.eq_ignore_ascii_case(name)))?;
+        parent = Some(tag.id);
+    }
+    parent
+}
+
+// Badges for a row in the given mode ("name", "path" or "leaf"). The tooltip
+// always carries the full path; "leaf" drops tags that are an ancestor of another
+// tag on the same row.
+pub fn tag_badges(tags: &[TagInfo], all: &[TagInfo], mode: &str) -> Vec<TagBadge> {
+    tags.iter()
+        .filter(|t| {
+            mode != "leaf" || !tags.iter().any(|other| tag_ancestors(other, all).iter().any(|a| a.id == t.id))
+        })
+        .map(|t| {
+            let path = tag_path(t, all);
+            let label = if mode == "path" { path.clone() } else { t.name.clone() };
+            TagBadge { tag: t.clone(), label, title: path }
+        })
+        .collect()
+}
+
+// Text to type when purges need a typed confirmation: the root's folder name, or
+// "all" for every file. Mirrors the backend's check.
+pub fn purge_phrase(root: Option<&str>) -> String {
+    match root {
+        Some(root) => tagme_paths::file_name(root).unwrap_or(root).to_string(),
+        None => "all".to_string(),
+    }
+}
+
+// Chip text for a recommendation, marked with the source that suggested it
+pub fn recommend_label(item: &leptos_recommender::RecommendItem) -> String {
+    match item.source.as_str() {
+        "onnx" => format!("{} ·AI", item.name),
+        "llm" => format!("{} ·LLM", item.name),
+        "llm-vision" => format!("{} ·VL", item.name),
+        "llm-content" => format!("{} ·Doc", item.name),
+        "rule" => format!("{} ·Rule", item.name),
+        source => match source.strip_prefix("plugin:") {
+            Some(plugin) => format!("{} ·{}", item.name, plugin),
+            None => item.name.clone(),
+        },
+    }
+}
@@ -0,0 +1,254 @@
+// Lexical path handling shared by the UI and the backend.
+//
+// Paths are compared component by component, so `C:\Data` never contains
+// `C:\Database`. Windows-style paths (drive letter, UNC or any backslash) treat
+// `/` and `\` alike and compare case-insensitively; everything else compares
+// exactly, as on Linux/macOS filesystems. Remote locations (`s3://bucket/a`,
+// `davs://nas/photos`) are paths too: the scheme and `//` form their prefix and
+// `/` separates the rest. Nothing here touches the disk.
+
+pub mod link;
+
+pub fn is_windows_style(path: &str) -> bool {
+    let b = path.as_bytes();
+    url_scheme(path).is_none() && (path.contains('\\') || (b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':'))
+}
+
+// "s3" for `s3://bucket/key`. A single letter before `:` is a drive, not a scheme.
+pub fn url_scheme(path: &str) -> Option<&str> {
+    let (scheme, _) = path.split_once("://")?;
+    let valid = scheme.len() >= 2
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+// Splits into (prefix, components). The prefix is "\\" for UNC paths, the
+// separator for absolute paths, `scheme://` for URLs and "" otherwise; "." and
+// empty components are dropped.
+fn split(path: &str) -> (&str, Vec<&str>) {
+    if let Some(scheme) = url_scheme(path) {
+        let (prefix, rest) = path.split_at(scheme.len() + 3);
+        let parts = rest.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        (prefix, parts)
+    } else if is_windows_style(path) {
+        let trimmed = path.trim_start_matches(['\\', '/']);
+        let leading = path.len() - trimmed.len();
+        let prefix = match leading {
+            0 => "",
+            1 => "\\",
+            _ => "\\\\",
+        };
+        let parts = trimmed.split(['\\', '/']).filter(|c| !c.is_empty() && *c != ".").collect();
+        (prefix, parts)
+    } else {
+        let prefix = if path.starts_with('/') { "/" } else { "" };
+        let parts = path.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        (prefix, parts)
+    }
+}
+
+// Native separators, no repeated or trailing separators except on a bare root
+// (`C:\`, `/`).
+pub fn normalize(path: &str) -> String {
+    let windows = is_windows_style(path);
+    let sep = if windows { "\\" } else { "/" };
+    let (prefix, parts) = split(path);
+    let mut out = format!("{}{}", prefix, parts.join(sep));
+    if windows && parts.len() == 1 && prefix.is_empty() && out.ends_with(':') {
+        out.push('\\');
+    }
+    out
+}
+
+// Components used for comparisons: prefix first, lowercased for Windows paths.
+// URL schemes are case-insensitive, the rest of a URL is not.
+fn key(path: &str) -> Vec<String> {
+    let windows = is_windows_style(path);
+    let (prefix, parts) = split(path);
+    std::iter::once(prefix.to_lowercase())
+        .chain(parts.into_iter().map(|c| if windows { c.to_lowercase() } else { c.to_string() }))
+        .collect()
+}
+
+pub fn same_path(a: &str, b: &str) -> bool {
+    key(a) == key(b)
+}
+
+// True when `path` is `root` itself or lies anywhere below it
+pub fn is_under(path: &str, root: &str) -> bool {
+    let p = key(path);
+    let r = key(root);
+    p.len() >= r.len() && p[..r.len()] == r[..]
+}
+
+// The root that owns `path`. Roots may be nested (a project folder inside a
+// bigger archive root); the deepest root containing a path always wins, so each
+// file has exactly one owner for root_id assignment, grouping and purging.
+pub fn longest_root<'a, I>(path: &str, roots: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let p = key(path);
+    roots
+        .into_iter()
+        .filter_map(|root| {
+            let r = key(root);
+            (p.len() >= r.len() && p[..r.len()] == r[..]).then_some((r.len(), root))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, root)| root)
+}
+
+// Last component of `path`, or None for a bare root (`C:\`, `/`)
+pub fn file_name(path: &str) -> Option<&str> {
+    let (_, parts) = split(path);
+    let last = parts.last().copied()?;
+    (!(is_windows_style(path) && parts.len() == 1 && last.ends_with(':'))).then_some(last)
+}
+
+// Drive (`C:\`), UNC (`\\server\share`) or Unix (`/`) absolute path
+pub fn is_absolute(path: &str) -> bool {
+    let b = path.as_bytes();
+    path.starts_with('/')
+        || path.starts_with("\\\\")
+        || (b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && matches!(b[2], b'\\' | b'/'))
+}
+
+// Paths in copied text, one per line, with the quotes Explorer's "Copy as path"
+// adds stripped. None unless every non-empty line is an absolute path, so copied
+// prose that happens to mention a path is ignored.
+pub fn paths_in_text(text: &str) -> Option<Vec<&str>> {
+    let paths: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim().trim_matches('"').trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!paths.is_empty() && paths.iter().all(|p| is_absolute(p))).then_some(paths)
+}
+
+// Moves `path` from under `from` to the same place under `to`; None when `path`
+// isn't inside `from`
+pub fn rebase(path: &str, from: &str, to: &str) -> Option<String> {
+    if !is_under(path, from) {
+        return None;
+    }
+    let (_, parts) = split(path);
+    let (_, root_parts) = split(from);
+    let rest = &parts[root_parts.len()..];
+    let mut out = normalize(to);
+    if rest.is_empty() {
+        return Some(out);
+    }
+    let sep = if is_windows_style(&out) { "\\" } else { "/" };
+    if !out.ends_with(sep) {
+        out.push_str(sep);
+    }
+    out.push_str(&rest.join(sep));
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siblings_with_shared_prefix_are_not_contained() {
+        assert!(!is_under(r"C:\Database\a.txt", r"C:\Data"));
+        assert!(!is_under("/srv/photos2/a.jpg", "/srv/photos"));
+        assert!(is_under(r"C:\Data\a.txt", r"C:\Data"));
+        assert!(is_under("/srv/photos/a.jpg", "/srv/photos/"));
+    }
+
+    #[test]
+    fn root_contains_itself() {
+        assert!(is_under(r"D:\Photos", r"D:\Photos\"));
+        assert!(is_under("/home/me", "/home/me"));
+    }
+
+    #[test]
+    fn windows_paths_ignore_case_and_separator_style() {
+        assert!(is_under("c:/data/Sub/a.txt", r"C:\Data"));
+        assert!(same_path(r"D:\Photos\\2024\", "d:/photos/2024"));
+    }
+
+    #[test]
+    fn unix_paths_are_case_sensitive() {
+        assert!(!is_under("/home/Me/a.txt", "/home/me"));
+        assert!(!same_path("/a/B", "/a/b"));
+    }
+
+    #[test]
+    fn normalize_keeps_bare_roots() {
+        assert_eq!(normalize(r"C:\"), r"C:\");
+        assert_eq!(normalize("C:"), r"C:\");
+        assert_eq!(normalize("/"), "/");
+        assert_eq!(normalize(r"D:/Photos//2024/"), r"D:\Photos\2024");
+        assert_eq!(normalize(r"\\nas\share\"), r"\\nas\share");
+        assert_eq!(normalize("/srv/./photos/"), "/srv/photos");
+    }
+
+    #[test]
+    fn drive_root_contains_everything_on_the_drive() {
+        assert!(is_under(r"C:\Users\a.txt", r"C:\"));
+        assert!(!is_under(r"D:\Users\a.txt", r"C:\"));
+    }
+
+    #[test]
+    fn unc_and_absolute_prefixes_differ() {
+        assert!(!is_under(r"\\nas\share\a", r"\nas\share"));
+        assert!(is_under(r"\\nas\share\a", r"\\NAS\share"));
+    }
+
+    #[test]
+    fn rebase_moves_paths_between_roots() {
+        assert_eq!(rebase("/data/link/a/b.jpg", "/data/link", "/mnt/real").as_deref(), Some("/mnt/real/a/b.jpg"));
+        assert_eq!(rebase(r"D:\Photos\x.jpg", r"d:\photos\", r"D:\Photos").as_deref(), Some(r"D:\Photos\x.jpg"));
+        assert_eq!(rebase(r"C:\Data", r"C:\Data", r"E:\").as_deref(), Some(r"E:\"));
+        assert_eq!(rebase(r"C:\Data\a", r"C:\", r"E:\").as_deref(), Some(r"E:\Data\a"));
+        assert_eq!(rebase(r"C:\Database\a", r"C:\Data", r"E:\Data"), None);
+    }
+
+    #[test]
+    fn file_name_skips_trailing_separators_and_roots() {
+        assert_eq!(file_name(r"D:\Photos\2024\"), Some("2024"));
+        assert_eq!(file_name("/srv/photos"), Some("photos"));
+        assert_eq!(file_name(r"C:\"), None);
+        assert_eq!(file_name("/"), None);
+    }
+
+    #[test]
+    fn paths_in_text_requires_every_line_to_be_a_path() {
+        assert_eq!(
+            paths_in_text("\"C:\\Photos\\a.jpg\"\r\n\"\\\\nas\\share\\b.pdf\"\r\n"),
+            Some(vec![r"C:\Photos\a.jpg", r"\\nas\share\b.pdf"])
+        );
+        assert_eq!(paths_in_text("  /home/me/notes.md  "), Some(vec!["/home/me/notes.md"]));
+        assert_eq!(paths_in_text("see /home/me/notes.md"), None);
+        assert_eq!(paths_in_text("/home/me/a\nrelative/b"), None);
+        assert_eq!(paths_in_text("C:relative"), None);
+        assert_eq!(paths_in_text("  \n"), None);
+    }
+
+    #[test]
+    fn urls_keep_their_scheme_and_compare_by_component() {
+        assert_eq!(url_scheme("s3://bucket/a"), Some("s3"));
+        assert_eq!(url_scheme(r"C://x"), None);
+        assert_eq!(url_scheme("/srv/a://b"), None);
+        assert_eq!(normalize("davs://nas/photos//2024/"), "davs://nas/photos/2024");
+        assert!(!is_windows_style(r"s3://bucket/a\b"));
+        assert!(is_under("s3://bucket/photos/a.jpg", "S3://bucket/photos/"));
+        assert!(!is_under("s3://bucket/photos2/a.jpg", "s3://bucket/photos"));
+        assert!(!same_path("s3://Bucket/a", "s3://bucket/a"));
+        assert_eq!(file_name("davs://nas/photos/a.jpg"), Some("a.jpg"));
+        assert_eq!(rebase("s3://b/old/x.jpg", "s3://b/old", "s3://b/new").as_deref(), Some("s3://b/new/x.jpg"));
+    }
+
+    #[test]
+    fn longest_root_picks_deepest_match() {
+        let roots = [r"C:\Data", r"C:\Data\Projects", r"C:\Database"];
+        assert_eq!(longest_root(r"C:\Data\Projects\x.rs", roots), Some(r"C:\Data\Projects"));
+        assert_eq!(longest_root(r"C:\Data\x.rs", roots), Some(r"C:\Data"));
+        assert_eq!(longest_root(r"C:\Other\x.rs", roots), None);
+    }
+}
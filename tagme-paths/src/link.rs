@@ -0,0 +1,164 @@
+// `tagme://filter?...` links: a file list view (tag filter, roots and search
+// limits) as a URL that can be bookmarked or shared. Tags are named by their
+// path in the tree ("Projects/Client A") rather than by id, so a link made in
+// one library opens the same view in another that uses the same tags.
+//
+//     tagme://filter?tags=Projects/Client%20A,urgent&mode=or&roots=C%3A%5CWork&ext=pdf,png&name=invoice&after=1700000000
+//
+// Names are percent-encoded per segment, so `/` and `,` inside a name survive.
+
+pub const SCHEME: &str = "tagme://";
+const FILTER: &str = "tagme://filter";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterLink {
+    // Each tag as its path from the top of the tree
+    pub tags: Vec<Vec<String>>,
+    // AND (the default) or OR between the tags
+    pub use_and: bool,
+    pub roots: Vec<String>,
+    pub extensions: Vec<String>,
+    pub name_contains: Option<String>,
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+}
+
+pub fn is_link(text: &str) -> bool {
+    text.get(..SCHEME.len()).is_some_and(|s| s.eq_ignore_ascii_case(SCHEME))
+}
+
+impl FilterLink {
+    pub fn to_url(&self) -> String {
+        let list = |items: &[String]| items.iter().map(|i| encode(i)).collect::<Vec<_>>().join(",");
+        let mut params = Vec::new();
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|path| path.iter().map(|s| encode(s)).collect::<Vec<_>>().join("/")).collect();
+            params.push(format!("tags={}", tags.join(",")));
+        }
+        if !self.use_and {
+            params.push("mode=or".to_string());
+        }
+        if !self.roots.is_empty() {
+            params.push(format!("roots={}", list(&self.roots)));
+        }
+        if !self.extensions.is_empty() {
+            params.push(format!("ext={}", list(&self.extensions)));
+        }
+        if let Some(name) = &self.name_contains {
+            params.push(format!("name={}", encode(name)));
+        }
+        if let Some(after) = self.modified_after {
+            params.push(format!("after={}", after));
+        }
+        if let Some(before) = self.modified_before {
+            params.push(format!("before={}", before));
+        }
+        if params.is_empty() {
+            FILTER.to_string()
+        } else {
+            format!("{}?{}", FILTER, params.join("&"))
+        }
+    }
+
+    // None for anything but a filter link; unknown parameters are ignored so
+    // older versions still open links from newer ones
+    pub fn parse(url: &str) -> Option<FilterLink> {
+        let rest = url.trim();
+        if !is_link(rest) {
+            return None;
+        }
+        let rest = &rest[SCHEME.len()..];
+        let (target, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if !target.trim_end_matches('/').eq_ignore_ascii_case("filter") {
+            return None;
+        }
+        let list = |value: &str| value.split(',').filter(|v| !v.is_empty()).map(decode).collect::<Vec<_>>();
+        let mut link = FilterLink { use_and: true, ..Default::default() };
+        for (key, value) in query.split('&').filter_map(|kv| kv.split_once('=')) {
+            match key {
+                "tags" => {
+                    link.tags = value
+                        .split(',')
+                        .map(|path| path.split('/').filter(|s| !s.is_empty()).map(decode).collect::<Vec<_>>())
+                        .filter(|path| !path.is_empty())
+                        .collect()
+                }
+                "mode" => link.use_and = !value.eq_ignore_ascii_case("or"),
+                "roots" => link.roots = list(value),
+                "ext" => link.extensions = list(value),
+                "name" => link.name_contains = Some(decode(value)).filter(|n| !n.is_empty()),
+                "after" => link.modified_after = value.parse().ok(),
+                "before" => link.modified_before = value.parse().ok(),
+                _ => {}
+            }
+        }
+        Some(link)
+    }
+}
+
+fn encode(text: &str) -> String {
+    let mut out = String::new();
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_field() {
+        let link = FilterLink {
+            tags: vec![vec!["Projects".into(), "Client A/B, Inc".into()], vec!["urgent".into()]],
+            use_and: false,
+            roots: vec![r"C:\Work".into(), "/home/me/Photos".into()],
+            extensions: vec!["pdf".into(), "png".into()],
+            name_contains: Some("100% done".into()),
+            modified_after: Some(1_700_000_000),
+            modified_before: Some(1_800_000_000),
+        };
+        let url = link.to_url();
+        assert!(url.starts_with("tagme://filter?tags=Projects/Client%20A%2FB%2C%20Inc,urgent&mode=or&roots="));
+        assert_eq!(FilterLink::parse(&url), Some(link));
+    }
+
+    #[test]
+    fn defaults_and_foreign_links() {
+        let empty = FilterLink { use_and: true, ..Default::default() };
+        assert_eq!(empty.to_url(), "tagme://filter");
+        assert_eq!(FilterLink::parse("tagme://filter"), Some(empty.clone()));
+        assert_eq!(FilterLink::parse("TagMe://filter/?future=1"), Some(empty));
+        assert_eq!(FilterLink::parse("tagme://settings"), None);
+        assert_eq!(FilterLink::parse("https://filter?tags=a"), None);
+        assert_eq!(FilterLink::parse("tagme://filter?name=a+b").unwrap().name_contains.as_deref(), Some("a b"));
+    }
+}
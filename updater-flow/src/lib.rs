@@ -1,24 +1,77 @@
 use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Updater, UpdaterExt};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct UpdateInfo {
     pub current: String,
     pub latest: Option<String>,
     pub has_update: bool,
+    // Markdown body of the release from the update manifest
+    pub notes: Option<String>,
+    // Set by the app when the user skipped or snoozed this exact version
+    #[serde(default)]
+    pub suppressed: bool,
 }
 
-pub async fn check(app_handle: AppHandle) -> Result<UpdateInfo, String> {
+// Pre-releases publish their manifest under a fixed "beta" tag, since GitHub's
+// "latest" redirect skips pre-releases.
+pub const BETA_ENDPOINT: &str = "https://github.com/liu0fanyi/tagme/releases/download/beta/latest.json";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "beta" => UpdateChannel::Beta,
+            _ => UpdateChannel::Stable,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
+// Stable keeps the endpoints from tauri.conf.json; beta swaps in its own manifest.
+fn updater_for(app_handle: &AppHandle, channel: UpdateChannel, proxy: Option<&str>) -> Result<Updater, String> {
+    let mut builder = app_handle.updater_builder();
+    if channel == UpdateChannel::Beta {
+        let url = BETA_ENDPOINT.parse::<tauri::Url>().map_err(|e| e.to_string())?;
+        builder = builder.endpoints(vec![url]).map_err(|e| e.to_string())?;
+    }
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy.parse::<tauri::Url>().map_err(|e| format!("invalid proxy: {}", e))?);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+pub async fn check(app_handle: AppHandle, channel: UpdateChannel, proxy: Option<String>) -> Result<UpdateInfo, String> {
     let current = app_handle.package_info().version.to_string();
-    let updater = app_handle.updater().map_err(|e| e.to_string())?;
+    let updater = updater_for(&app_handle, channel, proxy.as_deref())?;
     match updater.check().await.map_err(|e| e.to_string())? {
-        Some(update) => Ok(UpdateInfo { current, latest: Some(update.version.clone()), has_update: true }),
-        None => Ok(UpdateInfo { current, latest: None, has_update: false }),
+        Some(update) => Ok(UpdateInfo { current, latest: Some(update.version.clone()), has_update: true, notes: update.body.clone(), suppressed: false }),
+        None => Ok(UpdateInfo { current, latest: None, has_update: false, notes: None, suppressed: false }),
     }
 }
 
-pub async fn install(app_handle: AppHandle) -> Result<(), String> {
-    let updater = app_handle.updater().map_err(|e| e.to_string())?;
+// `before_install` receives the version and release notes right before the
+// installer runs, since on some platforms the process exits during install.
+pub async fn install(
+    app_handle: AppHandle,
+    channel: UpdateChannel,
+    proxy: Option<String>,
+    before_install: impl FnOnce(String, Option<String>),
+) -> Result<(), String> {
+    let updater = updater_for(&app_handle, channel, proxy.as_deref())?;
     if let Some(update) = updater.check().await.map_err(|e| e.to_string())? {
         let app = app_handle.clone();
         let bytes = update
@@ -31,6 +84,7 @@ pub async fn install(app_handle: AppHandle) -> Result<(), String> {
             .await
             .map_err(|e| e.to_string())?;
         let _ = app_handle.emit("update-download-complete", ());
+        before_install(update.version.clone(), update.body.clone());
         update.install(bytes).map_err(|e| e.to_string())?;
     }
     Ok(())